@@ -1,14 +1,17 @@
 use crate::grpc::models::{CollectionsResponse, VersionInfo};
 use crate::grpc::qdrant::condition::ConditionOneOf;
+use crate::grpc::qdrant::facet_value::Variant;
 use crate::grpc::qdrant::point_id::PointIdOptions;
 use crate::grpc::qdrant::r#match::MatchValue;
 use crate::grpc::qdrant::with_payload_selector::SelectorOptions;
 use crate::grpc::qdrant::{
-    CollectionDescription, CollectionOperationResponse, Condition, FieldCondition, Filter,
-    GeoBoundingBox, GeoPoint, GeoRadius, HasIdCondition, HealthCheckReply, HnswConfigDiff,
-    IsEmptyCondition, ListCollectionsResponse, Match, PayloadExcludeSelector,
-    PayloadIncludeSelector, PayloadSchemaInfo, PayloadSchemaType, PointId, Range, ScoredPoint,
-    SearchParams, ValuesCount, WithPayloadSelector,
+    CardinalityExplain, CollectionDescription, CollectionOperationResponse, Condition, FacetValue,
+    FieldCondition, Filter, FilterExplain, GeoBoundingBox, GeoPoint, GeoRadius, HasIdCondition,
+    HealthCheckReply, HnswConfigDiff, IsEmptyCondition, ListCollectionsResponse, Match,
+    PayloadExcludeSelector, PayloadIncludeSelector, PayloadSchemaInfo, PayloadSchemaType,
+    PointExplain, PointId, ProductQuantizationConfig, Range, RecommendQueryInternal, ScoredPoint,
+    SearchParams, SearchStrategy, SegmentExplain, SegmentSearchExplain, SparseVector, ValuesCount,
+    Vector, VectorStorageDatatype, WithPayloadSelector,
 };
 
 use prost_types::value::Kind;
@@ -214,6 +217,7 @@ impl From<SearchParams> for segment::types::SearchParams {
     fn from(params: SearchParams) -> Self {
         Self {
             hnsw_ef: params.hnsw_ef.map(|x| x as usize),
+            exact: params.exact.unwrap_or(false),
         }
     }
 }
@@ -222,6 +226,75 @@ impl From<segment::types::SearchParams> for SearchParams {
     fn from(params: segment::types::SearchParams) -> Self {
         Self {
             hnsw_ef: params.hnsw_ef.map(|x| x as u64),
+            exact: Some(params.exact),
+        }
+    }
+}
+
+impl From<RecommendQueryInternal> for segment::types::RecommendQuery {
+    fn from(query: RecommendQueryInternal) -> Self {
+        Self {
+            positive: query.positive.into_iter().map(|v| v.data).collect(),
+            negative: query.negative.into_iter().map(|v| v.data).collect(),
+        }
+    }
+}
+
+impl From<segment::types::RecommendQuery> for RecommendQueryInternal {
+    fn from(query: segment::types::RecommendQuery) -> Self {
+        Self {
+            positive: query
+                .positive
+                .into_iter()
+                .map(|data| Vector { data })
+                .collect(),
+            negative: query
+                .negative
+                .into_iter()
+                .map(|data| Vector { data })
+                .collect(),
+        }
+    }
+}
+
+impl From<SparseVector> for segment::types::SparseVector {
+    fn from(vector: SparseVector) -> Self {
+        Self {
+            indices: vector.indices,
+            values: vector.values,
+        }
+    }
+}
+
+impl From<segment::types::SparseVector> for SparseVector {
+    fn from(vector: segment::types::SparseVector) -> Self {
+        Self {
+            indices: vector.indices,
+            values: vector.values,
+        }
+    }
+}
+
+impl From<segment::types::FacetValue> for FacetValue {
+    fn from(value: segment::types::FacetValue) -> Self {
+        let variant = match value {
+            segment::types::FacetValue::Keyword(keyword) => Variant::Keyword(keyword),
+            segment::types::FacetValue::Integer(integer) => Variant::Integer(integer),
+        };
+        Self {
+            variant: Some(variant),
+        }
+    }
+}
+
+impl TryFrom<FacetValue> for segment::types::FacetValue {
+    type Error = Status;
+
+    fn try_from(value: FacetValue) -> Result<Self, Self::Error> {
+        match value.variant {
+            Some(Variant::Keyword(keyword)) => Ok(Self::Keyword(keyword)),
+            Some(Variant::Integer(integer)) => Ok(Self::Integer(integer)),
+            None => Err(Status::invalid_argument("Unknown facet value")),
         }
     }
 }
@@ -266,6 +339,117 @@ impl TryFrom<ScoredPoint> for segment::types::ScoredPoint {
     }
 }
 
+impl From<segment::types::SegmentExplain> for SegmentExplain {
+    fn from(explain: segment::types::SegmentExplain) -> Self {
+        Self {
+            search: Some(explain.search.into()),
+            points: explain
+                .points
+                .into_iter()
+                .map(|point| PointExplain {
+                    id: Some(point.id.into()),
+                    filter: Some(point.filter.into()),
+                })
+                .collect(),
+        }
+    }
+}
+
+impl TryFrom<SegmentExplain> for segment::types::SegmentExplain {
+    type Error = Status;
+
+    fn try_from(explain: SegmentExplain) -> Result<Self, Self::Error> {
+        Ok(Self {
+            search: explain
+                .search
+                .ok_or_else(|| Status::invalid_argument("Segment search explanation is missing"))?
+                .try_into()?,
+            points: explain
+                .points
+                .into_iter()
+                .map(|point| {
+                    Ok(segment::types::PointExplain {
+                        id: point
+                            .id
+                            .ok_or_else(|| Status::invalid_argument("Point does not have an ID"))?
+                            .try_into()?,
+                        filter: point.filter.unwrap_or_default().into(),
+                    })
+                })
+                .collect::<Result<_, Status>>()?,
+        })
+    }
+}
+
+impl From<segment::types::SegmentSearchExplain> for SegmentSearchExplain {
+    fn from(explain: segment::types::SegmentSearchExplain) -> Self {
+        let strategy = match explain.strategy {
+            segment::types::SearchStrategy::Plain => SearchStrategy::Plain,
+            segment::types::SearchStrategy::Graph => SearchStrategy::Graph,
+            segment::types::SearchStrategy::Sparse => SearchStrategy::Sparse,
+        };
+        Self {
+            strategy: strategy as i32,
+            cardinality: explain.cardinality.map(|cardinality| CardinalityExplain {
+                min: cardinality.min as u64,
+                exp: cardinality.exp as u64,
+                max: cardinality.max as u64,
+                indexing_threshold: cardinality.indexing_threshold as u64,
+            }),
+            sampled_above_threshold: explain.sampled_above_threshold,
+            visited_points: explain.visited_points as u64,
+        }
+    }
+}
+
+impl TryFrom<SegmentSearchExplain> for segment::types::SegmentSearchExplain {
+    type Error = Status;
+
+    fn try_from(explain: SegmentSearchExplain) -> Result<Self, Self::Error> {
+        let strategy = match SearchStrategy::from_i32(explain.strategy) {
+            Some(SearchStrategy::Plain) => segment::types::SearchStrategy::Plain,
+            Some(SearchStrategy::Graph) => segment::types::SearchStrategy::Graph,
+            Some(SearchStrategy::Sparse) => segment::types::SearchStrategy::Sparse,
+            None => return Err(Status::invalid_argument("Malformed SearchStrategy type")),
+        };
+        Ok(Self {
+            strategy,
+            cardinality: explain.cardinality.map(|cardinality| {
+                segment::types::CardinalityExplain {
+                    min: cardinality.min as usize,
+                    exp: cardinality.exp as usize,
+                    max: cardinality.max as usize,
+                    indexing_threshold: cardinality.indexing_threshold as usize,
+                }
+            }),
+            sampled_above_threshold: explain.sampled_above_threshold,
+            visited_points: explain.visited_points as usize,
+        })
+    }
+}
+
+impl From<segment::types::FilterExplain> for FilterExplain {
+    fn from(explain: segment::types::FilterExplain) -> Self {
+        Self {
+            should: explain.should.unwrap_or_default(),
+            must: explain.must.unwrap_or_default(),
+            must_not: explain.must_not.unwrap_or_default(),
+        }
+    }
+}
+
+impl From<FilterExplain> for segment::types::FilterExplain {
+    fn from(explain: FilterExplain) -> Self {
+        // Empty list of conditions is not distinguished from the missing one in gRPC
+        let conditions = |results: Vec<bool>| Some(results).filter(|results| !results.is_empty());
+        Self {
+            should: conditions(explain.should),
+            must: conditions(explain.must),
+            must_not: conditions(explain.must_not),
+        }
+    }
+}
+
 impl TryFrom<PointId> for segment::types::PointIdType {
     type Error = Status;
 
@@ -605,6 +789,44 @@ impl From<HnswConfigDiff> for segment::types::HnswConfig {
             m: hnsw_config.m.unwrap_or_default() as usize,
             ef_construct: hnsw_config.ef_construct.unwrap_or_default() as usize,
             full_scan_threshold: hnsw_config.full_scan_threshold.unwrap_or_default() as usize,
+            on_disk: hnsw_config.on_disk,
+            incremental: hnsw_config.incremental,
+        }
+    }
+}
+
+impl From<ProductQuantizationConfig> for segment::types::ProductQuantizationConfig {
+    fn from(config: ProductQuantizationConfig) -> Self {
+        Self {
+            bucket_size: config.bucket_size as usize,
+            rescore: config.rescore,
+        }
+    }
+}
+
+impl From<segment::types::ProductQuantizationConfig> for ProductQuantizationConfig {
+    fn from(config: segment::types::ProductQuantizationConfig) -> Self {
+        Self {
+            bucket_size: config.bucket_size as u64,
+            rescore: config.rescore,
+        }
+    }
+}
+
+impl From<VectorStorageDatatype> for segment::types::VectorStorageDatatype {
+    fn from(datatype: VectorStorageDatatype) -> Self {
+        match datatype {
+            VectorStorageDatatype::Float32 => Self::Float32,
+            VectorStorageDatatype::Float16 => Self::Float16,
+        }
+    }
+}
+
+impl From<segment::types::VectorStorageDatatype> for VectorStorageDatatype {
+    fn from(datatype: segment::types::VectorStorageDatatype) -> Self {
+        match datatype {
+            segment::types::VectorStorageDatatype::Float32 => Self::Float32,
+            segment::types::VectorStorageDatatype::Float16 => Self::Float16,
         }
     }
 }
@@ -52,6 +52,25 @@ pub struct HnswConfigDiff {
     ///in this case full-scan search should be preferred by query planner and additional indexing is not required.
     #[prost(uint64, optional, tag="3")]
     pub full_scan_threshold: ::core::option::Option<u64>,
+    ///
+    ///Store HNSW index on disk. If set to false, index will be stored in RAM. Default: false
+    #[prost(bool, optional, tag="4")]
+    pub on_disk: ::core::option::Option<bool>,
+    ///
+    ///Build HNSW index of appendable segments incrementally, on each update. If set to false, appendable segments are not indexed until optimization. Default: false
+    #[prost(bool, optional, tag="5")]
+    pub incremental: ::core::option::Option<bool>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ProductQuantizationConfig {
+    ///
+    ///Number of vector dimensions, encoded by a single byte. Larger the value - less RAM is required, less accurate the scores are.
+    #[prost(uint64, tag="1")]
+    pub bucket_size: u64,
+    ///
+    ///Re-score top candidates of the index search with original vectors, stored on disk. Default: true
+    #[prost(bool, optional, tag="2")]
+    pub rescore: ::core::option::Option<bool>,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct WalConfigDiff {
@@ -114,6 +133,10 @@ pub struct OptimizersConfigDiff {
     ///Max number of threads, which can be used for optimization. If 0 - `NUM_CPU - 1` will be used
     #[prost(uint64, optional, tag="9")]
     pub max_optimization_threads: ::core::option::Option<u64>,
+    ///
+    ///Max number of update operations, waiting to be applied to a shard. New updates are rejected while the queue is full. If not set or 0 - the queue is not limited
+    #[prost(uint64, optional, tag="10")]
+    pub max_queued_updates: ::core::option::Option<u64>,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct CreateCollection {
@@ -141,6 +164,12 @@ pub struct CreateCollection {
     /// Wait timeout for operation commit in seconds, if not specified - default value will be supplied
     #[prost(uint64, optional, tag="8")]
     pub timeout: ::core::option::Option<u64>,
+    /// Configuration of the product quantization of large segments
+    #[prost(message, optional, tag="9")]
+    pub product_quantization: ::core::option::Option<ProductQuantizationConfig>,
+    /// Type of stored vector elements, default = Float32
+    #[prost(enumeration="VectorStorageDatatype", optional, tag="10")]
+    pub vector_datatype: ::core::option::Option<i32>,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct UpdateCollection {
@@ -153,6 +182,12 @@ pub struct UpdateCollection {
     /// Wait timeout for operation commit in seconds, if not specified - default value will be supplied
     #[prost(uint64, optional, tag="3")]
     pub timeout: ::core::option::Option<u64>,
+    /// New configuration of vector index
+    #[prost(message, optional, tag="4")]
+    pub hnsw_config: ::core::option::Option<HnswConfigDiff>,
+    /// New configuration of the Write-Ahead-Log, takes effect after restart of the service
+    #[prost(message, optional, tag="5")]
+    pub wal_config: ::core::option::Option<WalConfigDiff>,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct DeleteCollection {
@@ -164,6 +199,83 @@ pub struct DeleteCollection {
     pub timeout: ::core::option::Option<u64>,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PauseOptimizers {
+    /// Name of the collection
+    #[prost(string, tag="1")]
+    pub collection_name: ::prost::alloc::string::String,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ResumeOptimizers {
+    /// Name of the collection
+    #[prost(string, tag="1")]
+    pub collection_name: ::prost::alloc::string::String,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ForceOptimize {
+    /// Name of the collection
+    #[prost(string, tag="1")]
+    pub collection_name: ::prost::alloc::string::String,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct EvaluateRecall {
+    /// Name of the collection
+    #[prost(string, tag="1")]
+    pub collection_name: ::prost::alloc::string::String,
+    /// Max number of points, sampled from each indexed segment to be used as queries
+    #[prost(uint64, tag="2")]
+    pub sample: u64,
+    /// Number of nearest neighbours to compare
+    #[prost(uint64, tag="3")]
+    pub top: u64,
+    /// Size of the beam of the index search. Index default is used if not specified
+    #[prost(uint64, optional, tag="4")]
+    pub hnsw_ef: ::core::option::Option<u64>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SegmentRecall {
+    /// Shard, which holds the segment
+    #[prost(uint32, tag="1")]
+    pub shard_id: u32,
+    /// Id of the segment within the shard
+    #[prost(uint64, tag="2")]
+    pub segment_id: u64,
+    /// Number of sampled points, used as queries
+    #[prost(uint64, tag="3")]
+    pub queries: u64,
+    /// Mean share of the exact nearest neighbours, found by the index search
+    #[prost(double, tag="4")]
+    pub recall: f64,
+    /// Mean time of the index search in seconds
+    #[prost(double, tag="5")]
+    pub mean_search_time: f64,
+    /// Size of the beam, used by the index search
+    #[prost(uint64, tag="6")]
+    pub ef: u64,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RecallReport {
+    /// Quality of each indexed segment
+    #[prost(message, repeated, tag="1")]
+    pub segments: ::prost::alloc::vec::Vec<SegmentRecall>,
+    /// Number of queries in all segments
+    #[prost(uint64, tag="2")]
+    pub queries: u64,
+    /// Mean recall of all queries
+    #[prost(double, tag="3")]
+    pub recall: f64,
+    /// Mean time of the index search of all queries in seconds
+    #[prost(double, tag="4")]
+    pub mean_search_time: f64,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct EvaluateRecallResponse {
+    #[prost(message, optional, tag="1")]
+    pub result: ::core::option::Option<RecallReport>,
+    /// Time spent to process
+    #[prost(double, tag="2")]
+    pub time: f64,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
 pub struct CollectionOperationResponse {
     /// if operation made changes
     #[prost(bool, tag="1")]
@@ -183,6 +295,12 @@ pub struct CollectionParams {
     /// Number of shards in collection
     #[prost(uint32, tag="3")]
     pub shard_number: u32,
+    /// Configuration of the product quantization of large segments
+    #[prost(message, optional, tag="4")]
+    pub product_quantization: ::core::option::Option<ProductQuantizationConfig>,
+    /// Type of stored vector elements
+    #[prost(enumeration="VectorStorageDatatype", tag="5")]
+    pub vector_datatype: i32,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct CollectionConfig {
@@ -289,6 +407,16 @@ pub enum Distance {
     Cosine = 1,
     Euclid = 2,
     Dot = 3,
+    Hamming = 4,
+    Manhattan = 5,
+}
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum VectorStorageDatatype {
+    /// 4 bytes per element
+    Float32 = 0,
+    /// Half precision, 2 bytes per element
+    Float16 = 1,
 }
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
 #[repr(i32)]
@@ -496,6 +624,90 @@ pub mod collections_client {
             );
             self.inner.unary(request.into_request(), path, codec).await
         }
+        ///
+        ///Cancel running optimizations of the collection and do not start new ones until resumed. Pause is not persisted, optimizers run again after restart
+        pub async fn pause_optimizers(
+            &mut self,
+            request: impl tonic::IntoRequest<super::PauseOptimizers>,
+        ) -> Result<tonic::Response<super::CollectionOperationResponse>, tonic::Status> {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/qdrant.Collections/PauseOptimizers",
+            );
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+        ///
+        ///Resume previously paused optimizers of the collection
+        pub async fn resume_optimizers(
+            &mut self,
+            request: impl tonic::IntoRequest<super::ResumeOptimizers>,
+        ) -> Result<tonic::Response<super::CollectionOperationResponse>, tonic::Status> {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/qdrant.Collections/ResumeOptimizers",
+            );
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+        ///
+        ///Merge and index segments of the collection regardless of the configured optimizer thresholds
+        pub async fn force_optimize(
+            &mut self,
+            request: impl tonic::IntoRequest<super::ForceOptimize>,
+        ) -> Result<tonic::Response<super::CollectionOperationResponse>, tonic::Status> {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/qdrant.Collections/ForceOptimize",
+            );
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+        ///
+        ///Compare results of the index search with the exact search for sampled stored points
+        pub async fn evaluate_recall(
+            &mut self,
+            request: impl tonic::IntoRequest<super::EvaluateRecall>,
+        ) -> Result<tonic::Response<super::EvaluateRecallResponse>, tonic::Status> {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/qdrant.Collections/EvaluateRecall",
+            );
+            self.inner.unary(request.into_request(), path, codec).await
+        }
     }
 }
 /// Generated server implementations.
@@ -541,6 +753,30 @@ pub mod collections_server {
             &self,
             request: tonic::Request<super::ChangeAliases>,
         ) -> Result<tonic::Response<super::CollectionOperationResponse>, tonic::Status>;
+        ///
+        ///Cancel running optimizations of the collection and do not start new ones until resumed. Pause is not persisted, optimizers run again after restart
+        async fn pause_optimizers(
+            &self,
+            request: tonic::Request<super::PauseOptimizers>,
+        ) -> Result<tonic::Response<super::CollectionOperationResponse>, tonic::Status>;
+        ///
+        ///Resume previously paused optimizers of the collection
+        async fn resume_optimizers(
+            &self,
+            request: tonic::Request<super::ResumeOptimizers>,
+        ) -> Result<tonic::Response<super::CollectionOperationResponse>, tonic::Status>;
+        ///
+        ///Merge and index segments of the collection regardless of the configured optimizer thresholds
+        async fn force_optimize(
+            &self,
+            request: tonic::Request<super::ForceOptimize>,
+        ) -> Result<tonic::Response<super::CollectionOperationResponse>, tonic::Status>;
+        ///
+        ///Compare results of the index search with the exact search for sampled stored points
+        async fn evaluate_recall(
+            &self,
+            request: tonic::Request<super::EvaluateRecall>,
+        ) -> Result<tonic::Response<super::EvaluateRecallResponse>, tonic::Status>;
     }
     #[derive(Debug)]
     pub struct CollectionsServer<T: Collections> {
@@ -819,95 +1055,247 @@ pub mod collections_server {
                     };
                     Box::pin(fut)
                 }
-                _ => {
-                    Box::pin(async move {
-                        Ok(
-                            http::Response::builder()
-                                .status(200)
-                                .header("grpc-status", "12")
-                                .header("content-type", "application/grpc")
-                                .body(empty_body())
-                                .unwrap(),
-                        )
-                    })
+                "/qdrant.Collections/PauseOptimizers" => {
+                    #[allow(non_camel_case_types)]
+                    struct PauseOptimizersSvc<T: Collections>(pub Arc<T>);
+                    impl<
+                        T: Collections,
+                    > tonic::server::UnaryService<super::PauseOptimizers>
+                    for PauseOptimizersSvc<T> {
+                        type Response = super::CollectionOperationResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::PauseOptimizers>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).pause_optimizers(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = PauseOptimizersSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
                 }
-            }
-        }
-    }
-    impl<T: Collections> Clone for CollectionsServer<T> {
-        fn clone(&self) -> Self {
-            let inner = self.inner.clone();
-            Self {
-                inner,
-                accept_compression_encodings: self.accept_compression_encodings,
-                send_compression_encodings: self.send_compression_encodings,
-            }
-        }
-    }
-    impl<T: Collections> Clone for _Inner<T> {
-        fn clone(&self) -> Self {
-            Self(self.0.clone())
-        }
-    }
-    impl<T: std::fmt::Debug> std::fmt::Debug for _Inner<T> {
-        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-            write!(f, "{:?}", self.0)
-        }
-    }
-    impl<T: Collections> tonic::transport::NamedService for CollectionsServer<T> {
-        const NAME: &'static str = "qdrant.Collections";
-    }
-}
-#[derive(Clone, PartialEq, ::prost::Message)]
-pub struct GetCollectionInfoRequestInternal {
-    #[prost(message, optional, tag="1")]
-    pub get_collection_info_request: ::core::option::Option<GetCollectionInfoRequest>,
-    #[prost(uint32, tag="2")]
-    pub shard_id: u32,
-}
-/// Generated client implementations.
-pub mod collections_internal_client {
-    #![allow(unused_variables, dead_code, missing_docs, clippy::let_unit_value)]
-    use tonic::codegen::*;
-    #[derive(Debug, Clone)]
-    pub struct CollectionsInternalClient<T> {
-        inner: tonic::client::Grpc<T>,
-    }
-    impl CollectionsInternalClient<tonic::transport::Channel> {
-        /// Attempt to create a new client by connecting to a given endpoint.
-        pub async fn connect<D>(dst: D) -> Result<Self, tonic::transport::Error>
-        where
-            D: std::convert::TryInto<tonic::transport::Endpoint>,
-            D::Error: Into<StdError>,
-        {
-            let conn = tonic::transport::Endpoint::new(dst)?.connect().await?;
-            Ok(Self::new(conn))
-        }
-    }
-    impl<T> CollectionsInternalClient<T>
-    where
-        T: tonic::client::GrpcService<tonic::body::BoxBody>,
-        T::Error: Into<StdError>,
-        T::ResponseBody: Body<Data = Bytes> + Send + 'static,
-        <T::ResponseBody as Body>::Error: Into<StdError> + Send,
-    {
-        pub fn new(inner: T) -> Self {
-            let inner = tonic::client::Grpc::new(inner);
-            Self { inner }
-        }
-        pub fn with_interceptor<F>(
-            inner: T,
-            interceptor: F,
-        ) -> CollectionsInternalClient<InterceptedService<T, F>>
-        where
-            F: tonic::service::Interceptor,
-            T::ResponseBody: Default,
-            T: tonic::codegen::Service<
-                http::Request<tonic::body::BoxBody>,
-                Response = http::Response<
-                    <T as tonic::client::GrpcService<tonic::body::BoxBody>>::ResponseBody,
-                >,
-            >,
+                "/qdrant.Collections/ResumeOptimizers" => {
+                    #[allow(non_camel_case_types)]
+                    struct ResumeOptimizersSvc<T: Collections>(pub Arc<T>);
+                    impl<
+                        T: Collections,
+                    > tonic::server::UnaryService<super::ResumeOptimizers>
+                    for ResumeOptimizersSvc<T> {
+                        type Response = super::CollectionOperationResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ResumeOptimizers>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).resume_optimizers(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = ResumeOptimizersSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/qdrant.Collections/ForceOptimize" => {
+                    #[allow(non_camel_case_types)]
+                    struct ForceOptimizeSvc<T: Collections>(pub Arc<T>);
+                    impl<
+                        T: Collections,
+                    > tonic::server::UnaryService<super::ForceOptimize>
+                    for ForceOptimizeSvc<T> {
+                        type Response = super::CollectionOperationResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ForceOptimize>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).force_optimize(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = ForceOptimizeSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/qdrant.Collections/EvaluateRecall" => {
+                    #[allow(non_camel_case_types)]
+                    struct EvaluateRecallSvc<T: Collections>(pub Arc<T>);
+                    impl<
+                        T: Collections,
+                    > tonic::server::UnaryService<super::EvaluateRecall>
+                    for EvaluateRecallSvc<T> {
+                        type Response = super::EvaluateRecallResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::EvaluateRecall>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).evaluate_recall(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = EvaluateRecallSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                _ => {
+                    Box::pin(async move {
+                        Ok(
+                            http::Response::builder()
+                                .status(200)
+                                .header("grpc-status", "12")
+                                .header("content-type", "application/grpc")
+                                .body(empty_body())
+                                .unwrap(),
+                        )
+                    })
+                }
+            }
+        }
+    }
+    impl<T: Collections> Clone for CollectionsServer<T> {
+        fn clone(&self) -> Self {
+            let inner = self.inner.clone();
+            Self {
+                inner,
+                accept_compression_encodings: self.accept_compression_encodings,
+                send_compression_encodings: self.send_compression_encodings,
+            }
+        }
+    }
+    impl<T: Collections> Clone for _Inner<T> {
+        fn clone(&self) -> Self {
+            Self(self.0.clone())
+        }
+    }
+    impl<T: std::fmt::Debug> std::fmt::Debug for _Inner<T> {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "{:?}", self.0)
+        }
+    }
+    impl<T: Collections> tonic::transport::NamedService for CollectionsServer<T> {
+        const NAME: &'static str = "qdrant.Collections";
+    }
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetCollectionInfoRequestInternal {
+    #[prost(message, optional, tag="1")]
+    pub get_collection_info_request: ::core::option::Option<GetCollectionInfoRequest>,
+    #[prost(uint32, tag="2")]
+    pub shard_id: u32,
+}
+/// Generated client implementations.
+pub mod collections_internal_client {
+    #![allow(unused_variables, dead_code, missing_docs, clippy::let_unit_value)]
+    use tonic::codegen::*;
+    #[derive(Debug, Clone)]
+    pub struct CollectionsInternalClient<T> {
+        inner: tonic::client::Grpc<T>,
+    }
+    impl CollectionsInternalClient<tonic::transport::Channel> {
+        /// Attempt to create a new client by connecting to a given endpoint.
+        pub async fn connect<D>(dst: D) -> Result<Self, tonic::transport::Error>
+        where
+            D: std::convert::TryInto<tonic::transport::Endpoint>,
+            D::Error: Into<StdError>,
+        {
+            let conn = tonic::transport::Endpoint::new(dst)?.connect().await?;
+            Ok(Self::new(conn))
+        }
+    }
+    impl<T> CollectionsInternalClient<T>
+    where
+        T: tonic::client::GrpcService<tonic::body::BoxBody>,
+        T::Error: Into<StdError>,
+        T::ResponseBody: Body<Data = Bytes> + Send + 'static,
+        <T::ResponseBody as Body>::Error: Into<StdError> + Send,
+    {
+        pub fn new(inner: T) -> Self {
+            let inner = tonic::client::Grpc::new(inner);
+            Self { inner }
+        }
+        pub fn with_interceptor<F>(
+            inner: T,
+            interceptor: F,
+        ) -> CollectionsInternalClient<InterceptedService<T, F>>
+        where
+            F: tonic::service::Interceptor,
+            T::ResponseBody: Default,
+            T: tonic::codegen::Service<
+                http::Request<tonic::body::BoxBody>,
+                Response = http::Response<
+                    <T as tonic::client::GrpcService<tonic::body::BoxBody>>::ResponseBody,
+                >,
+            >,
             <T as tonic::codegen::Service<
                 http::Request<tonic::body::BoxBody>,
             >>::Error: Into<StdError> + Send + Sync,
@@ -1127,6 +1515,14 @@ pub struct UpsertPoints {
     pub points: ::prost::alloc::vec::Vec<PointStruct>,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
+pub struct UpsertPointsChunk {
+    /// name of the collection, must be the same for all chunks of the stream
+    #[prost(string, tag="1")]
+    pub collection_name: ::prost::alloc::string::String,
+    #[prost(message, repeated, tag="2")]
+    pub points: ::prost::alloc::vec::Vec<PointStruct>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
 pub struct DeletePoints {
     /// name of the collection
     #[prost(string, tag="1")]
@@ -1259,6 +1655,80 @@ pub struct SearchParams {
     ///Larger the value - more accurate the result, more time required for search.
     #[prost(uint64, optional, tag="1")]
     pub hnsw_ef: ::core::option::Option<u64>,
+    ///
+    ///Search without approximation. If set to true, search may run long but with exact results.
+    #[prost(bool, optional, tag="2")]
+    pub exact: ::core::option::Option<bool>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Diversity {
+    /// Trade-off between relevance and diversity, from 0 to 1. 1 - only relevance is considered, 0 - only diversity
+    #[prost(float, tag="1")]
+    pub lambda: f32,
+    /// Number of the most relevant points to select the results from. Should not be less than `top` and must not exceed 1000
+    #[prost(uint64, tag="2")]
+    pub candidates: u64,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ScoreBoost {
+    /// Terms to add to the similarity score
+    #[prost(message, repeated, tag="1")]
+    pub terms: ::prost::alloc::vec::Vec<BoostTerm>,
+    /// Number of the most similar points to rescore. Should not be less than `top`
+    #[prost(uint64, tag="2")]
+    pub candidates: u64,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct BoostTerm {
+    #[prost(oneof="boost_term::Term", tags="1, 2, 3")]
+    pub term: ::core::option::Option<boost_term::Term>,
+}
+/// Nested message and enum types in `BoostTerm`.
+pub mod boost_term {
+    #[derive(Clone, PartialEq, ::prost::Oneof)]
+    pub enum Term {
+        /// `weight * value`
+        #[prost(message, tag="1")]
+        Linear(super::LinearTerm),
+        /// `weight * exp(-|value - target| / scale)`
+        #[prost(message, tag="2")]
+        ExpDecay(super::DecayTerm),
+        /// `weight * exp(-((value - target) / scale)^2)`
+        #[prost(message, tag="3")]
+        GaussDecay(super::DecayTerm),
+    }
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct LinearTerm {
+    /// Payload field with the value
+    #[prost(string, tag="1")]
+    pub key: ::prost::alloc::string::String,
+    #[prost(float, tag="2")]
+    pub weight: f32,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct DecayTerm {
+    /// Payload field with the value
+    #[prost(string, tag="1")]
+    pub key: ::prost::alloc::string::String,
+    /// Distance from the target, at which the term is decreased `e` times. Measured in seconds for datetime fields
+    #[prost(double, tag="4")]
+    pub scale: f64,
+    #[prost(float, tag="5")]
+    pub weight: f32,
+    #[prost(oneof="decay_term::Target", tags="2, 3")]
+    pub target: ::core::option::Option<decay_term::Target>,
+}
+/// Nested message and enum types in `DecayTerm`.
+pub mod decay_term {
+    #[derive(Clone, PartialEq, ::prost::Oneof)]
+    pub enum Target {
+        #[prost(double, tag="2")]
+        Number(f64),
+        /// RFC 3339 datetime in UTC
+        #[prost(string, tag="3")]
+        Datetime(::prost::alloc::string::String),
+    }
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct SearchPoints {
@@ -1286,6 +1756,126 @@ pub struct SearchPoints {
     /// If provided - cut off results with worse scores
     #[prost(float, optional, tag="8")]
     pub score_threshold: ::core::option::Option<f32>,
+    /// Timeout for the request in seconds, if not specified - the request is not limited in time
+    #[prost(uint64, optional, tag="9")]
+    pub timeout: ::core::option::Option<u64>,
+    /// If provided - results are diversified with Maximal Marginal Relevance
+    #[prost(message, optional, tag="10")]
+    pub diversity: ::core::option::Option<Diversity>,
+    /// If provided - scores of the most similar points are adjusted with their payload values. Not supported for Euclid distance. `score_threshold` is compared with the adjusted scores
+    #[prost(message, optional, tag="11")]
+    pub score_boost: ::core::option::Option<ScoreBoost>,
+    /// Number of the best results to skip, e.g. to return the next page of results
+    #[prost(uint64, optional, tag="12")]
+    pub offset: ::core::option::Option<u64>,
+    /// Return the explanation of how each segment was searched
+    #[prost(bool, optional, tag="13")]
+    pub with_explain: ::core::option::Option<bool>,
+    /// Bit-packed binary vector to look for, alternative to `vector` for collections with Hamming distance. Most significant bit of each byte goes first
+    #[prost(bytes="vec", tag="14")]
+    pub binary_vector: ::prost::alloc::vec::Vec<u8>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SearchPointGroups {
+    /// name of the collection
+    #[prost(string, tag="1")]
+    pub collection_name: ::prost::alloc::string::String,
+    /// vector
+    #[prost(float, repeated, tag="2")]
+    pub vector: ::prost::alloc::vec::Vec<f32>,
+    /// Filter conditions - return only those points that satisfy the specified conditions
+    #[prost(message, optional, tag="3")]
+    pub filter: ::core::option::Option<Filter>,
+    /// Payload field to group by, must be a keyword or an integer. Points without this field are not returned
+    #[prost(string, tag="4")]
+    pub group_by: ::prost::alloc::string::String,
+    /// Max number of groups to return
+    #[prost(uint32, tag="5")]
+    pub groups: u32,
+    /// Max number of points in each group, `groups * group_size` must not exceed 10000
+    #[prost(uint32, tag="6")]
+    pub group_size: u32,
+    /// Return point vector with the result.
+    #[prost(bool, optional, tag="7")]
+    pub with_vector: ::core::option::Option<bool>,
+    /// Options for specifying which payload to include or not
+    #[prost(message, optional, tag="8")]
+    pub with_payload: ::core::option::Option<WithPayloadSelector>,
+    /// Search config
+    #[prost(message, optional, tag="9")]
+    pub params: ::core::option::Option<SearchParams>,
+    /// If provided - cut off results with worse scores
+    #[prost(float, optional, tag="10")]
+    pub score_threshold: ::core::option::Option<f32>,
+    /// Timeout for the request in seconds, if not specified - the request is not limited in time
+    #[prost(uint64, optional, tag="11")]
+    pub timeout: ::core::option::Option<u64>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SearchHybridPoints {
+    /// name of the collection
+    #[prost(string, tag="1")]
+    pub collection_name: ::prost::alloc::string::String,
+    /// vector
+    #[prost(float, repeated, tag="2")]
+    pub vector: ::prost::alloc::vec::Vec<f32>,
+    /// Look for sparse vectors with the largest dot product with this
+    #[prost(message, optional, tag="3")]
+    pub sparse_vector: ::core::option::Option<SparseVector>,
+    /// Filter conditions - return only those points that satisfy the specified conditions
+    #[prost(message, optional, tag="4")]
+    pub filter: ::core::option::Option<Filter>,
+    /// Max number of result, each of the searches also looks for this number of points
+    #[prost(uint64, tag="5")]
+    pub top: u64,
+    /// Return point vector with the result.
+    #[prost(bool, optional, tag="6")]
+    pub with_vector: ::core::option::Option<bool>,
+    /// Options for specifying which payload to include or not
+    #[prost(message, optional, tag="7")]
+    pub with_payload: ::core::option::Option<WithPayloadSelector>,
+    /// Search config of the dense search
+    #[prost(message, optional, tag="8")]
+    pub params: ::core::option::Option<SearchParams>,
+    /// How to combine the results of both searches, default = Rrf
+    #[prost(enumeration="Fusion", optional, tag="9")]
+    pub fusion: ::core::option::Option<i32>,
+    /// Weight of the dense scores in WeightedSum fusion, from 0 to 1. Sparse scores get `1 - dense_weight`, default = 0.5
+    #[prost(float, optional, tag="10")]
+    pub dense_weight: ::core::option::Option<f32>,
+    /// Timeout for the request in seconds, if not specified - the request is not limited in time
+    #[prost(uint64, optional, tag="11")]
+    pub timeout: ::core::option::Option<u64>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct FacetPoints {
+    /// name of the collection
+    #[prost(string, tag="1")]
+    pub collection_name: ::prost::alloc::string::String,
+    /// Payload field to count values of, must be indexed as a keyword or an integer
+    #[prost(string, tag="2")]
+    pub key: ::prost::alloc::string::String,
+    /// Filter conditions - count only those points that satisfy the specified conditions
+    #[prost(message, optional, tag="3")]
+    pub filter: ::core::option::Option<Filter>,
+    /// Max number of values to return, default = 10
+    #[prost(uint64, optional, tag="4")]
+    pub limit: ::core::option::Option<u64>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct DistanceMatrixPoints {
+    /// name of the collection
+    #[prost(string, tag="1")]
+    pub collection_name: ::prost::alloc::string::String,
+    /// Filter conditions - sample only those points that satisfy the specified conditions
+    #[prost(message, optional, tag="2")]
+    pub filter: ::core::option::Option<Filter>,
+    /// Number of points to sample, default = 10, max = 1000
+    #[prost(uint64, optional, tag="3")]
+    pub sample: ::core::option::Option<u64>,
+    /// Number of the nearest neighbours to return for each sampled point, default = 3
+    #[prost(uint64, optional, tag="4")]
+    pub limit: ::core::option::Option<u64>,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct ScrollPoints {
@@ -1306,6 +1896,23 @@ pub struct ScrollPoints {
     /// Options for specifying which payload to include or not
     #[prost(message, optional, tag="6")]
     pub with_payload: ::core::option::Option<WithPayloadSelector>,
+    /// Timeout for the request in seconds, if not specified - the request is not limited in time
+    #[prost(uint64, optional, tag="7")]
+    pub timeout: ::core::option::Option<u64>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Vector {
+    #[prost(float, repeated, tag="1")]
+    pub data: ::prost::alloc::vec::Vec<f32>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SparseVector {
+    /// Indices of the non-zero elements, must be unique
+    #[prost(uint32, repeated, tag="1")]
+    pub indices: ::prost::alloc::vec::Vec<u32>,
+    /// Values of the non-zero elements, in the same order as indices
+    #[prost(float, repeated, tag="2")]
+    pub values: ::prost::alloc::vec::Vec<f32>,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct RecommendPoints {
@@ -1336,6 +1943,21 @@ pub struct RecommendPoints {
     /// If provided - cut off results with worse scores
     #[prost(float, optional, tag="9")]
     pub score_threshold: ::core::option::Option<f32>,
+    /// Timeout for the request in seconds, if not specified - the request is not limited in time
+    #[prost(uint64, optional, tag="10")]
+    pub timeout: ::core::option::Option<u64>,
+    /// How to use positive and negative examples, default = AverageVector
+    #[prost(enumeration="RecommendStrategy", optional, tag="11")]
+    pub strategy: ::core::option::Option<i32>,
+    /// Look for vectors closest to those, given explicitly
+    #[prost(message, repeated, tag="12")]
+    pub positive_vectors: ::prost::alloc::vec::Vec<Vector>,
+    /// Try to avoid vectors like this, given explicitly
+    #[prost(message, repeated, tag="13")]
+    pub negative_vectors: ::prost::alloc::vec::Vec<Vector>,
+    /// Number of the best results to skip, e.g. to return the next page of results
+    #[prost(uint64, optional, tag="14")]
+    pub offset: ::core::option::Option<u64>,
 }
 // ---------------------------------------------
 // ---------------- RPC Response ---------------
@@ -1350,6 +1972,18 @@ pub struct PointsOperationResponse {
     pub time: f64,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
+pub struct UpsertStreamResponse {
+    /// Number of upserted points
+    #[prost(uint64, tag="1")]
+    pub points_count: u64,
+    /// Number of update operations, used to write the points
+    #[prost(uint64, tag="2")]
+    pub operations_count: u64,
+    /// Time spent to process
+    #[prost(double, tag="3")]
+    pub time: f64,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
 pub struct UpdateResult {
     /// Number of operation
     #[prost(uint64, tag="1")]
@@ -1359,6 +1993,65 @@ pub struct UpdateResult {
     pub status: i32,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CardinalityExplain {
+    /// Minimal possible number of points, which satisfy the filter
+    #[prost(uint64, tag="1")]
+    pub min: u64,
+    /// Expected number of points, which satisfy the filter
+    #[prost(uint64, tag="2")]
+    pub exp: u64,
+    /// Maximal possible number of points, which satisfy the filter
+    #[prost(uint64, tag="3")]
+    pub max: u64,
+    /// Filters with fewer points are searched with the plain strategy
+    #[prost(uint64, tag="4")]
+    pub indexing_threshold: u64,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SegmentSearchExplain {
+    #[prost(enumeration="SearchStrategy", tag="1")]
+    pub strategy: i32,
+    /// Only present if the strategy was chosen by the filter
+    #[prost(message, optional, tag="2")]
+    pub cardinality: ::core::option::Option<CardinalityExplain>,
+    /// Only present if the cardinality estimation was not enough to choose the strategy
+    #[prost(bool, optional, tag="3")]
+    pub sampled_above_threshold: ::core::option::Option<bool>,
+    /// Number of points, scored during the search
+    #[prost(uint64, tag="4")]
+    pub visited_points: u64,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct FilterExplain {
+    /// Results of the `should` conditions for the point
+    #[prost(bool, repeated, tag="1")]
+    pub should: ::prost::alloc::vec::Vec<bool>,
+    /// Results of the `must` conditions for the point
+    #[prost(bool, repeated, tag="2")]
+    pub must: ::prost::alloc::vec::Vec<bool>,
+    /// Results of the `must_not` conditions for the point
+    #[prost(bool, repeated, tag="3")]
+    pub must_not: ::prost::alloc::vec::Vec<bool>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PointExplain {
+    /// Point id
+    #[prost(message, optional, tag="1")]
+    pub id: ::core::option::Option<PointId>,
+    /// Which conditions of the filter the point satisfies
+    #[prost(message, optional, tag="2")]
+    pub filter: ::core::option::Option<FilterExplain>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SegmentExplain {
+    /// Decisions of the search planner of the segment
+    #[prost(message, optional, tag="1")]
+    pub search: ::core::option::Option<SegmentSearchExplain>,
+    /// Results of the filter conditions for each point, found in the segment. Empty if the search is not filtered
+    #[prost(message, repeated, tag="2")]
+    pub points: ::prost::alloc::vec::Vec<PointExplain>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
 pub struct ScoredPoint {
     /// Point id
     #[prost(message, optional, tag="1")]
@@ -1383,6 +2076,112 @@ pub struct SearchResponse {
     /// Time spent to process
     #[prost(double, tag="2")]
     pub time: f64,
+    /// Explanation of each searched segment, including the segments without found points, if requested
+    #[prost(message, repeated, tag="3")]
+    pub explain: ::prost::alloc::vec::Vec<SegmentExplain>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GroupId {
+    #[prost(oneof="group_id::Kind", tags="1, 2")]
+    pub kind: ::core::option::Option<group_id::Kind>,
+}
+/// Nested message and enum types in `GroupId`.
+pub mod group_id {
+    #[derive(Clone, PartialEq, ::prost::Oneof)]
+    pub enum Kind {
+        /// Keyword value of the grouping field
+        #[prost(string, tag="1")]
+        Keyword(::prost::alloc::string::String),
+        /// Integer value of the grouping field
+        #[prost(int64, tag="2")]
+        Integer(i64),
+    }
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PointGroup {
+    /// Value of the grouping field
+    #[prost(message, optional, tag="1")]
+    pub id: ::core::option::Option<GroupId>,
+    /// Best points of the group, ordered by score
+    #[prost(message, repeated, tag="2")]
+    pub hits: ::prost::alloc::vec::Vec<ScoredPoint>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GroupsResult {
+    /// Groups, ordered by the score of their best point
+    #[prost(message, repeated, tag="1")]
+    pub groups: ::prost::alloc::vec::Vec<PointGroup>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SearchGroupsResponse {
+    #[prost(message, optional, tag="1")]
+    pub result: ::core::option::Option<GroupsResult>,
+    /// Time spent to process
+    #[prost(double, tag="2")]
+    pub time: f64,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct FacetValue {
+    #[prost(oneof="facet_value::Variant", tags="1, 2")]
+    pub variant: ::core::option::Option<facet_value::Variant>,
+}
+/// Nested message and enum types in `FacetValue`.
+pub mod facet_value {
+    #[derive(Clone, PartialEq, ::prost::Oneof)]
+    pub enum Variant {
+        /// Keyword value of the field
+        #[prost(string, tag="1")]
+        Keyword(::prost::alloc::string::String),
+        /// Integer value of the field
+        #[prost(int64, tag="2")]
+        Integer(i64),
+    }
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct FacetHit {
+    /// Value of the field
+    #[prost(message, optional, tag="1")]
+    pub value: ::core::option::Option<FacetValue>,
+    /// Number of points with the value
+    #[prost(uint64, tag="2")]
+    pub count: u64,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct FacetResult {
+    /// Most frequent values, ordered by the number of points
+    #[prost(message, repeated, tag="1")]
+    pub hits: ::prost::alloc::vec::Vec<FacetHit>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct FacetResponse {
+    #[prost(message, optional, tag="1")]
+    pub result: ::core::option::Option<FacetResult>,
+    /// Time spent to process
+    #[prost(double, tag="2")]
+    pub time: f64,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct DistanceMatrix {
+    /// Sampled points
+    #[prost(message, repeated, tag="1")]
+    pub ids: ::prost::alloc::vec::Vec<PointId>,
+    /// Row of each score, index of the sampled point in `ids`
+    #[prost(uint64, repeated, tag="2")]
+    pub rows: ::prost::alloc::vec::Vec<u64>,
+    /// Column of each score, index of the neighbour in `ids`
+    #[prost(uint64, repeated, tag="3")]
+    pub cols: ::prost::alloc::vec::Vec<u64>,
+    /// Scores, from the most similar neighbour to the least similar one within each row
+    #[prost(float, repeated, tag="4")]
+    pub scores: ::prost::alloc::vec::Vec<f32>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct DistanceMatrixResponse {
+    #[prost(message, optional, tag="1")]
+    pub result: ::core::option::Option<DistanceMatrix>,
+    /// Time spent to process
+    #[prost(double, tag="2")]
+    pub time: f64,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct ScrollResponse {
@@ -1581,6 +2380,12 @@ pub struct PointStruct {
     pub vector: ::prost::alloc::vec::Vec<f32>,
     #[prost(map="string, message", tag="3")]
     pub payload: ::std::collections::HashMap<::prost::alloc::string::String, ::prost_types::Value>,
+    /// Sparse vector (optional), used along with the dense one in hybrid search
+    #[prost(message, optional, tag="4")]
+    pub sparse_vector: ::core::option::Option<SparseVector>,
+    /// Bit-packed binary vector, alternative to `vector` for collections with Hamming distance. Most significant bit of each byte goes first
+    #[prost(bytes="vec", tag="5")]
+    pub binary_vector: ::prost::alloc::vec::Vec<u8>,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct GeoPoint {
@@ -1599,6 +2404,22 @@ pub enum FieldType {
 }
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
 #[repr(i32)]
+pub enum RecommendStrategy {
+    /// Average positive and negative vectors and search with `avg_pos + avg_pos - avg_neg`
+    AverageVector = 0,
+    /// Score candidates against each example separately, scores are in range (-1, 1)
+    BestScore = 1,
+}
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum Fusion {
+    /// Reciprocal rank fusion, only ranks of the points are used
+    Rrf = 0,
+    /// Weighted sum of scores, min-max normalized within each result list
+    WeightedSum = 1,
+}
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
 pub enum UpdateStatus {
     UnknownUpdateStatus = 0,
     /// Update is received, but not processed yet
@@ -1606,6 +2427,16 @@ pub enum UpdateStatus {
     /// Update is applied and ready for search
     Completed = 2,
 }
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum SearchStrategy {
+    /// Every point, which satisfies the filter, is scored
+    Plain = 0,
+    /// Points are found with the HNSW graph
+    Graph = 1,
+    /// Points are found with the index of sparse vectors
+    Sparse = 2,
+}
 /// Generated client implementations.
 pub mod points_client {
     #![allow(unused_variables, dead_code, missing_docs, clippy::let_unit_value)]
@@ -1850,6 +2681,90 @@ pub mod points_client {
             self.inner.unary(request.into_request(), path, codec).await
         }
         ///
+        ///Retrieve closest points, grouped by the value of the payload field
+        pub async fn search_groups(
+            &mut self,
+            request: impl tonic::IntoRequest<super::SearchPointGroups>,
+        ) -> Result<tonic::Response<super::SearchGroupsResponse>, tonic::Status> {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/qdrant.Points/SearchGroups",
+            );
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+        ///
+        ///Retrieve points, found by both dense and sparse vectors, with the results of both searches fused into one list
+        pub async fn search_hybrid(
+            &mut self,
+            request: impl tonic::IntoRequest<super::SearchHybridPoints>,
+        ) -> Result<tonic::Response<super::SearchResponse>, tonic::Status> {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/qdrant.Points/SearchHybrid",
+            );
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+        ///
+        ///Count points with each value of the payload field, only points that satisfy the filter are counted
+        pub async fn facet(
+            &mut self,
+            request: impl tonic::IntoRequest<super::FacetPoints>,
+        ) -> Result<tonic::Response<super::FacetResponse>, tonic::Status> {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/qdrant.Points/Facet",
+            );
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+        ///
+        ///Sample random points and find the nearest neighbours of each of them among the sample
+        pub async fn distance_matrix(
+            &mut self,
+            request: impl tonic::IntoRequest<super::DistanceMatrixPoints>,
+        ) -> Result<tonic::Response<super::DistanceMatrixResponse>, tonic::Status> {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/qdrant.Points/DistanceMatrix",
+            );
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+        ///
         ///Iterate over all or filtered points points
         pub async fn scroll(
             &mut self,
@@ -1887,6 +2802,29 @@ pub mod points_client {
             let path = http::uri::PathAndQuery::from_static("/qdrant.Points/Recommend");
             self.inner.unary(request.into_request(), path, codec).await
         }
+        ///
+        ///Insert or update a large amount of points, sent as a stream of chunks. Points are written in batches, the next batch is read only after the previous one is applied.
+        pub async fn upsert_stream(
+            &mut self,
+            request: impl tonic::IntoStreamingRequest<Message = super::UpsertPointsChunk>,
+        ) -> Result<tonic::Response<super::UpsertStreamResponse>, tonic::Status> {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/qdrant.Points/UpsertStream",
+            );
+            self.inner
+                .client_streaming(request.into_streaming_request(), path, codec)
+                .await
+        }
     }
 }
 /// Generated server implementations.
@@ -1951,6 +2889,30 @@ pub mod points_server {
             request: tonic::Request<super::SearchPoints>,
         ) -> Result<tonic::Response<super::SearchResponse>, tonic::Status>;
         ///
+        ///Retrieve closest points, grouped by the value of the payload field
+        async fn search_groups(
+            &self,
+            request: tonic::Request<super::SearchPointGroups>,
+        ) -> Result<tonic::Response<super::SearchGroupsResponse>, tonic::Status>;
+        ///
+        ///Retrieve points, found by both dense and sparse vectors, with the results of both searches fused into one list
+        async fn search_hybrid(
+            &self,
+            request: tonic::Request<super::SearchHybridPoints>,
+        ) -> Result<tonic::Response<super::SearchResponse>, tonic::Status>;
+        ///
+        ///Count points with each value of the payload field, only points that satisfy the filter are counted
+        async fn facet(
+            &self,
+            request: tonic::Request<super::FacetPoints>,
+        ) -> Result<tonic::Response<super::FacetResponse>, tonic::Status>;
+        ///
+        ///Sample random points and find the nearest neighbours of each of them among the sample
+        async fn distance_matrix(
+            &self,
+            request: tonic::Request<super::DistanceMatrixPoints>,
+        ) -> Result<tonic::Response<super::DistanceMatrixResponse>, tonic::Status>;
+        ///
         ///Iterate over all or filtered points points
         async fn scroll(
             &self,
@@ -1962,6 +2924,12 @@ pub mod points_server {
             &self,
             request: tonic::Request<super::RecommendPoints>,
         ) -> Result<tonic::Response<super::RecommendResponse>, tonic::Status>;
+        ///
+        ///Insert or update a large amount of points, sent as a stream of chunks. Points are written in batches, the next batch is read only after the previous one is applied.
+        async fn upsert_stream(
+            &self,
+            request: tonic::Request<tonic::Streaming<super::UpsertPointsChunk>>,
+        ) -> Result<tonic::Response<super::UpsertStreamResponse>, tonic::Status>;
     }
     #[derive(Debug)]
     pub struct PointsServer<T: Points> {
@@ -2199,21 +3167,175 @@ pub mod points_server {
                     struct ClearPayloadSvc<T: Points>(pub Arc<T>);
                     impl<
                         T: Points,
-                    > tonic::server::UnaryService<super::ClearPayloadPoints>
-                    for ClearPayloadSvc<T> {
-                        type Response = super::PointsOperationResponse;
+                    > tonic::server::UnaryService<super::ClearPayloadPoints>
+                    for ClearPayloadSvc<T> {
+                        type Response = super::PointsOperationResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ClearPayloadPoints>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move {
+                                (*inner).clear_payload(request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = ClearPayloadSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/qdrant.Points/CreateFieldIndex" => {
+                    #[allow(non_camel_case_types)]
+                    struct CreateFieldIndexSvc<T: Points>(pub Arc<T>);
+                    impl<
+                        T: Points,
+                    > tonic::server::UnaryService<super::CreateFieldIndexCollection>
+                    for CreateFieldIndexSvc<T> {
+                        type Response = super::PointsOperationResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::CreateFieldIndexCollection>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move {
+                                (*inner).create_field_index(request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = CreateFieldIndexSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/qdrant.Points/DeleteFieldIndex" => {
+                    #[allow(non_camel_case_types)]
+                    struct DeleteFieldIndexSvc<T: Points>(pub Arc<T>);
+                    impl<
+                        T: Points,
+                    > tonic::server::UnaryService<super::DeleteFieldIndexCollection>
+                    for DeleteFieldIndexSvc<T> {
+                        type Response = super::PointsOperationResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::DeleteFieldIndexCollection>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move {
+                                (*inner).delete_field_index(request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = DeleteFieldIndexSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/qdrant.Points/Search" => {
+                    #[allow(non_camel_case_types)]
+                    struct SearchSvc<T: Points>(pub Arc<T>);
+                    impl<T: Points> tonic::server::UnaryService<super::SearchPoints>
+                    for SearchSvc<T> {
+                        type Response = super::SearchResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::SearchPoints>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).search(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = SearchSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/qdrant.Points/SearchGroups" => {
+                    #[allow(non_camel_case_types)]
+                    struct SearchGroupsSvc<T: Points>(pub Arc<T>);
+                    impl<
+                        T: Points,
+                    > tonic::server::UnaryService<super::SearchPointGroups>
+                    for SearchGroupsSvc<T> {
+                        type Response = super::SearchGroupsResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::ClearPayloadPoints>,
+                            request: tonic::Request<super::SearchPointGroups>,
                         ) -> Self::Future {
                             let inner = self.0.clone();
-                            let fut = async move {
-                                (*inner).clear_payload(request).await
-                            };
+                            let fut = async move { (*inner).search_groups(request).await };
                             Box::pin(fut)
                         }
                     }
@@ -2222,7 +3344,7 @@ pub mod points_server {
                     let inner = self.inner.clone();
                     let fut = async move {
                         let inner = inner.0;
-                        let method = ClearPayloadSvc(inner);
+                        let method = SearchGroupsSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -2234,26 +3356,24 @@ pub mod points_server {
                     };
                     Box::pin(fut)
                 }
-                "/qdrant.Points/CreateFieldIndex" => {
+                "/qdrant.Points/SearchHybrid" => {
                     #[allow(non_camel_case_types)]
-                    struct CreateFieldIndexSvc<T: Points>(pub Arc<T>);
+                    struct SearchHybridSvc<T: Points>(pub Arc<T>);
                     impl<
                         T: Points,
-                    > tonic::server::UnaryService<super::CreateFieldIndexCollection>
-                    for CreateFieldIndexSvc<T> {
-                        type Response = super::PointsOperationResponse;
+                    > tonic::server::UnaryService<super::SearchHybridPoints>
+                    for SearchHybridSvc<T> {
+                        type Response = super::SearchResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::CreateFieldIndexCollection>,
+                            request: tonic::Request<super::SearchHybridPoints>,
                         ) -> Self::Future {
                             let inner = self.0.clone();
-                            let fut = async move {
-                                (*inner).create_field_index(request).await
-                            };
+                            let fut = async move { (*inner).search_hybrid(request).await };
                             Box::pin(fut)
                         }
                     }
@@ -2262,7 +3382,7 @@ pub mod points_server {
                     let inner = self.inner.clone();
                     let fut = async move {
                         let inner = inner.0;
-                        let method = CreateFieldIndexSvc(inner);
+                        let method = SearchHybridSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -2274,26 +3394,24 @@ pub mod points_server {
                     };
                     Box::pin(fut)
                 }
-                "/qdrant.Points/DeleteFieldIndex" => {
+                "/qdrant.Points/Facet" => {
                     #[allow(non_camel_case_types)]
-                    struct DeleteFieldIndexSvc<T: Points>(pub Arc<T>);
+                    struct FacetSvc<T: Points>(pub Arc<T>);
                     impl<
                         T: Points,
-                    > tonic::server::UnaryService<super::DeleteFieldIndexCollection>
-                    for DeleteFieldIndexSvc<T> {
-                        type Response = super::PointsOperationResponse;
+                    > tonic::server::UnaryService<super::FacetPoints>
+                    for FacetSvc<T> {
+                        type Response = super::FacetResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::DeleteFieldIndexCollection>,
+                            request: tonic::Request<super::FacetPoints>,
                         ) -> Self::Future {
                             let inner = self.0.clone();
-                            let fut = async move {
-                                (*inner).delete_field_index(request).await
-                            };
+                            let fut = async move { (*inner).facet(request).await };
                             Box::pin(fut)
                         }
                     }
@@ -2302,7 +3420,7 @@ pub mod points_server {
                     let inner = self.inner.clone();
                     let fut = async move {
                         let inner = inner.0;
-                        let method = DeleteFieldIndexSvc(inner);
+                        let method = FacetSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -2314,22 +3432,24 @@ pub mod points_server {
                     };
                     Box::pin(fut)
                 }
-                "/qdrant.Points/Search" => {
+                "/qdrant.Points/DistanceMatrix" => {
                     #[allow(non_camel_case_types)]
-                    struct SearchSvc<T: Points>(pub Arc<T>);
-                    impl<T: Points> tonic::server::UnaryService<super::SearchPoints>
-                    for SearchSvc<T> {
-                        type Response = super::SearchResponse;
+                    struct DistanceMatrixSvc<T: Points>(pub Arc<T>);
+                    impl<
+                        T: Points,
+                    > tonic::server::UnaryService<super::DistanceMatrixPoints>
+                    for DistanceMatrixSvc<T> {
+                        type Response = super::DistanceMatrixResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::SearchPoints>,
+                            request: tonic::Request<super::DistanceMatrixPoints>,
                         ) -> Self::Future {
                             let inner = self.0.clone();
-                            let fut = async move { (*inner).search(request).await };
+                            let fut = async move { (*inner).distance_matrix(request).await };
                             Box::pin(fut)
                         }
                     }
@@ -2338,7 +3458,7 @@ pub mod points_server {
                     let inner = self.inner.clone();
                     let fut = async move {
                         let inner = inner.0;
-                        let method = SearchSvc(inner);
+                        let method = DistanceMatrixSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -2422,6 +3542,44 @@ pub mod points_server {
                     };
                     Box::pin(fut)
                 }
+                "/qdrant.Points/UpsertStream" => {
+                    #[allow(non_camel_case_types)]
+                    struct UpsertStreamSvc<T: Points>(pub Arc<T>);
+                    impl<
+                        T: Points,
+                    > tonic::server::ClientStreamingService<super::UpsertPointsChunk>
+                    for UpsertStreamSvc<T> {
+                        type Response = super::UpsertStreamResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<tonic::Streaming<super::UpsertPointsChunk>>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).upsert_stream(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = UpsertStreamSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            );
+                        let res = grpc.client_streaming(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
                 _ => {
                     Box::pin(async move {
                         Ok(
@@ -2516,6 +3674,19 @@ pub struct SearchPointsInternal {
     pub search_points: ::core::option::Option<SearchPoints>,
     #[prost(uint32, tag="2")]
     pub shard_id: u32,
+    /// If present - used as a query instead of the search vector
+    #[prost(message, optional, tag="3")]
+    pub recommend_query: ::core::option::Option<RecommendQueryInternal>,
+    /// If present - used as a query instead of the search vector
+    #[prost(message, optional, tag="4")]
+    pub sparse_query: ::core::option::Option<SparseVector>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RecommendQueryInternal {
+    #[prost(message, repeated, tag="1")]
+    pub positive: ::prost::alloc::vec::Vec<Vector>,
+    #[prost(message, repeated, tag="2")]
+    pub negative: ::prost::alloc::vec::Vec<Vector>,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct ScrollPointsInternal {
@@ -2538,6 +3709,73 @@ pub struct GetPointsInternal {
     #[prost(uint32, tag="2")]
     pub shard_id: u32,
 }
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct FacetPointsInternal {
+    #[prost(message, optional, tag="1")]
+    pub facet_points: ::core::option::Option<FacetPoints>,
+    #[prost(uint32, tag="2")]
+    pub shard_id: u32,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SamplePointsInternal {
+    #[prost(string, tag="1")]
+    pub collection_name: ::prost::alloc::string::String,
+    /// Sample only points that satisfy the specified conditions
+    #[prost(message, optional, tag="2")]
+    pub filter: ::core::option::Option<Filter>,
+    /// Number of points to sample
+    #[prost(uint64, tag="3")]
+    pub size: u64,
+    #[prost(uint32, tag="4")]
+    pub shard_id: u32,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SamplePointsResponse {
+    #[prost(message, repeated, tag="1")]
+    pub ids: ::prost::alloc::vec::Vec<PointId>,
+    /// Number of points the sample is taken from
+    #[prost(uint64, tag="2")]
+    pub total: u64,
+    /// Time spent to process
+    #[prost(double, tag="3")]
+    pub time: f64,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SampleQuery {
+    #[prost(message, optional, tag="1")]
+    pub id: ::core::option::Option<PointId>,
+    /// Stored vector of the sampled point. If empty - it is read from the shard, which stores the point
+    #[prost(float, repeated, tag="2")]
+    pub vector: ::prost::alloc::vec::Vec<f32>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SampleNeighboursInternal {
+    #[prost(string, tag="1")]
+    pub collection_name: ::prost::alloc::string::String,
+    /// Sampled points, neighbours are searched among them
+    #[prost(message, repeated, tag="2")]
+    pub queries: ::prost::alloc::vec::Vec<SampleQuery>,
+    /// Max number of neighbours of each point
+    #[prost(uint64, tag="3")]
+    pub top: u64,
+    #[prost(uint32, tag="4")]
+    pub shard_id: u32,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SampleNeighbours {
+    /// Neighbours with internal scores
+    #[prost(message, repeated, tag="1")]
+    pub neighbours: ::prost::alloc::vec::Vec<ScoredPoint>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SampleNeighboursResponse {
+    /// Neighbours of each query, in the order of queries
+    #[prost(message, repeated, tag="1")]
+    pub result: ::prost::alloc::vec::Vec<SampleNeighbours>,
+    /// Time spent to process
+    #[prost(double, tag="2")]
+    pub time: f64,
+}
 /// Generated client implementations.
 pub mod points_internal_client {
     #![allow(unused_variables, dead_code, missing_docs, clippy::let_unit_value)]
@@ -2811,6 +4049,63 @@ pub mod points_internal_client {
             );
             self.inner.unary(request.into_request(), path, codec).await
         }
+        pub async fn facet(
+            &mut self,
+            request: impl tonic::IntoRequest<super::FacetPointsInternal>,
+        ) -> Result<tonic::Response<super::FacetResponse>, tonic::Status> {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/qdrant.PointsInternal/Facet",
+            );
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+        pub async fn sample(
+            &mut self,
+            request: impl tonic::IntoRequest<super::SamplePointsInternal>,
+        ) -> Result<tonic::Response<super::SamplePointsResponse>, tonic::Status> {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/qdrant.PointsInternal/Sample",
+            );
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+        pub async fn sample_neighbours(
+            &mut self,
+            request: impl tonic::IntoRequest<super::SampleNeighboursInternal>,
+        ) -> Result<tonic::Response<super::SampleNeighboursResponse>, tonic::Status> {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/qdrant.PointsInternal/SampleNeighbours",
+            );
+            self.inner.unary(request.into_request(), path, codec).await
+        }
     }
 }
 /// Generated server implementations.
@@ -2864,6 +4159,18 @@ pub mod points_internal_server {
             &self,
             request: tonic::Request<super::GetPointsInternal>,
         ) -> Result<tonic::Response<super::GetResponse>, tonic::Status>;
+        async fn facet(
+            &self,
+            request: tonic::Request<super::FacetPointsInternal>,
+        ) -> Result<tonic::Response<super::FacetResponse>, tonic::Status>;
+        async fn sample(
+            &self,
+            request: tonic::Request<super::SamplePointsInternal>,
+        ) -> Result<tonic::Response<super::SamplePointsResponse>, tonic::Status>;
+        async fn sample_neighbours(
+            &self,
+            request: tonic::Request<super::SampleNeighboursInternal>,
+        ) -> Result<tonic::Response<super::SampleNeighboursResponse>, tonic::Status>;
     }
     #[derive(Debug)]
     pub struct PointsInternalServer<T: PointsInternal> {
@@ -3344,6 +4651,120 @@ pub mod points_internal_server {
                     };
                     Box::pin(fut)
                 }
+                "/qdrant.PointsInternal/Facet" => {
+                    #[allow(non_camel_case_types)]
+                    struct FacetSvc<T: PointsInternal>(pub Arc<T>);
+                    impl<
+                        T: PointsInternal,
+                    > tonic::server::UnaryService<super::FacetPointsInternal>
+                    for FacetSvc<T> {
+                        type Response = super::FacetResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::FacetPointsInternal>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).facet(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = FacetSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/qdrant.PointsInternal/Sample" => {
+                    #[allow(non_camel_case_types)]
+                    struct SampleSvc<T: PointsInternal>(pub Arc<T>);
+                    impl<
+                        T: PointsInternal,
+                    > tonic::server::UnaryService<super::SamplePointsInternal>
+                    for SampleSvc<T> {
+                        type Response = super::SamplePointsResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::SamplePointsInternal>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).sample(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = SampleSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/qdrant.PointsInternal/SampleNeighbours" => {
+                    #[allow(non_camel_case_types)]
+                    struct SampleNeighboursSvc<T: PointsInternal>(pub Arc<T>);
+                    impl<
+                        T: PointsInternal,
+                    > tonic::server::UnaryService<super::SampleNeighboursInternal>
+                    for SampleNeighboursSvc<T> {
+                        type Response = super::SampleNeighboursResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::SampleNeighboursInternal>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).sample_neighbours(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = SampleNeighboursSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
                 _ => {
                     Box::pin(async move {
                         Ok(
@@ -3,10 +3,10 @@ use std::sync::Arc;
 use parking_lot::RwLock;
 use tokio::runtime::Handle;
 
-use segment::types::{PointIdType, ScoredPoint, WithPayload};
+use segment::types::{PointIdType, WithPayload};
 
 use crate::collection_manager::holders::segment_holder::SegmentHolder;
-use crate::operations::types::{CollectionResult, Record, SearchRequest};
+use crate::operations::types::{CollectionResult, CoreSearchRequest, Record, SearchExplainResult};
 
 /// Interface for implementing search and retrieve functions
 #[async_trait::async_trait]
@@ -15,9 +15,9 @@ pub trait CollectionSearcher {
         &self,
         segments: &RwLock<SegmentHolder>,
         // Request is supposed to be a read only, that is why no mutex used
-        request: Arc<SearchRequest>,
+        request: Arc<CoreSearchRequest>,
         runtime_handle: &Handle,
-    ) -> CollectionResult<Vec<ScoredPoint>>;
+    ) -> CollectionResult<SearchExplainResult>;
 
     async fn retrieve(
         &self,
@@ -96,7 +96,7 @@ mod tests {
 
         let vectors = vec![vec![2., 2., 2., 2.], vec![2., 0., 2., 0.]];
 
-        let res = upsert_points(&segments, 100, &points, &vectors, &None);
+        let res = upsert_points(&segments, 100, &points, &vectors, &None, &None);
         assert!(matches!(res, Ok(1)));
 
         let records = searcher
@@ -1,8 +1,10 @@
 use std::num::NonZeroU32;
 use std::path::Path;
+use std::sync::Arc;
 
 use parking_lot::RwLock;
 use rand::Rng;
+use rayon::{ThreadPool, ThreadPoolBuilder};
 use serde_json::json;
 
 use segment::entry::entry_point::SegmentEntry;
@@ -104,6 +106,10 @@ pub fn build_test_holder(path: &Path) -> RwLock<SegmentHolder> {
     RwLock::new(holder)
 }
 
+pub(crate) fn indexing_pool() -> Arc<ThreadPool> {
+    Arc::new(ThreadPoolBuilder::new().num_threads(2).build().unwrap())
+}
+
 pub(crate) fn get_merge_optimizer(
     segment_path: &Path,
     collection_temp_dir: &Path,
@@ -115,6 +121,7 @@ pub(crate) fn get_merge_optimizer(
             memmap_threshold: 1000000,
             indexing_threshold: 1000000,
             payload_indexing_threshold: 1000000,
+            indexing_pool: indexing_pool(),
         },
         segment_path.to_owned(),
         collection_temp_dir.to_owned(),
@@ -122,6 +129,8 @@ pub(crate) fn get_merge_optimizer(
             vector_size: 4,
             distance: Distance::Dot,
             shard_number: NonZeroU32::new(1).unwrap(),
+            product_quantization: None,
+            vector_datatype: Default::default(),
         },
         Default::default(),
     )
@@ -136,6 +145,7 @@ pub(crate) fn get_indexing_optimizer(
             memmap_threshold: 100,
             indexing_threshold: 100,
             payload_indexing_threshold: 100,
+            indexing_pool: indexing_pool(),
         },
         segment_path.to_owned(),
         collection_temp_dir.to_owned(),
@@ -143,6 +153,8 @@ pub(crate) fn get_indexing_optimizer(
             vector_size: 4,
             distance: Distance::Dot,
             shard_number: NonZeroU32::new(1).unwrap(),
+            product_quantization: None,
+            vector_datatype: Default::default(),
         },
         Default::default(),
     )
@@ -2,12 +2,13 @@ use crate::collection_manager::holders::segment_holder::LockedSegment;
 use parking_lot::RwLock;
 use segment::entry::entry_point::{OperationResult, SegmentEntry, SegmentFailedState};
 use segment::types::{
-    Condition, Filter, Payload, PayloadKeyType, PayloadKeyTypeRef, PayloadSchemaType, PointIdType,
-    ScoredPoint, SearchParams, SegmentConfig, SegmentInfo, SegmentType, SeqNumberType,
-    VectorElementType, WithPayload,
+    Condition, FacetValue, Filter, Payload, PayloadKeyType, PayloadKeyTypeRef, PayloadSchemaType,
+    PointIdType, QueryVector, ScoredPoint, SearchParams, SegmentConfig, SegmentExplain,
+    SegmentInfo, SegmentType, SeqNumberType, SparseVector, VectorElementType, WithPayload,
 };
 use std::cmp::max;
 use std::collections::{HashMap, HashSet};
+use std::sync::atomic::AtomicBool;
 use std::sync::Arc;
 
 type LockedRmSet = Arc<RwLock<HashSet<PointIdType>>>;
@@ -46,10 +47,14 @@ impl ProxySegment {
     }
 
     fn move_point(&self, op_num: SeqNumberType, point_id: PointIdType) -> OperationResult<bool> {
-        let (vector, payload) = {
+        let (vector, payload, sparse_vector) = {
             let segment_arc = self.wrapped_segment.get();
             let segment = segment_arc.read();
-            (segment.vector(point_id)?, segment.payload(point_id)?)
+            (
+                segment.vector(point_id)?,
+                segment.payload(point_id)?,
+                segment.sparse_vector(point_id)?,
+            )
         };
 
         let mut deleted_points = self.deleted_points.write();
@@ -60,6 +65,9 @@ impl ProxySegment {
 
         write_segment.upsert_point(op_num, point_id, &vector)?;
         write_segment.set_full_payload(op_num, point_id, &payload)?;
+        if let Some(sparse_vector) = sparse_vector {
+            write_segment.set_sparse_vector(op_num, point_id, &sparse_vector)?;
+        }
 
         Ok(true)
     }
@@ -98,6 +106,50 @@ impl ProxySegment {
             }
         }
     }
+
+    /// Remove explanation of the condition, added by `add_deleted_points_condition_to_filter`,
+    /// so that explained conditions match the original filter
+    fn remove_deleted_points_condition_explain(
+        filter: Option<&Filter>,
+        explain: &mut [SegmentExplain],
+    ) {
+        for segment_explain in explain {
+            let filter = match filter {
+                None => {
+                    segment_explain.points.clear();
+                    continue;
+                }
+                Some(filter) => filter,
+            };
+            for point in &mut segment_explain.points {
+                match &mut point.filter.must_not {
+                    Some(must_not) if filter.must_not.is_some() => {
+                        must_not.pop();
+                    }
+                    must_not => *must_not = None,
+                }
+            }
+        }
+    }
+
+    /// Facet of the wrapped segment only, without points, moved into the write segment.
+    /// Write segment is shared by all proxies of the optimization, so it should be counted separately.
+    pub fn wrapped_facet(
+        &self,
+        key: PayloadKeyTypeRef,
+        filter: Option<&Filter>,
+    ) -> OperationResult<HashMap<FacetValue, usize>> {
+        let has_deleted_points = !self.deleted_points.read().is_empty();
+        if has_deleted_points {
+            let wrapped_filter = self.add_deleted_points_condition_to_filter(filter);
+            self.wrapped_segment
+                .get()
+                .read()
+                .facet(key, Some(&wrapped_filter))
+        } else {
+            self.wrapped_segment.get().read().facet(key, filter)
+        }
+    }
 }
 
 impl SegmentEntry for ProxySegment {
@@ -117,57 +169,69 @@ impl SegmentEntry for ProxySegment {
             .or_else(|| self.wrapped_segment.get().read().point_version(point_id))
     }
 
-    fn search(
+    fn search_query(
         &self,
-        vector: &[VectorElementType],
+        query: &QueryVector,
         with_payload: &WithPayload,
         with_vector: bool,
+        with_explain: bool,
         filter: Option<&Filter>,
         top: usize,
         params: Option<&SearchParams>,
-    ) -> OperationResult<Vec<ScoredPoint>> {
+        is_stopped: &AtomicBool,
+    ) -> OperationResult<(Vec<ScoredPoint>, Vec<SegmentExplain>)> {
         let deleted_points = self.deleted_points.read();
 
         // Some point might be deleted after temporary segment creation
         // We need to prevent them from being found by search request
         // That is why we need to pass additional filter for deleted points
         let do_update_filter = !deleted_points.is_empty();
-        let mut wrapped_result = if do_update_filter {
+        let (mut wrapped_result, mut wrapped_explain) = if do_update_filter {
             // ToDo: Come up with better way to pass deleted points into Filter
             // e.g. implement AtomicRefCell for Serializer.
             // This copy might slow process down if there will be a lot of deleted points
             let wrapped_filter = self.add_deleted_points_condition_to_filter(filter);
 
-            self.wrapped_segment.get().read().search(
-                vector,
-                with_payload,
-                with_vector,
-                Some(&wrapped_filter),
-                top,
-                params,
-            )?
+            let (wrapped_result, mut wrapped_explain) =
+                self.wrapped_segment.get().read().search_query(
+                    query,
+                    with_payload,
+                    with_vector,
+                    with_explain,
+                    Some(&wrapped_filter),
+                    top,
+                    params,
+                    is_stopped,
+                )?;
+            Self::remove_deleted_points_condition_explain(filter, &mut wrapped_explain);
+            (wrapped_result, wrapped_explain)
         } else {
-            self.wrapped_segment.get().read().search(
-                vector,
+            self.wrapped_segment.get().read().search_query(
+                query,
                 with_payload,
                 with_vector,
+                with_explain,
                 filter,
                 top,
                 params,
+                is_stopped,
             )?
         };
 
-        let mut write_result = self.write_segment.get().read().search(
-            vector,
+        let (mut write_result, mut write_explain) = self.write_segment.get().read().search_query(
+            query,
             with_payload,
             with_vector,
+            with_explain,
             filter,
             top,
             params,
+            is_stopped,
         )?;
 
         wrapped_result.append(&mut write_result);
-        Ok(wrapped_result)
+        wrapped_explain.append(&mut write_explain);
+        Ok((wrapped_result, wrapped_explain))
     }
 
     fn upsert_point(
@@ -253,6 +317,19 @@ impl SegmentEntry for ProxySegment {
             .clear_payload(op_num, point_id)
     }
 
+    fn set_sparse_vector(
+        &mut self,
+        op_num: SeqNumberType,
+        point_id: PointIdType,
+        sparse_vector: &SparseVector,
+    ) -> OperationResult<bool> {
+        self.move_if_exists(op_num, point_id)?;
+        self.write_segment
+            .get()
+            .write()
+            .set_sparse_vector(op_num, point_id, sparse_vector)
+    }
+
     fn vector(&self, point_id: PointIdType) -> OperationResult<Vec<VectorElementType>> {
         return if self.deleted_points.read().contains(&point_id) {
             self.write_segment.get().read().vector(point_id)
@@ -283,6 +360,21 @@ impl SegmentEntry for ProxySegment {
         };
     }
 
+    fn sparse_vector(&self, point_id: PointIdType) -> OperationResult<Option<SparseVector>> {
+        return if self.deleted_points.read().contains(&point_id) {
+            self.write_segment.get().read().sparse_vector(point_id)
+        } else {
+            {
+                let write_segment = self.write_segment.get();
+                let segment_guard = write_segment.read();
+                if segment_guard.has_point(point_id) {
+                    return segment_guard.sparse_vector(point_id);
+                }
+            }
+            self.wrapped_segment.get().read().sparse_vector(point_id)
+        };
+    }
+
     /// Not implemented for proxy
     fn iter_points(&self) -> Box<dyn Iterator<Item = PointIdType> + '_> {
         // iter_points is not available for Proxy implementation
@@ -295,30 +387,78 @@ impl SegmentEntry for ProxySegment {
         offset: Option<PointIdType>,
         limit: usize,
         filter: Option<&'a Filter>,
+        is_stopped: &AtomicBool,
     ) -> Vec<PointIdType> {
         let deleted_points = self.deleted_points.read();
         let mut read_points = if deleted_points.is_empty() {
             self.wrapped_segment
                 .get()
                 .read()
-                .read_filtered(offset, limit, filter)
+                .read_filtered(offset, limit, filter, is_stopped)
         } else {
             let wrapped_filter = self.add_deleted_points_condition_to_filter(filter);
-            self.wrapped_segment
-                .get()
-                .read()
-                .read_filtered(offset, limit, Some(&wrapped_filter))
+            self.wrapped_segment.get().read().read_filtered(
+                offset,
+                limit,
+                Some(&wrapped_filter),
+                is_stopped,
+            )
         };
         let mut write_segment_points = self
             .write_segment
             .get()
             .read()
-            .read_filtered(offset, limit, filter);
+            .read_filtered(offset, limit, filter, is_stopped);
         read_points.append(&mut write_segment_points);
         read_points.sort_unstable();
         read_points
     }
 
+    fn facet(
+        &self,
+        key: PayloadKeyTypeRef,
+        filter: Option<&Filter>,
+    ) -> OperationResult<HashMap<FacetValue, usize>> {
+        let mut counts = self.wrapped_facet(key, filter)?;
+        for (value, count) in self.write_segment.get().read().facet(key, filter)? {
+            *counts.entry(value).or_insert(0) += count;
+        }
+        Ok(counts)
+    }
+
+    fn sample_neighbours(
+        &self,
+        queries: &[(PointIdType, Vec<VectorElementType>)],
+        points: &[PointIdType],
+        top: usize,
+    ) -> OperationResult<Vec<Vec<ScoredPoint>>> {
+        // Points, moved into the write segment, should not be found in the wrapped one
+        let wrapped_points: Vec<_> = {
+            let deleted_points = self.deleted_points.read();
+            points
+                .iter()
+                .copied()
+                .filter(|point_id| !deleted_points.contains(point_id))
+                .collect()
+        };
+        let mut wrapped_result =
+            self.wrapped_segment
+                .get()
+                .read()
+                .sample_neighbours(queries, &wrapped_points, top)?;
+        let write_result = self
+            .write_segment
+            .get()
+            .read()
+            .sample_neighbours(queries, points, top)?;
+        for (wrapped_neighbours, mut write_neighbours) in
+            wrapped_result.iter_mut().zip(write_result)
+        {
+            wrapped_neighbours.append(&mut write_neighbours);
+        }
+        Ok(wrapped_result)
+    }
+
     fn has_point(&self, point_id: PointIdType) -> bool {
         return if self.deleted_points.read().contains(&point_id) {
             self.write_segment.get().read().has_point(point_id)
@@ -512,6 +652,7 @@ mod tests {
                 None,
                 10,
                 None,
+                &AtomicBool::new(false),
             )
             .unwrap();
 
@@ -541,6 +682,7 @@ mod tests {
 
     #[test]
     fn test_read_filter() {
+        let stopped = AtomicBool::new(false);
         let dir = TempDir::new("segment_dir").unwrap();
         let original_segment = LockedSegment::new(build_segment_1(dir.path()));
 
@@ -549,13 +691,16 @@ mod tests {
             "blue".to_string().into(),
         )));
 
-        let original_points = original_segment.get().read().read_filtered(None, 100, None);
+        let original_points = original_segment
+            .get()
+            .read()
+            .read_filtered(None, 100, None, &stopped);
 
         let original_points_filtered =
             original_segment
                 .get()
                 .read()
-                .read_filtered(None, 100, Some(&filter));
+                .read_filtered(None, 100, Some(&filter), &stopped);
 
         let write_segment = LockedSegment::new(empty_segment(dir.path()));
         let deleted_points = Arc::new(RwLock::new(HashSet::<PointIdType>::new()));
@@ -575,8 +720,8 @@ mod tests {
 
         proxy_segment.delete_point(100, 2.into()).unwrap();
 
-        let proxy_res = proxy_segment.read_filtered(None, 100, None);
-        let proxy_res_filtered = proxy_segment.read_filtered(None, 100, Some(&filter));
+        let proxy_res = proxy_segment.read_filtered(None, 100, None, &stopped);
+        let proxy_res_filtered = proxy_segment.read_filtered(None, 100, Some(&filter), &stopped);
 
         assert_eq!(original_points_filtered.len() - 1, proxy_res_filtered.len());
         assert_eq!(original_points.len() - 1, proxy_res.len());
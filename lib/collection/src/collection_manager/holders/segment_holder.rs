@@ -312,9 +312,17 @@ impl<'s> SegmentHolder {
                     |_appendable_idx, appendable_write_segment| {
                         let vector = write_segment.vector(point_id)?;
                         let payload = write_segment.payload(point_id)?;
+                        let sparse_vector = write_segment.sparse_vector(point_id)?;
 
                         appendable_write_segment.upsert_point(op_num, point_id, &vector)?;
                         appendable_write_segment.set_full_payload(op_num, point_id, &payload)?;
+                        if let Some(sparse_vector) = sparse_vector {
+                            appendable_write_segment.set_sparse_vector(
+                                op_num,
+                                point_id,
+                                &sparse_vector,
+                            )?;
+                        }
 
                         write_segment.delete_point(op_num, point_id)?;
 
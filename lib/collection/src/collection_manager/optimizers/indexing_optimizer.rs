@@ -1,7 +1,7 @@
 use std::collections::HashSet;
 use std::path::{Path, PathBuf};
 
-use segment::types::{HnswConfig, Indexes, PayloadIndexType, SegmentType, StorageType};
+use segment::types::{Distance, HnswConfig, Indexes, PayloadIndexType, SegmentType, StorageType};
 
 use crate::collection_manager::holders::segment_holder::{
     LockedSegment, LockedSegmentHolder, SegmentId,
@@ -64,10 +64,21 @@ impl IndexingOptimizer {
                     return None; // Never optimize already optimized segment
                 }
 
-                // Apply indexing to plain segments which have grown too big
+                // Apply indexing to plain segments which have grown too big.
+                // Incrementally indexed write segments are converted into regular indexed ones as well.
                 let is_vector_indexed = match segment_config.index {
                     Indexes::Plain { .. } => false,
-                    Indexes::Hnsw(_) => true,
+                    Indexes::Hnsw(segment_hnsw_config) => {
+                        !segment_hnsw_config.incremental.unwrap_or(false)
+                    }
+                };
+
+                // Re-build index of segments, which were indexed with different HNSW params
+                let is_index_outdated = match segment_config.index {
+                    Indexes::Plain { .. } => false,
+                    Indexes::Hnsw(segment_hnsw_config) => {
+                        segment_hnsw_config.mismatch_requires_rebuild(&self.hnsw_config)
+                    }
                 };
 
                 let is_payload_indexed = match segment_config.payload_index.unwrap_or_default() {
@@ -77,10 +88,21 @@ impl IndexingOptimizer {
 
                 let is_memmaped = match segment_config.storage_type {
                     StorageType::InMemory => false,
-                    StorageType::Mmap => true,
+                    StorageType::Mmap | StorageType::ProductQuantized(_) => true,
                 };
 
-                let big_for_mmap = vector_count >= self.thresholds_config.memmap_threshold;
+                // Re-build storage of segments, which were quantized with different params
+                let is_quantization_outdated = match segment_config.storage_type {
+                    StorageType::InMemory => false,
+                    StorageType::Mmap => self.collection_params.product_quantization.is_some(),
+                    StorageType::ProductQuantized(quantization_config) => {
+                        self.collection_params.product_quantization != Some(quantization_config)
+                    }
+                };
+
+                // Bit-packed binary vectors are always kept in RAM
+                let big_for_mmap = vector_count >= self.thresholds_config.memmap_threshold
+                    && self.collection_params.distance != Distance::Hamming;
                 let big_for_index = vector_count >= self.thresholds_config.indexing_threshold;
                 let big_for_payload_index =
                     vector_count >= self.thresholds_config.payload_indexing_threshold;
@@ -89,6 +111,8 @@ impl IndexingOptimizer {
 
                 let require_indexing = (big_for_mmap && !is_memmaped)
                     || (big_for_index && !is_vector_indexed)
+                    || is_index_outdated
+                    || is_quantization_outdated
                     || (has_payload && big_for_payload_index && !is_payload_indexed);
 
                 match require_indexing {
@@ -146,13 +170,17 @@ mod tests {
     use serde_json::json;
     use tempdir::TempDir;
 
-    use segment::types::{Payload, PayloadSchemaType, StorageType};
+    use segment::entry::entry_point::SegmentEntry;
+    use segment::types::{
+        Payload, PayloadSchemaType, QueryVector, SparseVector, StorageType, WithPayload,
+    };
 
-    use crate::collection_manager::fixtures::random_segment;
+    use crate::collection_manager::fixtures::{indexing_pool, random_segment};
     use crate::collection_manager::holders::segment_holder::SegmentHolder;
     use crate::collection_manager::segments_updater::{
-        process_field_index_operation, process_point_operation,
+        process_field_index_operation, process_payload_operation, process_point_operation,
     };
+    use crate::operations::payload_ops::{PayloadOps, SetPayload};
     use crate::operations::point_ops::{
         Batch, PointInsertOperations, PointOperations, PointsBatch,
     };
@@ -194,6 +222,7 @@ mod tests {
                 memmap_threshold: 1000,
                 indexing_threshold: 1000,
                 payload_indexing_threshold: 50,
+                indexing_pool: indexing_pool(),
             },
             segments_dir.path().to_owned(),
             segments_temp_dir.path().to_owned(),
@@ -201,6 +230,8 @@ mod tests {
                 vector_size: segment_config.vector_size,
                 distance: segment_config.distance,
                 shard_number: NonZeroU32::new(1).unwrap(),
+                product_quantization: None,
+                vector_datatype: Default::default(),
             },
             Default::default(),
         );
@@ -324,6 +355,7 @@ mod tests {
                         Some(point_payload.clone()),
                         Some(point_payload),
                     ]),
+                    sparse_vectors: None,
                 },
             }));
 
@@ -389,6 +421,7 @@ mod tests {
                         vec![0.0, 1.0, 0.5, 1.0],
                     ],
                     payloads: None,
+                    sparse_vectors: None,
                 },
             }));
 
@@ -399,4 +432,216 @@ mod tests {
         )
         .unwrap();
     }
+
+    #[test]
+    fn test_hnsw_config_change() {
+        init();
+
+        let mut holder = SegmentHolder::default();
+
+        let stopped = AtomicBool::new(false);
+        let dim = 4;
+
+        let segments_dir = TempDir::new("segments_dir").unwrap();
+        let segments_temp_dir = TempDir::new("segments_temp_dir").unwrap();
+        let mut opnum = 101..1000000;
+
+        let segment = random_segment(segments_dir.path(), opnum.next().unwrap(), 200, dim);
+        let segment_config = segment.segment_config.clone();
+        holder.add(segment);
+
+        let mut index_optimizer = IndexingOptimizer::new(
+            OptimizerThresholds {
+                memmap_threshold: 1000,
+                indexing_threshold: 50,
+                payload_indexing_threshold: 1000,
+                indexing_pool: indexing_pool(),
+            },
+            segments_dir.path().to_owned(),
+            segments_temp_dir.path().to_owned(),
+            CollectionParams {
+                vector_size: segment_config.vector_size,
+                distance: segment_config.distance,
+                shard_number: NonZeroU32::new(1).unwrap(),
+                product_quantization: None,
+                vector_datatype: Default::default(),
+            },
+            Default::default(),
+        );
+
+        let locked_holder = Arc::new(RwLock::new(holder));
+        let excluded_ids = Default::default();
+
+        // ---- Build index with default HNSW params
+        let suggested_to_optimize =
+            index_optimizer.check_condition(locked_holder.clone(), &excluded_ids);
+        assert_eq!(suggested_to_optimize.len(), 1);
+        index_optimizer
+            .optimize(locked_holder.clone(), suggested_to_optimize, &stopped)
+            .unwrap();
+
+        let suggested_to_optimize =
+            index_optimizer.check_condition(locked_holder.clone(), &excluded_ids);
+        assert!(suggested_to_optimize.is_empty());
+
+        // ---- Only incremental indexing does not apply to indexed segments
+        index_optimizer.hnsw_config.incremental = Some(true);
+
+        let suggested_to_optimize =
+            index_optimizer.check_condition(locked_holder.clone(), &excluded_ids);
+        assert!(suggested_to_optimize.is_empty());
+
+        // ---- Params, persisted in the index, should trigger re-indexing
+        index_optimizer.hnsw_config.full_scan_threshold += 1;
+        index_optimizer.hnsw_config.on_disk = Some(true);
+
+        let suggested_to_optimize =
+            index_optimizer.check_condition(locked_holder.clone(), &excluded_ids);
+        assert_eq!(suggested_to_optimize.len(), 1);
+        index_optimizer
+            .optimize(locked_holder.clone(), suggested_to_optimize, &stopped)
+            .unwrap();
+
+        let suggested_to_optimize =
+            index_optimizer.check_condition(locked_holder.clone(), &excluded_ids);
+        assert!(suggested_to_optimize.is_empty());
+
+        let hnsw_config = index_optimizer.hnsw_config;
+        let reindexed = locked_holder
+            .read()
+            .iter()
+            .map(|(_sid, segment)| segment.get().read().config())
+            .filter(|config| {
+                matches!(
+                    config.index,
+                    Indexes::Hnsw(segment_hnsw_config)
+                        if segment_hnsw_config.full_scan_threshold == hnsw_config.full_scan_threshold
+                            && segment_hnsw_config.on_disk == Some(true)
+                )
+            })
+            .count();
+        assert_eq!(
+            reindexed, 1,
+            "Testing that full scan threshold and on-disk flag take effect"
+        );
+
+        // ---- Changed graph params should trigger re-indexing
+        index_optimizer.hnsw_config.m = 32;
+
+        let suggested_to_optimize =
+            index_optimizer.check_condition(locked_holder.clone(), &excluded_ids);
+        assert_eq!(suggested_to_optimize.len(), 1);
+        index_optimizer
+            .optimize(locked_holder.clone(), suggested_to_optimize, &stopped)
+            .unwrap();
+
+        let suggested_to_optimize =
+            index_optimizer.check_condition(locked_holder.clone(), &excluded_ids);
+        assert!(suggested_to_optimize.is_empty());
+
+        let reindexed = locked_holder
+            .read()
+            .iter()
+            .map(|(_sid, segment)| segment.get().read().config())
+            .filter(
+                |config| matches!(config.index, Indexes::Hnsw(hnsw_config) if hnsw_config.m == 32),
+            )
+            .count();
+        assert_eq!(
+            reindexed, 1,
+            "Testing that segment is re-indexed with new params"
+        );
+    }
+
+    #[test]
+    fn test_sparse_vector_kept_on_update_of_indexed_point() {
+        init();
+
+        let mut holder = SegmentHolder::default();
+
+        let stopped = AtomicBool::new(false);
+        let dim = 4;
+
+        let segments_dir = TempDir::new("segments_dir").unwrap();
+        let segments_temp_dir = TempDir::new("segments_temp_dir").unwrap();
+        let mut opnum = 101..1000000;
+
+        let point_id = 0.into();
+        let sparse_vector = SparseVector {
+            indices: vec![1, 7],
+            values: vec![1.0, 2.0],
+        };
+        let mut segment = random_segment(segments_dir.path(), opnum.next().unwrap(), 200, dim);
+        let op_num = opnum.next().unwrap();
+        segment
+            .upsert_point(op_num, point_id, &[1.0, 0.0, 0.0, 0.0])
+            .unwrap();
+        segment
+            .set_sparse_vector(op_num, point_id, &sparse_vector)
+            .unwrap();
+        let segment_config = segment.segment_config.clone();
+        holder.add(segment);
+
+        let index_optimizer = IndexingOptimizer::new(
+            OptimizerThresholds {
+                memmap_threshold: 1000,
+                indexing_threshold: 50,
+                payload_indexing_threshold: 1000,
+                indexing_pool: indexing_pool(),
+            },
+            segments_dir.path().to_owned(),
+            segments_temp_dir.path().to_owned(),
+            CollectionParams {
+                vector_size: segment_config.vector_size,
+                distance: segment_config.distance,
+                shard_number: NonZeroU32::new(1).unwrap(),
+                product_quantization: None,
+                vector_datatype: Default::default(),
+            },
+            Default::default(),
+        );
+
+        let locked_holder = Arc::new(RwLock::new(holder));
+        let suggested_to_optimize =
+            index_optimizer.check_condition(locked_holder.clone(), &Default::default());
+        assert_eq!(suggested_to_optimize.len(), 1);
+        index_optimizer
+            .optimize(locked_holder.clone(), suggested_to_optimize, &stopped)
+            .unwrap();
+
+        // Point is moved out of the indexed segment to apply the update
+        process_payload_operation(
+            locked_holder.deref(),
+            opnum.next().unwrap(),
+            PayloadOps::SetPayload(SetPayload {
+                payload: json!({"color": "red"}).into(),
+                points: vec![point_id],
+            }),
+        )
+        .unwrap();
+
+        let found = locked_holder
+            .read()
+            .iter()
+            .flat_map(|(_sid, segment)| {
+                segment
+                    .get()
+                    .read()
+                    .search_query(
+                        &QueryVector::Sparse(sparse_vector.clone()),
+                        &WithPayload::default(),
+                        false,
+                        false,
+                        None,
+                        10,
+                        None,
+                        &stopped,
+                    )
+                    .unwrap()
+                    .0
+            })
+            .map(|point| point.id)
+            .collect_vec();
+        assert_eq!(found, vec![point_id]);
+    }
 }
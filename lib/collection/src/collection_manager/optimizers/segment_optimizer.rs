@@ -5,14 +5,15 @@ use std::sync::Arc;
 
 use itertools::Itertools;
 use parking_lot::{RwLock, RwLockUpgradableReadGuard};
+use rayon::ThreadPool;
 
 use segment::entry::entry_point::SegmentEntry;
 use segment::segment::Segment;
 use segment::segment_constructor::segment_builder::SegmentBuilder;
-use segment::segment_constructor::simple_segment_constructor::build_simple_segment;
+use segment::segment_constructor::simple_segment_constructor::build_appendable_segment;
 use segment::types::{
-    HnswConfig, Indexes, PayloadIndexType, PayloadKeyType, PayloadSchemaType, PointIdType,
-    SegmentConfig, StorageType,
+    Distance, HnswConfig, Indexes, PayloadIndexType, PayloadKeyType, PayloadSchemaType,
+    PointIdType, SegmentConfig, StorageType,
 };
 
 use crate::collection_manager::holders::proxy_segment::ProxySegment;
@@ -27,6 +28,9 @@ pub struct OptimizerThresholds {
     pub memmap_threshold: usize,
     pub indexing_threshold: usize,
     pub payload_indexing_threshold: usize,
+    /// Threads used to build HNSW graphs. Shared by all optimizations of the shard,
+    /// so concurrent optimizations don't multiply the number of threads
+    pub indexing_pool: Arc<ThreadPool>,
 }
 
 /// SegmentOptimizer - trait implementing common functionality of the optimizers
@@ -63,17 +67,12 @@ pub trait SegmentOptimizer {
     /// Build temp segment
     fn temp_segment(&self) -> CollectionResult<LockedSegment> {
         let collection_params = self.collection_params();
-        let config = SegmentConfig {
-            vector_size: collection_params.vector_size,
-            distance: collection_params.distance,
-            index: Indexes::Plain {},
-            payload_index: Some(PayloadIndexType::Plain),
-            storage_type: StorageType::InMemory,
-        };
-        Ok(LockedSegment::new(build_simple_segment(
+        Ok(LockedSegment::new(build_appendable_segment(
             self.collection_path(),
-            config.vector_size,
-            config.distance,
+            collection_params.vector_size,
+            collection_params.distance,
+            &self.hnsw_config(),
+            collection_params.vector_datatype,
         )?))
     }
 
@@ -106,7 +105,11 @@ pub trait SegmentOptimizer {
             vector_size: collection_params.vector_size,
             distance: collection_params.distance,
             index: if is_indexed {
-                Indexes::Hnsw(self.hnsw_config())
+                // Only write segments are indexed incrementally, optimized segments are built once
+                Indexes::Hnsw(HnswConfig {
+                    incremental: None,
+                    ..self.hnsw_config()
+                })
             } else {
                 Indexes::Plain {}
             },
@@ -115,11 +118,16 @@ pub trait SegmentOptimizer {
             } else {
                 PayloadIndexType::Plain
             }),
-            storage_type: if is_on_disk {
-                StorageType::Mmap
-            } else {
-                StorageType::InMemory
+            storage_type: match (is_on_disk, collection_params.product_quantization) {
+                // Bit-packed binary vectors are always kept in RAM
+                _ if collection_params.distance == Distance::Hamming => StorageType::InMemory,
+                (true, Some(quantization_config)) => {
+                    StorageType::ProductQuantized(quantization_config)
+                }
+                (true, None) => StorageType::Mmap,
+                (false, _) => StorageType::InMemory,
             },
+            vector_datatype: collection_params.vector_datatype,
         };
 
         Ok(SegmentBuilder::new(
@@ -251,7 +259,10 @@ pub trait SegmentOptimizer {
                 .insert(field.to_owned(), schema_type.to_owned());
         }
 
-        let mut optimized_segment: Segment = segment_builder.build(stopped)?;
+        let mut optimized_segment: Segment = self
+            .threshold_config()
+            .indexing_pool
+            .install(|| segment_builder.build(stopped))?;
 
         // Delete points in 2 steps
         // First step - delete all points with read lock
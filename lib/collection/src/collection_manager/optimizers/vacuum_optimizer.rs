@@ -114,7 +114,7 @@ impl SegmentOptimizer for VacuumOptimizer {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::collection_manager::fixtures::random_segment;
+    use crate::collection_manager::fixtures::{indexing_pool, random_segment};
     use crate::collection_manager::holders::segment_holder::SegmentHolder;
     use itertools::Itertools;
     use parking_lot::RwLock;
@@ -193,6 +193,7 @@ mod tests {
                 memmap_threshold: 1000000,
                 indexing_threshold: 1000000,
                 payload_indexing_threshold: 1000000,
+                indexing_pool: indexing_pool(),
             },
             dir.path().to_owned(),
             temp_dir.path().to_owned(),
@@ -200,6 +201,8 @@ mod tests {
                 vector_size: 4,
                 distance: Distance::Dot,
                 shard_number: NonZeroU32::new(1).unwrap(),
+                product_quantization: None,
+                vector_datatype: Default::default(),
             },
             Default::default(),
         );
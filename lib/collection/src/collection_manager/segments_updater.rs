@@ -1,10 +1,11 @@
 use std::collections::{HashMap, HashSet};
+use std::sync::atomic::AtomicBool;
 
 use parking_lot::{RwLock, RwLockWriteGuard};
 
 use segment::types::{
     Filter, Payload, PayloadKeyType, PayloadKeyTypeRef, PayloadSchemaType, PointIdType,
-    SeqNumberType, VectorElementType,
+    SeqNumberType, SparseVector, VectorElementType,
 };
 
 use crate::collection_manager::holders::segment_holder::SegmentHolder;
@@ -16,6 +17,7 @@ use crate::operations::types::{CollectionError, CollectionResult, VectorType};
 use crate::operations::FieldIndexOperations;
 use itertools::Itertools;
 use segment::entry::entry_point::{OperationResult, SegmentEntry};
+use segment::index::sparse_vector_index::check_sparse_vector;
 
 /// A collection of functions for updating points and payloads stored in segments
 
@@ -108,9 +110,10 @@ pub(crate) fn clear_payload_by_filter(
     filter: &Filter,
 ) -> CollectionResult<usize> {
     let mut points_to_clear: Vec<PointIdType> = Vec::new();
+    let not_stopped = AtomicBool::new(false);
 
     segments.apply_segments(|s| {
-        let points = s.read_filtered(None, usize::MAX, Some(filter));
+        let points = s.read_filtered(None, usize::MAX, Some(filter), &not_stopped);
         points_to_clear.extend_from_slice(points.as_slice());
         Ok(true)
     })?;
@@ -152,11 +155,19 @@ fn upsert_with_payload(
     point_id: PointIdType,
     vector: &[VectorElementType],
     payload: Option<&Payload>,
+    sparse_vector: Option<&SparseVector>,
 ) -> OperationResult<bool> {
+    // Malformed sparse vector should not leave the point half-updated
+    if let Some(sparse_vector) = sparse_vector {
+        check_sparse_vector(sparse_vector)?;
+    }
     let mut res = segment.upsert_point(op_num, point_id, vector)?;
     if let Some(full_payload) = payload {
         res &= segment.set_payload(op_num, point_id, full_payload)?;
     }
+    if let Some(sparse_vector) = sparse_vector {
+        res &= segment.set_sparse_vector(op_num, point_id, sparse_vector)?;
+    }
     Ok(res)
 }
 
@@ -169,6 +180,7 @@ pub(crate) fn upsert_points(
     ids: &[PointIdType],
     vectors: &[VectorType],
     payloads: &Option<Vec<Option<Payload>>>,
+    sparse_vectors: &Option<Vec<Option<SparseVector>>>,
 ) -> CollectionResult<usize> {
     let vectors_map: HashMap<PointIdType, &VectorType> = ids.iter().cloned().zip(vectors).collect();
     let payloads_map: HashMap<PointIdType, &Payload> = match payloads {
@@ -182,6 +194,14 @@ pub(crate) fn upsert_points(
             })
             .collect(),
     };
+    let sparse_vectors_map: HashMap<PointIdType, &SparseVector> = match sparse_vectors {
+        None => Default::default(),
+        Some(sparse_vectors) => ids
+            .iter()
+            .zip(sparse_vectors)
+            .filter_map(|(id, sparse_vector)| sparse_vector.as_ref().map(|vector| (*id, vector)))
+            .collect(),
+    };
 
     let segments = segments.read();
     // Update points in writable segments
@@ -193,6 +213,7 @@ pub(crate) fn upsert_points(
                 id,
                 vectors_map[&id],
                 payloads_map.get(&id).cloned(),
+                sparse_vectors_map.get(&id).cloned(),
             )
         })?;
 
@@ -220,6 +241,7 @@ pub(crate) fn upsert_points(
                 point_id,
                 vectors_map[&point_id],
                 payloads_map.get(&point_id).cloned(),
+                sparse_vectors_map.get(&point_id).cloned(),
             )? as usize;
         }
     };
@@ -235,29 +257,31 @@ pub(crate) fn process_point_operation(
     match point_operation {
         PointOperations::DeletePoints { ids, .. } => delete_points(&segments.read(), op_num, &ids),
         PointOperations::UpsertPoints(operation) => {
-            let (ids, vectors, payloads) = match operation {
+            let (ids, vectors, payloads, sparse_vectors) = match operation {
                 PointInsertOperations::PointsBatch(PointsBatch {
                     batch:
                         Batch {
                             ids,
                             vectors,
                             payloads,
-                            ..
+                            sparse_vectors,
                         },
-                }) => (ids, vectors, payloads),
+                }) => (ids, vectors, payloads, sparse_vectors),
                 PointInsertOperations::PointsList(PointsList { points }) => {
                     let mut ids = vec![];
                     let mut vectors = vec![];
                     let mut payloads = vec![];
+                    let mut sparse_vectors = vec![];
                     for point in points {
                         ids.push(point.id);
                         vectors.push(point.vector);
-                        payloads.push(point.payload)
+                        payloads.push(point.payload);
+                        sparse_vectors.push(point.sparse_vector);
                     }
-                    (ids, vectors, Some(payloads))
+                    (ids, vectors, Some(payloads), Some(sparse_vectors))
                 }
             };
-            let res = upsert_points(segments, op_num, &ids, &vectors, &payloads)?;
+            let res = upsert_points(segments, op_num, &ids, &vectors, &payloads, &sparse_vectors)?;
             Ok(res)
         }
         PointOperations::DeletePointsByFilter(filter) => {
@@ -1,4 +1,5 @@
 use std::collections::{HashMap, HashSet};
+use std::sync::atomic::AtomicBool;
 use std::sync::Arc;
 
 use futures::future::try_join_all;
@@ -8,12 +9,16 @@ use segment::entry::entry_point::OperationError;
 use tokio::runtime::Handle;
 
 use segment::spaces::tools::peek_top_largest_scores_iterable;
-use segment::types::{PointIdType, ScoredPoint, SeqNumberType, WithPayload, WithPayloadInterface};
+use segment::types::{
+    PointIdType, ScoredPoint, SegmentExplain, SeqNumberType, WithPayload, WithPayloadInterface,
+};
 
 use crate::collection_manager::collection_managers::CollectionSearcher;
 use crate::collection_manager::holders::segment_holder::{LockedSegment, SegmentHolder};
+use crate::common::request_timeout::with_timeout;
 use crate::operations::types::CollectionResult;
-use crate::operations::types::{Record, SearchRequest};
+use crate::operations::types::{CoreSearchRequest, Record, SearchExplainResult};
+use crate::score_boost::ScoreBooster;
 
 /// Simple implementation of segment manager
 ///  - rebuild segment for memory optimization purposes
@@ -24,16 +29,17 @@ impl SimpleCollectionSearcher {
     pub fn new() -> Self {
         Self {}
     }
-}
 
-#[async_trait::async_trait]
-impl CollectionSearcher for SimpleCollectionSearcher {
-    async fn search(
+    /// Same as `search`, but searches could also be interrupted by raising `is_stopped` flag.
+    /// Segments, which notice the flag, return the best points found so far.
+    /// The flag is also raised on timeout of the request.
+    pub async fn search_with_stop(
         &self,
         segments: &RwLock<SegmentHolder>,
-        request: Arc<SearchRequest>,
+        request: Arc<CoreSearchRequest>,
         runtime_handle: &Handle,
-    ) -> CollectionResult<Vec<ScoredPoint>> {
+        is_stopped: Arc<AtomicBool>,
+    ) -> CollectionResult<SearchExplainResult> {
         // Using { } block to ensure segments variable is dropped in the end of it
         // and is not transferred across the all_searches.await? boundary as it
         // does not impl Send trait
@@ -43,18 +49,21 @@ impl CollectionSearcher for SimpleCollectionSearcher {
             let some_segment = segments.iter().next();
 
             if some_segment.is_none() {
-                return Ok(vec![]);
+                return Ok(SearchExplainResult::default());
             }
 
             segments
                 .iter()
-                .map(|(_id, segment)| search_in_segment(segment.clone(), request.clone()))
+                .map(|(_id, segment)| {
+                    search_in_segment(segment.clone(), request.clone(), is_stopped.clone())
+                })
                 .map(|f| runtime_handle.spawn(f))
                 .collect()
         };
 
         let all_searches = try_join_all(searches);
-        let all_search_results = all_searches.await?;
+        let all_search_results =
+            with_timeout(all_searches, request.timeout, &is_stopped, "Search").await??;
 
         match all_search_results
             .iter()
@@ -65,12 +74,17 @@ impl CollectionSearcher for SimpleCollectionSearcher {
             Some(error) => return Err(error),
         }
 
+        let (all_points, all_explain): (Vec<_>, Vec<_>) = all_search_results
+            .into_iter()
+            .map(Result::unwrap) // already checked for errors
+            .unzip();
+
         let mut seen_idx: HashSet<PointIdType> = HashSet::new();
 
         let top_scores = peek_top_largest_scores_iterable(
-            all_search_results
+            all_points
                 .into_iter()
-                .flat_map(Result::unwrap) // already checked for errors
+                .flatten()
                 .sorted_by_key(|a| (a.id, 1 - a.version as i64)) // Prefer higher version first
                 .dedup_by(|a, b| a.id == b.id) // Keep only highest version
                 .filter(|scored| {
@@ -81,7 +95,25 @@ impl CollectionSearcher for SimpleCollectionSearcher {
             request.top,
         );
 
-        Ok(top_scores)
+        Ok(SearchExplainResult {
+            points: top_scores,
+            segments: all_explain.into_iter().flatten().collect(),
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl CollectionSearcher for SimpleCollectionSearcher {
+    async fn search(
+        &self,
+        segments: &RwLock<SegmentHolder>,
+        request: Arc<CoreSearchRequest>,
+        runtime_handle: &Handle,
+    ) -> CollectionResult<SearchExplainResult> {
+        // Raised on timeout to interrupt searches, which are still running
+        let is_stopped = Arc::new(AtomicBool::new(false));
+        self.search_with_stop(segments, request, runtime_handle, is_stopped)
+            .await
     }
 
     async fn retrieve(
@@ -130,8 +162,9 @@ impl CollectionSearcher for SimpleCollectionSearcher {
 
 async fn search_in_segment(
     segment: LockedSegment,
-    request: Arc<SearchRequest>,
-) -> CollectionResult<Vec<ScoredPoint>> {
+    request: Arc<CoreSearchRequest>,
+    is_stopped: Arc<AtomicBool>,
+) -> CollectionResult<(Vec<ScoredPoint>, Vec<SegmentExplain>)> {
     let with_payload_interface = request
         .with_payload
         .as_ref()
@@ -139,16 +172,54 @@ async fn search_in_segment(
     let with_payload = WithPayload::from(with_payload_interface);
     let with_vector = request.with_vector;
 
-    let res = segment.get().read().search(
-        &request.vector,
-        &with_payload,
+    // Boosted scores are only computed for the oversampled set of the most similar points,
+    // the best of them are selected after merging the results of all segments
+    let booster = request
+        .score_boost
+        .as_ref()
+        .map(ScoreBooster::new)
+        .transpose()?;
+    let top = request
+        .score_boost
+        .as_ref()
+        .map_or(request.top, |score_boost| score_boost.candidates);
+
+    // Boosting needs the whole payload of each candidate, it is loaded once by the search
+    let search_with_payload = match booster {
+        Some(_) => WithPayload {
+            enable: true,
+            payload_selector: None,
+        },
+        None => with_payload.clone(),
+    };
+
+    let segment = segment.get();
+    let segment = segment.read();
+    let (mut res, explain) = segment.search_query(
+        &request.query,
+        &search_with_payload,
         with_vector,
+        request.with_explain,
         request.filter.as_ref(),
-        request.top,
+        top,
         request.params.as_ref(),
+        &is_stopped,
     )?;
 
-    Ok(res)
+    if let Some(booster) = booster {
+        for point in &mut res {
+            let payload = point.payload.take().unwrap_or_default();
+            point.score += booster.boost(&payload);
+            if with_payload.enable {
+                point.payload = Some(match &with_payload.payload_selector {
+                    Some(selector) => selector.process(payload),
+                    None => payload,
+                });
+            }
+        }
+    }
+
+    Ok((res, explain))
 }
 
 #[cfg(test)]
@@ -156,6 +227,7 @@ mod tests {
     use tempdir::TempDir;
 
     use crate::collection_manager::fixtures::build_test_holder;
+    use crate::operations::types::SearchRequest;
 
     use super::*;
 
@@ -169,20 +241,29 @@ mod tests {
 
         let query = vec![1.0, 1.0, 1.0, 1.0];
 
-        let req = Arc::new(SearchRequest {
-            vector: query,
-            with_payload: None,
-            with_vector: false,
-            filter: None,
-            params: None,
-            top: 5,
-            score_threshold: None,
-        });
+        let req: Arc<CoreSearchRequest> = Arc::new(
+            SearchRequest {
+                vector: query,
+                with_payload: None,
+                with_vector: false,
+                filter: None,
+                params: None,
+                top: 5,
+                offset: 0,
+                score_threshold: None,
+                timeout: None,
+                diversity: None,
+                score_boost: None,
+                with_explain: false,
+            }
+            .into(),
+        );
 
         let result = searcher
             .search(&segment_holder, req, &Handle::current())
             .await
-            .unwrap();
+            .unwrap()
+            .points;
 
         // eprintln!("result = {:?}", &result);
 
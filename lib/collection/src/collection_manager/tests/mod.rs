@@ -10,6 +10,7 @@ use segment::entry::entry_point::SegmentEntry;
 use segment::types::{PayloadKeyType, PayloadSchemaType, PointIdType};
 use std::collections::{HashMap, HashSet};
 use std::path::Path;
+use std::sync::atomic::AtomicBool;
 use std::sync::Arc;
 use tempdir::TempDir;
 
@@ -58,13 +59,19 @@ fn test_update_proxy_segments() {
 
     for i in 1..10 {
         let ids = vec![(100 * i + 1).into(), (100 * i + 2).into()];
-        upsert_points(&segments, 1000 + i, &ids, &vectors, &None).unwrap();
+        upsert_points(&segments, 1000 + i, &ids, &vectors, &None, &None).unwrap();
     }
 
+    let stopped = AtomicBool::new(false);
     let all_ids = segments
         .read()
         .iter()
-        .flat_map(|(_id, segment)| segment.get().read().read_filtered(None, 100, None))
+        .flat_map(|(_id, segment)| {
+            segment
+                .get()
+                .read()
+                .read_filtered(None, 100, None, &stopped)
+        })
         .sorted()
         .collect_vec();
 
@@ -91,10 +98,26 @@ fn test_move_points_to_copy_on_write() {
     let proxy_id = wrap_proxy(segments.clone(), sid1, dir.path());
 
     let vectors = vec![vec![0.0, 0.0, 0.0, 0.0], vec![0.0, 0.0, 0.0, 0.0]];
-    upsert_points(&segments, 1001, &[1.into(), 2.into()], &vectors, &None).unwrap();
+    upsert_points(
+        &segments,
+        1001,
+        &[1.into(), 2.into()],
+        &vectors,
+        &None,
+        &None,
+    )
+    .unwrap();
 
     let vectors = vec![vec![0.0, 0.0, 0.0, 0.0], vec![0.0, 0.0, 0.0, 0.0]];
-    upsert_points(&segments, 1002, &[2.into(), 3.into()], &vectors, &None).unwrap();
+    upsert_points(
+        &segments,
+        1002,
+        &[2.into(), 3.into()],
+        &vectors,
+        &None,
+        &None,
+    )
+    .unwrap();
 
     let segments_write = segments.write();
 
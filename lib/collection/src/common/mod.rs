@@ -1 +1,2 @@
+pub mod request_timeout;
 pub mod stoppable_task;
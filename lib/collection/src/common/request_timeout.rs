@@ -0,0 +1,56 @@
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+use crate::operations::types::{CollectionError, CollectionResult};
+
+/// Awaits `future`, limiting the waiting time with `timeout` seconds, if specified.
+///
+/// On timeout `is_stopped` flag is raised, so that blocking work, spawned by the future,
+/// could be interrupted, and `CollectionError::Timeout` is returned.
+pub async fn with_timeout<F, T>(
+    future: F,
+    timeout: Option<u64>,
+    is_stopped: &AtomicBool,
+    operation: &str,
+) -> CollectionResult<T>
+where
+    F: Future<Output = T>,
+{
+    match timeout {
+        None => Ok(future.await),
+        Some(timeout_sec) => {
+            match tokio::time::timeout(Duration::from_secs(timeout_sec), future).await {
+                Ok(res) => Ok(res),
+                Err(_elapsed) => {
+                    is_stopped.store(true, Ordering::Relaxed);
+                    Err(CollectionError::timeout(timeout_sec, operation))
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_with_timeout() {
+        let is_stopped = AtomicBool::new(false);
+
+        let res = with_timeout(async { 42 }, Some(1), &is_stopped, "test").await;
+        assert_eq!(res.unwrap(), 42);
+        assert!(!is_stopped.load(Ordering::Relaxed));
+
+        let res = with_timeout(
+            tokio::time::sleep(Duration::from_secs(10)),
+            Some(0),
+            &is_stopped,
+            "test",
+        )
+        .await;
+        assert!(matches!(res, Err(CollectionError::Timeout { .. })));
+        assert!(is_stopped.load(Ordering::Relaxed));
+    }
+}
@@ -9,7 +9,7 @@ use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use wal::WalOptions;
 
-use segment::types::{Distance, HnswConfig};
+use segment::types::{Distance, HnswConfig, ProductQuantizationConfig, VectorStorageDatatype};
 
 use crate::operations::types::{CollectionError, CollectionResult};
 use crate::optimizers_builder::OptimizersConfig;
@@ -52,6 +52,13 @@ pub struct CollectionParams {
     /// Number of shards the collection has
     #[serde(default = "default_shard_number")]
     pub shard_number: NonZeroU32,
+    /// Store vectors of segments, larger than `memmap_threshold`, as product quantization codes.
+    /// If none - vectors are stored in memmap file without quantization.
+    #[serde(default)]
+    pub product_quantization: Option<ProductQuantizationConfig>,
+    /// Type of stored vector elements
+    #[serde(default)]
+    pub vector_datatype: VectorStorageDatatype,
 }
 
 fn default_shard_number() -> NonZeroU32 {
@@ -0,0 +1,167 @@
+use std::collections::HashMap;
+
+use itertools::Itertools;
+use rand::Rng;
+use segment::spaces::tools::peek_top_largest_scores_iterable;
+use segment::types::{Distance, PointIdType, ScoredPoint};
+
+use crate::operations::types::{DistanceMatrix, PointsSample};
+
+/// Merge uniform samples of the shards into a single uniform sample of `size` points.
+///
+/// Each point of the shard sample stands for `total / ids.len()` points of the shard,
+/// so points are selected with weighted random sampling without replacement (Efraimidis-Spirakis).
+pub fn merge_samples<R: Rng + ?Sized>(
+    samples: Vec<PointsSample>,
+    size: usize,
+    rng: &mut R,
+) -> PointsSample {
+    let total = samples.iter().map(|sample| sample.total).sum();
+    let ids = samples
+        .into_iter()
+        .flat_map(|sample| {
+            let weight = sample.total as f64 / sample.ids.len().max(1) as f64;
+            sample.ids.into_iter().map(move |id| (id, weight))
+        })
+        .map(|(id, weight)| (rng.gen::<f64>().powf(1.0 / weight), id))
+        // Keys are in [0, 1] range, so they are always comparable
+        .sorted_by(|(key_a, _), (key_b, _)| key_b.partial_cmp(key_a).unwrap())
+        .take(size)
+        .map(|(_, id)| id)
+        .collect();
+    PointsSample { ids, total }
+}
+
+/// Select `top` neighbours with the largest scores.
+/// If the same point is found in several segments, only its newest version is used.
+pub fn merge_neighbours(
+    neighbours: impl IntoIterator<Item = Vec<ScoredPoint>>,
+    top: usize,
+) -> Vec<ScoredPoint> {
+    let mut newest: HashMap<PointIdType, ScoredPoint> = HashMap::new();
+    for point in neighbours.into_iter().flatten() {
+        match newest.get(&point.id) {
+            Some(existing) if existing.version >= point.version => {}
+            _ => {
+                newest.insert(point.id, point);
+            }
+        }
+    }
+    peek_top_largest_scores_iterable(newest.into_values(), top)
+}
+
+/// Build a sparse matrix of scores from the neighbours of each sampled point.
+/// `neighbours[i]` are the neighbours of `ids[i]` with internal scores, which are post-processed here.
+pub fn build_matrix(
+    ids: Vec<PointIdType>,
+    neighbours: Vec<Vec<ScoredPoint>>,
+    distance: Distance,
+) -> DistanceMatrix {
+    let positions: HashMap<PointIdType, usize> = ids
+        .iter()
+        .enumerate()
+        .map(|(position, &id)| (id, position))
+        .collect();
+    let mut rows = vec![];
+    let mut cols = vec![];
+    let mut scores = vec![];
+    for (row, row_neighbours) in neighbours.into_iter().enumerate() {
+        for neighbour in row_neighbours {
+            // Neighbours might be deleted from the sample after they were found
+            if let Some(&col) = positions.get(&neighbour.id) {
+                rows.push(row);
+                cols.push(col);
+                scores.push(distance.postprocess_score(neighbour.score));
+            }
+        }
+    }
+    DistanceMatrix {
+        ids,
+        rows,
+        cols,
+        scores,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+    use segment::types::ScoreType;
+
+    use super::*;
+
+    fn point(id: u64, version: u64, score: ScoreType) -> ScoredPoint {
+        ScoredPoint {
+            id: id.into(),
+            version,
+            score,
+            payload: None,
+            vector: None,
+        }
+    }
+
+    #[test]
+    fn test_merge_samples() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let samples = vec![
+            PointsSample {
+                ids: (0..10).map(|id: u64| id.into()).collect(),
+                total: 1000,
+            },
+            PointsSample {
+                ids: (100..103).map(|id: u64| id.into()).collect(),
+                total: 3,
+            },
+            PointsSample {
+                ids: vec![],
+                total: 0,
+            },
+        ];
+
+        let merged = merge_samples(samples.clone(), 5, &mut rng);
+        assert_eq!(merged.total, 1003);
+        assert_eq!(merged.ids.len(), 5);
+        assert_eq!(merged.ids.iter().unique().count(), 5);
+
+        let merged = merge_samples(samples, 20, &mut rng);
+        assert_eq!(merged.ids.len(), 13);
+    }
+
+    #[test]
+    fn test_merge_neighbours() {
+        let merged = merge_neighbours(
+            vec![
+                vec![point(1, 1, 0.9), point(2, 1, 0.5)],
+                vec![point(1, 2, 0.3), point(3, 1, 0.7), point(4, 1, 0.1)],
+            ],
+            2,
+        );
+        // Newest version of the point 1 has a lower score
+        let ids: Vec<_> = merged.iter().map(|point| point.id).collect();
+        assert_eq!(ids, vec![3.into(), 2.into()]);
+    }
+
+    #[test]
+    fn test_build_matrix() {
+        let ids = vec![10.into(), 20.into(), 30.into()];
+        let neighbours = vec![
+            vec![point(30, 0, -1.0), point(20, 0, -2.0)],
+            vec![point(10, 0, -2.0), point(40, 0, -0.5)],
+            vec![],
+        ];
+
+        let matrix = build_matrix(ids.clone(), neighbours, Distance::Euclid);
+        assert_eq!(matrix.ids, ids);
+        assert_eq!(matrix.rows, vec![0, 0, 1]);
+        assert_eq!(matrix.cols, vec![2, 1, 0]);
+        assert_eq!(
+            matrix.scores,
+            vec![
+                Distance::Euclid.postprocess_score(-1.0),
+                Distance::Euclid.postprocess_score(-2.0),
+                Distance::Euclid.postprocess_score(-2.0),
+            ]
+        );
+    }
+}
@@ -0,0 +1,150 @@
+use std::collections::HashMap;
+
+use segment::types::{PointIdType, ScoreType, ScoredPoint};
+
+use crate::operations::types::Diversity;
+
+/// Rerank candidates with Maximal Marginal Relevance.
+///
+/// Both `relevance` and `similarities` are internal scores of the distance function:
+/// `relevance[i]` is the score of the `i`-th candidate to the query, `similarities[i]` are the
+/// scores of the `i`-th candidate to other candidates. Pairs without a score are not penalized.
+pub fn mmr_rerank(
+    candidates: Vec<ScoredPoint>,
+    relevance: &[ScoreType],
+    similarities: &[Vec<ScoredPoint>],
+    diversity: Diversity,
+    top: usize,
+) -> Vec<ScoredPoint> {
+    let positions: HashMap<PointIdType, usize> = candidates
+        .iter()
+        .enumerate()
+        .map(|(idx, point)| (point.id, idx))
+        .collect();
+    let similarity: Vec<HashMap<usize, ScoreType>> = similarities
+        .iter()
+        .map(|neighbours| {
+            neighbours
+                .iter()
+                .filter_map(|point| positions.get(&point.id).map(|&idx| (idx, point.score)))
+                .collect()
+        })
+        .collect();
+
+    // Similarity of each candidate to the closest of already selected points
+    let mut max_similarity: Vec<Option<ScoreType>> = vec![None; candidates.len()];
+    let mut remaining: Vec<usize> = (0..candidates.len()).collect();
+    let mut selected: Vec<usize> = Vec::with_capacity(top);
+
+    while selected.len() < top && !remaining.is_empty() {
+        let mmr_score = |idx: usize| match max_similarity[idx] {
+            None => relevance[idx],
+            Some(max_similarity) => {
+                diversity.lambda * relevance[idx] - (1.0 - diversity.lambda) * max_similarity
+            }
+        };
+
+        // On equal scores the first, more relevant, candidate wins
+        let mut best_pos = 0;
+        for pos in 1..remaining.len() {
+            if mmr_score(remaining[pos]) > mmr_score(remaining[best_pos]) {
+                best_pos = pos;
+            }
+        }
+        let best = remaining.remove(best_pos);
+        selected.push(best);
+
+        for &idx in &remaining {
+            if let Some(&score) = similarity[best].get(&idx) {
+                max_similarity[idx] = Some(max_similarity[idx].map_or(score, |max| max.max(score)));
+            }
+        }
+    }
+
+    let mut candidates: Vec<_> = candidates.into_iter().map(Some).collect();
+    selected
+        .into_iter()
+        .map(|idx| candidates[idx].take().unwrap())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use segment::types::Distance;
+
+    fn point(id: u64, score: ScoreType) -> ScoredPoint {
+        ScoredPoint {
+            id: id.into(),
+            version: 0,
+            score,
+            payload: None,
+            vector: None,
+        }
+    }
+
+    #[test]
+    fn test_mmr_rerank() {
+        let query = vec![1.0, 0.0];
+        let vectors = vec![
+            vec![1.0, 0.0],
+            vec![0.99, 0.1],
+            vec![0.7, 0.7],
+            vec![0.0, 1.0],
+        ];
+        let relevance: Vec<_> = vectors
+            .iter()
+            .map(|vector| Distance::Dot.similarity(&query, vector))
+            .collect();
+        let candidates: Vec<_> = relevance
+            .iter()
+            .enumerate()
+            .map(|(idx, &score)| point(idx as u64 + 1, score))
+            .collect();
+        let similarities: Vec<Vec<_>> = vectors
+            .iter()
+            .enumerate()
+            .map(|(idx, vector)| {
+                vectors
+                    .iter()
+                    .enumerate()
+                    .filter(|&(other_idx, _)| other_idx != idx)
+                    .map(|(other_idx, other)| {
+                        point(
+                            other_idx as u64 + 1,
+                            Distance::Dot.similarity(vector, other),
+                        )
+                    })
+                    .collect()
+            })
+            .collect();
+
+        // Only relevance matters
+        let result = mmr_rerank(
+            candidates.clone(),
+            &relevance,
+            &similarities,
+            Diversity {
+                lambda: 1.0,
+                candidates: 4,
+            },
+            3,
+        );
+        let ids: Vec<_> = result.iter().map(|point| point.id).collect();
+        assert_eq!(ids, vec![1.into(), 2.into(), 3.into()]);
+
+        // Near-duplicate of the best point is pushed down
+        let result = mmr_rerank(
+            candidates,
+            &relevance,
+            &similarities,
+            Diversity {
+                lambda: 0.3,
+                candidates: 4,
+            },
+            3,
+        );
+        let ids: Vec<_> = result.iter().map(|point| point.id).collect();
+        assert_eq!(ids, vec![1.into(), 4.into(), 3.into()]);
+    }
+}
@@ -0,0 +1,141 @@
+use std::collections::HashMap;
+
+use segment::types::{Order, PointIdType, ScoreType, ScoredPoint};
+
+/// Constant of the reciprocal rank fusion, reduces the impact of the top ranks
+pub const RRF_K: ScoreType = 60.0;
+
+/// Sum contributions of each point over all lists and select `top` points with the largest sums.
+/// Payload and vector of the point are taken from the first list it is found in.
+fn fuse(lists: Vec<Vec<(ScoredPoint, ScoreType)>>, top: usize) -> Vec<ScoredPoint> {
+    let mut fused: Vec<ScoredPoint> = Vec::new();
+    let mut positions: HashMap<PointIdType, usize> = HashMap::new();
+    for (point, contribution) in lists.into_iter().flatten() {
+        match positions.get(&point.id) {
+            Some(&position) => fused[position].score += contribution,
+            None => {
+                positions.insert(point.id, fused.len());
+                fused.push(ScoredPoint {
+                    score: contribution,
+                    ..point
+                });
+            }
+        }
+    }
+    // Stable sort keeps points with equal scores in the order of the lists
+    fused.sort_by(|a, b| b.cmp(a));
+    fused.truncate(top);
+    fused
+}
+
+/// Combine ranked lists with reciprocal rank fusion: each point gets `1 / (RRF_K + rank)`
+/// from each list it is found in, ranks start from 1.
+pub fn reciprocal_rank_fusion(lists: Vec<Vec<ScoredPoint>>, top: usize) -> Vec<ScoredPoint> {
+    let contributions = lists
+        .into_iter()
+        .map(|list| {
+            list.into_iter()
+                .enumerate()
+                .map(|(rank, point)| (point, 1.0 / (RRF_K + (rank + 1) as ScoreType)))
+                .collect()
+        })
+        .collect();
+    fuse(contributions, top)
+}
+
+/// Combine scored lists with a weighted sum of scores.
+/// Scores of each list are min-max normalized into [0, 1] range, so that 1 is the best score
+/// of the list regardless of its `Order`.
+pub fn weighted_score_fusion(
+    lists: Vec<(Vec<ScoredPoint>, Order, ScoreType)>,
+    top: usize,
+) -> Vec<ScoredPoint> {
+    let contributions = lists
+        .into_iter()
+        .map(|(list, order, weight)| {
+            let min = list
+                .iter()
+                .map(|point| point.score)
+                .fold(ScoreType::INFINITY, ScoreType::min);
+            let max = list
+                .iter()
+                .map(|point| point.score)
+                .fold(ScoreType::NEG_INFINITY, ScoreType::max);
+            let range = max - min;
+            list.into_iter()
+                .map(|point| {
+                    let normalized = if range > 0.0 {
+                        match order {
+                            Order::LargeBetter => (point.score - min) / range,
+                            Order::SmallBetter => (max - point.score) / range,
+                        }
+                    } else {
+                        1.0
+                    };
+                    (point, weight * normalized)
+                })
+                .collect()
+        })
+        .collect();
+    fuse(contributions, top)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn points(scored: &[(u64, ScoreType)]) -> Vec<ScoredPoint> {
+        scored
+            .iter()
+            .map(|&(id, score)| ScoredPoint {
+                id: id.into(),
+                version: 0,
+                score,
+                payload: None,
+                vector: None,
+            })
+            .collect()
+    }
+
+    fn ids(points: &[ScoredPoint]) -> Vec<PointIdType> {
+        points.iter().map(|point| point.id).collect()
+    }
+
+    #[test]
+    fn test_reciprocal_rank_fusion() {
+        let dense = points(&[(1, 0.9), (2, 0.8), (3, 0.7)]);
+        let sparse = points(&[(3, 12.0), (4, 10.0), (1, 1.0)]);
+
+        let result = reciprocal_rank_fusion(vec![dense, sparse], 3);
+        // 1: 1/61 + 1/63, 3: 1/63 + 1/61, 2: 1/62, 4: 1/62
+        assert_eq!(ids(&result), vec![1.into(), 3.into(), 2.into()]);
+        assert!((result[0].score - (1.0 / 61.0 + 1.0 / 63.0)).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_weighted_score_fusion() {
+        // Euclid distances, smaller is better
+        let dense = points(&[(1, 0.0), (2, 1.0), (3, 2.0)]);
+        let sparse = points(&[(3, 10.0), (4, 5.0), (2, 0.0)]);
+
+        let result = weighted_score_fusion(
+            vec![
+                (dense.clone(), Order::SmallBetter, 0.5),
+                (sparse.clone(), Order::LargeBetter, 0.5),
+            ],
+            4,
+        );
+        // 1: 0.5, 2: 0.25, 3: 0.5, 4: 0.25
+        assert_eq!(ids(&result), vec![1.into(), 3.into(), 2.into(), 4.into()]);
+        assert_eq!(result[0].score, 0.5);
+
+        let result = weighted_score_fusion(
+            vec![
+                (dense, Order::SmallBetter, 0.2),
+                (sparse, Order::LargeBetter, 0.8),
+            ],
+            2,
+        );
+        assert_eq!(ids(&result), vec![3.into(), 4.into()]);
+    }
+}
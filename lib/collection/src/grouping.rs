@@ -0,0 +1,141 @@
+use std::collections::HashMap;
+
+use itertools::Itertools;
+use segment::types::{PayloadKeyType, ScoredPoint};
+use serde_json::Value;
+
+use crate::operations::types::{GroupId, PointGroup};
+
+impl GroupId {
+    /// Extract group ids from the value of the payload field.
+    /// Only keywords and integers could be used for grouping, other values are ignored.
+    fn from_value(value: &Value) -> Vec<GroupId> {
+        match value {
+            Value::String(keyword) => vec![GroupId::Keyword(keyword.clone())],
+            Value::Number(number) => number.as_i64().map(GroupId::Integer).into_iter().collect(),
+            Value::Array(values) => values
+                .iter()
+                .filter_map(|value| match value {
+                    Value::String(keyword) => Some(GroupId::Keyword(keyword.clone())),
+                    Value::Number(number) => number.as_i64().map(GroupId::Integer),
+                    _ => None,
+                })
+                .collect(),
+            _ => vec![],
+        }
+    }
+}
+
+/// Distributes search results into groups by the value of the payload field.
+/// Points are expected to come in the order of score, so the first points of each group are the best ones
+/// and groups are ordered by the score of their first point.
+pub struct GroupsAggregator {
+    group_by: PayloadKeyType,
+    max_groups: usize,
+    group_size: usize,
+    groups: Vec<PointGroup>,
+    group_positions: HashMap<GroupId, usize>,
+}
+
+impl GroupsAggregator {
+    pub fn new(group_by: PayloadKeyType, max_groups: usize, group_size: usize) -> Self {
+        GroupsAggregator {
+            group_by,
+            max_groups,
+            group_size,
+            groups: Vec::with_capacity(max_groups),
+            group_positions: HashMap::new(),
+        }
+    }
+
+    /// Add point to each group it belongs to.
+    /// New groups are only created until `max_groups` is reached, the rest of the points are ignored.
+    pub fn add_point(&mut self, point: &ScoredPoint) {
+        let group_ids = match point
+            .payload
+            .as_ref()
+            .and_then(|payload| payload.get_value(&self.group_by))
+        {
+            None => return,
+            Some(value) => GroupId::from_value(value),
+        };
+
+        for group_id in group_ids.into_iter().unique() {
+            match self.group_positions.get(&group_id) {
+                Some(&position) => {
+                    let group = &mut self.groups[position];
+                    if group.hits.len() < self.group_size {
+                        group.hits.push(point.clone());
+                    }
+                }
+                None => {
+                    if self.groups.len() < self.max_groups {
+                        self.group_positions
+                            .insert(group_id.clone(), self.groups.len());
+                        self.groups.push(PointGroup {
+                            id: group_id,
+                            hits: vec![point.clone()],
+                        });
+                    }
+                }
+            }
+        }
+    }
+
+    /// Whether all requested groups are found and filled, so further search would not change the result
+    pub fn is_full(&self) -> bool {
+        self.groups.len() == self.max_groups
+            && self
+                .groups
+                .iter()
+                .all(|group| group.hits.len() == self.group_size)
+    }
+
+    pub fn into_groups(self) -> Vec<PointGroup> {
+        self.groups
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use segment::types::Payload;
+    use serde_json::json;
+
+    fn point(id: u64, score: f32, payload: Value) -> ScoredPoint {
+        let payload: Payload = serde_json::from_value(payload).unwrap();
+        ScoredPoint {
+            id: id.into(),
+            version: 0,
+            score,
+            payload: Some(payload),
+            vector: None,
+        }
+    }
+
+    #[test]
+    fn test_groups_aggregation() {
+        let mut aggregator = GroupsAggregator::new("product".to_string(), 2, 2);
+
+        aggregator.add_point(&point(1, 0.9, json!({"product": "a"})));
+        aggregator.add_point(&point(2, 0.8, json!({"product": "a"})));
+        aggregator.add_point(&point(3, 0.7, json!({"product": "a"})));
+        aggregator.add_point(&point(4, 0.6, json!({"color": "red"})));
+        aggregator.add_point(&point(5, 0.5, json!({"product": 10})));
+        assert!(!aggregator.is_full());
+
+        aggregator.add_point(&point(6, 0.4, json!({"product": ["c", 10]})));
+        assert!(aggregator.is_full());
+
+        let groups = aggregator.into_groups();
+        assert_eq!(groups.len(), 2);
+
+        assert_eq!(groups[0].id, GroupId::Keyword("a".to_string()));
+        let ids: Vec<_> = groups[0].hits.iter().map(|hit| hit.id).collect();
+        assert_eq!(ids, vec![1.into(), 2.into()]);
+
+        assert_eq!(groups[1].id, GroupId::Integer(10));
+        let ids: Vec<_> = groups[1].hits.iter().map(|hit| hit.id).collect();
+        assert_eq!(ids, vec![5.into(), 6.into()]);
+    }
+}
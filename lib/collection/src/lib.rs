@@ -7,10 +7,16 @@ use std::{
     io,
     path::{Path, PathBuf},
     sync::Arc,
+    time::SystemTime,
 };
 
+use crate::distance_matrix::{build_matrix, merge_neighbours, merge_samples};
+use crate::diversity::mmr_rerank;
+use crate::fusion::{reciprocal_rank_fusion, weighted_score_fusion};
+use crate::grouping::GroupsAggregator;
 use crate::operations::types::PointRequest;
 use crate::operations::OperationToShard;
+use crate::score_boost::check_score_boost;
 use crate::shard::ShardOperation;
 use collection_manager::collection_managers::CollectionSearcher;
 use config::CollectionConfig;
@@ -19,20 +25,26 @@ use futures::{stream::futures_unordered::FuturesUnordered, StreamExt};
 use hashring::HashRing;
 use itertools::Itertools;
 use operations::{
-    config_diff::OptimizersConfigDiff,
+    config_diff::{DiffConfig, HnswConfigDiff, OptimizersConfigDiff, WalConfigDiff},
     types::{
-        CollectionError, CollectionInfo, CollectionResult, RecommendRequest, Record, ScrollRequest,
-        ScrollResult, SearchRequest, UpdateResult,
+        CollectionError, CollectionInfo, CollectionResult, CoreSearchRequest, DistanceMatrix,
+        DistanceMatrixRequest, FacetHit, FacetRequest, FacetResult, Fusion, GroupsResult,
+        HybridSearchRequest, PointsSample, RecallReport, RecallRequest, RecommendExample,
+        RecommendRequest, RecommendStrategy, Record, SampleNeighboursRequest, ScrollRequest,
+        ScrollResult, SearchExplainResult, SearchGroupsRequest, SearchRequest, UpdateResult,
     },
     CollectionUpdateOperations, SplitByShard, Validate,
 };
-use optimizers_builder::OptimizersConfig;
+use optimizers_builder::build_indexing_pool;
+use parking_lot::RwLock;
+use rayon::ThreadPool;
 use segment::spaces::tools::peek_top_smallest_scores_iterable;
 use segment::types::Order;
 use segment::{
     spaces::tools::peek_top_largest_scores_iterable,
     types::{
-        Condition, ExtendedPointId, Filter, HasIdCondition, ScoredPoint, VectorElementType,
+        Condition, ExtendedPointId, Filter, HasIdCondition, IsEmptyCondition, PayloadField,
+        PayloadSchemaType, QueryVector, RecommendQuery, ScoredPoint, VectorElementType,
         WithPayload, WithPayloadInterface,
     },
 };
@@ -43,8 +55,13 @@ use tokio::runtime::Handle;
 pub mod collection_manager;
 mod common;
 pub mod config;
+mod distance_matrix;
+mod diversity;
+mod fusion;
+mod grouping;
 pub mod operations;
 pub mod optimizers_builder;
+mod score_boost;
 pub mod shard;
 mod update_handler;
 mod wal;
@@ -54,6 +71,23 @@ mod tests;
 
 pub type CollectionId = String;
 
+/// How many times the search for groups is repeated with a wider `top`, if groups are not filled
+const MAX_GROUPS_SEARCH_ITERATIONS: usize = 4;
+/// How much `top` grows on each iteration of the search for groups
+const GROUPS_SEARCH_WIDENING_FACTOR: usize = 4;
+/// Max `top` of the search for groups, including widened ones
+const MAX_GROUPS_SEARCH_TOP: usize = 10_000;
+/// Max number of candidates of the search with diversity, all pairs of them are scored
+const MAX_DIVERSITY_CANDIDATES: usize = 1_000;
+/// Number of values returned by the facet, if the limit is not specified
+const DEFAULT_FACET_LIMIT: usize = 10;
+/// Number of sampled points of the distance matrix, if not specified
+const DEFAULT_MATRIX_SAMPLE: usize = 10;
+/// Max number of sampled points, all pairs of them are scored
+const MAX_MATRIX_SAMPLE: usize = 1_000;
+/// Number of neighbours of each sampled point in the distance matrix, if not specified
+const DEFAULT_MATRIX_LIMIT: usize = 3;
+
 pub type PeerId = u64;
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
@@ -77,9 +111,15 @@ impl State {
         config: CollectionConfig,
         collection: &mut Collection,
     ) -> CollectionResult<()> {
-        log::warn!("Applying only optimizers config snapshot. Other config updates are not yet implemented.");
+        let current_params = collection.config.read().params.clone();
+        if config.params != current_params {
+            log::warn!("Collection params update is not yet implemented. Applying only optimizers, HNSW and WAL config snapshot.");
+        }
         collection
-            .update_optimizer_params(config.optimizer_config)
+            .update_config(CollectionConfig {
+                params: current_params,
+                ..config
+            })
             .await
     }
 
@@ -107,7 +147,10 @@ impl State {
 pub struct Collection {
     shards: HashMap<ShardId, Shard>,
     ring: HashRing<ShardId>,
-    config: CollectionConfig,
+    config: RwLock<CollectionConfig>,
+    /// Thread pool for building HNSW graphs, shared by optimizers of all local shards
+    indexing_pool: RwLock<Arc<ThreadPool>>,
+    path: PathBuf,
     /// Tracks whether `before_drop` fn has been called.
     before_drop_called: bool,
 }
@@ -119,6 +162,7 @@ impl Collection {
         config: &CollectionConfig,
     ) -> Result<Self, CollectionError> {
         config.save(path)?;
+        let indexing_pool = build_indexing_pool(&config.optimizer_config)?;
         let mut ring = HashRing::new();
         let mut shards: HashMap<ShardId, Shard> = HashMap::new();
         for shard_id in 0..config.params.shard_number.get() {
@@ -127,7 +171,15 @@ impl Collection {
                 .map_err(|err| CollectionError::ServiceError {
                     error: format!("Can't create shard {shard_id} directory. Error: {}", err),
                 })
-                .and_then(|()| LocalShard::build(shard_id, id.clone(), &shard_path, config));
+                .and_then(|()| {
+                    LocalShard::build(
+                        shard_id,
+                        id.clone(),
+                        &shard_path,
+                        config,
+                        indexing_pool.clone(),
+                    )
+                });
             let shard = match shard {
                 Ok(shard) => shard,
                 Err(err) => {
@@ -145,7 +197,9 @@ impl Collection {
         Ok(Self {
             shards,
             ring,
-            config: config.clone(),
+            config: RwLock::new(config.clone()),
+            indexing_pool: RwLock::new(indexing_pool),
+            path: path.to_owned(),
             before_drop_called: false,
         })
     }
@@ -158,6 +212,8 @@ impl Collection {
                 path.to_str().unwrap()
             )
         });
+        let indexing_pool = build_indexing_pool(&config.optimizer_config)
+            .unwrap_or_else(|err| panic!("Can't create indexing thread pool, error: {}", err));
         let mut ring = HashRing::new();
         let mut shards = HashMap::new();
 
@@ -168,14 +224,25 @@ impl Collection {
             let shard_path = shard_path(path, shard_id);
             shards.insert(
                 shard_id,
-                Shard::Local(LocalShard::load(shard_id, id.clone(), &shard_path, &config).await),
+                Shard::Local(
+                    LocalShard::load(
+                        shard_id,
+                        id.clone(),
+                        &shard_path,
+                        &config,
+                        indexing_pool.clone(),
+                    )
+                    .await,
+                ),
             );
             ring.add(shard_id);
         }
         Self {
             shards,
             ring,
-            config,
+            config: RwLock::new(config),
+            indexing_pool: RwLock::new(indexing_pool),
+            path: path.to_owned(),
             before_drop_called: false,
         }
     }
@@ -299,32 +366,57 @@ impl Collection {
     ) -> CollectionResult<Vec<ScoredPoint>> {
         if request.positive.is_empty() {
             return Err(CollectionError::BadRequest {
-                description: "At least one positive vector ID required".to_owned(),
+                description: "At least one positive example required".to_owned(),
             });
         }
 
+        let (vector_size, distance) = {
+            let config = self.config.read();
+            (config.params.vector_size, config.params.distance)
+        };
+
+        for example in request.positive.iter().chain(&request.negative) {
+            if let RecommendExample::Vector(vector) = example {
+                if vector.len() != vector_size {
+                    return Err(CollectionError::BadInput {
+                        description: format!(
+                            "Wrong dimension of the example vector: expected {}, got {}",
+                            vector_size,
+                            vector.len()
+                        ),
+                    });
+                }
+            }
+        }
+
         let reference_vectors_ids = request
             .positive
             .iter()
             .chain(&request.negative)
-            .cloned()
+            .filter_map(|example| match example {
+                RecommendExample::PointId(point_id) => Some(*point_id),
+                RecommendExample::Vector(_) => None,
+            })
             .collect_vec();
 
-        let vectors = self
-            .retrieve(
-                PointRequest {
-                    ids: reference_vectors_ids.clone(),
-                    with_payload: Some(WithPayloadInterface::Bool(true)),
-                    with_vector: true,
-                },
-                segment_searcher,
-                shard_selection,
-            )
-            .await?;
-        let vectors_map: HashMap<ExtendedPointId, Vec<VectorElementType>> = vectors
-            .into_iter()
-            .map(|rec| (rec.id, rec.vector.unwrap()))
-            .collect();
+        let vectors_map: HashMap<ExtendedPointId, Vec<VectorElementType>> =
+            if reference_vectors_ids.is_empty() {
+                HashMap::new()
+            } else {
+                self.retrieve(
+                    PointRequest {
+                        ids: reference_vectors_ids.clone(),
+                        with_payload: Some(WithPayloadInterface::Bool(true)),
+                        with_vector: true,
+                    },
+                    segment_searcher,
+                    shard_selection,
+                )
+                .await?
+                .into_iter()
+                .map(|rec| (rec.id, rec.vector.unwrap()))
+                .collect()
+            };
 
         for &point_id in &reference_vectors_ids {
             if !vectors_map.contains_key(&point_id) {
@@ -334,33 +426,42 @@ impl Collection {
             }
         }
 
-        let avg_positive = avg_vectors(
-            request
-                .positive
-                .iter()
-                .map(|vid| vectors_map.get(vid).unwrap()),
-        );
+        // Stored vectors are already preprocessed, so the explicit examples should be too
+        let example_vector = |example: &RecommendExample| match example {
+            RecommendExample::PointId(point_id) => vectors_map[point_id].clone(),
+            RecommendExample::Vector(vector) => distance
+                .preprocess_vector(vector)
+                .unwrap_or_else(|| vector.clone()),
+        };
+        let positive_vectors = request.positive.iter().map(example_vector).collect_vec();
+        let negative_vectors = request.negative.iter().map(example_vector).collect_vec();
 
-        let search_vector = if request.negative.is_empty() {
-            avg_positive
-        } else {
-            let avg_negative = avg_vectors(
-                request
-                    .negative
-                    .iter()
-                    .map(|vid| vectors_map.get(vid).unwrap()),
-            );
+        let query = match request.strategy {
+            RecommendStrategy::AverageVector => {
+                let avg_positive = avg_vectors(positive_vectors.iter());
 
-            avg_positive
-                .iter()
-                .cloned()
-                .zip(avg_negative.iter().cloned())
-                .map(|(pos, neg)| pos + pos - neg)
-                .collect()
+                let search_vector = if negative_vectors.is_empty() {
+                    avg_positive
+                } else {
+                    let avg_negative = avg_vectors(negative_vectors.iter());
+
+                    avg_positive
+                        .iter()
+                        .cloned()
+                        .zip(avg_negative.iter().cloned())
+                        .map(|(pos, neg)| pos + pos - neg)
+                        .collect()
+                };
+                QueryVector::Nearest(search_vector)
+            }
+            RecommendStrategy::BestScore => QueryVector::Recommend(RecommendQuery {
+                positive: positive_vectors,
+                negative: negative_vectors,
+            }),
         };
 
-        let search_request = SearchRequest {
-            vector: search_vector,
+        let search_request = CoreSearchRequest {
+            query,
             filter: Some(Filter {
                 should: None,
                 must: request
@@ -373,18 +474,23 @@ impl Collection {
             }),
             with_payload: request.with_payload.clone(),
             with_vector: request.with_vector,
+            with_explain: false,
             params: request.params,
             top: request.top,
             score_threshold: request.score_threshold,
+            timeout: request.timeout,
+            score_boost: None,
         };
 
-        self.search(
+        self.core_search_page(
             search_request,
+            request.offset,
             segment_searcher,
             search_runtime_handle,
             shard_selection,
         )
         .await
+        .map(|result| result.points)
     }
 
     pub async fn search(
@@ -394,21 +500,483 @@ impl Collection {
         search_runtime_handle: &Handle,
         shard_selection: Option<ShardId>,
     ) -> CollectionResult<Vec<ScoredPoint>> {
+        self.search_with_explain(
+            request,
+            segment_searcher,
+            search_runtime_handle,
+            shard_selection,
+        )
+        .await
+        .map(|result| result.points)
+    }
+
+    /// Same as `search`, but the found points are returned alongside the explanation of how each
+    /// segment was searched, if it is requested with `with_explain`
+    pub async fn search_with_explain(
+        &self,
+        mut request: SearchRequest,
+        segment_searcher: &(dyn CollectionSearcher + Sync),
+        search_runtime_handle: &Handle,
+        shard_selection: Option<ShardId>,
+    ) -> CollectionResult<SearchExplainResult> {
+        let offset = request.offset;
+        let page_end = page_end(offset, request.top)?;
+
+        if let Some(score_boost) = &mut request.score_boost {
+            if request.diversity.is_some() {
+                return Err(CollectionError::BadRequest {
+                    description: "Score boosting can't be combined with diversity".to_owned(),
+                });
+            }
+            let distance = self.config.read().params.distance;
+            check_score_boost(score_boost, distance, page_end, SystemTime::now())?;
+        }
+
+        let diversity = match request.diversity {
+            None => {
+                return self
+                    .core_search_page(
+                        request.into(),
+                        offset,
+                        segment_searcher,
+                        search_runtime_handle,
+                        shard_selection,
+                    )
+                    .await
+            }
+            Some(diversity) => diversity,
+        };
+
+        if !(0.0..=1.0).contains(&diversity.lambda) {
+            return Err(CollectionError::BadRequest {
+                description: "Diversity lambda must be in range from 0 to 1".to_owned(),
+            });
+        }
+        if diversity.candidates < page_end {
+            return Err(CollectionError::BadRequest {
+                description: "Number of diversity candidates should not be less than offset + top"
+                    .to_owned(),
+            });
+        }
+        if diversity.candidates > MAX_DIVERSITY_CANDIDATES {
+            return Err(CollectionError::BadRequest {
+                description: format!(
+                    "Number of diversity candidates must not exceed {}",
+                    MAX_DIVERSITY_CANDIDATES
+                ),
+            });
+        }
+
+        let distance = self.config.read().params.distance;
+        let with_vector = request.with_vector;
+        let mut candidates_request = CoreSearchRequest::from(request);
+        let with_payload = candidates_request.with_payload.take();
+        candidates_request.top = diversity.candidates;
+        candidates_request.with_vector = false;
+
+        let SearchExplainResult {
+            points: candidates,
+            segments,
+        } = self
+            .core_search_with_explain(
+                candidates_request,
+                segment_searcher,
+                search_runtime_handle,
+                shard_selection,
+            )
+            .await?;
+
+        // Candidates are compared with each other inside the segments, which store them.
+        // Points of different shards can only be compared with the vectors of the candidates.
+        let ids = candidates.iter().map(|point| point.id).collect_vec();
+        let queries: Vec<_> = if self.target_shards(shard_selection)?.len() > 1 {
+            let vectors: HashMap<_, _> = self
+                .retrieve(
+                    PointRequest {
+                        ids: ids.clone(),
+                        with_payload: None,
+                        with_vector: true,
+                    },
+                    segment_searcher,
+                    shard_selection,
+                )
+                .await?
+                .into_iter()
+                .filter_map(|record| record.vector.map(|vector| (record.id, vector)))
+                .collect();
+            ids.iter()
+                .map(|id| (*id, vectors.get(id).cloned()))
+                .collect()
+        } else {
+            ids.iter().map(|id| (*id, None)).collect()
+        };
+        let similarities = self
+            .sample_neighbours(
+                SampleNeighboursRequest {
+                    queries,
+                    top: candidates.len(),
+                },
+                shard_selection,
+            )
+            .await?;
+        let relevance = candidates
+            .iter()
+            .map(|point| distance.internal_score(point.score))
+            .collect_vec();
+
+        let mut result = mmr_rerank(candidates, &relevance, &similarities, diversity, page_end)
+            .into_iter()
+            .skip(offset)
+            .collect_vec();
+        self.fill_page(
+            &mut result,
+            with_payload,
+            with_vector,
+            segment_searcher,
+            shard_selection,
+        )
+        .await?;
+        Ok(SearchExplainResult {
+            points: result,
+            segments,
+        })
+    }
+
+    /// Search for the page of the results after the `offset` best points.
+    /// Payload and vectors are only loaded for the points of the page, after merging the results
+    /// of all shards.
+    async fn core_search_page(
+        &self,
+        mut request: CoreSearchRequest,
+        offset: usize,
+        segment_searcher: &(dyn CollectionSearcher + Sync),
+        search_runtime_handle: &Handle,
+        shard_selection: Option<ShardId>,
+    ) -> CollectionResult<SearchExplainResult> {
+        if offset == 0 {
+            return self
+                .core_search_with_explain(
+                    request,
+                    segment_searcher,
+                    search_runtime_handle,
+                    shard_selection,
+                )
+                .await;
+        }
+
+        let with_payload = request.with_payload.take();
+        let with_vector = request.with_vector;
+        request.with_vector = false;
+        request.top = page_end(offset, request.top)?;
+
+        let SearchExplainResult { points, segments } = self
+            .core_search_with_explain(
+                request,
+                segment_searcher,
+                search_runtime_handle,
+                shard_selection,
+            )
+            .await?;
+        let mut page = points.into_iter().skip(offset).collect_vec();
+        self.fill_page(
+            &mut page,
+            with_payload,
+            with_vector,
+            segment_searcher,
+            shard_selection,
+        )
+        .await?;
+        Ok(SearchExplainResult {
+            points: page,
+            segments,
+        })
+    }
+
+    /// Load payload and vectors of the points, which were searched without them
+    async fn fill_page(
+        &self,
+        points: &mut [ScoredPoint],
+        with_payload: Option<WithPayloadInterface>,
+        with_vector: bool,
+        segment_searcher: &(dyn CollectionSearcher + Sync),
+        shard_selection: Option<ShardId>,
+    ) -> CollectionResult<()> {
+        let fill_payload = with_payload
+            .as_ref()
+            .map_or(false, |with_payload| WithPayload::from(with_payload).enable);
+        if points.is_empty() || !(fill_payload || with_vector) {
+            return Ok(());
+        }
+
+        let mut records: HashMap<ExtendedPointId, Record> = self
+            .retrieve(
+                PointRequest {
+                    ids: points.iter().map(|point| point.id).collect(),
+                    with_payload,
+                    with_vector,
+                },
+                segment_searcher,
+                shard_selection,
+            )
+            .await?
+            .into_iter()
+            .map(|record| (record.id, record))
+            .collect();
+
+        // Points, deleted after the search, are returned without payload and vector
+        for point in points.iter_mut() {
+            if let Some(record) = records.remove(&point.id) {
+                if fill_payload {
+                    point.payload = record.payload;
+                }
+                if with_vector {
+                    point.vector = record.vector;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Same as `search`, but the query is not limited to a single vector
+    pub async fn core_search(
+        &self,
+        request: CoreSearchRequest,
+        segment_searcher: &(dyn CollectionSearcher + Sync),
+        search_runtime_handle: &Handle,
+        shard_selection: Option<ShardId>,
+    ) -> CollectionResult<Vec<ScoredPoint>> {
+        self.core_search_with_explain(
+            request,
+            segment_searcher,
+            search_runtime_handle,
+            shard_selection,
+        )
+        .await
+        .map(|result| result.points)
+    }
+
+    /// Same as `core_search`, but explanations of the segments of all shards are returned too
+    async fn core_search_with_explain(
+        &self,
+        request: CoreSearchRequest,
+        segment_searcher: &(dyn CollectionSearcher + Sync),
+        search_runtime_handle: &Handle,
+        shard_selection: Option<ShardId>,
+    ) -> CollectionResult<SearchExplainResult> {
         let request = Arc::new(request);
         let target_shards = self.target_shards(shard_selection)?;
         let all_searches = target_shards
             .iter()
             .map(|shard| shard.search(request.clone(), segment_searcher, search_runtime_handle));
 
-        let all_searches_res = try_join_all(all_searches).await?.into_iter().flatten();
-        let distance = self.config.params.distance;
+        let (all_points, all_segments): (Vec<_>, Vec<_>) = try_join_all(all_searches)
+            .await?
+            .into_iter()
+            .map(|result| (result.points, result.segments))
+            .unzip();
+        let all_searches_res = all_points.into_iter().flatten();
+        let distance = self.config.read().params.distance;
 
-        let top_result = match distance.distance_order() {
+        let top_result = match request.score_order(distance) {
             Order::LargeBetter => peek_top_largest_scores_iterable(all_searches_res, request.top),
             Order::SmallBetter => peek_top_smallest_scores_iterable(all_searches_res, request.top),
         };
 
-        Ok(top_result)
+        Ok(SearchExplainResult {
+            points: top_result,
+            segments: all_segments.into_iter().flatten().collect(),
+        })
+    }
+
+    /// Search with both dense and sparse vectors and fuse the results into a single list.
+    ///
+    /// Both searches are performed concurrently, each one looks for `top` points.
+    /// Returned scores are the scores of the fusion, not the similarities.
+    pub async fn hybrid_search(
+        &self,
+        request: HybridSearchRequest,
+        segment_searcher: &(dyn CollectionSearcher + Sync),
+        search_runtime_handle: &Handle,
+        shard_selection: Option<ShardId>,
+    ) -> CollectionResult<Vec<ScoredPoint>> {
+        let fusion = request.fusion.unwrap_or_default();
+        let dense_weight = request.dense_weight.unwrap_or(0.5);
+        if !(0.0..=1.0).contains(&dense_weight) {
+            return Err(CollectionError::BadRequest {
+                description: "Dense weight must be in range from 0 to 1".to_owned(),
+            });
+        }
+
+        let dense_request = CoreSearchRequest {
+            query: QueryVector::Nearest(request.vector),
+            filter: request.filter.clone(),
+            params: request.params,
+            top: request.top,
+            with_payload: request.with_payload.clone(),
+            with_vector: request.with_vector,
+            with_explain: false,
+            score_threshold: None,
+            timeout: request.timeout,
+            score_boost: None,
+        };
+        let sparse_request = CoreSearchRequest {
+            query: QueryVector::Sparse(request.sparse_vector),
+            filter: request.filter,
+            params: None,
+            top: request.top,
+            with_payload: request.with_payload,
+            with_vector: request.with_vector,
+            with_explain: false,
+            score_threshold: None,
+            timeout: request.timeout,
+            score_boost: None,
+        };
+        let dense_order = dense_request.score_order(self.config.read().params.distance);
+
+        let (dense_result, sparse_result) = futures::try_join!(
+            self.core_search(
+                dense_request,
+                segment_searcher,
+                search_runtime_handle,
+                shard_selection,
+            ),
+            self.core_search(
+                sparse_request,
+                segment_searcher,
+                search_runtime_handle,
+                shard_selection,
+            ),
+        )?;
+
+        let result = match fusion {
+            Fusion::Rrf => reciprocal_rank_fusion(vec![dense_result, sparse_result], request.top),
+            Fusion::WeightedSum => weighted_score_fusion(
+                vec![
+                    (dense_result, dense_order, dense_weight),
+                    (sparse_result, Order::LargeBetter, 1.0 - dense_weight),
+                ],
+                request.top,
+            ),
+        };
+        Ok(result)
+    }
+
+    /// Search for the best groups of points with the same value of the payload field.
+    ///
+    /// Only points with the grouping field are searched, and only this field is loaded during the search.
+    /// If the found points are not enough to fill all groups, the search is repeated with a wider `top`.
+    /// Requested payload and vectors are loaded for the returned points only.
+    pub async fn search_groups(
+        &self,
+        request: SearchGroupsRequest,
+        segment_searcher: &(dyn CollectionSearcher + Sync),
+        search_runtime_handle: &Handle,
+        shard_selection: Option<ShardId>,
+    ) -> CollectionResult<GroupsResult> {
+        if request.groups == 0 || request.group_size == 0 {
+            return Err(CollectionError::BadRequest {
+                description: "Number of groups and group size must be positive".to_owned(),
+            });
+        }
+
+        let filter = Filter {
+            should: None,
+            must: request
+                .filter
+                .clone()
+                .map(|filter| vec![Condition::Filter(filter)]),
+            must_not: Some(vec![Condition::IsEmpty(IsEmptyCondition {
+                is_empty: PayloadField {
+                    key: request.group_by.clone(),
+                },
+            })]),
+        };
+
+        let mut top = request
+            .groups
+            .checked_mul(request.group_size)
+            .filter(|&top| top <= MAX_GROUPS_SEARCH_TOP)
+            .ok_or_else(|| CollectionError::BadRequest {
+                description: format!(
+                    "Number of groups multiplied by group size must not exceed {}",
+                    MAX_GROUPS_SEARCH_TOP
+                ),
+            })?;
+        let mut iteration = 1;
+        let mut groups = loop {
+            let search_request = CoreSearchRequest {
+                query: QueryVector::Nearest(request.vector.clone()),
+                filter: Some(filter.clone()),
+                params: request.params,
+                top,
+                with_payload: Some(WithPayloadInterface::Fields(vec![request.group_by.clone()])),
+                with_vector: false,
+                with_explain: false,
+                score_threshold: request.score_threshold,
+                timeout: request.timeout,
+                score_boost: None,
+            };
+            let points = self
+                .core_search(
+                    search_request,
+                    segment_searcher,
+                    search_runtime_handle,
+                    shard_selection,
+                )
+                .await?;
+
+            let exhausted = points.len() < top;
+            let mut aggregator =
+                GroupsAggregator::new(request.group_by.clone(), request.groups, request.group_size);
+            for point in &points {
+                aggregator.add_point(point);
+            }
+
+            if exhausted
+                || aggregator.is_full()
+                || iteration == MAX_GROUPS_SEARCH_ITERATIONS
+                || top == MAX_GROUPS_SEARCH_TOP
+            {
+                break aggregator.into_groups();
+            }
+            top = top
+                .saturating_mul(GROUPS_SEARCH_WIDENING_FACTOR)
+                .min(MAX_GROUPS_SEARCH_TOP);
+            iteration += 1;
+        };
+
+        if request.with_payload.is_none() && !request.with_vector {
+            for hit in groups.iter_mut().flat_map(|group| group.hits.iter_mut()) {
+                hit.payload = None;
+            }
+        } else {
+            let ids = groups
+                .iter()
+                .flat_map(|group| group.hits.iter().map(|hit| hit.id))
+                .unique()
+                .collect_vec();
+            let records: HashMap<_, _> = self
+                .retrieve(
+                    PointRequest {
+                        ids,
+                        with_payload: request.with_payload.clone(),
+                        with_vector: request.with_vector,
+                    },
+                    segment_searcher,
+                    shard_selection,
+                )
+                .await?
+                .into_iter()
+                .map(|record| (record.id, record))
+                .collect();
+            for hit in groups.iter_mut().flat_map(|group| group.hits.iter_mut()) {
+                let record = records.get(&hit.id);
+                hit.payload = record.and_then(|record| record.payload.clone());
+                hit.vector = record.and_then(|record| record.vector.clone());
+            }
+        }
+
+        Ok(GroupsResult { groups })
     }
 
     pub async fn scroll_by(
@@ -447,6 +1015,7 @@ impl Collection {
                 &with_payload_interface,
                 with_vector,
                 request.filter.as_ref(),
+                request.timeout,
             )
         });
 
@@ -499,42 +1068,257 @@ impl Collection {
         Ok(points)
     }
 
-    /// Updates shard optimization params:
+    /// Count points with each value of the keyword or integer field among points, matching the filter.
+    ///
+    /// Counts of all values are merged over the shards, so the most frequent values are exact.
+    pub async fn facet(
+        &self,
+        request: FacetRequest,
+        shard_selection: Option<ShardId>,
+    ) -> CollectionResult<FacetResult> {
+        // Field indexes are created in all shards, so the schema of any local shard is enough
+        let local_shard = match shard_selection {
+            Some(shard_id) => Some(self.local_shard_by_id(shard_id)?),
+            None => self
+                .all_shards()
+                .find(|shard| matches!(shard, Shard::Local(_))),
+        };
+        let field_type = match local_shard {
+            Some(Shard::Local(local_shard)) => {
+                local_shard.payload_schema().get(&request.key).copied()
+            }
+            _ => self
+                .info(shard_selection)
+                .await?
+                .payload_schema
+                .get(&request.key)
+                .map(|info| info.data_type),
+        };
+        match field_type {
+            Some(PayloadSchemaType::Keyword | PayloadSchemaType::Integer) => {}
+            _ => {
+                return Err(CollectionError::BadRequest {
+                    description: format!(
+                        "Field `{}` must be indexed as keyword or integer to count its values",
+                        request.key
+                    ),
+                })
+            }
+        }
+
+        let limit = request.limit.unwrap_or(DEFAULT_FACET_LIMIT);
+        let request = Arc::new(request);
+        let target_shards = self.target_shards(shard_selection)?;
+        let facet_futures = target_shards
+            .iter()
+            .map(|shard| shard.facet(request.clone()));
+        let all_shard_collection_results = try_join_all(facet_futures).await?;
+
+        let mut counts: HashMap<_, usize> = HashMap::new();
+        for hit in all_shard_collection_results.into_iter().flatten() {
+            *counts.entry(hit.value).or_insert(0) += hit.count;
+        }
+        let hits = counts
+            .into_iter()
+            .map(|(value, count)| FacetHit { value, count })
+            .sorted_by(|a, b| b.count.cmp(&a.count).then_with(|| a.value.cmp(&b.value)))
+            .take(limit)
+            .collect();
+        Ok(FacetResult { hits })
+    }
+
+    /// Uniform random sample of `size` points, which satisfy the filter
+    pub async fn sample(
+        &self,
+        filter: Option<&Filter>,
+        size: usize,
+        shard_selection: Option<ShardId>,
+    ) -> CollectionResult<PointsSample> {
+        if size > MAX_MATRIX_SAMPLE {
+            return Err(CollectionError::BadRequest {
+                description: format!("Sample size must not exceed {}", MAX_MATRIX_SAMPLE),
+            });
+        }
+        let target_shards = self.target_shards(shard_selection)?;
+        let sample_futures = target_shards.iter().map(|shard| shard.sample(filter, size));
+        let samples = try_join_all(sample_futures).await?;
+        Ok(merge_samples(samples, size, &mut rand::thread_rng()))
+    }
+
+    /// Nearest neighbours of each query among the query points, with internal scores
+    pub async fn sample_neighbours(
+        &self,
+        request: SampleNeighboursRequest,
+        shard_selection: Option<ShardId>,
+    ) -> CollectionResult<Vec<Vec<ScoredPoint>>> {
+        let request = Arc::new(request);
+        let target_shards = self.target_shards(shard_selection)?;
+        let neighbours_futures = target_shards
+            .iter()
+            .map(|shard| shard.sample_neighbours(request.clone()));
+        let all_shard_collection_results = try_join_all(neighbours_futures).await?;
+
+        let mut neighbours: Vec<Vec<Vec<ScoredPoint>>> = vec![vec![]; request.queries.len()];
+        for shard_neighbours in all_shard_collection_results {
+            for (query_neighbours, found) in neighbours.iter_mut().zip(shard_neighbours) {
+                query_neighbours.push(found);
+            }
+        }
+        Ok(neighbours
+            .into_iter()
+            .map(|query_neighbours| merge_neighbours(query_neighbours, request.top))
+            .collect())
+    }
+
+    /// Sample random points, which satisfy the filter, and find the nearest neighbours of each
+    /// of them among the sample.
+    ///
+    /// Result is a sparse matrix of scores between the sampled points.
+    pub async fn distance_matrix(
+        &self,
+        request: DistanceMatrixRequest,
+        segment_searcher: &(dyn CollectionSearcher + Sync),
+        shard_selection: Option<ShardId>,
+    ) -> CollectionResult<DistanceMatrix> {
+        let sample_size = request.sample.unwrap_or(DEFAULT_MATRIX_SAMPLE);
+        let limit = request.limit.unwrap_or(DEFAULT_MATRIX_LIMIT);
+        if sample_size == 0 || limit == 0 {
+            return Err(CollectionError::BadRequest {
+                description: "Sample size and limit must be positive".to_string(),
+            });
+        }
+
+        let sample = self
+            .sample(request.filter.as_ref(), sample_size, shard_selection)
+            .await?;
+        let queries: Vec<_> = self
+            .retrieve(
+                PointRequest {
+                    ids: sample.ids,
+                    with_payload: None,
+                    with_vector: true,
+                },
+                segment_searcher,
+                shard_selection,
+            )
+            .await?
+            .into_iter()
+            .filter_map(|record| record.vector.map(|vector| (record.id, Some(vector))))
+            .sorted_by_key(|(id, _)| *id)
+            .collect();
+        let ids = queries.iter().map(|(id, _)| *id).collect_vec();
+
+        let neighbours = self
+            .sample_neighbours(
+                SampleNeighboursRequest {
+                    queries,
+                    top: limit,
+                },
+                shard_selection,
+            )
+            .await?;
+        let distance = self.config.read().params.distance;
+        Ok(build_matrix(ids, neighbours, distance))
+    }
+
+    /// Updates collection params:
     /// - Saves new params on disk
-    /// - Stops existing optimization loop
+    /// - Stops existing optimization loop of each local shard
     /// - Runs new optimizers with new params
-    pub async fn update_optimizer_params_from_diff(
+    ///
+    /// Segments indexed with outdated HNSW params are re-indexed by the optimizers.
+    /// New WAL params are only saved: the open WAL can't be re-configured,
+    /// so they take effect after restart of the service.
+    pub async fn update_params_from_diff(
         &self,
-        optimizer_config_diff: OptimizersConfigDiff,
+        optimizers_config_diff: Option<OptimizersConfigDiff>,
+        hnsw_config_diff: Option<HnswConfigDiff>,
+        wal_config_diff: Option<WalConfigDiff>,
     ) -> CollectionResult<()> {
+        let mut config = self.config.read().clone();
+        if let Some(diff) = optimizers_config_diff {
+            config.optimizer_config = diff.update(&config.optimizer_config)?;
+        }
+        if let Some(diff) = hnsw_config_diff {
+            config.hnsw_config = diff.update(&config.hnsw_config)?;
+        }
+        if let Some(diff) = wal_config_diff {
+            config.wal_config = diff.update(&config.wal_config)?;
+        }
+        self.update_config(config).await
+    }
+
+    /// Replaces config of the collection and all its local shards.
+    /// Collection params (vector size, distance, number of shards) are expected to stay unchanged.
+    /// Indexing thread pool is re-built, if the number of optimization threads is changed.
+    pub async fn update_config(&self, config: CollectionConfig) -> CollectionResult<()> {
+        let current_threads = self
+            .config
+            .read()
+            .optimizer_config
+            .get_max_optimization_threads();
+        let indexing_pool =
+            if config.optimizer_config.get_max_optimization_threads() == current_threads {
+                self.indexing_pool.read().clone()
+            } else {
+                build_indexing_pool(&config.optimizer_config)?
+            };
         for shard in self.all_shards() {
             if let Shard::Local(shard) = shard {
                 shard
-                    .update_optimizer_with_diff(optimizer_config_diff.clone())
+                    .update_config(config.clone(), indexing_pool.clone())
                     .await?;
             }
         }
+        config.save(&self.path)?;
+        *self.config.write() = config;
+        *self.indexing_pool.write() = indexing_pool;
         Ok(())
     }
 
-    /// Updates shard optimization params:
-    /// - Saves new params on disk
-    /// - Stops existing optimization loop
-    /// - Runs new optimizers with new params
-    pub async fn update_optimizer_params(
-        &self,
-        optimizer_config: OptimizersConfig,
-    ) -> CollectionResult<()> {
+    /// Pauses optimizers of all local shards.
+    /// Running optimizations are cancelled, new ones are not started until resumed.
+    /// Pause is kept in memory only, optimizers run again after restart of the service.
+    pub fn pause_optimizers(&self) -> CollectionResult<()> {
         for shard in self.all_shards() {
             if let Shard::Local(shard) = shard {
-                shard
-                    .update_optimizer_with_config(optimizer_config.clone())
-                    .await?;
+                shard.pause_optimizers()?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Resumes optimizers of all local shards
+    pub fn resume_optimizers(&self) -> CollectionResult<()> {
+        for shard in self.all_shards() {
+            if let Shard::Local(shard) = shard {
+                shard.resume_optimizers()?;
             }
         }
         Ok(())
     }
 
+    /// Triggers merge and indexing of local shard segments regardless of optimizer thresholds
+    pub fn force_optimize(&self) -> CollectionResult<()> {
+        for shard in self.all_shards() {
+            if let Shard::Local(shard) = shard {
+                shard.force_optimize()?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Measures quality of the vector index in all local shards
+    pub async fn evaluate_recall(&self, request: &RecallRequest) -> CollectionResult<RecallReport> {
+        let mut segments = vec![];
+        for (&shard_id, shard) in &self.shards {
+            if let Shard::Local(shard) = shard {
+                segments.extend(shard.evaluate_recall(shard_id, request).await?);
+            }
+        }
+        Ok(RecallReport::new(segments))
+    }
+
     pub async fn info(&self, shard_selection: Option<ShardId>) -> CollectionResult<CollectionInfo> {
         let target_shards = self.target_shards(shard_selection)?;
         let first_shard = target_shards
@@ -575,7 +1359,7 @@ impl Collection {
 
     pub fn state(&self, this_peer_id: PeerId) -> State {
         State {
-            config: self.config.clone(),
+            config: self.config.read().clone(),
             shard_to_peer: self
                 .shards
                 .iter()
@@ -597,6 +1381,15 @@ pub fn shard_path(collection_path: &Path, shard_id: ShardId) -> PathBuf {
     collection_path.join(format!("{shard_id}"))
 }
 
+/// Number of the best points, which should be found to return `top` points after `offset` ones
+fn page_end(offset: usize, top: usize) -> CollectionResult<usize> {
+    offset
+        .checked_add(top)
+        .ok_or_else(|| CollectionError::BadRequest {
+            description: "Sum of offset and top is too large".to_string(),
+        })
+}
+
 pub fn avg_vectors<'a>(
     vectors: impl Iterator<Item = &'a Vec<VectorElementType>>,
 ) -> Vec<VectorElementType> {
@@ -33,6 +33,10 @@ pub struct HnswConfigDiff {
     /// If payload chunk is smaller than `full_scan_threshold` additional indexing won't be used -
     /// in this case full-scan search should be preferred by query planner and additional indexing is not required.
     pub full_scan_threshold: Option<usize>,
+    /// Store HNSW index on disk. If set to false, index will be stored in RAM. Default: false
+    pub on_disk: Option<bool>,
+    /// Build HNSW index of appendable segments incrementally, on each update. If set to false, appendable segments are not indexed until optimization. Default: false
+    pub incremental: Option<bool>,
 }
 
 #[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, Merge, PartialEq, Eq, Hash)]
@@ -76,6 +80,9 @@ pub struct OptimizersConfigDiff {
     pub flush_interval_sec: Option<u64>,
     /// Maximum available threads for optimization workers
     pub max_optimization_threads: Option<usize>,
+    /// Maximum number of update operations, waiting to be applied to a shard.
+    /// New updates are rejected while the queue is full. If not set or 0 - the queue is not limited.
+    pub max_queued_updates: Option<usize>,
 }
 
 impl std::hash::Hash for OptimizersConfigDiff {
@@ -89,6 +96,7 @@ impl std::hash::Hash for OptimizersConfigDiff {
         self.payload_indexing_threshold.hash(state);
         self.flush_interval_sec.hash(state);
         self.max_optimization_threads.hash(state);
+        self.max_queued_updates.hash(state);
     }
 }
 
@@ -104,6 +112,7 @@ impl PartialEq for OptimizersConfigDiff {
             && self.payload_indexing_threshold == other.payload_indexing_threshold
             && self.flush_interval_sec == other.flush_interval_sec
             && self.max_optimization_threads == other.max_optimization_threads
+            && self.max_queued_updates == other.max_queued_updates
     }
 }
 
@@ -156,6 +165,7 @@ mod tests {
             payload_indexing_threshold: 20_000,
             flush_interval_sec: 30,
             max_optimization_threads: 1,
+            max_queued_updates: None,
         };
         let update: OptimizersConfigDiff =
             serde_json::from_str(r#"{ "indexing_threshold": 10000 }"#).unwrap();
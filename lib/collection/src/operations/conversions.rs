@@ -2,12 +2,19 @@ use crate::config::{CollectionParams, WalConfig};
 use crate::operations::config_diff::{HnswConfigDiff, WalConfigDiff};
 use crate::operations::point_ops::PointsSelector::PointIdsSelector;
 use crate::operations::point_ops::{FilterSelector, PointIdsList, PointStruct, PointsSelector};
-use crate::operations::types::{CollectionStatus, OptimizersStatus, UpdateStatus};
+use crate::operations::types::{
+    BoostTerm, CollectionStatus, DecayTarget, DecayTerm, DistanceMatrix, DistanceMatrixRequest,
+    Diversity, FacetHit, FacetRequest, FacetResult, Fusion, GroupId, GroupsResult,
+    HybridSearchRequest, LinearTerm, OptimizersStatus, PointGroup, RecallReport, RecommendStrategy,
+    ScoreBoost, SearchGroupsRequest, SearchRequest, SegmentRecall, UpdateStatus,
+};
 use crate::{
     CollectionConfig, CollectionInfo, OptimizersConfig, OptimizersConfigDiff, Record, UpdateResult,
 };
 use api::grpc::conversions::{payload_to_proto, proto_to_payloads};
 use itertools::Itertools;
+use segment::spaces::binary::unpack_bytes;
+use segment::types::VectorElementType;
 use std::collections::HashMap;
 use std::num::NonZeroU32;
 use tonic::Status;
@@ -18,6 +25,8 @@ impl From<api::grpc::qdrant::HnswConfigDiff> for HnswConfigDiff {
             m: value.m.map(|v| v as usize),
             ef_construct: value.ef_construct.map(|v| v as usize),
             full_scan_threshold: value.full_scan_threshold.map(|v| v as usize),
+            on_disk: value.on_disk,
+            incremental: value.incremental,
         }
     }
 }
@@ -43,6 +52,7 @@ impl From<api::grpc::qdrant::OptimizersConfigDiff> for OptimizersConfigDiff {
             payload_indexing_threshold: value.payload_indexing_threshold.map(|v| v as usize),
             flush_interval_sec: value.flush_interval_sec,
             max_optimization_threads: value.max_optimization_threads.map(|v| v as usize),
+            max_queued_updates: value.max_queued_updates.map(|v| v as usize),
         }
     }
 }
@@ -87,14 +97,25 @@ impl From<CollectionInfo> for api::grpc::qdrant::CollectionInfo {
                         segment::types::Distance::Cosine => api::grpc::qdrant::Distance::Cosine,
                         segment::types::Distance::Euclid => api::grpc::qdrant::Distance::Euclid,
                         segment::types::Distance::Dot => api::grpc::qdrant::Distance::Dot,
+                        segment::types::Distance::Hamming => api::grpc::qdrant::Distance::Hamming,
+                        segment::types::Distance::Manhattan => {
+                            api::grpc::qdrant::Distance::Manhattan
+                        }
                     }
                     .into(),
                     shard_number: config.params.shard_number.get(),
+                    product_quantization: config.params.product_quantization.map(|v| v.into()),
+                    vector_datatype: api::grpc::qdrant::VectorStorageDatatype::from(
+                        config.params.vector_datatype,
+                    )
+                    .into(),
                 }),
                 hnsw_config: Some(api::grpc::qdrant::HnswConfigDiff {
                     m: Some(config.hnsw_config.m as u64),
                     ef_construct: Some(config.hnsw_config.ef_construct as u64),
                     full_scan_threshold: Some(config.hnsw_config.full_scan_threshold as u64),
+                    on_disk: config.hnsw_config.on_disk,
+                    incremental: config.hnsw_config.incremental,
                 }),
                 optimizer_config: Some(api::grpc::qdrant::OptimizersConfigDiff {
                     deleted_threshold: Some(config.optimizer_config.deleted_threshold),
@@ -114,6 +135,10 @@ impl From<CollectionInfo> for api::grpc::qdrant::CollectionInfo {
                     max_optimization_threads: Some(
                         config.optimizer_config.max_optimization_threads as u64,
                     ),
+                    max_queued_updates: config
+                        .optimizer_config
+                        .max_queued_updates
+                        .map(|v| v as u64),
                 }),
                 wal_config: Some(api::grpc::qdrant::WalConfigDiff {
                     wal_capacity_mb: Some(config.wal_config.wal_capacity_mb as u64),
@@ -150,6 +175,30 @@ impl TryFrom<api::grpc::qdrant::RetrievedPoint> for Record {
     }
 }
 
+impl From<SegmentRecall> for api::grpc::qdrant::SegmentRecall {
+    fn from(segment: SegmentRecall) -> Self {
+        Self {
+            shard_id: segment.shard_id,
+            segment_id: segment.segment_id as u64,
+            queries: segment.queries as u64,
+            recall: segment.recall,
+            mean_search_time: segment.mean_search_time,
+            ef: segment.ef as u64,
+        }
+    }
+}
+
+impl From<RecallReport> for api::grpc::qdrant::RecallReport {
+    fn from(report: RecallReport) -> Self {
+        Self {
+            segments: report.segments.into_iter().map(|s| s.into()).collect(),
+            queries: report.queries as u64,
+            recall: report.recall,
+            mean_search_time: report.mean_search_time,
+        }
+    }
+}
+
 impl TryFrom<i32> for CollectionStatus {
     type Error = Status;
 
@@ -163,6 +212,320 @@ impl TryFrom<i32> for CollectionStatus {
     }
 }
 
+impl TryFrom<i32> for RecommendStrategy {
+    type Error = Status;
+
+    fn try_from(value: i32) -> Result<Self, Self::Error> {
+        match api::grpc::qdrant::RecommendStrategy::from_i32(value) {
+            Some(api::grpc::qdrant::RecommendStrategy::AverageVector) => {
+                Ok(RecommendStrategy::AverageVector)
+            }
+            Some(api::grpc::qdrant::RecommendStrategy::BestScore) => {
+                Ok(RecommendStrategy::BestScore)
+            }
+            None => Err(Status::invalid_argument("Malformed RecommendStrategy type")),
+        }
+    }
+}
+
+impl TryFrom<i32> for Fusion {
+    type Error = Status;
+
+    fn try_from(value: i32) -> Result<Self, Self::Error> {
+        match api::grpc::qdrant::Fusion::from_i32(value) {
+            Some(api::grpc::qdrant::Fusion::Rrf) => Ok(Fusion::Rrf),
+            Some(api::grpc::qdrant::Fusion::WeightedSum) => Ok(Fusion::WeightedSum),
+            None => Err(Status::invalid_argument("Malformed Fusion type")),
+        }
+    }
+}
+
+/// Binary vectors could be passed over gRPC as bit-packed bytes instead of numbers
+fn vector_from_grpc(
+    vector: Vec<VectorElementType>,
+    binary_vector: Vec<u8>,
+) -> Result<Vec<VectorElementType>, Status> {
+    match (vector.is_empty(), binary_vector.is_empty()) {
+        (_, true) => Ok(vector),
+        (true, false) => Ok(unpack_bytes(&binary_vector)),
+        (false, false) => Err(Status::invalid_argument(
+            "Only one of `vector` and `binary_vector` could be set",
+        )),
+    }
+}
+
+impl TryFrom<api::grpc::qdrant::SearchPoints> for SearchRequest {
+    type Error = Status;
+
+    fn try_from(value: api::grpc::qdrant::SearchPoints) -> Result<Self, Self::Error> {
+        Ok(Self {
+            vector: vector_from_grpc(value.vector, value.binary_vector)?,
+            filter: value.filter.map(|f| f.try_into()).transpose()?,
+            params: value.params.map(|p| p.into()),
+            top: value.top as usize,
+            offset: value.offset.unwrap_or(0) as usize,
+            with_payload: value.with_payload.map(|wp| wp.try_into()).transpose()?,
+            with_vector: value.with_vector.unwrap_or(false),
+            score_threshold: value.score_threshold,
+            timeout: value.timeout,
+            diversity: value.diversity.map(|d| d.into()),
+            score_boost: value.score_boost.map(|b| b.try_into()).transpose()?,
+            with_explain: value.with_explain.unwrap_or(false),
+        })
+    }
+}
+
+impl From<api::grpc::qdrant::Diversity> for Diversity {
+    fn from(value: api::grpc::qdrant::Diversity) -> Self {
+        Self {
+            lambda: value.lambda,
+            candidates: value.candidates as usize,
+        }
+    }
+}
+
+impl From<api::grpc::qdrant::LinearTerm> for LinearTerm {
+    fn from(value: api::grpc::qdrant::LinearTerm) -> Self {
+        Self {
+            key: value.key,
+            weight: value.weight,
+        }
+    }
+}
+
+impl From<LinearTerm> for api::grpc::qdrant::LinearTerm {
+    fn from(value: LinearTerm) -> Self {
+        Self {
+            key: value.key,
+            weight: value.weight,
+        }
+    }
+}
+
+impl From<api::grpc::qdrant::DecayTerm> for DecayTerm {
+    fn from(value: api::grpc::qdrant::DecayTerm) -> Self {
+        Self {
+            key: value.key,
+            target: value.target.map(|target| match target {
+                api::grpc::qdrant::decay_term::Target::Number(number) => {
+                    DecayTarget::Number(number)
+                }
+                api::grpc::qdrant::decay_term::Target::Datetime(datetime) => {
+                    DecayTarget::Datetime(datetime)
+                }
+            }),
+            scale: value.scale,
+            weight: value.weight,
+        }
+    }
+}
+
+impl From<DecayTerm> for api::grpc::qdrant::DecayTerm {
+    fn from(value: DecayTerm) -> Self {
+        Self {
+            key: value.key,
+            target: value.target.map(|target| match target {
+                DecayTarget::Number(number) => {
+                    api::grpc::qdrant::decay_term::Target::Number(number)
+                }
+                DecayTarget::Datetime(datetime) => {
+                    api::grpc::qdrant::decay_term::Target::Datetime(datetime)
+                }
+            }),
+            scale: value.scale,
+            weight: value.weight,
+        }
+    }
+}
+
+impl TryFrom<api::grpc::qdrant::BoostTerm> for BoostTerm {
+    type Error = Status;
+
+    fn try_from(value: api::grpc::qdrant::BoostTerm) -> Result<Self, Self::Error> {
+        use api::grpc::qdrant::boost_term::Term;
+        match value.term {
+            Some(Term::Linear(linear)) => Ok(BoostTerm::Linear(linear.into())),
+            Some(Term::ExpDecay(decay)) => Ok(BoostTerm::ExpDecay(decay.into())),
+            Some(Term::GaussDecay(decay)) => Ok(BoostTerm::GaussDecay(decay.into())),
+            None => Err(Status::invalid_argument("Boost term is missing")),
+        }
+    }
+}
+
+impl From<BoostTerm> for api::grpc::qdrant::BoostTerm {
+    fn from(value: BoostTerm) -> Self {
+        use api::grpc::qdrant::boost_term::Term;
+        let term = match value {
+            BoostTerm::Linear(linear) => Term::Linear(linear.into()),
+            BoostTerm::ExpDecay(decay) => Term::ExpDecay(decay.into()),
+            BoostTerm::GaussDecay(decay) => Term::GaussDecay(decay.into()),
+        };
+        Self { term: Some(term) }
+    }
+}
+
+impl TryFrom<api::grpc::qdrant::ScoreBoost> for ScoreBoost {
+    type Error = Status;
+
+    fn try_from(value: api::grpc::qdrant::ScoreBoost) -> Result<Self, Self::Error> {
+        Ok(Self {
+            terms: value
+                .terms
+                .into_iter()
+                .map(|term| term.try_into())
+                .collect::<Result<_, _>>()?,
+            candidates: value.candidates as usize,
+        })
+    }
+}
+
+impl From<ScoreBoost> for api::grpc::qdrant::ScoreBoost {
+    fn from(value: ScoreBoost) -> Self {
+        Self {
+            terms: value.terms.into_iter().map(|term| term.into()).collect(),
+            candidates: value.candidates as u64,
+        }
+    }
+}
+
+impl TryFrom<api::grpc::qdrant::SearchPointGroups> for SearchGroupsRequest {
+    type Error = Status;
+
+    fn try_from(value: api::grpc::qdrant::SearchPointGroups) -> Result<Self, Self::Error> {
+        Ok(Self {
+            vector: value.vector,
+            filter: value.filter.map(|f| f.try_into()).transpose()?,
+            params: value.params.map(|p| p.into()),
+            group_by: value.group_by,
+            groups: value.groups as usize,
+            group_size: value.group_size as usize,
+            with_payload: value.with_payload.map(|wp| wp.try_into()).transpose()?,
+            with_vector: value.with_vector.unwrap_or(false),
+            score_threshold: value.score_threshold,
+            timeout: value.timeout,
+        })
+    }
+}
+
+impl TryFrom<api::grpc::qdrant::SearchHybridPoints> for HybridSearchRequest {
+    type Error = Status;
+
+    fn try_from(value: api::grpc::qdrant::SearchHybridPoints) -> Result<Self, Self::Error> {
+        Ok(Self {
+            vector: value.vector,
+            sparse_vector: value
+                .sparse_vector
+                .ok_or_else(|| Status::invalid_argument("Sparse vector is missing"))?
+                .into(),
+            fusion: value.fusion.map(|f| f.try_into()).transpose()?,
+            dense_weight: value.dense_weight,
+            filter: value.filter.map(|f| f.try_into()).transpose()?,
+            params: value.params.map(|p| p.into()),
+            top: value.top as usize,
+            with_payload: value.with_payload.map(|wp| wp.try_into()).transpose()?,
+            with_vector: value.with_vector.unwrap_or(false),
+            timeout: value.timeout,
+        })
+    }
+}
+
+impl From<GroupId> for api::grpc::qdrant::GroupId {
+    fn from(value: GroupId) -> Self {
+        let kind = match value {
+            GroupId::Keyword(keyword) => api::grpc::qdrant::group_id::Kind::Keyword(keyword),
+            GroupId::Integer(integer) => api::grpc::qdrant::group_id::Kind::Integer(integer),
+        };
+        Self { kind: Some(kind) }
+    }
+}
+
+impl From<PointGroup> for api::grpc::qdrant::PointGroup {
+    fn from(group: PointGroup) -> Self {
+        Self {
+            id: Some(group.id.into()),
+            hits: group.hits.into_iter().map(|hit| hit.into()).collect(),
+        }
+    }
+}
+
+impl From<GroupsResult> for api::grpc::qdrant::GroupsResult {
+    fn from(result: GroupsResult) -> Self {
+        Self {
+            groups: result
+                .groups
+                .into_iter()
+                .map(|group| group.into())
+                .collect(),
+        }
+    }
+}
+
+impl TryFrom<api::grpc::qdrant::FacetPoints> for FacetRequest {
+    type Error = Status;
+
+    fn try_from(value: api::grpc::qdrant::FacetPoints) -> Result<Self, Self::Error> {
+        Ok(Self {
+            key: value.key,
+            filter: value.filter.map(|f| f.try_into()).transpose()?,
+            limit: value.limit.map(|limit| limit as usize),
+        })
+    }
+}
+
+impl From<FacetHit> for api::grpc::qdrant::FacetHit {
+    fn from(hit: FacetHit) -> Self {
+        Self {
+            value: Some(hit.value.into()),
+            count: hit.count as u64,
+        }
+    }
+}
+
+impl TryFrom<api::grpc::qdrant::FacetHit> for FacetHit {
+    type Error = Status;
+
+    fn try_from(hit: api::grpc::qdrant::FacetHit) -> Result<Self, Self::Error> {
+        Ok(Self {
+            value: hit
+                .value
+                .ok_or_else(|| Status::invalid_argument("Facet value is missing"))?
+                .try_into()?,
+            count: hit.count as usize,
+        })
+    }
+}
+
+impl From<FacetResult> for api::grpc::qdrant::FacetResult {
+    fn from(result: FacetResult) -> Self {
+        Self {
+            hits: result.hits.into_iter().map(|hit| hit.into()).collect(),
+        }
+    }
+}
+
+impl TryFrom<api::grpc::qdrant::DistanceMatrixPoints> for DistanceMatrixRequest {
+    type Error = Status;
+
+    fn try_from(value: api::grpc::qdrant::DistanceMatrixPoints) -> Result<Self, Self::Error> {
+        Ok(Self {
+            filter: value.filter.map(|f| f.try_into()).transpose()?,
+            sample: value.sample.map(|sample| sample as usize),
+            limit: value.limit.map(|limit| limit as usize),
+        })
+    }
+}
+
+impl From<DistanceMatrix> for api::grpc::qdrant::DistanceMatrix {
+    fn from(matrix: DistanceMatrix) -> Self {
+        Self {
+            ids: matrix.ids.into_iter().map(|id| id.into()).collect(),
+            rows: matrix.rows.into_iter().map(|row| row as u64).collect(),
+            cols: matrix.cols.into_iter().map(|col| col as u64).collect(),
+            scores: matrix.scores,
+        }
+    }
+}
+
 impl From<api::grpc::qdrant::OptimizersConfigDiff> for OptimizersConfig {
     fn from(optimizer_config: api::grpc::qdrant::OptimizersConfigDiff) -> Self {
         Self {
@@ -182,6 +545,7 @@ impl From<api::grpc::qdrant::OptimizersConfigDiff> for OptimizersConfig {
             max_optimization_threads: optimizer_config
                 .max_optimization_threads
                 .unwrap_or_default() as usize,
+            max_queued_updates: optimizer_config.max_queued_updates.map(|v| v as usize),
         }
     }
 }
@@ -213,6 +577,17 @@ impl TryFrom<api::grpc::qdrant::CollectionConfig> for CollectionConfig {
                         Some(distance) => distance,
                     },
                     shard_number: NonZeroU32::new(params.shard_number).unwrap(),
+                    product_quantization: params.product_quantization.map(|v| v.into()),
+                    vector_datatype: match api::grpc::qdrant::VectorStorageDatatype::from_i32(
+                        params.vector_datatype,
+                    ) {
+                        None => {
+                            return Err(Status::invalid_argument(
+                                "Malformed CollectionParams vector_datatype",
+                            ))
+                        }
+                        Some(datatype) => datatype.into(),
+                    },
                 },
             },
             hnsw_config: match config.hnsw_config {
@@ -279,6 +654,8 @@ impl TryFrom<api::grpc::qdrant::PointStruct> for PointStruct {
             id,
             vector,
             payload,
+            sparse_vector,
+            binary_vector,
         } = value;
 
         let converted_payload = proto_to_payloads(payload)?;
@@ -287,8 +664,9 @@ impl TryFrom<api::grpc::qdrant::PointStruct> for PointStruct {
             id: id
                 .ok_or_else(|| Status::invalid_argument("Empty ID is not allowed"))?
                 .try_into()?,
-            vector,
+            vector: vector_from_grpc(vector, binary_vector)?,
             payload: Some(converted_payload),
+            sparse_vector: sparse_vector.map(|vector| vector.into()),
         })
     }
 }
@@ -301,6 +679,7 @@ impl TryFrom<PointStruct> for api::grpc::qdrant::PointStruct {
             id,
             vector,
             payload,
+            sparse_vector,
         } = value;
 
         let converted_payload = match payload {
@@ -312,6 +691,8 @@ impl TryFrom<PointStruct> for api::grpc::qdrant::PointStruct {
             id: Some(id.into()),
             vector,
             payload: converted_payload,
+            sparse_vector: sparse_vector.map(|vector| vector.into()),
+            binary_vector: vec![],
         })
     }
 }
@@ -3,6 +3,7 @@ mod conversions;
 pub mod payload_ops;
 pub mod point_ops;
 pub mod types;
+pub mod vector_input;
 
 use std::collections::HashMap;
 
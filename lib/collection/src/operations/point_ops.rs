@@ -1,8 +1,10 @@
 use crate::operations::types::VectorType;
+use crate::operations::vector_input::{deserialize_vector, deserialize_vectors, VectorInput};
 use crate::ShardId;
 use hashring::HashRing;
 use schemars::JsonSchema;
-use segment::types::{Filter, Payload, PointIdType};
+use segment::index::sparse_vector_index::check_sparse_vector;
+use segment::types::{Filter, Payload, PointIdType, SparseVector};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -18,17 +20,25 @@ pub struct PointStruct {
     /// Point id
     pub id: PointIdType,
     /// Vector
+    #[serde(deserialize_with = "deserialize_vector")]
+    #[schemars(with = "VectorInput")]
     pub vector: VectorType,
     /// Payload values (optional)
     pub payload: Option<Payload>,
+    /// Sparse vector (optional), used along with the dense one in hybrid search
+    pub sparse_vector: Option<SparseVector>,
 }
 
 #[derive(Debug, Deserialize, Serialize, JsonSchema, Default, Clone)]
 #[serde(rename_all = "snake_case")]
 pub struct Batch {
     pub ids: Vec<PointIdType>,
+    #[serde(deserialize_with = "deserialize_vectors")]
+    #[schemars(with = "Vec<VectorInput>")]
     pub vectors: Vec<VectorType>,
     pub payloads: Option<Vec<Option<Payload>>>,
+    /// Sparse vectors of the points (optional)
+    pub sparse_vectors: Option<Vec<Option<SparseVector>>>,
 }
 
 #[derive(Debug, Deserialize, Serialize, JsonSchema, Default, Clone)]
@@ -99,7 +109,15 @@ impl Validate for PointOperations {
 impl Validate for PointInsertOperations {
     fn validate(&self) -> CollectionResult<()> {
         match self {
-            PointInsertOperations::PointsList(_) => Ok(()),
+            PointInsertOperations::PointsList(PointsList { points }) => {
+                for sparse_vector in points
+                    .iter()
+                    .filter_map(|point| point.sparse_vector.as_ref())
+                {
+                    check_sparse_vector(sparse_vector)?;
+                }
+                Ok(())
+            }
             PointInsertOperations::PointsBatch(PointsBatch { batch }) => {
                 if batch.ids.len() != batch.vectors.len() {
                     return Err(CollectionError::BadInput {
@@ -121,6 +139,20 @@ impl Validate for PointInsertOperations {
                         });
                     }
                 }
+                if let Some(sparse_vectors) = &batch.sparse_vectors {
+                    if sparse_vectors.len() != batch.ids.len() {
+                        return Err(CollectionError::BadInput {
+                            description: format!(
+                                "Amount of ids ({}) and sparse vectors ({}) does not match",
+                                batch.ids.len(),
+                                sparse_vectors.len()
+                            ),
+                        });
+                    }
+                    for sparse_vector in sparse_vectors.iter().flatten() {
+                        check_sparse_vector(sparse_vector)?;
+                    }
+                }
                 Ok(())
             }
         }
@@ -145,6 +177,13 @@ impl SplitByShard for PointsBatch {
                     .get_or_insert(Vec::new())
                     .push(payloads[i].clone())
             }
+            if let Some(sparse_vectors) = &batch.sparse_vectors {
+                shard_batch
+                    .batch
+                    .sparse_vectors
+                    .get_or_insert(Vec::new())
+                    .push(sparse_vectors[i].clone())
+            }
         }
         OperationToShard::by_shard(batch_by_shard)
     }
@@ -219,6 +258,7 @@ mod tests {
                 ids: vec![PointIdType::NumId(0)],
                 vectors: vec![],
                 payloads: None,
+                sparse_vectors: None,
             },
         });
         assert!(matches!(
@@ -231,6 +271,7 @@ mod tests {
                 ids: vec![PointIdType::NumId(0)],
                 vectors: vec![vec![0.1]],
                 payloads: None,
+                sparse_vectors: None,
             },
         });
         assert!(matches!(batch.validate(), Ok(())));
@@ -240,6 +281,23 @@ mod tests {
                 ids: vec![PointIdType::NumId(0)],
                 vectors: vec![vec![0.1]],
                 payloads: Some(vec![]),
+                sparse_vectors: None,
+            },
+        });
+        assert!(matches!(
+            batch.validate(),
+            Err(CollectionError::BadInput { description: _ })
+        ));
+
+        let batch = PointInsertOperations::PointsBatch(PointsBatch {
+            batch: Batch {
+                ids: vec![PointIdType::NumId(0)],
+                vectors: vec![vec![0.1]],
+                payloads: None,
+                sparse_vectors: Some(vec![Some(SparseVector {
+                    indices: vec![1, 1],
+                    values: vec![0.1, 0.2],
+                })]),
             },
         });
         assert!(matches!(
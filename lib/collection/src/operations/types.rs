@@ -12,10 +12,14 @@ use tokio::{
 
 use segment::entry::entry_point::OperationError;
 use segment::types::{
-    Filter, Payload, PayloadIndexInfo, PayloadKeyType, PointIdType, ScoreType, SearchParams,
-    SeqNumberType, VectorElementType, WithPayloadInterface,
+    Distance, FacetValue, Filter, Order, Payload, PayloadIndexInfo, PayloadKeyType, PointIdType,
+    QueryVector, ScoreType, ScoredPoint, SearchParams, SegmentExplain, SeqNumberType, SparseVector,
+    VectorElementType, WithPayloadInterface,
 };
 
+use crate::collection_manager::holders::segment_holder::SegmentId;
+use crate::operations::vector_input::{deserialize_vector, VectorInput};
+use crate::shard::ShardId;
 use crate::{config::CollectionConfig, wal::WalError};
 use std::collections::HashMap;
 use tonic::codegen::http::uri::InvalidUri;
@@ -114,6 +118,10 @@ pub struct ScrollRequest {
     /// Whether to return the point vector with the result?
     #[serde(default)]
     pub with_vector: bool,
+    /// Timeout for the request in seconds.
+    /// If the request is not completed within the timeout, it is cancelled and an error is returned.
+    /// If not specified - the request is not limited in time.
+    pub timeout: Option<u64>,
 }
 
 impl Default for ScrollRequest {
@@ -124,6 +132,7 @@ impl Default for ScrollRequest {
             filter: None,
             with_payload: Some(WithPayloadInterface::Bool(true)),
             with_vector: false,
+            timeout: None,
         }
     }
 }
@@ -145,6 +154,8 @@ pub struct ScrollResult {
 #[serde(rename_all = "snake_case")]
 pub struct SearchRequest {
     /// Look for vectors closest to this
+    #[serde(deserialize_with = "deserialize_vector")]
+    #[schemars(with = "VectorInput")]
     pub vector: Vec<VectorElementType>,
     /// Look only for points which satisfies this conditions
     pub filter: Option<Filter>,
@@ -152,6 +163,10 @@ pub struct SearchRequest {
     pub params: Option<SearchParams>,
     /// Max number of result to return
     pub top: usize,
+    /// Number of the best results to skip, e.g. to return the next page of results.
+    /// Skipped points are still searched for, so large offsets are as slow as large `top`.
+    #[serde(default)]
+    pub offset: usize,
     /// Select which payload to return with the response. Default: None
     pub with_payload: Option<WithPayloadInterface>,
     /// Whether to return the point vector with the result?
@@ -162,6 +177,358 @@ pub struct SearchRequest {
     /// Score of the returned result might be higher or smalled than the threshold depending on the
     /// Distance function used. E.g. for cosine similarity only higher scores will be returned.
     pub score_threshold: Option<ScoreType>,
+    /// Timeout for the request in seconds.
+    /// If the request is not completed within the timeout, it is cancelled and an error is returned.
+    /// If not specified - the request is not limited in time.
+    pub timeout: Option<u64>,
+    /// If specified - results are diversified with Maximal Marginal Relevance.
+    /// Returned scores are still the similarities to the query vector, but the order is defined by MMR.
+    pub diversity: Option<Diversity>,
+    /// If specified - scores of the most similar points are adjusted with their payload values.
+    /// Only available for distances, where larger score is better, so requests to collections
+    /// with `Euclid` distance are rejected. Can't be combined with `diversity`.
+    /// `score_threshold` is compared with the adjusted scores.
+    pub score_boost: Option<ScoreBoost>,
+    /// Whether to return the explanation of how each segment was searched: search strategy and
+    /// results of the filter conditions for the found points. Not available in REST, where the
+    /// explanation is returned by the `search/explain` endpoint.
+    #[serde(skip)]
+    pub with_explain: bool,
+}
+
+/// Parameters of the Maximal Marginal Relevance reranking.
+/// Points are selected one by one from the most relevant candidates, each next point maximizes
+/// `lambda * similarity_to_query - (1 - lambda) * max_similarity_to_selected_points`.
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub struct Diversity {
+    /// Trade-off between relevance and diversity, from 0 to 1.
+    /// 1 - only relevance is considered, 0 - only diversity.
+    pub lambda: f32,
+    /// Number of the most relevant points to select the results from. Should not be less than `top` and must not exceed 1000.
+    pub candidates: usize,
+}
+
+/// Formula to rescore the most similar points with numeric values of their payload.
+/// Final score of the point is `similarity + sum of all terms`, the score threshold of the request
+/// is applied to it. Not supported for `Euclid` distance, where smaller score is better.
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub struct ScoreBoost {
+    /// Terms to add to the similarity score
+    pub terms: Vec<BoostTerm>,
+    /// Number of the most similar points to rescore. Should not be less than `top`.
+    /// Points outside of the candidates are not returned, even if their boosted score would be higher.
+    pub candidates: usize,
+}
+
+/// Single term of the score boosting formula.
+/// Terms are computed from the payload field `key`. Numbers are used as is, strings are parsed as
+/// RFC 3339 datetimes in UTC and converted into seconds since the Unix epoch.
+/// If the field is missing or has no suitable value - the term is 0.
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum BoostTerm {
+    /// `weight * value`
+    Linear(LinearTerm),
+    /// `weight * exp(-|value - target| / scale)`
+    ExpDecay(DecayTerm),
+    /// `weight * exp(-((value - target) / scale)^2)`
+    GaussDecay(DecayTerm),
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub struct LinearTerm {
+    /// Payload field with the value
+    pub key: PayloadKeyType,
+    pub weight: f32,
+}
+
+/// Decays from `weight` at the `target` to 0 far from it
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub struct DecayTerm {
+    /// Payload field with the value
+    pub key: PayloadKeyType,
+    /// Value with the largest term. Default: current time, only suitable for datetime fields
+    pub target: Option<DecayTarget>,
+    /// Distance from the target, at which the term is decreased `e` times.
+    /// Should be positive, measured in seconds for datetime fields.
+    pub scale: f64,
+    pub weight: f32,
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum DecayTarget {
+    Number(f64),
+    /// RFC 3339 datetime in UTC
+    Datetime(String),
+}
+
+/// Search request, prepared for the execution on shards and segments.
+/// Unlike `SearchRequest`, the query is not limited to a single vector.
+#[derive(Debug, Clone)]
+pub struct CoreSearchRequest {
+    pub query: QueryVector,
+    pub filter: Option<Filter>,
+    pub params: Option<SearchParams>,
+    pub top: usize,
+    pub with_payload: Option<WithPayloadInterface>,
+    pub with_vector: bool,
+    pub with_explain: bool,
+    pub score_threshold: Option<ScoreType>,
+    pub timeout: Option<u64>,
+    pub score_boost: Option<ScoreBoost>,
+}
+
+impl CoreSearchRequest {
+    /// Which scores of the request are better, depends on the query and the distance function
+    pub fn score_order(&self, distance: Distance) -> Order {
+        match self.query {
+            QueryVector::Nearest(_) => distance.distance_order(),
+            QueryVector::Recommend(_) | QueryVector::Sparse(_) => Order::LargeBetter,
+        }
+    }
+
+    /// Convert score of the segment search into the score of the response
+    pub fn postprocess_score(&self, distance: Distance, score: ScoreType) -> ScoreType {
+        match self.query {
+            QueryVector::Nearest(_) => distance.postprocess_score(score),
+            QueryVector::Recommend(_) | QueryVector::Sparse(_) => score,
+        }
+    }
+
+    /// Checks if processed score satisfies threshold condition
+    pub fn check_threshold(
+        &self,
+        distance: Distance,
+        score: ScoreType,
+        threshold: ScoreType,
+    ) -> bool {
+        match self.query {
+            QueryVector::Nearest(_) => distance.check_threshold(score, threshold),
+            QueryVector::Recommend(_) | QueryVector::Sparse(_) => score > threshold,
+        }
+    }
+}
+
+impl From<SearchRequest> for CoreSearchRequest {
+    fn from(request: SearchRequest) -> Self {
+        CoreSearchRequest {
+            query: QueryVector::Nearest(request.vector),
+            filter: request.filter,
+            params: request.params,
+            top: request.top,
+            with_payload: request.with_payload,
+            with_vector: request.with_vector,
+            with_explain: request.with_explain,
+            score_threshold: request.score_threshold,
+            timeout: request.timeout,
+            score_boost: request.score_boost,
+        }
+    }
+}
+
+/// Search request with grouping of the results.
+/// Looks for the most similar points, same as `SearchRequest`, but returns them grouped by the
+/// value of the payload field. Each group contains the best points with the same value.
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct SearchGroupsRequest {
+    /// Look for vectors closest to this
+    #[serde(deserialize_with = "deserialize_vector")]
+    #[schemars(with = "VectorInput")]
+    pub vector: Vec<VectorElementType>,
+    /// Look only for points which satisfies this conditions
+    pub filter: Option<Filter>,
+    /// Additional search params
+    pub params: Option<SearchParams>,
+    /// Payload field to group by, must be a keyword or an integer.
+    /// Points without this field are not returned.
+    pub group_by: PayloadKeyType,
+    /// Max number of groups to return
+    pub groups: usize,
+    /// Max number of points in each group, `groups * group_size` must not exceed 10000
+    pub group_size: usize,
+    /// Select which payload to return with the response. Default: None
+    pub with_payload: Option<WithPayloadInterface>,
+    /// Whether to return the point vector with the result?
+    #[serde(default)]
+    pub with_vector: bool,
+    /// Define a minimal score threshold for the result.
+    /// If defined, less similar results will not be returned.
+    pub score_threshold: Option<ScoreType>,
+    /// Timeout for the request in seconds.
+    /// If the request is not completed within the timeout, it is cancelled and an error is returned.
+    /// If not specified - the request is not limited in time.
+    pub timeout: Option<u64>,
+}
+
+/// Value of the payload field, which identifies the group
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq, Eq, Hash)]
+#[serde(untagged)]
+pub enum GroupId {
+    Keyword(String),
+    Integer(i64),
+}
+
+/// Points with the same value of the grouping field
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone)]
+#[serde(rename_all = "snake_case")]
+pub struct PointGroup {
+    /// Value of the grouping field
+    pub id: GroupId,
+    /// Best points of the group, ordered by score
+    pub hits: Vec<ScoredPoint>,
+}
+
+/// Result of the search with the explanation of how each segment was searched
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, Default)]
+#[serde(rename_all = "snake_case")]
+pub struct SearchExplainResult {
+    /// Found points, same as the result of the search
+    pub points: Vec<ScoredPoint>,
+    /// Explanation of each searched segment, including segments where nothing was found
+    /// and segments whose points were cut from the result.
+    /// Empty if the explanation was not requested
+    pub segments: Vec<SegmentExplain>,
+}
+
+/// Result of the search with grouping
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone)]
+#[serde(rename_all = "snake_case")]
+pub struct GroupsResult {
+    /// Groups, ordered by the score of their best point
+    pub groups: Vec<PointGroup>,
+}
+
+/// Count points with each value of the keyword or integer payload field
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone)]
+#[serde(rename_all = "snake_case")]
+pub struct FacetRequest {
+    /// Payload field to count values of. Must be indexed as `keyword` or `integer`
+    pub key: PayloadKeyType,
+    /// Count only points which satisfy the conditions
+    pub filter: Option<Filter>,
+    /// Max number of values to return. Default: 10
+    pub limit: Option<usize>,
+}
+
+/// Value of the payload field with the number of points having it
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub struct FacetHit {
+    pub value: FacetValue,
+    /// Number of points with the value
+    pub count: usize,
+}
+
+/// Result of the facet counting
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone)]
+#[serde(rename_all = "snake_case")]
+pub struct FacetResult {
+    /// Most frequent values, ordered by the number of points
+    pub hits: Vec<FacetHit>,
+}
+
+/// Sample random points and find the most similar points for each of them among the sample
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone)]
+#[serde(rename_all = "snake_case")]
+pub struct DistanceMatrixRequest {
+    /// Sample only points which satisfy the conditions
+    pub filter: Option<Filter>,
+    /// Number of points to sample, must not exceed 1000. Default: 10
+    pub sample: Option<usize>,
+    /// Number of the nearest neighbours to return for each sampled point. Default: 3
+    pub limit: Option<usize>,
+}
+
+/// Scores between the sampled points and their nearest neighbours, as a sparse matrix in coordinate format.
+/// `scores[i]` is the score between `ids[rows[i]]` and `ids[cols[i]]`.
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub struct DistanceMatrix {
+    /// Sampled points
+    pub ids: Vec<PointIdType>,
+    /// Row of each score, index of the sampled point in `ids`
+    pub rows: Vec<usize>,
+    /// Column of each score, index of the neighbour in `ids`
+    pub cols: Vec<usize>,
+    /// Scores, from the most similar neighbour to the least similar one within each row
+    pub scores: Vec<ScoreType>,
+}
+
+/// Random sample of points, which satisfy the filter
+#[derive(Debug, Clone, PartialEq)]
+pub struct PointsSample {
+    pub ids: Vec<PointIdType>,
+    /// Number of points the sample is taken from
+    pub total: usize,
+}
+
+/// Search for the nearest neighbours of the sampled points among the sample itself
+#[derive(Debug, Clone)]
+pub struct SampleNeighboursRequest {
+    /// Sampled points with their stored vectors.
+    /// If vector is not set, it is read from the shard, which stores the point. Points of
+    /// other shards are not compared with such a query.
+    pub queries: Vec<(PointIdType, Option<VectorType>)>,
+    /// Max number of neighbours of each point
+    pub top: usize,
+}
+
+/// How to combine the results of the dense and the sparse search
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Fusion {
+    /// Reciprocal rank fusion: each point gets `1 / (k + rank)` from each list it is found in.
+    /// Only ranks are used, so scores of different scales could be combined.
+    Rrf,
+    /// Weighted sum of scores, min-max normalized within each list.
+    /// Points, not found in one of the lists, get 0 from it.
+    WeightedSum,
+}
+
+impl Default for Fusion {
+    fn default() -> Self {
+        Fusion::Rrf
+    }
+}
+
+/// Hybrid search request.
+/// Searches for the closest dense vectors and for the largest dot products of sparse vectors
+/// separately, then fuses both result lists into one.
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone)]
+#[serde(rename_all = "snake_case")]
+pub struct HybridSearchRequest {
+    /// Look for vectors closest to this
+    #[serde(deserialize_with = "deserialize_vector")]
+    #[schemars(with = "VectorInput")]
+    pub vector: Vec<VectorElementType>,
+    /// Look for sparse vectors with the largest dot product with this
+    pub sparse_vector: SparseVector,
+    /// How to combine the results of both searches. Default: rrf
+    pub fusion: Option<Fusion>,
+    /// Weight of the dense scores in `weighted_sum` fusion, from 0 to 1.
+    /// Sparse scores get `1 - dense_weight`. Default: 0.5
+    pub dense_weight: Option<f32>,
+    /// Look only for points which satisfies this conditions
+    pub filter: Option<Filter>,
+    /// Additional search params of the dense search
+    pub params: Option<SearchParams>,
+    /// Max number of result to return, each of the searches also looks for this number of points
+    pub top: usize,
+    /// Select which payload to return with the response. Default: None
+    pub with_payload: Option<WithPayloadInterface>,
+    /// Whether to return the point vector with the result?
+    #[serde(default)]
+    pub with_vector: bool,
+    /// Timeout for the request in seconds.
+    /// If the request is not completed within the timeout, it is cancelled and an error is returned.
+    /// If not specified - the request is not limited in time.
+    pub timeout: Option<u64>,
 }
 
 #[derive(Debug, Deserialize, Serialize, JsonSchema)]
@@ -176,9 +543,52 @@ pub struct PointRequest {
     pub with_vector: bool,
 }
 
+/// How to use positive and negative examples to find the results
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RecommendStrategy {
+    /// Average positive and negative vectors and create a single query with the formula
+    /// `query = avg_pos + avg_pos - avg_neg`. Then performs normal search.
+    AverageVector,
+    /// Score each candidate against every example separately.
+    /// If the candidate is closer to some positive example than to any negative one,
+    /// it gets a positive score, growing with the similarity to the best positive example.
+    /// Otherwise the score is negative. Works better with diverse positive examples.
+    /// Scores are in range (-1, 1), larger is better regardless of the distance function.
+    BestScore,
+}
+
+impl Default for RecommendStrategy {
+    fn default() -> Self {
+        RecommendStrategy::AverageVector
+    }
+}
+
+/// Example of the recommendation request
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum RecommendExample {
+    /// Id of the point, stored in the collection
+    PointId(PointIdType),
+    /// Vector of the example, e.g. an embedding of the user session
+    Vector(VectorType),
+}
+
+impl From<PointIdType> for RecommendExample {
+    fn from(id: PointIdType) -> Self {
+        RecommendExample::PointId(id)
+    }
+}
+
+impl From<VectorType> for RecommendExample {
+    fn from(vector: VectorType) -> Self {
+        RecommendExample::Vector(vector)
+    }
+}
+
 /// Recommendation request.
 /// Provides positive and negative examples of the vectors, which
-/// are already stored in the collection.
+/// are either stored in the collection or given explicitly.
 ///
 /// Service should look for the points which are closer to positive examples and at the same time
 /// further to negative examples. The concrete way of how to compare negative and positive distances
@@ -187,15 +597,22 @@ pub struct PointRequest {
 #[serde(rename_all = "snake_case")]
 pub struct RecommendRequest {
     /// Look for vectors closest to those
-    pub positive: Vec<PointIdType>,
+    pub positive: Vec<RecommendExample>,
     /// Try to avoid vectors like this
-    pub negative: Vec<PointIdType>,
+    pub negative: Vec<RecommendExample>,
+    /// How to use positive and negative examples. Default: `average_vector`
+    #[serde(default)]
+    pub strategy: RecommendStrategy,
     /// Look only for points which satisfies this conditions
     pub filter: Option<Filter>,
     /// Additional search params
     pub params: Option<SearchParams>,
     /// Max number of result to return
     pub top: usize,
+    /// Number of the best results to skip, e.g. to return the next page of results.
+    /// Skipped points are still searched for, so large offsets are as slow as large `top`.
+    #[serde(default)]
+    pub offset: usize,
     /// Select which payload to return with the response. Default: None
     pub with_payload: Option<WithPayloadInterface>,
     /// Whether to return the point vector with the result?
@@ -206,6 +623,82 @@ pub struct RecommendRequest {
     /// Score of the returned result might be higher or smalled than the threshold depending on the
     /// Distance function used. E.g. for cosine similarity only higher scores will be returned.
     pub score_threshold: Option<ScoreType>,
+    /// Timeout for the request in seconds.
+    /// If the request is not completed within the timeout, it is cancelled and an error is returned.
+    /// If not specified - the request is not limited in time.
+    pub timeout: Option<u64>,
+}
+
+/// Request for evaluation of the vector index quality.
+/// Stored points are used as queries, results of the index search are compared with the exact search.
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone)]
+#[serde(rename_all = "snake_case")]
+pub struct RecallRequest {
+    /// Max number of points, sampled from each indexed segment to be used as queries
+    pub sample: usize,
+    /// Number of nearest neighbours to compare
+    pub top: usize,
+    /// Size of the beam of the index search. Index default is used if not specified
+    pub hnsw_ef: Option<usize>,
+}
+
+/// Quality of the vector index of a single segment
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone)]
+#[serde(rename_all = "snake_case")]
+pub struct SegmentRecall {
+    /// Shard, which holds the segment
+    pub shard_id: ShardId,
+    /// Id of the segment within the shard
+    pub segment_id: SegmentId,
+    /// Number of sampled points, used as queries
+    pub queries: usize,
+    /// Mean share of the exact nearest neighbours, found by the index search
+    pub recall: f64,
+    /// Mean time of the index search in seconds
+    pub mean_search_time: f64,
+    /// Size of the beam, used by the index search
+    pub ef: usize,
+}
+
+/// Quality of the vector index of the collection
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone)]
+#[serde(rename_all = "snake_case")]
+pub struct RecallReport {
+    /// Quality of each indexed segment
+    pub segments: Vec<SegmentRecall>,
+    /// Number of queries in all segments
+    pub queries: usize,
+    /// Mean recall of all queries
+    pub recall: f64,
+    /// Mean time of the index search of all queries in seconds
+    pub mean_search_time: f64,
+}
+
+impl RecallReport {
+    pub fn new(segments: Vec<SegmentRecall>) -> Self {
+        let queries: usize = segments.iter().map(|segment| segment.queries).sum();
+        let (recall_sum, search_time_sum) =
+            segments.iter().fold((0.0, 0.0), |(recall, time), segment| {
+                (
+                    recall + segment.recall * segment.queries as f64,
+                    time + segment.mean_search_time * segment.queries as f64,
+                )
+            });
+        let (recall, mean_search_time) = if queries > 0 {
+            (
+                recall_sum / queries as f64,
+                search_time_sum / queries as f64,
+            )
+        } else {
+            (0.0, 0.0)
+        };
+        RecallReport {
+            segments,
+            queries,
+            recall,
+            mean_search_time,
+        }
+    }
 }
 
 #[derive(Error, Debug, Clone)]
@@ -221,6 +714,10 @@ pub enum CollectionError {
     BadRequest { description: String },
     #[error("Operation Cancelled: {description}")]
     Cancelled { description: String },
+    #[error("Timeout error: {description}")]
+    Timeout { description: String },
+    #[error("Too many requests: {description}")]
+    ResourceExhausted { description: String },
     #[error("Bad shard selection: {description}")]
     BadShardSelection { description: String },
     #[error(
@@ -241,6 +738,15 @@ impl CollectionError {
     pub fn bad_shard_selection(description: String) -> CollectionError {
         CollectionError::BadShardSelection { description }
     }
+
+    pub fn timeout(timeout_sec: u64, operation: &str) -> CollectionError {
+        CollectionError::Timeout {
+            description: format!(
+                "Operation '{}' timed out after {} seconds",
+                operation, timeout_sec
+            ),
+        }
+    }
 }
 
 impl From<OperationError> for CollectionError {
@@ -249,6 +755,9 @@ impl From<OperationError> for CollectionError {
             OperationError::WrongVector { .. } => Self::BadInput {
                 description: format!("{}", err),
             },
+            OperationError::WrongSparseVector { .. } => Self::BadInput {
+                description: format!("{}", err),
+            },
             OperationError::PointIdError { missed_point_id } => Self::NotFound { missed_point_id },
             OperationError::ServiceError { description } => {
                 Self::ServiceError { error: description }
@@ -260,6 +769,9 @@ impl From<OperationError> for CollectionError {
             OperationError::TypeInferenceError { .. } => Self::BadInput {
                 description: format!("{}", err),
             },
+            OperationError::WrongConfig { .. } => Self::BadInput {
+                description: format!("{}", err),
+            },
         }
     }
 }
@@ -340,6 +852,12 @@ impl From<tonic::Status> for CollectionError {
             tonic::Code::Internal => CollectionError::ServiceError {
                 error: "Internal error".to_string(),
             },
+            tonic::Code::DeadlineExceeded => CollectionError::Timeout {
+                description: err.message().to_string(),
+            },
+            tonic::Code::ResourceExhausted => CollectionError::ResourceExhausted {
+                description: err.message().to_string(),
+            },
             other => CollectionError::ServiceError {
                 error: format!("Tonic status error: {}", other),
             },
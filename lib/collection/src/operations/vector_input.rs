@@ -0,0 +1,168 @@
+//! Vectors are accepted by API in several formats, but always stored as arrays of numbers.
+//! Binary vectors could be passed as arrays of booleans or as base64 encoded bytes.
+
+use std::fmt;
+
+use schemars::JsonSchema;
+use segment::spaces::binary::unpack_bytes;
+use segment::types::VectorElementType;
+use serde::de::{self, SeqAccess, Visitor};
+use serde::{Deserialize, Deserializer};
+
+use crate::operations::types::VectorType;
+
+/// Vector data, only used to describe accepted formats in API schema
+#[derive(JsonSchema)]
+#[serde(untagged)]
+#[allow(dead_code)]
+pub enum VectorInput {
+    /// Vector elements
+    Numbers(Vec<VectorElementType>),
+    /// Binary vector elements, `true` is the same as `1.0`
+    Bits(Vec<bool>),
+    /// Base64 encoded bytes of binary vector, most significant bit of each byte goes first
+    Base64(String),
+}
+
+/// Deserialize vector from any of formats, listed in `VectorInput`
+pub fn deserialize_vector<'de, D>(deserializer: D) -> Result<VectorType, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    deserializer.deserialize_any(VectorVisitor)
+}
+
+/// Same as `deserialize_vector`, but for a list of vectors
+pub fn deserialize_vectors<'de, D>(deserializer: D) -> Result<Vec<VectorType>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    struct Wrapper(#[serde(deserialize_with = "deserialize_vector")] VectorType);
+
+    let vectors = Vec::<Wrapper>::deserialize(deserializer)?;
+    Ok(vectors.into_iter().map(|Wrapper(vector)| vector).collect())
+}
+
+struct VectorVisitor;
+
+impl<'de> Visitor<'de> for VectorVisitor {
+    type Value = VectorType;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("array of numbers, array of booleans or base64 encoded string")
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<Self::Value, E> {
+        let bytes = base64::decode(value)
+            .map_err(|err| E::custom(format!("Invalid base64 vector: {}", err)))?;
+        Ok(unpack_bytes(&bytes))
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut vector = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+        while let Some(VectorElement(value)) = seq.next_element()? {
+            vector.push(value);
+        }
+        Ok(vector)
+    }
+}
+
+/// Either a number or a boolean
+struct VectorElement(VectorElementType);
+
+impl<'de> Deserialize<'de> for VectorElement {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(VectorElementVisitor)
+    }
+}
+
+struct VectorElementVisitor;
+
+impl<'de> Visitor<'de> for VectorElementVisitor {
+    type Value = VectorElement;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("number or boolean")
+    }
+
+    fn visit_bool<E: de::Error>(self, value: bool) -> Result<Self::Value, E> {
+        Ok(VectorElement(if value { 1.0 } else { 0.0 }))
+    }
+
+    fn visit_i64<E: de::Error>(self, value: i64) -> Result<Self::Value, E> {
+        Ok(VectorElement(value as VectorElementType))
+    }
+
+    fn visit_u64<E: de::Error>(self, value: u64) -> Result<Self::Value, E> {
+        Ok(VectorElement(value as VectorElementType))
+    }
+
+    fn visit_f64<E: de::Error>(self, value: f64) -> Result<Self::Value, E> {
+        Ok(VectorElement(value as VectorElementType))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::operations::point_ops::{PointInsertOperations, PointStruct};
+    use crate::operations::types::SearchRequest;
+    use segment::types::ExtendedPointId;
+
+    #[test]
+    fn test_vector_formats() {
+        let request: SearchRequest =
+            serde_json::from_str(r#"{"vector": [true, false, 1, 0.5], "top": 3}"#).unwrap();
+        assert_eq!(request.vector, vec![1.0, 0.0, 1.0, 0.5]);
+
+        // 0b10100000 0b00000001
+        let request: SearchRequest =
+            serde_json::from_str(r#"{"vector": "oAE=", "top": 3}"#).unwrap();
+        assert_eq!(
+            request.vector,
+            vec![1.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 1.0]
+        );
+
+        assert!(serde_json::from_str::<SearchRequest>(r#"{"vector": "o!", "top": 3}"#).is_err());
+
+        let operation: PointInsertOperations = serde_json::from_str(
+            r#"{"batch": {"ids": [1, 2], "vectors": ["gA==", [false, true]]}}"#,
+        )
+        .unwrap();
+        match operation {
+            PointInsertOperations::PointsBatch(batch) => assert_eq!(
+                batch.batch.vectors,
+                vec![vec![1.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0], vec![0.0, 1.0]]
+            ),
+            PointInsertOperations::PointsList(_) => panic!("Batch is expected"),
+        }
+
+        let operation: PointInsertOperations =
+            serde_json::from_str(r#"{"points": [{"id": 1, "vector": [1, 0, true]}]}"#).unwrap();
+        match operation {
+            PointInsertOperations::PointsList(list) => {
+                assert_eq!(list.points[0].vector, vec![1.0, 0.0, 1.0])
+            }
+            PointInsertOperations::PointsBatch(_) => panic!("List is expected"),
+        }
+    }
+
+    #[test]
+    fn test_grpc_binary_vector() {
+        let grpc_point = api::grpc::qdrant::PointStruct {
+            id: Some(ExtendedPointId::from(1).into()),
+            vector: vec![],
+            payload: Default::default(),
+            sparse_vector: None,
+            binary_vector: vec![0b1000_0001],
+        };
+        let point = PointStruct::try_from(grpc_point.clone()).unwrap();
+        assert_eq!(point.vector, vec![1.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 1.0]);
+
+        let ambiguous_point = api::grpc::qdrant::PointStruct {
+            vector: vec![1.0],
+            ..grpc_point
+        };
+        assert!(PointStruct::try_from(ambiguous_point).is_err());
+    }
+}
@@ -3,10 +3,13 @@ use crate::collection_manager::optimizers::merge_optimizer::MergeOptimizer;
 use crate::collection_manager::optimizers::segment_optimizer::OptimizerThresholds;
 use crate::collection_manager::optimizers::vacuum_optimizer::VacuumOptimizer;
 use crate::config::CollectionParams;
+use crate::operations::types::{CollectionError, CollectionResult};
 use crate::update_handler::Optimizer;
+use rayon::{ThreadPool, ThreadPoolBuilder};
 use schemars::JsonSchema;
 use segment::types::HnswConfig;
 use serde::{Deserialize, Serialize};
+use std::cmp::max;
 use std::path::Path;
 use std::sync::Arc;
 
@@ -43,6 +46,35 @@ pub struct OptimizersConfig {
     pub flush_interval_sec: u64,
     /// Maximum available threads for optimization workers
     pub max_optimization_threads: usize,
+    /// Maximum number of update operations, waiting to be applied to a shard.
+    /// New updates are rejected while the queue is full. If not set or 0 - the queue is not limited.
+    #[serde(default)]
+    pub max_queued_updates: Option<usize>,
+}
+
+impl OptimizersConfig {
+    /// Number of threads available for optimizations, `0` in config means all CPUs but one
+    pub fn get_max_optimization_threads(&self) -> usize {
+        if self.max_optimization_threads == 0 {
+            max(num_cpus::get() - 1, 1)
+        } else {
+            self.max_optimization_threads
+        }
+    }
+}
+
+/// Builds thread pool, used by optimizers of all shards of the collection to construct HNSW graphs
+pub fn build_indexing_pool(
+    optimizers_config: &OptimizersConfig,
+) -> CollectionResult<Arc<ThreadPool>> {
+    let indexing_pool = ThreadPoolBuilder::new()
+        .thread_name(|idx| format!("hnsw-build-{idx}"))
+        .num_threads(optimizers_config.get_max_optimization_threads())
+        .build()
+        .map_err(|err| CollectionError::ServiceError {
+            error: format!("Failed to create indexing thread pool: {err}"),
+        })?;
+    Ok(Arc::new(indexing_pool))
 }
 
 pub fn build_optimizers(
@@ -50,6 +82,7 @@ pub fn build_optimizers(
     collection_params: &CollectionParams,
     optimizers_config: &OptimizersConfig,
     hnsw_config: &HnswConfig,
+    indexing_pool: Arc<ThreadPool>,
 ) -> Arc<Vec<Arc<Optimizer>>> {
     let segments_path = shard_path.join("segments");
     let temp_segments_path = shard_path.join("temp_segments");
@@ -58,6 +91,7 @@ pub fn build_optimizers(
         memmap_threshold: optimizers_config.memmap_threshold,
         indexing_threshold: optimizers_config.indexing_threshold,
         payload_indexing_threshold: optimizers_config.payload_indexing_threshold,
+        indexing_pool,
     };
 
     Arc::new(vec![
@@ -88,3 +122,46 @@ pub fn build_optimizers(
         )),
     ])
 }
+
+/// Builds optimizers, which are used to optimize collection on explicit user request.
+///
+/// Merge and indexing thresholds are ignored: all segments, which could be merged within
+/// `max_segment_size`, are merged, and all non-empty segments are indexed.
+/// Memmap threshold is preserved, as it defines storage type rather than optimization urgency.
+pub fn build_forced_optimizers(
+    shard_path: &Path,
+    collection_params: &CollectionParams,
+    optimizers_config: &OptimizersConfig,
+    hnsw_config: &HnswConfig,
+    indexing_pool: Arc<ThreadPool>,
+) -> Arc<Vec<Arc<Optimizer>>> {
+    let segments_path = shard_path.join("segments");
+    let temp_segments_path = shard_path.join("temp_segments");
+
+    let threshold_config = OptimizerThresholds {
+        memmap_threshold: optimizers_config.memmap_threshold,
+        // Empty segments are not worth indexing
+        indexing_threshold: 1,
+        payload_indexing_threshold: 1,
+        indexing_pool,
+    };
+
+    Arc::new(vec![
+        Arc::new(MergeOptimizer::new(
+            1,
+            optimizers_config.max_segment_size,
+            threshold_config.clone(),
+            segments_path.clone(),
+            temp_segments_path.clone(),
+            collection_params.clone(),
+            *hnsw_config,
+        )),
+        Arc::new(IndexingOptimizer::new(
+            threshold_config,
+            segments_path,
+            temp_segments_path,
+            collection_params.clone(),
+            *hnsw_config,
+        )),
+    ])
+}
@@ -0,0 +1,225 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use segment::types::{Distance, Order, Payload, ScoreType};
+use serde_json::Value;
+
+use crate::operations::types::{
+    BoostTerm, CollectionError, CollectionResult, DecayTarget, ScoreBoost,
+};
+
+/// Seconds since the Unix epoch, negative for earlier times
+fn timestamp(time: SystemTime) -> f64 {
+    match time.duration_since(UNIX_EPOCH) {
+        Ok(duration) => duration.as_secs_f64(),
+        Err(err) => -err.duration().as_secs_f64(),
+    }
+}
+
+/// Parse RFC 3339 datetime in UTC into seconds since the Unix epoch
+fn parse_datetime(datetime: &str) -> Option<f64> {
+    humantime::parse_rfc3339_weak(datetime).ok().map(timestamp)
+}
+
+/// Numeric value of the payload field for the boosting terms.
+/// For arrays the first suitable value is used.
+fn numeric_value(value: &Value) -> Option<f64> {
+    match value {
+        Value::Number(number) => number.as_f64(),
+        Value::String(datetime) => parse_datetime(datetime),
+        Value::Array(values) => values.iter().find_map(numeric_value),
+        _ => None,
+    }
+}
+
+/// Check that the score boosting could be applied to the search request.
+/// Decay terms without target are centered at `now`, so that all shards and segments boost the
+/// points relative to the same moment.
+pub fn check_score_boost(
+    score_boost: &mut ScoreBoost,
+    distance: Distance,
+    top: usize,
+    now: SystemTime,
+) -> CollectionResult<()> {
+    if let Order::SmallBetter = distance.distance_order() {
+        return Err(CollectionError::BadRequest {
+            description: format!(
+                "Score boosting is not supported for {:?} distance",
+                distance
+            ),
+        });
+    }
+    if score_boost.candidates < top {
+        return Err(CollectionError::BadRequest {
+            description: "Number of score boosting candidates should not be less than offset + top"
+                .to_owned(),
+        });
+    }
+    for term in &mut score_boost.terms {
+        if let BoostTerm::ExpDecay(decay) | BoostTerm::GaussDecay(decay) = term {
+            decay
+                .target
+                .get_or_insert(DecayTarget::Number(timestamp(now)));
+        }
+    }
+    ScoreBooster::new(score_boost).map(|_| ())
+}
+
+/// Computes the score boosting formula for the payload of the points.
+/// Targets of the decay terms are resolved once, on creation.
+pub struct ScoreBooster<'a> {
+    terms: Vec<(&'a BoostTerm, f64)>,
+}
+
+impl<'a> ScoreBooster<'a> {
+    /// All decay terms must have targets, they are set by `check_score_boost`
+    pub fn new(score_boost: &'a ScoreBoost) -> CollectionResult<Self> {
+        let terms = score_boost
+            .terms
+            .iter()
+            .map(|term| {
+                let decay = match term {
+                    BoostTerm::Linear(_) => return Ok((term, 0.0)),
+                    BoostTerm::ExpDecay(decay) | BoostTerm::GaussDecay(decay) => decay,
+                };
+                if decay.scale <= 0.0 {
+                    return Err(CollectionError::BadRequest {
+                        description: format!("Decay scale of `{}` must be positive", decay.key),
+                    });
+                }
+                let target = match &decay.target {
+                    None => {
+                        return Err(CollectionError::ServiceError {
+                            error: format!("Decay target of `{}` is not set", decay.key),
+                        })
+                    }
+                    Some(DecayTarget::Number(number)) => *number,
+                    Some(DecayTarget::Datetime(datetime)) => {
+                        parse_datetime(datetime).ok_or_else(|| CollectionError::BadRequest {
+                            description: format!(
+                                "Decay target `{}` is not a valid datetime",
+                                datetime
+                            ),
+                        })?
+                    }
+                };
+                Ok((term, target))
+            })
+            .collect::<CollectionResult<_>>()?;
+        Ok(ScoreBooster { terms })
+    }
+
+    /// Sum of all terms of the formula for the point with given payload
+    pub fn boost(&self, payload: &Payload) -> ScoreType {
+        self.terms
+            .iter()
+            .map(|&(term, target)| {
+                let (key, weight) = match term {
+                    BoostTerm::Linear(linear) => (&linear.key, linear.weight),
+                    BoostTerm::ExpDecay(decay) | BoostTerm::GaussDecay(decay) => {
+                        (&decay.key, decay.weight)
+                    }
+                };
+                let value = match payload.get_value(key).and_then(numeric_value) {
+                    None => return 0.0,
+                    Some(value) => value,
+                };
+                let factor = match term {
+                    BoostTerm::Linear(_) => value,
+                    BoostTerm::ExpDecay(decay) => (-(value - target).abs() / decay.scale).exp(),
+                    BoostTerm::GaussDecay(decay) => {
+                        (-((value - target) / decay.scale).powi(2)).exp()
+                    }
+                };
+                weight * factor as ScoreType
+            })
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use serde_json::json;
+
+    use super::*;
+    use crate::operations::types::{DecayTerm, LinearTerm};
+
+    fn payload(value: Value) -> Payload {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn test_score_booster() {
+        let mut score_boost = ScoreBoost {
+            terms: vec![
+                BoostTerm::Linear(LinearTerm {
+                    key: "popularity".to_string(),
+                    weight: 0.1,
+                }),
+                BoostTerm::ExpDecay(DecayTerm {
+                    key: "published".to_string(),
+                    target: None,
+                    scale: 86400.0,
+                    weight: 1.0,
+                }),
+                BoostTerm::GaussDecay(DecayTerm {
+                    key: "price".to_string(),
+                    target: Some(DecayTarget::Number(100.0)),
+                    scale: 10.0,
+                    weight: 0.5,
+                }),
+            ],
+            candidates: 10,
+        };
+        // 2022-06-02T00:00:00Z
+        let now = UNIX_EPOCH + Duration::from_secs(1654128000);
+        check_score_boost(&mut score_boost, Distance::Cosine, 10, now).unwrap();
+        // Decay without target is centered at the time of the check
+        assert!(matches!(
+            &score_boost.terms[1],
+            BoostTerm::ExpDecay(DecayTerm {
+                target: Some(DecayTarget::Number(target)),
+                ..
+            }) if *target == 1654128000.0
+        ));
+        let booster = ScoreBooster::new(&score_boost).unwrap();
+
+        assert_eq!(booster.boost(&payload(json!({"color": "red"}))), 0.0);
+        assert!((booster.boost(&payload(json!({"popularity": [5, 7]}))) - 0.5).abs() < 1e-6);
+
+        let published_now = booster.boost(&payload(json!({"published": "2022-06-02T00:00:00Z"})));
+        assert!((published_now - 1.0).abs() < 1e-6);
+        let published_yesterday =
+            booster.boost(&payload(json!({"published": "2022-06-01T00:00:00Z"})));
+        assert!((published_yesterday - (-1.0f32).exp()).abs() < 1e-6);
+
+        let price = booster.boost(&payload(json!({"price": 90, "popularity": "unknown"})));
+        assert!((price - 0.5 * (-1.0f32).exp()).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_check_score_boost() {
+        let mut score_boost = ScoreBoost {
+            terms: vec![BoostTerm::ExpDecay(DecayTerm {
+                key: "published".to_string(),
+                target: Some(DecayTarget::Datetime("2022-06-01T00:00:00Z".to_string())),
+                scale: 3600.0,
+                weight: 1.0,
+            })],
+            candidates: 10,
+        };
+        let now = SystemTime::now();
+        assert!(check_score_boost(&mut score_boost, Distance::Cosine, 10, now).is_ok());
+        assert!(check_score_boost(&mut score_boost, Distance::Cosine, 20, now).is_err());
+        assert!(check_score_boost(&mut score_boost, Distance::Euclid, 10, now).is_err());
+
+        score_boost.terms[0] = BoostTerm::ExpDecay(DecayTerm {
+            key: "published".to_string(),
+            target: Some(DecayTarget::Datetime("yesterday".to_string())),
+            scale: 3600.0,
+            weight: 1.0,
+        });
+        assert!(check_score_boost(&mut score_boost, Distance::Dot, 10, now).is_err());
+    }
+}
@@ -1,5 +1,6 @@
 use crate::operations::payload_ops::{DeletePayload, SetPayload};
 use crate::operations::point_ops::PointInsertOperations;
+use crate::operations::types::{CoreSearchRequest, FacetRequest, SampleNeighboursRequest};
 use crate::operations::CreateIndex;
 use crate::shard::remote_shard::RemoteShard;
 use crate::{CollectionError, CollectionResult};
@@ -9,10 +10,12 @@ use api::grpc::qdrant::{
     ClearPayloadPoints, ClearPayloadPointsInternal, CreateFieldIndexCollection,
     CreateFieldIndexCollectionInternal, DeleteFieldIndexCollection,
     DeleteFieldIndexCollectionInternal, DeletePayloadPoints, DeletePayloadPointsInternal,
-    DeletePoints, DeletePointsInternal, PointsIdsList, PointsSelector, SetPayloadPoints,
-    SetPayloadPointsInternal, UpsertPoints, UpsertPointsInternal,
+    DeletePoints, DeletePointsInternal, FacetPoints, FacetPointsInternal, PointsIdsList,
+    PointsSelector, SampleNeighboursInternal, SamplePointsInternal, SampleQuery, SearchPoints,
+    SearchPointsInternal, SetPayloadPoints, SetPayloadPointsInternal, UpsertPoints,
+    UpsertPointsInternal,
 };
-use segment::types::{Filter, PointIdType};
+use segment::types::{Filter, PointIdType, QueryVector};
 use tonic::Status;
 
 pub fn internal_upsert_points(
@@ -167,3 +170,82 @@ pub fn internal_delete_index(
         }),
     }
 }
+
+pub fn internal_search_points(
+    request: &CoreSearchRequest,
+    shard: &RemoteShard,
+) -> SearchPointsInternal {
+    // Vector of the search request is not used if the recommendation or sparse query is present
+    let (vector, recommend_query, sparse_query) = match &request.query {
+        QueryVector::Nearest(vector) => (vector.clone(), None, None),
+        QueryVector::Recommend(query) => (vec![], Some(query.clone().into()), None),
+        QueryVector::Sparse(query) => (vec![], None, Some(query.clone().into())),
+    };
+    SearchPointsInternal {
+        shard_id: shard.id,
+        search_points: Some(SearchPoints {
+            collection_name: shard.collection_id.clone(),
+            vector,
+            filter: request.filter.clone().map(|f| f.into()),
+            top: request.top as u64,
+            with_vector: Some(request.with_vector),
+            with_payload: request.with_payload.clone().map(|wp| wp.into()),
+            params: request.params.map(|sp| sp.into()),
+            score_threshold: request.score_threshold,
+            timeout: request.timeout,
+            diversity: None,
+            score_boost: request.score_boost.clone().map(|b| b.into()),
+            // Offset is applied after merging the results of all shards
+            offset: None,
+            with_explain: Some(request.with_explain),
+            binary_vector: vec![],
+        }),
+        recommend_query,
+        sparse_query,
+    }
+}
+
+pub fn internal_facet_points(request: &FacetRequest, shard: &RemoteShard) -> FacetPointsInternal {
+    FacetPointsInternal {
+        shard_id: shard.id,
+        facet_points: Some(FacetPoints {
+            collection_name: shard.collection_id.clone(),
+            key: request.key.clone(),
+            filter: request.filter.clone().map(|f| f.into()),
+            // Counts of all values are needed to merge the results of the shards exactly
+            limit: Some(u64::MAX),
+        }),
+    }
+}
+
+pub fn internal_sample_points(
+    filter: Option<&Filter>,
+    size: usize,
+    shard: &RemoteShard,
+) -> SamplePointsInternal {
+    SamplePointsInternal {
+        collection_name: shard.collection_id.clone(),
+        filter: filter.cloned().map(|f| f.into()),
+        size: size as u64,
+        shard_id: shard.id,
+    }
+}
+
+pub fn internal_sample_neighbours(
+    request: &SampleNeighboursRequest,
+    shard: &RemoteShard,
+) -> SampleNeighboursInternal {
+    SampleNeighboursInternal {
+        collection_name: shard.collection_id.clone(),
+        queries: request
+            .queries
+            .iter()
+            .map(|(id, vector)| SampleQuery {
+                id: Some((*id).into()),
+                vector: vector.clone().unwrap_or_default(),
+            })
+            .collect(),
+        top: request.top as u64,
+        shard_id: shard.id,
+    }
+}
@@ -9,33 +9,39 @@ use async_trait::async_trait;
 use indicatif::ProgressBar;
 use itertools::Itertools;
 use parking_lot::RwLock;
-use segment::segment_constructor::simple_segment_constructor::build_simple_segment;
-use std::cmp::max;
+use rand::Rng;
+use rayon::ThreadPool;
+use segment::segment_constructor::simple_segment_constructor::build_appendable_segment;
 use std::fs::create_dir_all;
-use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use tokio::runtime::{self, Handle, Runtime};
 use tokio::sync::{mpsc, mpsc::UnboundedSender, oneshot, Mutex, RwLock as TokioRwLock};
 
 use segment::types::{
-    ExtendedPointId, Filter, PayloadIndexInfo, PayloadKeyType, ScoredPoint, SegmentType,
-    WithPayload, WithPayloadInterface,
+    ExtendedPointId, FacetValue, Filter, Indexes, PayloadIndexInfo, PayloadKeyType,
+    PayloadSchemaType, ScoredPoint, SegmentType, WithPayload, WithPayloadInterface,
 };
 
 use crate::collection_manager::collection_managers::CollectionSearcher;
 use crate::collection_manager::collection_updater::CollectionUpdater;
-use crate::collection_manager::holders::segment_holder::SegmentHolder;
+use crate::collection_manager::holders::segment_holder::{LockedSegment, SegmentHolder};
+use crate::common::request_timeout::with_timeout;
 use crate::config::CollectionConfig;
-use crate::operations::config_diff::{DiffConfig, OptimizersConfigDiff};
+use crate::distance_matrix::merge_neighbours;
 use crate::operations::types::{
-    CollectionError, CollectionInfo, CollectionResult, CollectionStatus, OptimizersStatus, Record,
-    UpdateResult, UpdateStatus,
+    CollectionError, CollectionInfo, CollectionResult, CollectionStatus, CoreSearchRequest,
+    FacetHit, FacetRequest, OptimizersStatus, PointsSample, RecallRequest, Record,
+    SampleNeighboursRequest, SearchExplainResult, SegmentRecall, UpdateResult, UpdateStatus,
 };
 use crate::operations::CollectionUpdateOperations;
-use crate::optimizers_builder::{build_optimizers, OptimizersConfig};
+use crate::optimizers_builder::{build_forced_optimizers, build_optimizers};
 use crate::shard::ShardOperation;
-use crate::update_handler::{OperationData, Optimizer, UpdateHandler, UpdateSignal};
+use crate::update_handler::{
+    OperationData, Optimizer, QueuedUpdateGuard, UpdateHandler, UpdateSignal,
+};
 use crate::wal::SerdeWal;
-use crate::{CollectionId, PointRequest, SearchRequest, ShardId};
+use crate::{CollectionId, PointRequest, ShardId};
+use segment::entry::entry_point::SegmentEntry;
 use segment::segment_constructor::load_segment;
 use std::fs::{read_dir, remove_dir_all};
 
@@ -51,6 +57,8 @@ pub struct LocalShard {
     update_handler: Arc<Mutex<UpdateHandler>>,
     runtime_handle: Option<Runtime>,
     update_sender: ArcSwap<UnboundedSender<UpdateSignal>>,
+    /// Number of update operations, sent to the update worker but not yet applied
+    queued_updates: Arc<AtomicUsize>,
     path: PathBuf,
     before_drop_called: bool,
 }
@@ -65,15 +73,12 @@ impl LocalShard {
         config: CollectionConfig,
         wal: SerdeWal<CollectionUpdateOperations>,
         optimizers: Arc<Vec<Arc<Optimizer>>>,
+        indexing_pool: Arc<ThreadPool>,
         collection_path: &Path,
     ) -> Self {
         let segment_holder = Arc::new(RwLock::new(segment_holder));
 
-        let blocking_threads = if config.optimizer_config.max_optimization_threads == 0 {
-            max(num_cpus::get() - 1, 1)
-        } else {
-            config.optimizer_config.max_optimization_threads
-        };
+        let blocking_threads = config.optimizer_config.get_max_optimization_threads();
         let optimize_runtime = runtime::Builder::new_multi_thread()
             .worker_threads(3)
             .enable_time()
@@ -88,8 +93,17 @@ impl LocalShard {
 
         let locked_wal = Arc::new(Mutex::new(wal));
 
+        let forced_optimizers = build_forced_optimizers(
+            collection_path,
+            &config.params,
+            &config.optimizer_config,
+            &config.hnsw_config,
+            indexing_pool,
+        );
+
         let mut update_handler = UpdateHandler::new(
             optimizers,
+            forced_optimizers,
             optimize_runtime.handle().clone(),
             segment_holder.clone(),
             locked_wal.clone(),
@@ -106,6 +120,7 @@ impl LocalShard {
             update_handler: Arc::new(tokio::sync::Mutex::new(update_handler)),
             runtime_handle: Some(optimize_runtime),
             update_sender: ArcSwap::from_pointee(update_sender),
+            queued_updates: Arc::new(AtomicUsize::new(0)),
             path: collection_path.to_owned(),
             before_drop_called: false,
         }
@@ -115,11 +130,14 @@ impl LocalShard {
         self.segments.deref()
     }
 
+    /// Loads the shard from disk.
+    /// `indexing_pool` is shared by all shards of the collection.
     pub async fn load(
         id: ShardId,
         collection_id: CollectionId,
         shard_path: &Path,
         collection_config: &CollectionConfig,
+        indexing_pool: Arc<ThreadPool>,
     ) -> LocalShard {
         let wal_path = Self::wal_path(shard_path);
         let segments_path = Self::segments_path(shard_path);
@@ -166,6 +184,7 @@ impl LocalShard {
             &collection_config.params,
             &collection_config.optimizer_config,
             &collection_config.hnsw_config,
+            indexing_pool.clone(),
         );
 
         let collection = LocalShard::new(
@@ -175,6 +194,7 @@ impl LocalShard {
             collection_config.clone(),
             wal,
             optimizers,
+            indexing_pool,
             shard_path,
         );
 
@@ -192,11 +212,13 @@ impl LocalShard {
     }
 
     /// Creates new empty shard with given configuration, initializing all storages, optimizers and directories.
+    /// `indexing_pool` is shared by all shards of the collection.
     pub fn build(
         id: ShardId,
         collection_id: CollectionId,
         shard_path: &Path,
         config: &CollectionConfig,
+        indexing_pool: Arc<ThreadPool>,
     ) -> CollectionResult<LocalShard> {
         let wal_path = shard_path.join("wal");
 
@@ -213,10 +235,12 @@ impl LocalShard {
         let mut segment_holder = SegmentHolder::default();
 
         for _sid in 0..config.optimizer_config.default_segment_number {
-            let segment = build_simple_segment(
+            let segment = build_appendable_segment(
                 &segments_path,
                 config.params.vector_size,
                 config.params.distance,
+                &config.hnsw_config,
+                config.params.vector_datatype,
             )?;
             segment_holder.add(segment);
         }
@@ -229,6 +253,7 @@ impl LocalShard {
             &config.params,
             &config.optimizer_config,
             &config.hnsw_config,
+            indexing_pool.clone(),
         );
 
         let collection = LocalShard::new(
@@ -238,6 +263,7 @@ impl LocalShard {
             config.clone(),
             wal,
             optimizers,
+            indexing_pool,
             shard_path,
         );
 
@@ -275,41 +301,31 @@ impl LocalShard {
         bar.finish();
     }
 
-    /// Updates shard optimization params:
-    /// - Saves new params on disk
+    /// Updates shard config:
+    /// - Saves new config on disk
     /// - Stops existing optimization loop
     /// - Runs new optimizers with new params
-    pub async fn update_optimizer_with_diff(
+    ///
+    /// WAL config is only persisted, it is applied on the next load of the shard.
+    /// `indexing_pool` is the pool of the collection, re-built if the number of threads changed.
+    pub async fn update_config(
         &self,
-        optimizer_config_diff: OptimizersConfigDiff,
+        new_config: CollectionConfig,
+        indexing_pool: Arc<ThreadPool>,
     ) -> CollectionResult<()> {
-        log::debug!("Updating optimizer params");
+        log::debug!("Updating shard config");
         {
             let mut config = self.config.write().await;
-            config.optimizer_config = optimizer_config_diff.update(&config.optimizer_config)?;
+            *config = new_config;
             config.save(&self.path)?;
         }
-        self.on_optimizer_config_update().await
+        self.on_optimizer_config_update(indexing_pool).await
     }
 
-    /// Updates shard optimization params:
-    /// - Saves new params on disk
-    /// - Stops existing optimization loop
-    /// - Runs new optimizers with new params
-    pub async fn update_optimizer_with_config(
+    pub async fn on_optimizer_config_update(
         &self,
-        optimizer_config: OptimizersConfig,
+        indexing_pool: Arc<ThreadPool>,
     ) -> CollectionResult<()> {
-        log::debug!("Updating optimizer params");
-        {
-            let mut config = self.config.write().await;
-            config.optimizer_config = optimizer_config;
-            config.save(&self.path)?;
-        }
-        self.on_optimizer_config_update().await
-    }
-
-    pub async fn on_optimizer_config_update(&self) -> CollectionResult<()> {
         let config = self.config.read().await;
         let mut update_handler = self.update_handler.lock().await;
 
@@ -325,8 +341,16 @@ impl LocalShard {
             &config.params,
             &config.optimizer_config,
             &config.hnsw_config,
+            indexing_pool.clone(),
         );
         update_handler.optimizers = new_optimizers;
+        update_handler.forced_optimizers = build_forced_optimizers(
+            &self.path,
+            &config.params,
+            &config.optimizer_config,
+            &config.hnsw_config,
+            indexing_pool,
+        );
         update_handler.flush_interval_sec = config.optimizer_config.flush_interval_sec;
         update_handler.run_workers(update_receiver);
         self.update_sender.load().send(UpdateSignal::Nop)?;
@@ -334,6 +358,95 @@ impl LocalShard {
         Ok(())
     }
 
+    /// Cancels running optimizations and prevents new ones from starting until resumed.
+    /// Updates are still accepted while optimizers are paused.
+    /// Pause is not persisted, loaded shard always starts with running optimizers.
+    pub fn pause_optimizers(&self) -> CollectionResult<()> {
+        self.update_sender
+            .load()
+            .send(UpdateSignal::PauseOptimizers)?;
+        Ok(())
+    }
+
+    /// Resumes optimizers after pause and checks if any optimization is required
+    pub fn resume_optimizers(&self) -> CollectionResult<()> {
+        self.update_sender
+            .load()
+            .send(UpdateSignal::ResumeOptimizers)?;
+        Ok(())
+    }
+
+    /// Triggers merge and indexing of segments regardless of the configured thresholds
+    pub fn force_optimize(&self) -> CollectionResult<()> {
+        self.update_sender
+            .load()
+            .send(UpdateSignal::ForceOptimize)?;
+        Ok(())
+    }
+
+    /// Types of the indexed payload fields of all segments, without collecting the rest of the info
+    pub fn payload_schema(&self) -> HashMap<PayloadKeyType, PayloadSchemaType> {
+        let mut schema = HashMap::new();
+        for (_, segment) in self.segments.read().iter() {
+            schema.extend(segment.get().read().get_indexed_fields());
+        }
+        schema
+    }
+
+    /// Measures quality of the vector index of all HNSW-indexed segments of the shard.
+    /// Segments under optimization are skipped.
+    pub async fn evaluate_recall(
+        &self,
+        shard_id: ShardId,
+        request: &RecallRequest,
+    ) -> CollectionResult<Vec<SegmentRecall>> {
+        let segments = self.segments.clone();
+        let request = request.clone();
+        // Both index and exact searches are CPU-bound, so they should not block async runtime
+        tokio::task::spawn_blocking(move || -> CollectionResult<Vec<SegmentRecall>> {
+            // Index of the proxied segment is going to be replaced by the optimizer.
+            // Handles are cloned, so the holder is not locked during the evaluation.
+            let original_segments: Vec<_> = segments
+                .read()
+                .iter()
+                .filter_map(|(&segment_id, segment)| match segment {
+                    LockedSegment::Original(segment) => Some((segment_id, segment.clone())),
+                    LockedSegment::Proxy(_) => None,
+                })
+                .collect();
+
+            let is_stopped = AtomicBool::new(false);
+            let mut result = vec![];
+            for (segment_id, segment) in original_segments {
+                let segment = segment.read();
+                if !matches!(segment.segment_config.index, Indexes::Hnsw(_)) {
+                    continue;
+                }
+                let evaluation = segment.evaluate_recall(
+                    request.sample,
+                    request.top,
+                    request.hnsw_ef,
+                    &is_stopped,
+                )?;
+                let mean_search_time = if evaluation.queries > 0 {
+                    evaluation.search_time.as_secs_f64() / evaluation.queries as f64
+                } else {
+                    0.0
+                };
+                result.push(SegmentRecall {
+                    shard_id,
+                    segment_id,
+                    queries: evaluation.queries,
+                    recall: evaluation.recall,
+                    mean_search_time,
+                    ef: evaluation.ef,
+                });
+            }
+            Ok(result)
+        })
+        .await?
+    }
+
     pub async fn before_drop(&mut self) {
         // Finishes update tasks right before destructor stuck to do so with runtime
         self.update_sender.load().send(UpdateSignal::Stop).unwrap();
@@ -363,6 +476,108 @@ impl LocalShard {
     }
 }
 
+/// Handles of all segments of the shard. The holder is only locked while the handles are cloned,
+/// so that long reading of the segments does not block the optimizers and the updates.
+fn segment_handles(segments: &RwLock<SegmentHolder>) -> Vec<LockedSegment> {
+    segments
+        .read()
+        .iter()
+        .map(|(_, segment)| segment.clone())
+        .collect()
+}
+
+/// Number of point ids, read from each segment at once while sampling
+const SAMPLE_READ_BATCH: usize = 1_000;
+
+/// Uniform random sample of `size` points of all segments, which satisfy the filter.
+/// Points are read in batches and selected with reservoir sampling, so memory does not depend on
+/// the number of points in the shard.
+fn sample_points(
+    segments: &[LockedSegment],
+    filter: Option<&Filter>,
+    size: usize,
+    rng: &mut impl Rng,
+) -> PointsSample {
+    let is_stopped = AtomicBool::new(false);
+    let mut ids = Vec::with_capacity(size);
+    let mut total = 0;
+    let mut offset = None;
+    loop {
+        // Proxies of the same optimization share the write segment, so points might be repeated
+        let mut batch = segments
+            .iter()
+            .flat_map(|segment| {
+                segment.get().read().read_filtered(
+                    offset,
+                    SAMPLE_READ_BATCH + 1,
+                    filter,
+                    &is_stopped,
+                )
+            })
+            .sorted()
+            .dedup()
+            .take(SAMPLE_READ_BATCH + 1)
+            .collect_vec();
+        // The extra point starts the next batch
+        offset = if batch.len() > SAMPLE_READ_BATCH {
+            batch.pop()
+        } else {
+            None
+        };
+
+        for id in batch {
+            total += 1;
+            if ids.len() < size {
+                ids.push(id);
+            } else {
+                let position = rng.gen_range(0..total);
+                if position < size {
+                    ids[position] = id;
+                }
+            }
+        }
+        if offset.is_none() {
+            return PointsSample { ids, total };
+        }
+    }
+}
+
+/// Count values of the facet field over all segments of the shard.
+/// Proxies of the same optimization share the write segment, so it is counted only once.
+fn count_facet_values(
+    segments: &[LockedSegment],
+    request: &FacetRequest,
+) -> CollectionResult<HashMap<FacetValue, usize>> {
+    let filter = request.filter.as_ref();
+    let mut counts: HashMap<FacetValue, usize> = HashMap::new();
+    let mut write_segments: Vec<Arc<RwLock<dyn SegmentEntry>>> = vec![];
+    let mut add_counts = |segment_counts: HashMap<FacetValue, usize>| {
+        for (value, count) in segment_counts {
+            *counts.entry(value).or_insert(0) += count;
+        }
+    };
+    for segment in segments {
+        match segment {
+            LockedSegment::Original(segment) => {
+                add_counts(segment.read().facet(&request.key, filter)?)
+            }
+            LockedSegment::Proxy(proxy) => {
+                let proxy = proxy.read();
+                add_counts(proxy.wrapped_facet(&request.key, filter)?);
+                let write_segment = proxy.write_segment.get();
+                let is_counted = write_segments.iter().any(|counted| {
+                    Arc::as_ptr(counted) as *const () == Arc::as_ptr(&write_segment) as *const ()
+                });
+                if !is_counted {
+                    add_counts(write_segment.read().facet(&request.key, filter)?);
+                    write_segments.push(write_segment);
+                }
+            }
+        }
+    }
+    Ok(counts)
+}
+
 #[async_trait]
 #[allow(unused_variables)]
 impl ShardOperation for &LocalShard {
@@ -381,15 +596,32 @@ impl ShardOperation for &LocalShard {
             (None, None)
         };
 
+        let max_queued_updates = self.config.read().await.optimizer_config.max_queued_updates;
+
         let operation_id = {
             let mut wal_lock = self.wal.lock().await;
+            // Reject operation before writing it into WAL, so it won't be applied on recovery
+            let queued_updates = self.queued_updates.load(Ordering::Relaxed);
+            // Zero limit means that the queue is not limited
+            if let Some(max_queued_updates) = max_queued_updates.filter(|&limit| limit > 0) {
+                if queued_updates >= max_queued_updates {
+                    return Err(CollectionError::ResourceExhausted {
+                        description: format!(
+                            "{} update operations are already waiting to be applied to the shard, limit is {}",
+                            queued_updates, max_queued_updates
+                        ),
+                    });
+                }
+            }
             let operation_id = wal_lock.write(&operation)?;
+            // If the operation is not delivered, the guard is dropped together with it
             self.update_sender
                 .load()
                 .send(UpdateSignal::Operation(OperationData {
                     op_num: operation_id,
                     operation,
                     sender: callback_sender,
+                    queued: QueuedUpdateGuard::new(self.queued_updates.clone()),
                 }))?;
             operation_id
         };
@@ -416,21 +648,40 @@ impl ShardOperation for &LocalShard {
         with_payload_interface: &WithPayloadInterface,
         with_vector: bool,
         filter: Option<&Filter>,
+        timeout: Option<u64>,
     ) -> CollectionResult<Vec<Record>> {
-        // ToDo: Make faster points selection with a set
-        let segments = self.segments();
-        let point_ids = segments
-            .read()
-            .iter()
-            .flat_map(|(_, segment)| segment.get().read().read_filtered(offset, limit, filter))
-            .sorted()
-            .dedup()
-            .take(limit)
-            .collect_vec();
+        // Raised on timeout to interrupt reading of the segments
+        let is_stopped = Arc::new(AtomicBool::new(false));
+
+        let read_points = {
+            let segments = self.segments.clone();
+            let filter = filter.cloned();
+            let is_stopped = is_stopped.clone();
+            // Filtered reading might require a full scan, so it should not block async runtime
+            tokio::task::spawn_blocking(move || {
+                // ToDo: Make faster points selection with a set
+                segments
+                    .read()
+                    .iter()
+                    .flat_map(|(_, segment)| {
+                        segment.get().read().read_filtered(
+                            offset,
+                            limit,
+                            filter.as_ref(),
+                            &is_stopped,
+                        )
+                    })
+                    .sorted()
+                    .dedup()
+                    .take(limit)
+                    .collect_vec()
+            })
+        };
+        let point_ids = with_timeout(read_points, timeout, &is_stopped, "Scroll").await??;
 
         let with_payload = WithPayload::from(with_payload_interface);
         let mut points = segment_searcher
-            .retrieve(segments, &point_ids, &with_payload, with_vector)
+            .retrieve(self.segments(), &point_ids, &with_payload, with_vector)
             .await?;
         points.sort_by_key(|point| point.id);
 
@@ -483,27 +734,32 @@ impl ShardOperation for &LocalShard {
 
     async fn search(
         &self,
-        request: Arc<SearchRequest>,
+        request: Arc<CoreSearchRequest>,
         segment_searcher: &(dyn CollectionSearcher + Sync),
         search_runtime_handle: &Handle,
-    ) -> CollectionResult<Vec<ScoredPoint>> {
+    ) -> CollectionResult<SearchExplainResult> {
         let res = segment_searcher
             .search(self.segments(), request.clone(), search_runtime_handle)
             .await?;
         let distance = self.config.read().await.params.distance;
-        let processed_res = res.into_iter().map(|mut scored_point| {
-            scored_point.score = distance.postprocess_score(scored_point.score);
+        let processed_res = res.points.into_iter().map(|mut scored_point| {
+            scored_point.score = request.postprocess_score(distance, scored_point.score);
             scored_point
         });
 
         let top_result = if let Some(threshold) = request.score_threshold {
             processed_res
-                .take_while(|scored_point| distance.check_threshold(scored_point.score, threshold))
+                .take_while(|scored_point| {
+                    request.check_threshold(distance, scored_point.score, threshold)
+                })
                 .collect()
         } else {
             processed_res.collect()
         };
-        Ok(top_result)
+        Ok(SearchExplainResult {
+            points: top_result,
+            segments: res.segments,
+        })
     }
 
     async fn retrieve(
@@ -517,6 +773,88 @@ impl ShardOperation for &LocalShard {
             .retrieve(self.segments(), &request.ids, with_payload, with_vector)
             .await
     }
+
+    async fn facet(&self, request: Arc<FacetRequest>) -> CollectionResult<Vec<FacetHit>> {
+        let segments = self.segments.clone();
+        // Without field index values are read from the payload storage, which is blocking
+        let counts = tokio::task::spawn_blocking(move || {
+            count_facet_values(&segment_handles(&segments), &request)
+        })
+        .await??;
+        Ok(counts
+            .into_iter()
+            .map(|(value, count)| FacetHit { value, count })
+            .collect())
+    }
+
+    async fn sample(&self, filter: Option<&Filter>, size: usize) -> CollectionResult<PointsSample> {
+        let segments = self.segments.clone();
+        let filter = filter.cloned();
+        // Filtered reading might require a full scan, so it should not block async runtime
+        let sample = tokio::task::spawn_blocking(move || {
+            sample_points(
+                &segment_handles(&segments),
+                filter.as_ref(),
+                size,
+                &mut rand::thread_rng(),
+            )
+        })
+        .await?;
+        Ok(sample)
+    }
+
+    async fn sample_neighbours(
+        &self,
+        request: Arc<SampleNeighboursRequest>,
+    ) -> CollectionResult<Vec<Vec<ScoredPoint>>> {
+        let segments = self.segments.clone();
+        // Scoring of all pairs of the sample is CPU-bound
+        tokio::task::spawn_blocking(move || -> CollectionResult<Vec<Vec<ScoredPoint>>> {
+            let segments = segment_handles(&segments);
+            let points = request.queries.iter().map(|(id, _)| *id).collect_vec();
+
+            // Not set vectors are read from the segments of this shard.
+            // Positions of the queries with known vectors are kept to return results in order.
+            let mut positions = vec![];
+            let mut queries = vec![];
+            for (position, (point_id, vector)) in request.queries.iter().enumerate() {
+                let vector = match vector {
+                    Some(vector) => Some(vector.clone()),
+                    None => segments.iter().find_map(|segment| {
+                        let segment = segment.get();
+                        let segment = segment.read();
+                        if segment.has_point(*point_id) {
+                            segment.vector(*point_id).ok()
+                        } else {
+                            None
+                        }
+                    }),
+                };
+                if let Some(vector) = vector {
+                    positions.push(position);
+                    queries.push((*point_id, vector));
+                }
+            }
+
+            // Neighbours of each query, found in each of the segments
+            let mut neighbours: Vec<Vec<Vec<ScoredPoint>>> = vec![vec![]; request.queries.len()];
+            for segment in &segments {
+                let segment_neighbours =
+                    segment
+                        .get()
+                        .read()
+                        .sample_neighbours(&queries, &points, request.top)?;
+                for (&position, found) in positions.iter().zip(segment_neighbours) {
+                    neighbours[position].push(found);
+                }
+            }
+            Ok(neighbours
+                .into_iter()
+                .map(|query_neighbours| merge_neighbours(query_neighbours, request.top))
+                .collect())
+        })
+        .await?
+    }
 }
 
 impl Drop for LocalShard {
@@ -532,3 +870,116 @@ impl Drop for LocalShard {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::collection_manager::fixtures::build_test_holder;
+    use crate::config::{CollectionParams, WalConfig};
+    use crate::operations::point_ops::Batch;
+    use crate::optimizers_builder::{build_indexing_pool, OptimizersConfig};
+    use segment::types::Distance;
+    use std::num::NonZeroU32;
+    use std::time::Duration;
+    use tempdir::TempDir;
+
+    fn insert_point(id: u64) -> CollectionUpdateOperations {
+        CollectionUpdateOperations::PointOperation(
+            Batch {
+                ids: vec![id.into()],
+                vectors: vec![vec![1.0, 0.0, 1.0, 1.0]],
+                payloads: None,
+                sparse_vectors: None,
+            }
+            .into(),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_queued_updates_limit() {
+        let shard_dir = TempDir::new("shard").unwrap();
+
+        let config = CollectionConfig {
+            params: CollectionParams {
+                vector_size: 4,
+                distance: Distance::Dot,
+                shard_number: NonZeroU32::new(1).unwrap(),
+                product_quantization: None,
+                vector_datatype: Default::default(),
+            },
+            hnsw_config: Default::default(),
+            optimizer_config: OptimizersConfig {
+                deleted_threshold: 0.9,
+                vacuum_min_vector_number: 1000,
+                default_segment_number: 1,
+                max_segment_size: 100_000,
+                memmap_threshold: 100_000,
+                indexing_threshold: 50_000,
+                payload_indexing_threshold: 20_000,
+                flush_interval_sec: 30,
+                max_optimization_threads: 1,
+                max_queued_updates: Some(1),
+            },
+            wal_config: WalConfig {
+                wal_capacity_mb: 1,
+                wal_segments_ahead: 0,
+            },
+        };
+
+        let indexing_pool = build_indexing_pool(&config.optimizer_config).unwrap();
+        let mut shard = LocalShard::build(
+            0,
+            "test".to_string(),
+            shard_dir.path(),
+            &config,
+            indexing_pool,
+        )
+        .unwrap();
+
+        {
+            // Update worker can't apply operations, while segments are locked
+            let _segments_lock = shard.segments().write();
+
+            shard.update(insert_point(1), false).await.unwrap();
+
+            let rejected = shard.update(insert_point(2), false).await;
+            assert!(matches!(
+                rejected,
+                Err(CollectionError::ResourceExhausted { .. })
+            ));
+        }
+
+        // Applied operation releases its place in the queue
+        while shard.queued_updates.load(Ordering::Relaxed) > 0 {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        shard.update(insert_point(2), true).await.unwrap();
+        assert_eq!(shard.queued_updates.load(Ordering::Relaxed), 0);
+
+        // Operation, which can't be delivered to the update worker, is not counted
+        shard.before_drop().await;
+        assert!(shard.update(insert_point(3), false).await.is_err());
+        assert_eq!(shard.queued_updates.load(Ordering::Relaxed), 0);
+    }
+
+    #[test]
+    fn test_sample_points() {
+        let dir = TempDir::new("segment_dir").unwrap();
+        let segments = segment_handles(&build_test_holder(dir.path()));
+        let mut rng = rand::thread_rng();
+
+        // Points 4 and 5 are stored in both segments
+        let sample = sample_points(&segments, None, 3, &mut rng);
+        assert_eq!(sample.total, 10);
+        assert_eq!(sample.ids.len(), 3);
+        assert_eq!(sample.ids.iter().unique().count(), 3);
+
+        let sample = sample_points(&segments, None, 20, &mut rng);
+        assert_eq!(sample.total, 10);
+        let expected: Vec<ExtendedPointId> = vec![1, 2, 3, 4, 5, 11, 12, 13, 14, 15]
+            .into_iter()
+            .map(|id: u64| id.into())
+            .collect();
+        assert_eq!(sample.ids, expected);
+    }
+}
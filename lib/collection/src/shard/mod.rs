@@ -2,10 +2,14 @@ mod conversions;
 pub mod local_shard;
 pub mod remote_shard;
 
+use crate::operations::types::{
+    CoreSearchRequest, FacetHit, FacetRequest, PointsSample, SampleNeighboursRequest,
+    SearchExplainResult,
+};
 use crate::shard::remote_shard::RemoteShard;
 use crate::{
     CollectionInfo, CollectionResult, CollectionSearcher, CollectionUpdateOperations, LocalShard,
-    PeerId, PointRequest, Record, SearchRequest, UpdateResult,
+    PeerId, PointRequest, Record, UpdateResult,
 };
 use async_trait::async_trait;
 use segment::types::{ExtendedPointId, Filter, ScoredPoint, WithPayload, WithPayloadInterface};
@@ -64,16 +68,17 @@ pub trait ShardOperation {
         with_payload_interface: &WithPayloadInterface,
         with_vector: bool,
         filter: Option<&Filter>,
+        timeout: Option<u64>,
     ) -> CollectionResult<Vec<Record>>;
 
     async fn info(&self) -> CollectionResult<CollectionInfo>;
 
     async fn search(
         &self,
-        request: Arc<SearchRequest>,
+        request: Arc<CoreSearchRequest>,
         segment_searcher: &(dyn CollectionSearcher + Sync),
         search_runtime_handle: &Handle,
-    ) -> CollectionResult<Vec<ScoredPoint>>;
+    ) -> CollectionResult<SearchExplainResult>;
 
     async fn retrieve(
         &self,
@@ -82,4 +87,17 @@ pub trait ShardOperation {
         with_payload: &WithPayload,
         with_vector: bool,
     ) -> CollectionResult<Vec<Record>>;
+
+    /// Number of points with each value of the field, not limited
+    async fn facet(&self, request: Arc<FacetRequest>) -> CollectionResult<Vec<FacetHit>>;
+
+    /// Uniform random sample of `size` points, which satisfy the filter
+    async fn sample(&self, filter: Option<&Filter>, size: usize) -> CollectionResult<PointsSample>;
+
+    /// Nearest neighbours of each query among query points, stored in this shard.
+    /// Scores are internal, not post-processed
+    async fn sample_neighbours(
+        &self,
+        request: Arc<SampleNeighboursRequest>,
+    ) -> CollectionResult<Vec<Vec<ScoredPoint>>>;
 }
@@ -1,25 +1,31 @@
 use crate::operations::payload_ops::PayloadOps;
 use crate::operations::point_ops::PointOperations;
+use crate::operations::types::{
+    FacetHit, FacetRequest, PointsSample, SampleNeighboursRequest, SearchExplainResult,
+};
 use crate::operations::FieldIndexOperations;
 use crate::shard::conversions::{
     internal_clear_payload, internal_clear_payload_by_filter, internal_create_index,
     internal_delete_index, internal_delete_payload, internal_delete_points,
-    internal_delete_points_by_filter, internal_set_payload, internal_upsert_points,
+    internal_delete_points_by_filter, internal_facet_points, internal_sample_neighbours,
+    internal_sample_points, internal_search_points, internal_set_payload, internal_upsert_points,
 };
 use crate::shard::{PeerId, ShardId, ShardOperation};
 use crate::{
     CollectionError, CollectionId, CollectionInfo, CollectionResult, CollectionSearcher,
-    CollectionUpdateOperations, PointRequest, Record, SearchRequest, UpdateResult,
+    CollectionUpdateOperations, CoreSearchRequest, PointRequest, Record, UpdateResult,
 };
 use api::grpc::qdrant::{
     collections_internal_client::CollectionsInternalClient,
     points_internal_client::PointsInternalClient, GetCollectionInfoRequest,
     GetCollectionInfoRequestInternal, GetPoints, GetPointsInternal, ScrollPoints,
-    ScrollPointsInternal, SearchPoints, SearchPointsInternal,
+    ScrollPointsInternal,
 };
 use api::grpc::timeout_channel;
 use async_trait::async_trait;
-use segment::types::{ExtendedPointId, Filter, ScoredPoint, WithPayload, WithPayloadInterface};
+use segment::types::{
+    ExtendedPointId, Filter, ScoredPoint, SegmentExplain, WithPayload, WithPayloadInterface,
+};
 use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::Duration;
@@ -148,6 +154,7 @@ impl ShardOperation for &RemoteShard {
         with_payload_interface: &WithPayloadInterface,
         with_vector: bool,
         filter: Option<&Filter>,
+        timeout: Option<u64>,
     ) -> CollectionResult<Vec<Record>> {
         let mut client = self.points_client().await?;
 
@@ -158,6 +165,7 @@ impl ShardOperation for &RemoteShard {
             limit: Some(limit as u32),
             with_vector: Some(with_vector),
             with_payload: Some(with_payload_interface.clone().into()),
+            timeout,
         };
         let request = tonic::Request::new(ScrollPointsInternal {
             scroll_points: Some(scroll_points),
@@ -191,34 +199,29 @@ impl ShardOperation for &RemoteShard {
 
     async fn search(
         &self,
-        request: Arc<SearchRequest>,
+        request: Arc<CoreSearchRequest>,
         segment_searcher: &(dyn CollectionSearcher + Sync),
         search_runtime_handle: &Handle,
-    ) -> CollectionResult<Vec<ScoredPoint>> {
+    ) -> CollectionResult<SearchExplainResult> {
         let mut client = self.points_client().await?;
 
-        let search_points = SearchPoints {
-            collection_name: self.collection_id.clone(),
-            vector: request.vector.clone(),
-            filter: request.filter.clone().map(|f| f.into()),
-            top: request.top as u64,
-            with_vector: Some(request.with_vector),
-            with_payload: request.with_payload.clone().map(|wp| wp.into()),
-            params: request.params.map(|sp| sp.into()),
-            score_threshold: request.score_threshold,
-        };
-        let request = tonic::Request::new(SearchPointsInternal {
-            search_points: Some(search_points),
-            shard_id: self.id,
-        });
+        let request = tonic::Request::new(internal_search_points(&request, self));
         let response = client.search(request).await?;
         let search_response = response.into_inner();
-        let result: Result<Vec<ScoredPoint>, Status> = search_response
+        let points: Result<Vec<ScoredPoint>, Status> = search_response
             .result
             .into_iter()
             .map(|scored| scored.try_into())
             .collect();
-        result.map_err(|e| e.into())
+        let segments: Result<Vec<SegmentExplain>, Status> = search_response
+            .explain
+            .into_iter()
+            .map(|explain| explain.try_into())
+            .collect();
+        Ok(SearchExplainResult {
+            points: points?,
+            segments: segments?,
+        })
     }
 
     async fn retrieve(
@@ -249,4 +252,60 @@ impl ShardOperation for &RemoteShard {
             .collect();
         result.map_err(|e| e.into())
     }
+
+    async fn facet(&self, request: Arc<FacetRequest>) -> CollectionResult<Vec<FacetHit>> {
+        let mut client = self.points_client().await?;
+
+        let request = tonic::Request::new(internal_facet_points(&request, self));
+        let response = client.facet(request).await?;
+        let facet_response = response.into_inner();
+        let result: Result<Vec<FacetHit>, Status> = facet_response
+            .result
+            .map(|result| result.hits)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|hit| hit.try_into())
+            .collect();
+        result.map_err(|e| e.into())
+    }
+
+    async fn sample(&self, filter: Option<&Filter>, size: usize) -> CollectionResult<PointsSample> {
+        let mut client = self.points_client().await?;
+
+        let request = tonic::Request::new(internal_sample_points(filter, size, self));
+        let response = client.sample(request).await?;
+        let sample_response = response.into_inner();
+        let ids: Result<Vec<ExtendedPointId>, Status> = sample_response
+            .ids
+            .into_iter()
+            .map(|id| id.try_into())
+            .collect();
+        Ok(PointsSample {
+            ids: ids?,
+            total: sample_response.total as usize,
+        })
+    }
+
+    async fn sample_neighbours(
+        &self,
+        request: Arc<SampleNeighboursRequest>,
+    ) -> CollectionResult<Vec<Vec<ScoredPoint>>> {
+        let mut client = self.points_client().await?;
+
+        let request = tonic::Request::new(internal_sample_neighbours(&request, self));
+        let response = client.sample_neighbours(request).await?;
+        let neighbours_response = response.into_inner();
+        let result: Result<Vec<Vec<ScoredPoint>>, Status> = neighbours_response
+            .result
+            .into_iter()
+            .map(|neighbours| {
+                neighbours
+                    .neighbours
+                    .into_iter()
+                    .map(|scored| scored.try_into())
+                    .collect()
+            })
+            .collect();
+        result.map_err(|e| e.into())
+    }
 }
@@ -1,11 +1,16 @@
 use crate::collection_manager::fixtures::{
-    get_indexing_optimizer, get_merge_optimizer, random_segment,
+    get_indexing_optimizer, get_merge_optimizer, indexing_pool, random_segment,
 };
 use crate::collection_manager::holders::segment_holder::{LockedSegment, SegmentHolder, SegmentId};
+use crate::config::CollectionParams;
+use crate::optimizers_builder::{build_forced_optimizers, OptimizersConfig};
 use crate::update_handler::{Optimizer, UpdateHandler};
 use futures::future::join_all;
 use itertools::Itertools;
 use parking_lot::RwLock;
+use segment::types::Distance;
+use std::fs::create_dir_all;
+use std::num::NonZeroU32;
 use std::sync::Arc;
 use std::time::Duration;
 use tempdir::TempDir;
@@ -109,3 +114,76 @@ async fn test_cancel_optimization() {
         }
     }
 }
+
+#[tokio::test]
+async fn test_forced_optimization() {
+    let shard_dir = TempDir::new("shard_dir").unwrap();
+    let segments_path = shard_dir.path().join("segments");
+    create_dir_all(&segments_path).unwrap();
+
+    let mut holder = SegmentHolder::default();
+
+    let small_segments = vec![
+        holder.add(random_segment(&segments_path, 100, 3, 4)),
+        holder.add(random_segment(&segments_path, 100, 3, 4)),
+        holder.add(random_segment(&segments_path, 100, 3, 4)),
+    ];
+
+    let optimizers_config = OptimizersConfig {
+        deleted_threshold: 0.9,
+        vacuum_min_vector_number: 1000,
+        default_segment_number: 5,
+        max_segment_size: 100_000,
+        memmap_threshold: 100_000,
+        indexing_threshold: 50_000,
+        payload_indexing_threshold: 20_000,
+        flush_interval_sec: 30,
+        max_optimization_threads: 2,
+        max_queued_updates: None,
+    };
+
+    let collection_params = CollectionParams {
+        vector_size: 4,
+        distance: Distance::Dot,
+        shard_number: NonZeroU32::new(1).unwrap(),
+        product_quantization: None,
+        vector_datatype: Default::default(),
+    };
+
+    let segments = Arc::new(RwLock::new(holder));
+
+    let merge_optimizer: Arc<Optimizer> =
+        Arc::new(get_merge_optimizer(&segments_path, shard_dir.path()));
+    let indexing_optimizer: Arc<Optimizer> =
+        Arc::new(get_indexing_optimizer(&segments_path, shard_dir.path()));
+    let optimizers = Arc::new(vec![merge_optimizer, indexing_optimizer]);
+
+    // Segments are too small and too few for regular optimizers
+    let handles = UpdateHandler::launch_optimization(optimizers, segments.clone(), |_| {});
+    assert!(handles.is_empty());
+
+    let forced_optimizers = build_forced_optimizers(
+        shard_dir.path(),
+        &collection_params,
+        &optimizers_config,
+        &Default::default(),
+        indexing_pool(),
+    );
+
+    let handles =
+        UpdateHandler::launch_optimization(forced_optimizers.clone(), segments.clone(), |_| {});
+    assert_eq!(handles.len(), 1);
+
+    let join_res = join_all(handles.into_iter().map(|x| x.join_handle).collect_vec()).await;
+    for res in join_res {
+        assert!(res.unwrap());
+    }
+
+    for sid in small_segments {
+        assert!(segments.read().get(sid).is_none());
+    }
+
+    // Nothing left to optimize even with forced optimizers
+    let handles = UpdateHandler::launch_optimization(forced_optimizers, segments.clone(), |_| {});
+    assert!(handles.is_empty());
+}
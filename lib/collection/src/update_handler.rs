@@ -11,6 +11,7 @@ use segment::entry::entry_point::OperationResult;
 use segment::types::SeqNumberType;
 use std::cmp::min;
 use std::collections::HashSet;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::sync::Arc;
 use tokio::runtime::Handle;
 use tokio::sync::{
@@ -22,6 +23,26 @@ use tokio::time::Duration;
 
 pub type Optimizer = dyn SegmentOptimizer + Sync + Send;
 
+/// Counts an update operation as queued for as long as the guard exists.
+/// The counter is released on drop, so operations, which are never applied, are not counted forever.
+#[derive(Debug)]
+pub struct QueuedUpdateGuard {
+    queued_updates: Arc<AtomicUsize>,
+}
+
+impl QueuedUpdateGuard {
+    pub fn new(queued_updates: Arc<AtomicUsize>) -> Self {
+        queued_updates.fetch_add(1, Ordering::Relaxed);
+        QueuedUpdateGuard { queued_updates }
+    }
+}
+
+impl Drop for QueuedUpdateGuard {
+    fn drop(&mut self) {
+        self.queued_updates.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
 /// Information, required to perform operation and notify regarding the result
 #[derive(Debug)]
 pub struct OperationData {
@@ -31,6 +52,8 @@ pub struct OperationData {
     pub operation: CollectionUpdateOperations,
     /// Callback notification channel
     pub sender: Option<oneshot::Sender<CollectionResult<usize>>>,
+    /// Keeps the operation counted as queued until it is applied
+    pub queued: QueuedUpdateGuard,
 }
 
 /// Signal, used to inform Updater process
@@ -42,6 +65,12 @@ pub enum UpdateSignal {
     Stop,
     /// Empty signal used to trigger optimizers
     Nop,
+    /// Cancel running optimizations and do not start new ones until resumed
+    PauseOptimizers,
+    /// Allow optimizers to run again after pause
+    ResumeOptimizers,
+    /// Run optimizers regardless of the configured thresholds
+    ForceOptimize,
 }
 
 /// Signal, used to inform Optimization process
@@ -52,12 +81,20 @@ pub enum OptimizerSignal {
     Stop,
     /// Empty signal used to trigger optimizers
     Nop,
+    /// Cancel running optimizations and do not start new ones until resumed
+    Pause,
+    /// Allow optimizers to run again after pause
+    Resume,
+    /// Run forced optimizers regardless of the configured thresholds
+    ForceOptimize,
 }
 
 /// Structure, which holds object, required for processing updates of the collection
 pub struct UpdateHandler {
     /// List of used optimizers
     pub optimizers: Arc<Vec<Arc<Optimizer>>>,
+    /// Optimizers, which ignore thresholds. Only used by explicit request
+    pub forced_optimizers: Arc<Vec<Arc<Optimizer>>>,
     /// How frequent can we flush data
     pub flush_interval_sec: u64,
     segments: LockedSegmentHolder,
@@ -73,11 +110,15 @@ pub struct UpdateHandler {
     /// WAL, required for operations
     wal: Arc<Mutex<SerdeWal<CollectionUpdateOperations>>>,
     optimization_handles: Arc<Mutex<Vec<StoppableTaskHandle<bool>>>>,
+    /// If set - optimizers are not triggered by updates. Survives restarts of the workers,
+    /// but not of the service: it is not persisted
+    optimizers_paused: Arc<AtomicBool>,
 }
 
 impl UpdateHandler {
     pub fn new(
         optimizers: Arc<Vec<Arc<Optimizer>>>,
+        forced_optimizers: Arc<Vec<Arc<Optimizer>>>,
         runtime_handle: Handle,
         segments: LockedSegmentHolder,
         wal: Arc<Mutex<SerdeWal<CollectionUpdateOperations>>>,
@@ -85,6 +126,7 @@ impl UpdateHandler {
     ) -> UpdateHandler {
         UpdateHandler {
             optimizers,
+            forced_optimizers,
             segments,
             update_worker: None,
             optimizer_worker: None,
@@ -94,6 +136,7 @@ impl UpdateHandler {
             wal,
             flush_interval_sec,
             optimization_handles: Arc::new(Mutex::new(vec![])),
+            optimizers_paused: Arc::new(AtomicBool::new(false)),
         }
     }
 
@@ -101,11 +144,13 @@ impl UpdateHandler {
         let (tx, rx) = mpsc::unbounded_channel();
         self.optimizer_worker = Some(self.runtime_handle.spawn(Self::optimization_worker_fn(
             self.optimizers.clone(),
+            self.forced_optimizers.clone(),
             tx.clone(),
             rx,
             self.segments.clone(),
             self.wal.clone(),
             self.optimization_handles.clone(),
+            self.optimizers_paused.clone(),
         )));
         self.update_worker = Some(self.runtime_handle.spawn(Self::update_worker_fn(
             update_receiver,
@@ -260,17 +305,62 @@ impl UpdateHandler {
         handles.retain(|h| !h.is_finished())
     }
 
+    /// Asks all running optimizations to stop and waits until they are finished.
+    /// Cancelled optimizations roll back, so no data is lost.
+    async fn cancel_optimizations(
+        optimization_handles: Arc<Mutex<Vec<StoppableTaskHandle<bool>>>>,
+    ) {
+        let mut opt_handles_guard = optimization_handles.lock().await;
+        let opt_handles = std::mem::take(&mut *opt_handles_guard);
+        let stopping_handles = opt_handles.into_iter().map(|h| h.stop()).collect_vec();
+
+        for res in stopping_handles {
+            if let Err(err) = res.await {
+                warn!("Optimization task failed while being cancelled: {}", err);
+            }
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
     async fn optimization_worker_fn(
         optimizers: Arc<Vec<Arc<Optimizer>>>,
+        forced_optimizers: Arc<Vec<Arc<Optimizer>>>,
         sender: UnboundedSender<OptimizerSignal>,
         mut receiver: UnboundedReceiver<OptimizerSignal>,
         segments: LockedSegmentHolder,
         wal: Arc<Mutex<SerdeWal<CollectionUpdateOperations>>>,
         optimization_handles: Arc<Mutex<Vec<StoppableTaskHandle<bool>>>>,
+        optimizers_paused: Arc<AtomicBool>,
     ) {
         while let Some(signal) = receiver.recv().await {
             match signal {
                 OptimizerSignal::Nop | OptimizerSignal::Operation(_) => {
+                    if Self::try_recover(segments.clone(), wal.clone())
+                        .await
+                        .is_err()
+                    {
+                        continue;
+                    }
+                    if optimizers_paused.load(Ordering::Relaxed) {
+                        continue;
+                    }
+                    Self::process_optimization(
+                        optimizers.clone(),
+                        segments.clone(),
+                        optimization_handles.clone(),
+                        sender.clone(),
+                    )
+                    .await;
+                }
+                OptimizerSignal::Pause => {
+                    optimizers_paused.store(true, Ordering::Relaxed);
+                    Self::cancel_optimizations(optimization_handles.clone()).await;
+                    info!("Optimizers paused");
+                }
+                OptimizerSignal::Resume => {
+                    optimizers_paused.store(false, Ordering::Relaxed);
+                    info!("Optimizers resumed");
+                    // Segments might have failed while optimizers were paused
                     if Self::try_recover(segments.clone(), wal.clone())
                         .await
                         .is_err()
@@ -285,6 +375,22 @@ impl UpdateHandler {
                     )
                     .await;
                 }
+                OptimizerSignal::ForceOptimize => {
+                    if Self::try_recover(segments.clone(), wal.clone())
+                        .await
+                        .is_err()
+                    {
+                        continue;
+                    }
+                    // Explicit request is served even if optimizers are paused
+                    Self::process_optimization(
+                        forced_optimizers.clone(),
+                        segments.clone(),
+                        optimization_handles.clone(),
+                        sender.clone(),
+                    )
+                    .await;
+                }
                 OptimizerSignal::Stop => break, // Stop gracefully
             }
         }
@@ -301,6 +407,7 @@ impl UpdateHandler {
                     op_num,
                     operation,
                     sender,
+                    queued,
                 }) => {
                     let res = match CollectionUpdater::update(&segments, op_num, operation) {
                         Ok(update_res) => optimize_sender
@@ -309,6 +416,8 @@ impl UpdateHandler {
                             .map_err(|send_err| send_err.into()),
                         Err(err) => Err(err),
                     };
+                    // Operation is applied, so it no longer occupies the queue
+                    drop(queued);
 
                     if let Some(feedback) = sender {
                         feedback.send(res).unwrap_or_else(|_| {
@@ -334,6 +443,15 @@ impl UpdateHandler {
                         );
                         })
                 }
+                UpdateSignal::PauseOptimizers => {
+                    Self::notify_optimizers(&optimize_sender, OptimizerSignal::Pause)
+                }
+                UpdateSignal::ResumeOptimizers => {
+                    Self::notify_optimizers(&optimize_sender, OptimizerSignal::Resume)
+                }
+                UpdateSignal::ForceOptimize => {
+                    Self::notify_optimizers(&optimize_sender, OptimizerSignal::ForceOptimize)
+                }
             }
         }
         // Transmitter was destroyed
@@ -342,6 +460,15 @@ impl UpdateHandler {
             .unwrap_or_else(|_| debug!("Optimizer already stopped"));
     }
 
+    fn notify_optimizers(
+        optimize_sender: &UnboundedSender<OptimizerSignal>,
+        signal: OptimizerSignal,
+    ) {
+        optimize_sender.send(signal).unwrap_or_else(|_| {
+            info!("Can't notify optimizers, assume process is dead. Restart is required");
+        })
+    }
+
     async fn flush_worker(
         segments: LockedSegmentHolder,
         wal: Arc<Mutex<SerdeWal<CollectionUpdateOperations>>>,
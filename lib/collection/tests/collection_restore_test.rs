@@ -38,6 +38,7 @@ async fn test_collection_reloading_with_shards(shard_number: u32) {
                     ids: vec![0, 1].into_iter().map(|x| x.into()).collect_vec(),
                     vectors: vec![vec![1.0, 0.0, 1.0, 1.0], vec![1.0, 0.0, 1.0, 0.0]],
                     payloads: None,
+                    sparse_vectors: None,
                 },
             })),
         );
@@ -69,6 +70,7 @@ async fn test_collection_payload_reloading_with_shards(shard_number: u32) {
                     ids: vec![0, 1].into_iter().map(|x| x.into()).collect_vec(),
                     vectors: vec![vec![1.0, 0.0, 1.0, 1.0], vec![1.0, 0.0, 1.0, 0.0]],
                     payloads: serde_json::from_str(r#"[{ "k": "v1" } , { "k": "v2"}]"#).unwrap(),
+                    sparse_vectors: None,
                 },
             })),
         );
@@ -90,6 +92,7 @@ async fn test_collection_payload_reloading_with_shards(shard_number: u32) {
                 filter: None,
                 with_payload: Some(WithPayloadInterface::Bool(true)),
                 with_vector: true,
+                timeout: None,
             },
             &searcher,
             None,
@@ -136,6 +139,7 @@ async fn test_collection_payload_custom_payload_with_shards(shard_number: u32) {
                         r#"[{ "k1": "v1" }, { "k1": "v2" , "k2": "v3", "k3": "v4"}]"#,
                     )
                     .unwrap(),
+                    sparse_vectors: None,
                 },
             })),
         );
@@ -158,6 +162,7 @@ async fn test_collection_payload_custom_payload_with_shards(shard_number: u32) {
                 filter: None,
                 with_payload: Some(WithPayloadInterface::Fields(vec![String::from("k2")])),
                 with_vector: true,
+                timeout: None,
             },
             &searcher,
             None,
@@ -190,6 +195,7 @@ async fn test_collection_payload_custom_payload_with_shards(shard_number: u32) {
                 filter: None,
                 with_payload: Some(PayloadSelectorExclude::new(vec!["k1".to_string()]).into()),
                 with_vector: false,
+                timeout: None,
             },
             &searcher,
             None,
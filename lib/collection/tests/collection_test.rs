@@ -8,16 +8,30 @@ use collection::{
     operations::{
         payload_ops::{PayloadOps, SetPayload},
         point_ops::{Batch, PointOperations, PointStruct},
-        types::{RecommendRequest, ScrollRequest, SearchRequest, UpdateStatus},
-        CollectionUpdateOperations,
+        types::{
+            BoostTerm, CollectionError, DecayTarget, DecayTerm, DistanceMatrixRequest, Diversity,
+            FacetHit, FacetRequest, Fusion, GroupId, HybridSearchRequest, LinearTerm,
+            RecommendExample, RecommendRequest, RecommendStrategy, ScoreBoost, ScrollRequest,
+            SearchGroupsRequest, SearchRequest, UpdateStatus,
+        },
+        CollectionUpdateOperations, CreateIndex, FieldIndexOperations,
     },
     Collection,
 };
-use segment::types::{Condition, HasIdCondition, Payload, PointIdType, WithPayloadInterface};
+use segment::types::{
+    Condition, Distance, FacetValue, FieldCondition, Filter, HasIdCondition, Match, Payload,
+    PayloadSchemaType, PointIdType, QueryVector, SparseVector, WithPayloadInterface,
+};
 
 use crate::common::{simple_collection_fixture, N_SHARDS};
+use collection::collection_manager::holders::segment_holder::SegmentHolder;
 use collection::collection_manager::simple_collection_searcher::SimpleCollectionSearcher;
-use collection::operations::types::PointRequest;
+use collection::operations::types::{CoreSearchRequest, PointRequest};
+use parking_lot::RwLock;
+use segment::entry::entry_point::SegmentEntry;
+use segment::segment_constructor::simple_segment_constructor::build_simple_segment;
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
 
 mod common;
 
@@ -46,6 +60,7 @@ async fn test_collection_updater_with_shards(shard_number: u32) {
                 vec![1.0, 0.0, 0.0, 0.0],
             ],
             payloads: None,
+            sparse_vectors: None,
         }
         .into(),
     );
@@ -66,7 +81,12 @@ async fn test_collection_updater_with_shards(shard_number: u32) {
         filter: None,
         params: None,
         top: 3,
+        offset: 0,
         score_threshold: None,
+        timeout: None,
+        diversity: None,
+        score_boost: None,
+        with_explain: false,
     };
 
     let segment_searcher = SimpleCollectionSearcher::new();
@@ -85,6 +105,81 @@ async fn test_collection_updater_with_shards(shard_number: u32) {
     collection.before_drop().await;
 }
 
+#[tokio::test]
+async fn test_collection_search_explain() {
+    test_collection_search_explain_with_shards(1).await;
+    test_collection_search_explain_with_shards(N_SHARDS).await;
+}
+
+async fn test_collection_search_explain_with_shards(shard_number: u32) {
+    let collection_dir = TempDir::new("collection").unwrap();
+
+    let mut collection = simple_collection_fixture(collection_dir.path(), shard_number).await;
+
+    let insert_points = CollectionUpdateOperations::PointOperation(
+        Batch {
+            ids: vec![0, 1, 2, 3, 4]
+                .into_iter()
+                .map(|x| x.into())
+                .collect_vec(),
+            vectors: vec![
+                vec![1.0, 0.0, 1.0, 1.0],
+                vec![1.0, 0.0, 1.0, 0.0],
+                vec![1.0, 1.0, 1.0, 1.0],
+                vec![1.0, 1.0, 0.0, 1.0],
+                vec![1.0, 0.0, 0.0, 0.0],
+            ],
+            payloads: None,
+            sparse_vectors: None,
+        }
+        .into(),
+    );
+    collection
+        .update_from_client(insert_points, true)
+        .await
+        .unwrap();
+
+    let search_request = SearchRequest {
+        vector: vec![1.0, 1.0, 1.0, 1.0],
+        with_payload: None,
+        with_vector: false,
+        filter: Some(Filter::new_must(Condition::HasId(HasIdCondition {
+            has_id: HashSet::from([2.into(), 3.into()]),
+        }))),
+        params: None,
+        top: 1,
+        offset: 0,
+        score_threshold: None,
+        timeout: None,
+        diversity: None,
+        score_boost: None,
+        with_explain: true,
+    };
+
+    let segment_searcher = SimpleCollectionSearcher::new();
+    let result = collection
+        .search_with_explain(search_request, &segment_searcher, &Handle::current(), None)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        result.points.iter().map(|point| point.id).collect_vec(),
+        vec![2.into()]
+    );
+
+    // Segments without hits are explained too
+    let segments_count = collection.info(None).await.unwrap().segments_count;
+    assert_eq!(result.segments.len(), segments_count);
+    let explained_ids: HashSet<_> = result
+        .segments
+        .iter()
+        .flat_map(|segment| segment.points.iter().map(|point| point.id))
+        .collect();
+    assert!(explained_ids.contains(&2.into()));
+    assert!(explained_ids.is_subset(&HashSet::from([2.into(), 3.into()])));
+    collection.before_drop().await;
+}
+
 #[tokio::test]
 async fn test_collection_search_with_payload_and_vector() {
     test_collection_search_with_payload_and_vector_with_shards(1).await;
@@ -104,6 +199,7 @@ async fn test_collection_search_with_payload_and_vector_with_shards(shard_number
                 r#"[{ "k": { "type": "keyword", "value": "v1" } }, { "k": "v2" , "v": "v3"}]"#,
             )
             .unwrap(),
+            sparse_vectors: None,
         }
         .into(),
     );
@@ -124,7 +220,12 @@ async fn test_collection_search_with_payload_and_vector_with_shards(shard_number
         filter: None,
         params: None,
         top: 3,
+        offset: 0,
         score_threshold: None,
+        timeout: None,
+        diversity: None,
+        score_boost: None,
+        with_explain: false,
     };
 
     let segment_searcher = SimpleCollectionSearcher::new();
@@ -170,6 +271,7 @@ async fn test_collection_loading_with_shards(shard_number: u32) {
                     vec![1.0, 0.0, 0.0, 0.0],
                 ],
                 payloads: None,
+                sparse_vectors: None,
             }
             .into(),
         );
@@ -219,6 +321,86 @@ async fn test_collection_loading_with_shards(shard_number: u32) {
     loaded_collection.before_drop().await;
 }
 
+#[tokio::test]
+async fn test_collection_config_update() {
+    test_collection_config_update_with_shards(1).await;
+    test_collection_config_update_with_shards(N_SHARDS).await;
+}
+
+async fn test_collection_config_update_with_shards(shard_number: u32) {
+    let collection_dir = TempDir::new("collection").unwrap();
+
+    {
+        let mut collection = simple_collection_fixture(collection_dir.path(), shard_number).await;
+
+        collection
+            .update_params_from_diff(
+                Some(serde_json::from_str(r#"{ "indexing_threshold": 10000 }"#).unwrap()),
+                Some(serde_json::from_str(r#"{ "m": 32 }"#).unwrap()),
+                Some(serde_json::from_str(r#"{ "wal_capacity_mb": 2 }"#).unwrap()),
+            )
+            .await
+            .unwrap();
+
+        let info = collection.info(None).await.unwrap();
+        assert_eq!(info.config.optimizer_config.indexing_threshold, 10_000);
+        assert_eq!(info.config.hnsw_config.m, 32);
+        assert_eq!(info.config.wal_config.wal_capacity_mb, 2);
+
+        collection.before_drop().await;
+    }
+
+    let mut loaded_collection = Collection::load("test".to_string(), collection_dir.path()).await;
+    let info = loaded_collection.info(None).await.unwrap();
+    assert_eq!(info.config.optimizer_config.indexing_threshold, 10_000);
+    assert_eq!(info.config.hnsw_config.m, 32);
+    assert_eq!(info.config.wal_config.wal_capacity_mb, 2);
+    loaded_collection.before_drop().await;
+}
+
+#[tokio::test]
+async fn test_collection_update_queue_limit() {
+    let collection_dir = TempDir::new("collection").unwrap();
+
+    let mut collection = simple_collection_fixture(collection_dir.path(), 1).await;
+
+    collection
+        .update_params_from_diff(
+            Some(serde_json::from_str(r#"{ "max_queued_updates": 10 }"#).unwrap()),
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+    let info = collection.info(None).await.unwrap();
+    assert_eq!(info.config.optimizer_config.max_queued_updates, Some(10));
+
+    // Zero limit means that the queue is not limited
+    collection
+        .update_params_from_diff(
+            Some(serde_json::from_str(r#"{ "max_queued_updates": 0 }"#).unwrap()),
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+
+    let insert_points = CollectionUpdateOperations::PointOperation(
+        Batch {
+            ids: vec![0.into(), 1.into()],
+            vectors: vec![vec![1.0, 0.0, 1.0, 1.0], vec![1.0, 0.0, 1.0, 0.0]],
+            payloads: None,
+            sparse_vectors: None,
+        }
+        .into(),
+    );
+
+    let insert_result = collection.update_from_client(insert_points, true).await;
+    assert!(insert_result.is_ok());
+
+    collection.before_drop().await;
+}
+
 #[test]
 fn test_deserialization() {
     let insert_points = CollectionUpdateOperations::PointOperation(
@@ -226,6 +408,7 @@ fn test_deserialization() {
             ids: vec![0.into(), 1.into()],
             vectors: vec![vec![1.0, 0.0, 1.0, 1.0], vec![1.0, 0.0, 1.0, 0.0]],
             payloads: None,
+            sparse_vectors: None,
         }
         .into(),
     );
@@ -246,11 +429,13 @@ fn test_deserialization2() {
                 id: 0.into(),
                 vector: vec![1.0, 0.0, 1.0, 1.0],
                 payload: None,
+                sparse_vector: None,
             },
             PointStruct {
                 id: 1.into(),
                 vector: vec![1.0, 0.0, 1.0, 0.0],
                 payload: None,
+                sparse_vector: None,
             },
         ]
         .into(),
@@ -294,6 +479,7 @@ async fn test_recommendation_api_with_shards(shard_number: u32) {
                 vec![0.0, 0.0, 0.0, 1.0],
             ],
             payloads: None,
+            sparse_vectors: None,
         }
         .into(),
     );
@@ -306,14 +492,17 @@ async fn test_recommendation_api_with_shards(shard_number: u32) {
     let result = collection
         .recommend_by(
             RecommendRequest {
-                positive: vec![0.into()],
-                negative: vec![8.into()],
+                positive: vec![RecommendExample::PointId(0.into())],
+                negative: vec![RecommendExample::PointId(8.into())],
+                strategy: RecommendStrategy::AverageVector,
                 filter: None,
                 params: None,
                 top: 5,
+                offset: 0,
                 with_payload: None,
                 with_vector: false,
                 score_threshold: None,
+                timeout: None,
             },
             &segment_searcher,
             &Handle::current(),
@@ -329,16 +518,16 @@ async fn test_recommendation_api_with_shards(shard_number: u32) {
 }
 
 #[tokio::test]
-async fn test_read_api() {
-    test_read_api_with_shards(1).await;
-    test_read_api_with_shards(N_SHARDS).await;
+async fn test_recommendation_best_score() {
+    test_recommendation_best_score_with_shards(1).await;
+    test_recommendation_best_score_with_shards(N_SHARDS).await;
 }
 
-async fn test_read_api_with_shards(shard_number: u32) {
+async fn test_recommendation_best_score_with_shards(shard_number: u32) {
     let collection_dir = TempDir::new("collection").unwrap();
     let mut collection = simple_collection_fixture(collection_dir.path(), shard_number).await;
 
-    let insert_points = CollectionUpdateOperations::PointOperation(PointOperations::UpsertPoints(
+    let insert_points = CollectionUpdateOperations::PointOperation(
         Batch {
             ids: vec![0, 1, 2, 3, 4, 5, 6, 7, 8]
                 .into_iter()
@@ -356,115 +545,984 @@ async fn test_read_api_with_shards(shard_number: u32) {
                 vec![0.0, 0.0, 0.0, 1.0],
             ],
             payloads: None,
+            sparse_vectors: None,
         }
         .into(),
-    ));
+    );
 
     collection
         .update_from_client(insert_points, true)
         .await
         .unwrap();
-
     let segment_searcher = SimpleCollectionSearcher::new();
     let result = collection
-        .scroll_by(
-            ScrollRequest {
-                offset: None,
-                limit: Some(2),
+        .recommend_by(
+            RecommendRequest {
+                positive: vec![
+                    RecommendExample::PointId(1.into()),
+                    RecommendExample::PointId(3.into()),
+                ],
+                negative: vec![RecommendExample::PointId(7.into())],
+                strategy: RecommendStrategy::BestScore,
                 filter: None,
-                with_payload: Some(WithPayloadInterface::Bool(true)),
+                params: None,
+                top: 6,
+                offset: 0,
+                with_payload: None,
                 with_vector: false,
+                score_threshold: None,
+                timeout: None,
             },
             &segment_searcher,
+            &Handle::current(),
             None,
         )
         .await
         .unwrap();
+    assert_eq!(result.len(), 6);
 
-    assert_eq!(result.next_page_offset, Some(2.into()));
-    assert_eq!(result.points.len(), 2);
+    // Points, similar to any of the positive examples, are found first
+    let top_ids: HashSet<PointIdType> = result[..2].iter().map(|x| x.id).collect();
+    assert_eq!(top_ids, HashSet::from([2.into(), 4.into()]));
+    assert!(result[..2].iter().all(|x| x.score > 0.0));
+    assert!(result[2..].iter().all(|x| x.score < 0.0));
+
+    // Points, similar to the negative example, are the last ones
+    let bottom_ids: HashSet<PointIdType> = result[4..].iter().map(|x| x.id).collect();
+    assert_eq!(bottom_ids, HashSet::from([0.into(), 8.into()]));
     collection.before_drop().await;
 }
 
 #[tokio::test]
-async fn test_collection_delete_points_by_filter() {
-    test_collection_delete_points_by_filter_with_shards(1).await;
-    test_collection_delete_points_by_filter_with_shards(N_SHARDS).await;
+async fn test_recommendation_vector_examples() {
+    test_recommendation_vector_examples_with_shards(1).await;
+    test_recommendation_vector_examples_with_shards(N_SHARDS).await;
 }
 
-async fn test_collection_delete_points_by_filter_with_shards(shard_number: u32) {
+async fn test_recommendation_vector_examples_with_shards(shard_number: u32) {
     let collection_dir = TempDir::new("collection").unwrap();
-
     let mut collection = simple_collection_fixture(collection_dir.path(), shard_number).await;
 
     let insert_points = CollectionUpdateOperations::PointOperation(
         Batch {
-            ids: vec![0, 1, 2, 3, 4]
+            ids: vec![0, 1, 2, 3, 4, 5, 6, 7, 8]
                 .into_iter()
                 .map(|x| x.into())
                 .collect_vec(),
             vectors: vec![
-                vec![1.0, 0.0, 1.0, 1.0],
-                vec![1.0, 0.0, 1.0, 0.0],
-                vec![1.0, 1.0, 1.0, 1.0],
-                vec![1.0, 1.0, 0.0, 1.0],
+                vec![0.0, 0.0, 1.0, 1.0],
+                vec![1.0, 0.0, 0.0, 0.0],
                 vec![1.0, 0.0, 0.0, 0.0],
+                vec![0.0, 1.0, 0.0, 0.0],
+                vec![0.0, 1.0, 0.0, 0.0],
+                vec![0.0, 0.0, 1.0, 0.0],
+                vec![0.0, 0.0, 1.0, 0.0],
+                vec![0.0, 0.0, 0.0, 1.0],
+                vec![0.0, 0.0, 0.0, 1.0],
             ],
             payloads: None,
+            sparse_vectors: None,
         }
         .into(),
     );
 
-    let insert_result = collection.update_from_client(insert_points, true).await;
+    collection
+        .update_from_client(insert_points, true)
+        .await
+        .unwrap();
+    let segment_searcher = SimpleCollectionSearcher::new();
+    let request = |positive: Vec<RecommendExample>| RecommendRequest {
+        positive,
+        negative: vec![RecommendExample::PointId(8.into())],
+        strategy: RecommendStrategy::AverageVector,
+        filter: None,
+        params: None,
+        top: 5,
+        offset: 0,
+        with_payload: None,
+        with_vector: false,
+        score_threshold: None,
+        timeout: None,
+    };
 
-    match insert_result {
-        Ok(res) => {
-            assert_eq!(res.status, UpdateStatus::Completed)
-        }
-        Err(err) => panic!("operation failed: {:?}", err),
-    }
+    let result = collection
+        .recommend_by(
+            request(vec![RecommendExample::Vector(vec![0.0, 0.0, 2.0, 0.0])]),
+            &segment_searcher,
+            &Handle::current(),
+            None,
+        )
+        .await
+        .unwrap();
+    assert!(!result.is_empty());
+    let top1 = &result[0];
 
-    // delete points with id (0, 3)
-    let to_be_deleted: HashSet<PointIdType> = vec![0.into(), 3.into()].into_iter().collect();
-    let delete_filter = segment::types::Filter {
-        should: None,
-        must: Some(vec![Condition::HasId(HasIdCondition::from(to_be_deleted))]),
-        must_not: None,
-    };
+    assert!(top1.id == 5.into() || top1.id == 6.into());
 
-    let delete_points = CollectionUpdateOperations::PointOperation(
-        PointOperations::DeletePointsByFilter(delete_filter),
-    );
+    let result = collection
+        .recommend_by(
+            request(vec![RecommendExample::Vector(vec![0.0, 0.0, 1.0])]),
+            &segment_searcher,
+            &Handle::current(),
+            None,
+        )
+        .await;
+    assert!(matches!(result, Err(CollectionError::BadInput { .. })));
+    collection.before_drop().await;
+}
 
-    let delete_result = collection.update_from_client(delete_points, true).await;
+#[tokio::test]
+async fn test_search_diversity() {
+    test_search_diversity_with_shards(1).await;
+    test_search_diversity_with_shards(N_SHARDS).await;
+}
 
-    match delete_result {
-        Ok(res) => {
-            assert_eq!(res.status, UpdateStatus::Completed)
+async fn test_search_diversity_with_shards(shard_number: u32) {
+    let collection_dir = TempDir::new("collection").unwrap();
+    let mut collection = simple_collection_fixture(collection_dir.path(), shard_number).await;
+
+    let insert_points = CollectionUpdateOperations::PointOperation(
+        Batch {
+            ids: vec![1, 2, 3, 4].into_iter().map(|x| x.into()).collect_vec(),
+            vectors: vec![
+                vec![1.0, 0.0, 0.0, 0.0],
+                vec![0.99, 0.1, 0.0, 0.0],
+                vec![0.7, 0.7, 0.0, 0.0],
+                vec![0.0, 1.0, 0.0, 0.0],
+            ],
+            payloads: None,
+            sparse_vectors: None,
         }
-        Err(err) => panic!("operation failed: {:?}", err),
-    }
+        .into(),
+    );
+
+    collection
+        .update_from_client(insert_points, true)
+        .await
+        .unwrap();
 
     let segment_searcher = SimpleCollectionSearcher::new();
     let result = collection
-        .scroll_by(
-            ScrollRequest {
-                offset: None,
-                limit: Some(10),
-                filter: None,
-                with_payload: Some(WithPayloadInterface::Bool(false)),
+        .search(
+            SearchRequest {
+                vector: vec![1.0, 0.0, 0.0, 0.0],
+                with_payload: None,
                 with_vector: false,
+                filter: None,
+                params: None,
+                top: 3,
+                offset: 0,
+                score_threshold: None,
+                timeout: None,
+                diversity: Some(Diversity {
+                    lambda: 0.3,
+                    candidates: 4,
+                }),
+                score_boost: None,
+                with_explain: false,
             },
             &segment_searcher,
+            &Handle::current(),
             None,
         )
         .await
         .unwrap();
 
-    // check if we only have 3 out of 5 points left and that the point id were really deleted
-    assert_eq!(result.points.len(), 3);
-    assert_eq!(result.points.get(0).unwrap().id, 1.into());
-    assert_eq!(result.points.get(1).unwrap().id, 2.into());
-    assert_eq!(result.points.get(2).unwrap().id, 4.into());
+    // Near-duplicate of the best point is replaced with more diverse ones
+    let ids = result.iter().map(|point| point.id).collect_vec();
+    assert_eq!(ids, vec![1.into(), 4.into(), 3.into()]);
+    assert!(result.iter().all(|point| point.vector.is_none()));
+
+    let result = collection
+        .search(
+            SearchRequest {
+                vector: vec![1.0, 0.0, 0.0, 0.0],
+                with_payload: None,
+                with_vector: true,
+                filter: None,
+                params: None,
+                top: 3,
+                offset: 0,
+                score_threshold: None,
+                timeout: None,
+                diversity: Some(Diversity {
+                    lambda: 0.3,
+                    candidates: 1_000_000,
+                }),
+                score_boost: None,
+                with_explain: false,
+            },
+            &segment_searcher,
+            &Handle::current(),
+            None,
+        )
+        .await;
+    assert!(matches!(result, Err(CollectionError::BadRequest { .. })));
     collection.before_drop().await;
 }
+
+#[tokio::test]
+async fn test_search_score_boost() {
+    test_search_score_boost_with_shards(1).await;
+    test_search_score_boost_with_shards(N_SHARDS).await;
+}
+
+async fn test_search_score_boost_with_shards(shard_number: u32) {
+    let collection_dir = TempDir::new("collection").unwrap();
+    let mut collection = simple_collection_fixture(collection_dir.path(), shard_number).await;
+
+    let payloads = vec![
+        None,
+        Some(r#"{"published":"2022-06-01T00:00:00Z"}"#),
+        Some(r#"{"popularity":10}"#),
+        Some(r#"{"popularity":1}"#),
+    ];
+    let insert_points = CollectionUpdateOperations::PointOperation(
+        Batch {
+            ids: vec![1, 2, 3, 4].into_iter().map(|x| x.into()).collect_vec(),
+            vectors: vec![
+                vec![1.0, 0.0, 0.0, 0.0],
+                vec![0.9, 0.0, 0.0, 0.0],
+                vec![0.8, 0.0, 0.0, 0.0],
+                vec![0.7, 0.0, 0.0, 0.0],
+            ],
+            payloads: Some(
+                payloads
+                    .into_iter()
+                    .map(|payload| payload.map(|payload| serde_json::from_str(payload).unwrap()))
+                    .collect_vec(),
+            ),
+            sparse_vectors: None,
+        }
+        .into(),
+    );
+
+    collection
+        .update_from_client(insert_points, true)
+        .await
+        .unwrap();
+
+    let score_boost = ScoreBoost {
+        terms: vec![
+            BoostTerm::Linear(LinearTerm {
+                key: "popularity".to_string(),
+                weight: 0.1,
+            }),
+            BoostTerm::ExpDecay(DecayTerm {
+                key: "published".to_string(),
+                target: Some(DecayTarget::Datetime("2022-06-01T00:00:00Z".to_string())),
+                scale: 86400.0,
+                weight: 1.0,
+            }),
+        ],
+        candidates: 4,
+    };
+    let request = |diversity| SearchRequest {
+        vector: vec![1.0, 0.0, 0.0, 0.0],
+        with_payload: None,
+        with_vector: false,
+        filter: None,
+        params: None,
+        top: 2,
+        offset: 0,
+        score_threshold: None,
+        timeout: None,
+        diversity,
+        score_boost: Some(score_boost.clone()),
+        with_explain: false,
+    };
+
+    let segment_searcher = SimpleCollectionSearcher::new();
+    let result = collection
+        .search(request(None), &segment_searcher, &Handle::current(), None)
+        .await
+        .unwrap();
+
+    // 1: 1.0, 2: 0.9 + 1.0, 3: 0.8 + 0.1 * 10, 4: 0.7 + 0.1 * 1
+    let ids = result.iter().map(|point| point.id).collect_vec();
+    assert_eq!(ids, vec![2.into(), 3.into()]);
+    assert!((result[0].score - 1.9).abs() < 1e-5);
+
+    // Threshold is compared with the boosted scores, all similarities are below it
+    let result = collection
+        .search(
+            SearchRequest {
+                score_threshold: Some(1.85),
+                ..request(None)
+            },
+            &segment_searcher,
+            &Handle::current(),
+            None,
+        )
+        .await
+        .unwrap();
+    let ids = result.iter().map(|point| point.id).collect_vec();
+    assert_eq!(ids, vec![2.into()]);
+
+    let diversity = Diversity {
+        lambda: 0.5,
+        candidates: 4,
+    };
+    let result = collection
+        .search(
+            request(Some(diversity)),
+            &segment_searcher,
+            &Handle::current(),
+            None,
+        )
+        .await;
+    assert!(matches!(result, Err(CollectionError::BadRequest { .. })));
+    collection.before_drop().await;
+}
+
+#[tokio::test]
+async fn test_search_offset() {
+    test_search_offset_with_shards(1).await;
+    test_search_offset_with_shards(N_SHARDS).await;
+}
+
+async fn test_search_offset_with_shards(shard_number: u32) {
+    let collection_dir = TempDir::new("collection").unwrap();
+    let mut collection = simple_collection_fixture(collection_dir.path(), shard_number).await;
+
+    let insert_points = CollectionUpdateOperations::PointOperation(
+        Batch {
+            ids: (0..10).map(|x: u64| x.into()).collect_vec(),
+            vectors: (0..10).map(|x| vec![x as f32, 0.0, 0.0, 0.0]).collect_vec(),
+            payloads: Some(
+                (0..10)
+                    .map(|x| Some(serde_json::from_str(&format!(r#"{{"num":{}}}"#, x)).unwrap()))
+                    .collect_vec(),
+            ),
+            sparse_vectors: None,
+        }
+        .into(),
+    );
+
+    collection
+        .update_from_client(insert_points, true)
+        .await
+        .unwrap();
+
+    let segment_searcher = SimpleCollectionSearcher::new();
+    let result = collection
+        .search(
+            SearchRequest {
+                vector: vec![1.0, 0.0, 0.0, 0.0],
+                with_payload: Some(WithPayloadInterface::Bool(true)),
+                with_vector: true,
+                filter: None,
+                params: None,
+                top: 3,
+                offset: 3,
+                score_threshold: None,
+                timeout: None,
+                diversity: None,
+                score_boost: None,
+                with_explain: false,
+            },
+            &segment_searcher,
+            &Handle::current(),
+            None,
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(
+        result.iter().map(|point| point.id).collect_vec(),
+        vec![6.into(), 5.into(), 4.into()]
+    );
+    let num = result[0]
+        .payload
+        .as_ref()
+        .unwrap()
+        .get_value("num")
+        .unwrap();
+    assert_eq!(num, &serde_json::json!(6));
+    assert_eq!(result[0].vector.as_deref(), Some(&[6.0, 0.0, 0.0, 0.0][..]));
+
+    let result = collection
+        .search(
+            SearchRequest {
+                vector: vec![1.0, 0.0, 0.0, 0.0],
+                with_payload: None,
+                with_vector: false,
+                filter: None,
+                params: None,
+                top: 3,
+                offset: usize::MAX,
+                score_threshold: None,
+                timeout: None,
+                diversity: None,
+                score_boost: None,
+                with_explain: false,
+            },
+            &segment_searcher,
+            &Handle::current(),
+            None,
+        )
+        .await;
+    assert!(matches!(result, Err(CollectionError::BadRequest { .. })));
+    collection.before_drop().await;
+}
+
+#[tokio::test]
+async fn test_search_groups() {
+    test_search_groups_with_shards(1).await;
+    test_search_groups_with_shards(N_SHARDS).await;
+}
+
+async fn test_search_groups_with_shards(shard_number: u32) {
+    let collection_dir = TempDir::new("collection").unwrap();
+    let mut collection = simple_collection_fixture(collection_dir.path(), shard_number).await;
+
+    // The best points all belong to the product "a", so the first search is not enough to fill groups
+    let product = |id: u64| match id {
+        0 => None,
+        10..=13 => Some(r#"{"product":"a"}"#),
+        _ => Some(r#"{"product":"b"}"#),
+    };
+    let insert_points = CollectionUpdateOperations::PointOperation(
+        Batch {
+            ids: (0..14).map(|x: u64| x.into()).collect_vec(),
+            vectors: (0..14).map(|x| vec![x as f32, 0.0, 0.0, 0.0]).collect_vec(),
+            payloads: Some(
+                (0..14)
+                    .map(|x| product(x).map(|payload| serde_json::from_str(payload).unwrap()))
+                    .collect_vec(),
+            ),
+            sparse_vectors: None,
+        }
+        .into(),
+    );
+
+    collection
+        .update_from_client(insert_points, true)
+        .await
+        .unwrap();
+
+    let segment_searcher = SimpleCollectionSearcher::new();
+    let result = collection
+        .search_groups(
+            SearchGroupsRequest {
+                vector: vec![1.0, 0.0, 0.0, 0.0],
+                filter: None,
+                params: None,
+                group_by: "product".to_string(),
+                groups: 3,
+                group_size: 2,
+                with_payload: None,
+                with_vector: true,
+                score_threshold: None,
+                timeout: None,
+            },
+            &segment_searcher,
+            &Handle::current(),
+            None,
+        )
+        .await
+        .unwrap();
+
+    // Only two groups exist, points without the grouping field are skipped
+    assert_eq!(result.groups.len(), 2);
+
+    let group_a = &result.groups[0];
+    assert_eq!(group_a.id, GroupId::Keyword("a".to_string()));
+    assert_eq!(
+        group_a.hits.iter().map(|hit| hit.id).collect_vec(),
+        vec![13.into(), 12.into()]
+    );
+
+    let group_b = &result.groups[1];
+    assert_eq!(group_b.id, GroupId::Keyword("b".to_string()));
+    assert_eq!(
+        group_b.hits.iter().map(|hit| hit.id).collect_vec(),
+        vec![9.into(), 8.into()]
+    );
+
+    // Payload is not requested, vectors are loaded for the returned points
+    for hit in result.groups.iter().flat_map(|group| &group.hits) {
+        assert!(hit.payload.is_none());
+        assert!(hit.vector.is_some());
+    }
+
+    // Too many points requested
+    let result = collection
+        .search_groups(
+            SearchGroupsRequest {
+                vector: vec![1.0, 0.0, 0.0, 0.0],
+                filter: None,
+                params: None,
+                group_by: "product".to_string(),
+                groups: usize::MAX,
+                group_size: 2,
+                with_payload: None,
+                with_vector: false,
+                score_threshold: None,
+                timeout: None,
+            },
+            &segment_searcher,
+            &Handle::current(),
+            None,
+        )
+        .await;
+    assert!(matches!(result, Err(CollectionError::BadRequest { .. })));
+    collection.before_drop().await;
+}
+
+#[tokio::test]
+async fn test_facet() {
+    test_facet_with_shards(1).await;
+    test_facet_with_shards(N_SHARDS).await;
+}
+
+async fn test_facet_with_shards(shard_number: u32) {
+    let collection_dir = TempDir::new("collection").unwrap();
+    let mut collection = simple_collection_fixture(collection_dir.path(), shard_number).await;
+
+    let payload = |id: u64| match id {
+        0 => None,
+        1..=4 => Some(r#"{"color":"red","size":1}"#),
+        5..=6 => Some(r#"{"color":["blue","red","blue"],"size":2}"#),
+        _ => Some(r#"{"color":"green","size":2}"#),
+    };
+    let insert_points = CollectionUpdateOperations::PointOperation(
+        Batch {
+            ids: (0..10).map(|x: u64| x.into()).collect_vec(),
+            vectors: (0..10).map(|x| vec![x as f32, 0.0, 0.0, 0.0]).collect_vec(),
+            payloads: Some(
+                (0..10)
+                    .map(|x| payload(x).map(|payload| serde_json::from_str(payload).unwrap()))
+                    .collect_vec(),
+            ),
+            sparse_vectors: None,
+        }
+        .into(),
+    );
+    collection
+        .update_from_client(insert_points, true)
+        .await
+        .unwrap();
+
+    // Values of the field could be counted only if it is indexed
+    let request = FacetRequest {
+        key: "color".to_string(),
+        filter: None,
+        limit: None,
+    };
+    let result = collection.facet(request.clone(), None).await;
+    assert!(matches!(result, Err(CollectionError::BadRequest { .. })));
+
+    let create_index = CollectionUpdateOperations::FieldIndexOperation(
+        FieldIndexOperations::CreateIndex(CreateIndex {
+            field_name: "color".to_string(),
+            field_type: Some(PayloadSchemaType::Keyword),
+        }),
+    );
+    collection
+        .update_from_client(create_index, true)
+        .await
+        .unwrap();
+
+    // Repeated values of the same point are counted once
+    let hit = |value: &str, count: usize| FacetHit {
+        value: FacetValue::Keyword(value.to_string()),
+        count,
+    };
+    let result = collection.facet(request.clone(), None).await.unwrap();
+    assert_eq!(
+        result.hits,
+        vec![hit("red", 6), hit("green", 3), hit("blue", 2)]
+    );
+
+    let filtered_request = FacetRequest {
+        filter: Some(Filter::new_must(Condition::Field(
+            FieldCondition::new_match("size".to_string(), Match::from(2)),
+        ))),
+        limit: Some(2),
+        ..request
+    };
+    let result = collection.facet(filtered_request, None).await.unwrap();
+    assert_eq!(result.hits, vec![hit("green", 3), hit("blue", 2)]);
+    collection.before_drop().await;
+}
+
+#[tokio::test]
+async fn test_distance_matrix() {
+    test_distance_matrix_with_shards(1).await;
+    test_distance_matrix_with_shards(N_SHARDS).await;
+}
+
+async fn test_distance_matrix_with_shards(shard_number: u32) {
+    let collection_dir = TempDir::new("collection").unwrap();
+    let mut collection = simple_collection_fixture(collection_dir.path(), shard_number).await;
+
+    let insert_points = CollectionUpdateOperations::PointOperation(
+        Batch {
+            ids: (1..=5).map(|x: u64| x.into()).collect_vec(),
+            vectors: (1..=5).map(|x| vec![x as f32, 0.0, 0.0, 0.0]).collect_vec(),
+            payloads: None,
+            sparse_vectors: None,
+        }
+        .into(),
+    );
+    collection
+        .update_from_client(insert_points, true)
+        .await
+        .unwrap();
+
+    let segment_searcher = SimpleCollectionSearcher::new();
+    let request = DistanceMatrixRequest {
+        filter: None,
+        sample: Some(0),
+        limit: Some(2),
+    };
+    let result = collection
+        .distance_matrix(request.clone(), &segment_searcher, None)
+        .await;
+    assert!(matches!(result, Err(CollectionError::BadRequest { .. })));
+
+    // All points are sampled, so neighbours are the same as in the full search
+    let request = DistanceMatrixRequest {
+        sample: Some(10),
+        ..request
+    };
+    let matrix = collection
+        .distance_matrix(request.clone(), &segment_searcher, None)
+        .await
+        .unwrap();
+    assert_eq!(matrix.ids, (1..=5).map(|x: u64| x.into()).collect_vec());
+    assert_eq!(matrix.rows, vec![0, 0, 1, 1, 2, 2, 3, 3, 4, 4]);
+    assert_eq!(matrix.cols, vec![4, 3, 4, 3, 4, 3, 4, 2, 3, 2]);
+    assert_eq!(
+        matrix.scores,
+        vec![5.0, 4.0, 10.0, 8.0, 15.0, 12.0, 20.0, 12.0, 20.0, 15.0]
+    );
+
+    let filtered_request = DistanceMatrixRequest {
+        filter: Some(Filter::new_must(Condition::HasId(HasIdCondition {
+            has_id: [1, 2, 3].iter().map(|&x: &u64| x.into()).collect(),
+        }))),
+        ..request
+    };
+    let matrix = collection
+        .distance_matrix(filtered_request, &segment_searcher, None)
+        .await
+        .unwrap();
+    assert_eq!(matrix.ids, (1..=3).map(|x: u64| x.into()).collect_vec());
+    assert_eq!(matrix.rows, vec![0, 0, 1, 1, 2, 2]);
+    assert_eq!(matrix.cols, vec![2, 1, 2, 0, 1, 0]);
+    assert_eq!(matrix.scores, vec![3.0, 2.0, 6.0, 2.0, 6.0, 3.0]);
+
+    let oversized_request = DistanceMatrixRequest {
+        sample: Some(1_000_000),
+        ..request
+    };
+    let result = collection
+        .distance_matrix(oversized_request, &segment_searcher, None)
+        .await;
+    assert!(matches!(result, Err(CollectionError::BadRequest { .. })));
+    collection.before_drop().await;
+}
+
+#[tokio::test]
+async fn test_hybrid_search() {
+    test_hybrid_search_with_shards(1).await;
+    test_hybrid_search_with_shards(N_SHARDS).await;
+}
+
+async fn test_hybrid_search_with_shards(shard_number: u32) {
+    let collection_dir = TempDir::new("collection").unwrap();
+    let mut collection = simple_collection_fixture(collection_dir.path(), shard_number).await;
+
+    let sparse = |indices: Vec<u32>, values: Vec<f32>| Some(SparseVector { indices, values });
+    let insert_points = CollectionUpdateOperations::PointOperation(
+        vec![
+            PointStruct {
+                id: 1.into(),
+                vector: vec![1.0, 0.0, 0.0, 0.0],
+                payload: None,
+                sparse_vector: None,
+            },
+            PointStruct {
+                id: 2.into(),
+                vector: vec![0.9, 0.1, 0.0, 0.0],
+                payload: None,
+                sparse_vector: sparse(vec![1], vec![1.0]),
+            },
+            PointStruct {
+                id: 3.into(),
+                vector: vec![0.5, 0.5, 0.0, 0.0],
+                payload: None,
+                sparse_vector: sparse(vec![7, 1], vec![0.5, 1.0]),
+            },
+            PointStruct {
+                id: 4.into(),
+                vector: vec![0.0, 1.0, 0.0, 0.0],
+                payload: None,
+                sparse_vector: sparse(vec![7], vec![1.0]),
+            },
+        ]
+        .into(),
+    );
+
+    collection
+        .update_from_client(insert_points, true)
+        .await
+        .unwrap();
+
+    let request = HybridSearchRequest {
+        vector: vec![1.0, 0.0, 0.0, 0.0],
+        sparse_vector: SparseVector {
+            indices: vec![7],
+            values: vec![1.0],
+        },
+        fusion: None,
+        dense_weight: None,
+        filter: None,
+        params: None,
+        top: 2,
+        with_payload: None,
+        with_vector: false,
+        timeout: None,
+    };
+
+    let segment_searcher = SimpleCollectionSearcher::new();
+
+    // Dense search finds [1, 2], sparse search finds [4, 3]
+    let result = collection
+        .hybrid_search(request.clone(), &segment_searcher, &Handle::current(), None)
+        .await
+        .unwrap();
+    let ids = result.iter().map(|point| point.id).collect_vec();
+    assert_eq!(ids, vec![1.into(), 4.into()]);
+
+    let result = collection
+        .hybrid_search(
+            HybridSearchRequest {
+                fusion: Some(Fusion::WeightedSum),
+                dense_weight: Some(0.2),
+                ..request.clone()
+            },
+            &segment_searcher,
+            &Handle::current(),
+            None,
+        )
+        .await
+        .unwrap();
+    let ids = result.iter().map(|point| point.id).collect_vec();
+    assert_eq!(ids, vec![4.into(), 1.into()]);
+    assert!((result[0].score - 0.8).abs() < 1e-6);
+
+    let result = collection
+        .hybrid_search(
+            HybridSearchRequest {
+                fusion: Some(Fusion::WeightedSum),
+                dense_weight: Some(1.5),
+                ..request
+            },
+            &segment_searcher,
+            &Handle::current(),
+            None,
+        )
+        .await;
+    assert!(matches!(result, Err(CollectionError::BadRequest { .. })));
+    collection.before_drop().await;
+}
+
+#[tokio::test]
+async fn test_read_api() {
+    test_read_api_with_shards(1).await;
+    test_read_api_with_shards(N_SHARDS).await;
+}
+
+async fn test_read_api_with_shards(shard_number: u32) {
+    let collection_dir = TempDir::new("collection").unwrap();
+    let mut collection = simple_collection_fixture(collection_dir.path(), shard_number).await;
+
+    let insert_points = CollectionUpdateOperations::PointOperation(PointOperations::UpsertPoints(
+        Batch {
+            ids: vec![0, 1, 2, 3, 4, 5, 6, 7, 8]
+                .into_iter()
+                .map(|x| x.into())
+                .collect_vec(),
+            vectors: vec![
+                vec![0.0, 0.0, 1.0, 1.0],
+                vec![1.0, 0.0, 0.0, 0.0],
+                vec![1.0, 0.0, 0.0, 0.0],
+                vec![0.0, 1.0, 0.0, 0.0],
+                vec![0.0, 1.0, 0.0, 0.0],
+                vec![0.0, 0.0, 1.0, 0.0],
+                vec![0.0, 0.0, 1.0, 0.0],
+                vec![0.0, 0.0, 0.0, 1.0],
+                vec![0.0, 0.0, 0.0, 1.0],
+            ],
+            payloads: None,
+            sparse_vectors: None,
+        }
+        .into(),
+    ));
+
+    collection
+        .update_from_client(insert_points, true)
+        .await
+        .unwrap();
+
+    let segment_searcher = SimpleCollectionSearcher::new();
+    let result = collection
+        .scroll_by(
+            ScrollRequest {
+                offset: None,
+                limit: Some(2),
+                filter: None,
+                with_payload: Some(WithPayloadInterface::Bool(true)),
+                with_vector: false,
+                timeout: None,
+            },
+            &segment_searcher,
+            None,
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(result.next_page_offset, Some(2.into()));
+    assert_eq!(result.points.len(), 2);
+    collection.before_drop().await;
+}
+
+#[tokio::test]
+async fn test_collection_delete_points_by_filter() {
+    test_collection_delete_points_by_filter_with_shards(1).await;
+    test_collection_delete_points_by_filter_with_shards(N_SHARDS).await;
+}
+
+async fn test_collection_delete_points_by_filter_with_shards(shard_number: u32) {
+    let collection_dir = TempDir::new("collection").unwrap();
+
+    let mut collection = simple_collection_fixture(collection_dir.path(), shard_number).await;
+
+    let insert_points = CollectionUpdateOperations::PointOperation(
+        Batch {
+            ids: vec![0, 1, 2, 3, 4]
+                .into_iter()
+                .map(|x| x.into())
+                .collect_vec(),
+            vectors: vec![
+                vec![1.0, 0.0, 1.0, 1.0],
+                vec![1.0, 0.0, 1.0, 0.0],
+                vec![1.0, 1.0, 1.0, 1.0],
+                vec![1.0, 1.0, 0.0, 1.0],
+                vec![1.0, 0.0, 0.0, 0.0],
+            ],
+            payloads: None,
+            sparse_vectors: None,
+        }
+        .into(),
+    );
+
+    let insert_result = collection.update_from_client(insert_points, true).await;
+
+    match insert_result {
+        Ok(res) => {
+            assert_eq!(res.status, UpdateStatus::Completed)
+        }
+        Err(err) => panic!("operation failed: {:?}", err),
+    }
+
+    // delete points with id (0, 3)
+    let to_be_deleted: HashSet<PointIdType> = vec![0.into(), 3.into()].into_iter().collect();
+    let delete_filter = segment::types::Filter {
+        should: None,
+        must: Some(vec![Condition::HasId(HasIdCondition::from(to_be_deleted))]),
+        must_not: None,
+    };
+
+    let delete_points = CollectionUpdateOperations::PointOperation(
+        PointOperations::DeletePointsByFilter(delete_filter),
+    );
+
+    let delete_result = collection.update_from_client(delete_points, true).await;
+
+    match delete_result {
+        Ok(res) => {
+            assert_eq!(res.status, UpdateStatus::Completed)
+        }
+        Err(err) => panic!("operation failed: {:?}", err),
+    }
+
+    let segment_searcher = SimpleCollectionSearcher::new();
+    let result = collection
+        .scroll_by(
+            ScrollRequest {
+                offset: None,
+                limit: Some(10),
+                filter: None,
+                with_payload: Some(WithPayloadInterface::Bool(false)),
+                with_vector: false,
+                timeout: None,
+            },
+            &segment_searcher,
+            None,
+        )
+        .await
+        .unwrap();
+
+    // check if we only have 3 out of 5 points left and that the point id were really deleted
+    assert_eq!(result.points.len(), 3);
+    assert_eq!(result.points.get(0).unwrap().id, 1.into());
+    assert_eq!(result.points.get(1).unwrap().id, 2.into());
+    assert_eq!(result.points.get(2).unwrap().id, 4.into());
+    collection.before_drop().await;
+}
+
+#[tokio::test]
+async fn test_stopped_search() {
+    let num_segments = 3;
+    let segments_dir = TempDir::new("segments").unwrap();
+
+    let mut holder = SegmentHolder::default();
+    for segment_idx in 0..num_segments {
+        let mut segment = build_simple_segment(segments_dir.path(), 4, Distance::Dot).unwrap();
+        for point_idx in 0..10u64 {
+            let point_id = segment_idx * 10 + point_idx;
+            segment
+                .upsert_point(
+                    point_id,
+                    point_id.into(),
+                    &[1.0, 0.0, 1.0, point_idx as f32],
+                )
+                .unwrap();
+        }
+        holder.add(segment);
+    }
+    let segments = RwLock::new(holder);
+
+    let request = Arc::new(CoreSearchRequest {
+        query: QueryVector::Nearest(vec![1.0, 1.0, 1.0, 1.0]),
+        filter: None,
+        params: None,
+        top: 5,
+        with_payload: None,
+        with_vector: false,
+        with_explain: true,
+        score_threshold: None,
+        timeout: None,
+        score_boost: None,
+    });
+    let searcher = SimpleCollectionSearcher::new();
+
+    let is_stopped = Arc::new(AtomicBool::new(false));
+    let result = searcher
+        .search_with_stop(&segments, request.clone(), &Handle::current(), is_stopped)
+        .await
+        .unwrap();
+    assert_eq!(result.points.len(), 5);
+    assert_eq!(result.segments.len(), num_segments as usize);
+
+    // Each segment notices the raised flag and interrupts its search
+    let is_stopped = Arc::new(AtomicBool::new(true));
+    let result = searcher
+        .search_with_stop(&segments, request, &Handle::current(), is_stopped)
+        .await;
+    assert!(matches!(result, Err(CollectionError::Cancelled { .. })));
+}
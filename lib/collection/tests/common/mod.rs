@@ -21,6 +21,7 @@ pub const TEST_OPTIMIZERS_CONFIG: OptimizersConfig = OptimizersConfig {
     payload_indexing_threshold: 20_000,
     flush_interval_sec: 30,
     max_optimization_threads: 2,
+    max_queued_updates: None,
 };
 
 #[allow(dead_code)]
@@ -34,6 +35,8 @@ pub async fn simple_collection_fixture(collection_path: &Path, shard_number: u32
         vector_size: 4,
         distance: Distance::Dot,
         shard_number: NonZeroU32::new(shard_number).expect("Shard number can not be zero"),
+        product_quantization: None,
+        vector_datatype: Default::default(),
     };
 
     Collection::new(
@@ -3,8 +3,9 @@ mod prof;
 use criterion::{criterion_group, criterion_main, Criterion};
 use rand::rngs::StdRng;
 use rand::{thread_rng, SeedableRng};
+use rayon::prelude::*;
 use segment::fixtures::index_fixtures::{FakeFilterContext, TestRawScorerProducer};
-use segment::index::hnsw_index::graph_layers::GraphLayers;
+use segment::index::hnsw_index::graph_layers_builder::GraphLayersBuilder;
 use segment::index::hnsw_index::point_scorer::FilteredScorer;
 use segment::spaces::simple::CosineMetric;
 use segment::types::PointOffsetType;
@@ -23,16 +24,39 @@ fn hnsw_benchmark(c: &mut Criterion) {
     group.bench_function("hnsw_index", |b| {
         b.iter(|| {
             let mut rng = thread_rng();
-            let mut graph_layers =
-                GraphLayers::new(NUM_VECTORS, M, M * 2, EF_CONSTRUCT, 10, USE_HEURISTIC);
+            let mut graph_layers_builder =
+                GraphLayersBuilder::new(NUM_VECTORS, M, M * 2, EF_CONSTRUCT, 10, USE_HEURISTIC);
+            for idx in 0..(NUM_VECTORS as PointOffsetType) {
+                let level = graph_layers_builder.get_random_layer(&mut rng);
+                graph_layers_builder.set_levels(idx, level);
+            }
             let fake_filter_context = FakeFilterContext {};
             for idx in 0..(NUM_VECTORS as PointOffsetType) {
                 let added_vector = vector_holder.vectors.get(idx).to_vec();
                 let raw_scorer = vector_holder.get_raw_scorer(added_vector);
                 let scorer = FilteredScorer::new(&raw_scorer, Some(&fake_filter_context));
-                let level = graph_layers.get_random_layer(&mut rng);
-                graph_layers.link_new_point(idx, level, scorer);
+                graph_layers_builder.link_new_point(idx, scorer);
+            }
+        })
+    });
+    group.bench_function("hnsw_index_parallel", |b| {
+        b.iter(|| {
+            let mut rng = thread_rng();
+            let mut graph_layers_builder =
+                GraphLayersBuilder::new(NUM_VECTORS, M, M * 2, EF_CONSTRUCT, 10, USE_HEURISTIC);
+            for idx in 0..(NUM_VECTORS as PointOffsetType) {
+                let level = graph_layers_builder.get_random_layer(&mut rng);
+                graph_layers_builder.set_levels(idx, level);
             }
+            (0..(NUM_VECTORS as PointOffsetType))
+                .into_par_iter()
+                .for_each(|idx| {
+                    let fake_filter_context = FakeFilterContext {};
+                    let added_vector = vector_holder.vectors.get(idx).to_vec();
+                    let raw_scorer = vector_holder.get_raw_scorer(added_vector);
+                    let scorer = FilteredScorer::new(&raw_scorer, Some(&fake_filter_context));
+                    graph_layers_builder.link_new_point(idx, scorer);
+                });
         })
     });
     group.finish();
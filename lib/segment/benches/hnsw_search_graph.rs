@@ -4,10 +4,11 @@ use criterion::{criterion_group, criterion_main, Criterion};
 use rand::rngs::StdRng;
 use rand::{thread_rng, SeedableRng};
 use segment::fixtures::index_fixtures::{random_vector, FakeFilterContext, TestRawScorerProducer};
-use segment::index::hnsw_index::graph_layers::GraphLayers;
+use segment::index::hnsw_index::graph_layers_builder::GraphLayersBuilder;
 use segment::index::hnsw_index::point_scorer::FilteredScorer;
 use segment::spaces::simple::CosineMetric;
 use segment::types::PointOffsetType;
+use std::sync::atomic::AtomicBool;
 
 const NUM_VECTORS: usize = 100000;
 const DIM: usize = 64;
@@ -24,23 +25,28 @@ fn hnsw_benchmark(c: &mut Criterion) {
     let mut rng = thread_rng();
     let fake_filter_context = FakeFilterContext {};
 
-    let mut graph_layers = GraphLayers::new(NUM_VECTORS, M, M * 2, EF_CONSTRUCT, 10, USE_HEURISTIC);
+    let mut graph_layers_builder =
+        GraphLayersBuilder::new(NUM_VECTORS, M, M * 2, EF_CONSTRUCT, 10, USE_HEURISTIC);
+    for idx in 0..(NUM_VECTORS as PointOffsetType) {
+        let level = graph_layers_builder.get_random_layer(&mut rng);
+        graph_layers_builder.set_levels(idx, level);
+    }
     for idx in 0..(NUM_VECTORS as PointOffsetType) {
         let added_vector = vector_holder.vectors.get(idx).to_vec();
         let raw_scorer = vector_holder.get_raw_scorer(added_vector);
         let scorer = FilteredScorer::new(&raw_scorer, Some(&fake_filter_context));
-        let level = graph_layers.get_random_layer(&mut rng);
-        graph_layers.link_new_point(idx, level, scorer);
+        graph_layers_builder.link_new_point(idx, scorer);
     }
+    let graph_layers = graph_layers_builder.into_graph_layers();
 
     group.bench_function("hnsw_search", |b| {
         b.iter(|| {
             let query = random_vector(&mut rng, DIM);
 
             let raw_scorer = vector_holder.get_raw_scorer(query);
-            let scorer = FilteredScorer::new(&raw_scorer, Some(&fake_filter_context));
+            let mut scorer = FilteredScorer::new(&raw_scorer, Some(&fake_filter_context));
 
-            graph_layers.search(TOP, EF, scorer);
+            graph_layers.search(TOP, EF, &mut scorer, &AtomicBool::new(false));
         })
     });
 
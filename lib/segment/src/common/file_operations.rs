@@ -72,6 +72,12 @@ pub fn atomic_save_json<N: DeserializeOwned + Serialize>(
     Ok(())
 }
 
+pub fn atomic_save_raw(path: &Path, data: &[u8]) -> FileOperationResult<()> {
+    let af = AtomicFile::new(path, AllowOverwrite);
+    af.write(|f| f.write_all(data))?;
+    Ok(())
+}
+
 pub fn read_json<N: DeserializeOwned + Serialize>(path: &Path) -> FileOperationResult<N> {
     let mut contents = String::new();
 
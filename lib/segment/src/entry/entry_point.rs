@@ -1,14 +1,15 @@
 use crate::common::file_operations::FileStorageError;
 use crate::types::{
-    Filter, Payload, PayloadKeyType, PayloadKeyTypeRef, PayloadSchemaType, PointIdType,
-    ScoredPoint, SearchParams, SegmentConfig, SegmentInfo, SegmentType, SeqNumberType,
-    VectorElementType, WithPayload,
+    FacetValue, Filter, Payload, PayloadKeyType, PayloadKeyTypeRef, PayloadSchemaType, PointIdType,
+    QueryVector, ScoredPoint, SearchParams, SegmentConfig, SegmentExplain, SegmentInfo,
+    SegmentType, SeqNumberType, SparseVector, VectorElementType, WithPayload,
 };
 use atomicwrites::Error as AtomicIoError;
 use rocksdb::Error;
 use std::collections::HashMap;
 use std::io::Error as IoError;
 use std::result;
+use std::sync::atomic::AtomicBool;
 use thiserror::Error;
 
 #[derive(Error, Debug, Clone)]
@@ -19,6 +20,8 @@ pub enum OperationError {
         expected_dim: usize,
         received_dim: usize,
     },
+    #[error("Wrong sparse vector: {description}")]
+    WrongSparseVector { description: String },
     #[error("No point with id {missed_point_id} found")]
     PointIdError { missed_point_id: PointIdType },
     #[error("Payload type does not match with previously given for field {field_name}. Expected: {expected_type}")]
@@ -28,6 +31,8 @@ pub enum OperationError {
     },
     #[error("Unable to infer type for the field '{field_name}'. Please specify `field_type`")]
     TypeInferenceError { field_name: PayloadKeyType },
+    #[error("Wrong segment configuration: {description}")]
+    WrongConfig { description: String },
     /// Service Error prevents further update of the collection until it is fixed.
     /// Should only be used for hardware, data corruption, IO, or other unexpected internal errors.
     #[error("Service runtime error: {description}")]
@@ -119,6 +124,9 @@ pub trait SegmentEntry {
     /// Get version of specified point
     fn point_version(&self, point_id: PointIdType) -> Option<SeqNumberType>;
 
+    /// Search for `top` closest points.
+    /// Returns `Cancelled` error if `is_stopped` flag is raised before the search is complete.
+    #[allow(clippy::too_many_arguments)]
     fn search(
         &self,
         vector: &[VectorElementType],
@@ -127,7 +135,37 @@ pub trait SegmentEntry {
         filter: Option<&Filter>,
         top: usize,
         params: Option<&SearchParams>,
-    ) -> OperationResult<Vec<ScoredPoint>>;
+        is_stopped: &AtomicBool,
+    ) -> OperationResult<Vec<ScoredPoint>> {
+        self.search_query(
+            &QueryVector::Nearest(vector.to_vec()),
+            with_payload,
+            with_vector,
+            false,
+            filter,
+            top,
+            params,
+            is_stopped,
+        )
+        .map(|(points, _)| points)
+    }
+
+    /// Search for `top` best scored points by the given query.
+    /// If `with_explain` is set, the explanation of each searched segment is returned alongside the
+    /// points, even if nothing is found in it.
+    /// Returns `Cancelled` error if `is_stopped` flag is raised before the search is complete.
+    #[allow(clippy::too_many_arguments)]
+    fn search_query(
+        &self,
+        query: &QueryVector,
+        with_payload: &WithPayload,
+        with_vector: bool,
+        with_explain: bool,
+        filter: Option<&Filter>,
+        top: usize,
+        params: Option<&SearchParams>,
+        is_stopped: &AtomicBool,
+    ) -> OperationResult<(Vec<ScoredPoint>, Vec<SegmentExplain>)>;
 
     fn upsert_point(
         &mut self,
@@ -169,20 +207,50 @@ pub trait SegmentEntry {
         point_id: PointIdType,
     ) -> OperationResult<bool>;
 
+    /// Set sparse vector of the existing point, replacing the previous one
+    fn set_sparse_vector(
+        &mut self,
+        op_num: SeqNumberType,
+        point_id: PointIdType,
+        sparse_vector: &SparseVector,
+    ) -> OperationResult<bool>;
+
     fn vector(&self, point_id: PointIdType) -> OperationResult<Vec<VectorElementType>>;
 
     fn payload(&self, point_id: PointIdType) -> OperationResult<Payload>;
 
+    fn sparse_vector(&self, point_id: PointIdType) -> OperationResult<Option<SparseVector>>;
+
     fn iter_points(&self) -> Box<dyn Iterator<Item = PointIdType> + '_>;
 
     /// Paginate over points which satisfies filtering condition starting with `offset` id including.
+    /// Reading is interrupted as soon as `is_stopped` flag is raised, partial result is returned in this case.
     fn read_filtered<'a>(
         &'a self,
         offset: Option<PointIdType>,
         limit: usize,
         filter: Option<&'a Filter>,
+        is_stopped: &AtomicBool,
     ) -> Vec<PointIdType>;
 
+    /// Count points, which satisfy filtering condition, with each value of the keyword or integer field.
+    /// Values are taken from the map index of the field, if it is built in this segment.
+    fn facet(
+        &self,
+        key: PayloadKeyTypeRef,
+        filter: Option<&Filter>,
+    ) -> OperationResult<HashMap<FacetValue, usize>>;
+
+    /// For each query find `top` points with the largest internal score among `points`, stored in this segment.
+    /// Queries, stored in this segment, are scored with `RawScorer::score_internal`, other queries - with their vectors.
+    /// The query point itself is not returned.
+    fn sample_neighbours(
+        &self,
+        queries: &[(PointIdType, Vec<VectorElementType>)],
+        points: &[PointIdType],
+        top: usize,
+    ) -> OperationResult<Vec<Vec<ScoredPoint>>>;
+
     /// Check if there is point with `point_id` in this segment.
     fn has_point(&self, point_id: PointIdType) -> bool;
 
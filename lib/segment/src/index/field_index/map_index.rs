@@ -2,6 +2,7 @@ use std::collections::HashMap;
 use std::hash::Hash;
 use std::{iter, mem};
 
+use itertools::Itertools;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
@@ -41,6 +42,22 @@ impl<N: Hash + Eq + Clone> PersistedMapIndex<N> {
         self.point_to_values.get(idx as usize)
     }
 
+    /// Number of the given points with each of the values
+    pub fn count_values(
+        &self,
+        points: impl Iterator<Item = PointOffsetType>,
+    ) -> HashMap<&N, usize> {
+        let mut counts = HashMap::new();
+        for idx in points {
+            if let Some(values) = self.get_values(idx) {
+                for value in values.iter().unique() {
+                    *counts.entry(value).or_insert(0) += 1;
+                }
+            }
+        }
+        counts
+    }
+
     pub fn check_value(&self, idx: PointOffsetType, reference: &N) -> bool {
         self.get_values(idx)
             .map(|values| values.iter().any(|x| x == reference))
@@ -37,6 +37,13 @@ impl EntryPoints {
         }
     }
 
+    /// Iterate over all entry points, including extra ones
+    pub fn iter(&self) -> impl Iterator<Item = &EntryPoint> {
+        self.entry_points
+            .iter()
+            .chain(self.extra_entry_points.iter())
+    }
+
     pub fn merge_from_other(&mut self, mut other: EntryPoints) {
         self.entry_points.append(&mut other.entry_points);
         // Do not merge `extra_entry_points` to prevent duplications
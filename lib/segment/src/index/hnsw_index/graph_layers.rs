@@ -1,20 +1,21 @@
 use crate::common::file_operations::{atomic_save_bin, read_bin};
 use crate::common::utils::rev_range;
-use crate::entry::entry_point::OperationResult;
-use crate::index::hnsw_index::entry_points::EntryPoints;
+use crate::entry::entry_point::{OperationError, OperationResult};
+use crate::index::hnsw_index::entry_points::{EntryPoint, EntryPoints};
+use crate::index::hnsw_index::graph_links::GraphLinks;
 use crate::index::hnsw_index::point_scorer::FilteredScorer;
 use crate::index::hnsw_index::search_context::SearchContext;
 use crate::index::visited_pool::{VisitedList, VisitedPool};
 use crate::spaces::tools::FixedLengthPriorityQueue;
-use crate::types::{PointOffsetType, ScoreType};
+use crate::types::PointOffsetType;
 use crate::vector_storage::ScoredPointOffset;
 use itertools::Itertools;
-use rand::distributions::Uniform;
-use rand::Rng;
+use log::debug;
 use serde::{Deserialize, Serialize};
-use std::cmp::{max, min};
-use std::collections::BinaryHeap;
+use std::cmp::max;
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
 
 pub type LinkContainer = Vec<PointOffsetType>;
 pub type LinkContainerRef<'a> = &'a [PointOffsetType];
@@ -22,132 +23,29 @@ pub type LayersContainer = Vec<LinkContainer>;
 
 pub const HNSW_GRAPH_FILE: &str = "graph.bin";
 
-#[derive(Deserialize, Serialize, Debug)]
-pub struct GraphLayers {
-    max_level: usize,
-    m: usize,
-    m0: usize,
-    ef_construct: usize,
-    level_factor: f64,
-    // Exclude points according to "not closer than base" heuristic?
-    use_heuristic: bool,
-    // Factor of level probability
-    links_layers: Vec<LayersContainer>,
-    entry_points: EntryPoints,
-
-    // Fields used on construction phase only
-    #[serde(skip)]
-    visited_pool: VisitedPool,
-}
-
-/// Object contains links between nodes for HNSW search
-///
-/// Assume all scores are similarities. Larger score = closer points
-impl GraphLayers {
-    pub fn new_with_params(
-        num_vectors: usize, // Initial number of points in index
-        m: usize,           // Expected M for non-first layer
-        m0: usize,          // Expected M for first layer
-        ef_construct: usize,
-        entry_points_num: usize, // Depends on number of points
-        use_heuristic: bool,
-        reserve: bool,
-    ) -> Self {
-        let mut links_layers: Vec<LayersContainer> = vec![];
-
-        for _i in 0..num_vectors {
-            let mut links: LinkContainer = Vec::new();
-            if reserve {
-                links.reserve(m0);
-            }
-            links_layers.push(vec![links]);
-        }
-
-        GraphLayers {
-            max_level: 0,
-            m,
-            m0,
-            ef_construct,
-            level_factor: 1.0 / (m as f64).ln(),
-            use_heuristic,
-            links_layers,
-            entry_points: EntryPoints::new(entry_points_num),
-            visited_pool: VisitedPool::new(),
-        }
-    }
-
-    pub fn new(
-        num_vectors: usize, // Initial number of points in index
-        m: usize,           // Expected M for non-first layer
-        m0: usize,          // Expected M for first layer
-        ef_construct: usize,
-        entry_points_num: usize, // Depends on number of points
-        use_heuristic: bool,
-    ) -> Self {
-        Self::new_with_params(
-            num_vectors,
-            m,
-            m0,
-            ef_construct,
-            entry_points_num,
-            use_heuristic,
-            true,
-        )
-    }
-
-    fn num_points(&self) -> usize {
-        self.links_layers.len()
-    }
-
-    pub fn point_level(&self, point_id: PointOffsetType) -> usize {
-        self.links_layers[point_id as usize].len() - 1
-    }
-
-    /// Get links of current point
-    fn links(&self, point_id: PointOffsetType, level: usize) -> LinkContainerRef {
-        &self.links_layers[point_id as usize][level]
-    }
+/// Search operations, shared by the ready graph and the graph under construction
+pub trait GraphLayersBase {
+    fn get_visited_list_from_pool(&self) -> VisitedList;
 
-    /// Get M based on current level
-    fn get_m(&self, level: usize) -> usize {
-        if level == 0 {
-            self.m0
-        } else {
-            self.m
-        }
-    }
+    fn return_visited_list_to_pool(&self, visited_list: VisitedList);
 
-    /// Generate random level for a new point, according to geometric distribution
-    pub fn get_random_layer<R>(&self, rng: &mut R) -> usize
+    /// Calls `f` for each link of the point on the given level
+    fn links_map<F>(&self, point_id: PointOffsetType, level: usize, f: F)
     where
-        R: Rng + ?Sized,
-    {
-        let distribution = Uniform::new(0.0, 1.0);
-        let sample: f64 = rng.sample(distribution);
-        let picked_level = -sample.ln() * self.level_factor;
-        picked_level.round() as usize
-    }
+        F: FnMut(PointOffsetType);
 
-    fn set_levels(&mut self, point_id: PointOffsetType, level: usize) {
-        if self.links_layers.len() <= point_id as usize {
-            self.links_layers.resize(point_id as usize, vec![]);
-        }
-        let point_layers = &mut self.links_layers[point_id as usize];
-        while point_layers.len() <= level {
-            let mut links = vec![];
-            links.reserve(self.m);
-            point_layers.push(links);
-        }
-        self.max_level = max(level, self.max_level);
-    }
+    /// Get M based on current level
+    fn get_m(&self, level: usize) -> usize;
 
     /// Greedy search for closest points within a single graph layer
+    /// Search is interrupted once `is_stopped` flag is raised.
     fn _search_on_level(
         &self,
         searcher: &mut SearchContext,
         level: usize,
         visited_list: &mut VisitedList,
         points_scorer: &mut FilteredScorer,
+        is_stopped: &AtomicBool,
     ) {
         let limit = self.get_m(level);
         let mut points_ids: Vec<PointOffsetType> = Vec::with_capacity(2 * limit);
@@ -157,12 +55,16 @@ impl GraphLayers {
                 break;
             }
 
+            if is_stopped.load(Ordering::Relaxed) {
+                break;
+            }
+
             points_ids.clear();
-            for link in self.links(candidate.idx, level) {
-                if !visited_list.check_and_update_visited(*link) {
-                    points_ids.push(*link);
+            self.links_map(candidate.idx, level, |link| {
+                if !visited_list.check_and_update_visited(link) {
+                    points_ids.push(link);
                 }
-            }
+            });
 
             let scores = points_scorer.score_points(&mut points_ids, limit);
             scores
@@ -179,12 +81,19 @@ impl GraphLayers {
         ef: usize,
         points_scorer: &mut FilteredScorer,
         existing_links: LinkContainerRef,
+        is_stopped: &AtomicBool,
     ) -> FixedLengthPriorityQueue<ScoredPointOffset> {
-        let mut visited_list = self.visited_pool.get(self.num_points());
+        let mut visited_list = self.get_visited_list_from_pool();
         visited_list.check_and_update_visited(level_entry.idx);
         let mut search_context = SearchContext::new(level_entry, ef);
 
-        self._search_on_level(&mut search_context, level, &mut visited_list, points_scorer);
+        self._search_on_level(
+            &mut search_context,
+            level,
+            &mut visited_list,
+            points_scorer,
+            is_stopped,
+        );
 
         for &existing_link in existing_links {
             if !visited_list.check(existing_link) {
@@ -195,7 +104,7 @@ impl GraphLayers {
             }
         }
 
-        self.visited_pool.return_back(visited_list);
+        self.return_visited_list_to_pool(visited_list);
         search_context.nearest
     }
 
@@ -222,7 +131,7 @@ impl GraphLayers {
                 changed = false;
 
                 links.clear();
-                links.extend_from_slice(self.links(current_point.idx, level));
+                self.links_map(current_point.idx, level, |link| links.push(link));
 
                 let scores = points_scorer.score_points(&mut links, limit);
                 scores.iter().copied().for_each(|score_point| {
@@ -236,236 +145,177 @@ impl GraphLayers {
         current_point
     }
 
-    /// Connect new point to links, so that links contains only closest points
-    fn connect_new_point<F>(
-        links: &mut LinkContainer,
-        new_point_id: PointOffsetType,
-        target_point_id: PointOffsetType,
-        level_m: usize,
-        mut score_internal: F,
-    ) where
-        F: FnMut(PointOffsetType, PointOffsetType) -> ScoreType,
-    {
-        // ToDo: binary search here ? (most likely does not worth it)
-        let new_to_target = score_internal(target_point_id, new_point_id);
-
-        let mut id_to_insert = links.len();
-        for (i, &item) in links.iter().enumerate() {
-            let target_to_link = score_internal(target_point_id, item);
-            if target_to_link < new_to_target {
-                id_to_insert = i;
-                break;
-            }
-        }
+    /// Search for `top` closest points on the zero level, starting from the given entry point
+    fn search_from_entry(
+        &self,
+        entry_point: EntryPoint,
+        top: usize,
+        ef: usize,
+        points_scorer: &mut FilteredScorer,
+        is_stopped: &AtomicBool,
+    ) -> Vec<ScoredPointOffset> {
+        let zero_level_entry =
+            self.search_entry(entry_point.point_id, entry_point.level, 0, points_scorer);
 
-        if links.len() < level_m {
-            links.insert(id_to_insert, new_point_id);
-        } else if id_to_insert != links.len() {
-            links.pop();
-            links.insert(id_to_insert, new_point_id);
-        }
+        let nearest = self.search_on_level(
+            zero_level_entry,
+            0,
+            max(top, ef),
+            points_scorer,
+            &[],
+            is_stopped,
+        );
+        nearest.into_iter().take(top).collect_vec()
     }
+}
 
-    /// <https://github.com/nmslib/hnswlib/issues/99>
-    fn select_candidate_with_heuristic_from_sorted<F>(
-        candidates: impl Iterator<Item = ScoredPointOffset>,
-        m: usize,
-        mut score_internal: F,
-    ) -> Vec<PointOffsetType>
-    where
-        F: FnMut(PointOffsetType, PointOffsetType) -> ScoreType,
-    {
-        let mut result_list = vec![];
-        result_list.reserve(m);
-        for current_closest in candidates {
-            if result_list.len() >= m {
-                break;
-            }
-            let mut is_good = true;
-            for &selected_point in &result_list {
-                let dist_to_already_selected = score_internal(current_closest.idx, selected_point);
-                if dist_to_already_selected > current_closest.score {
-                    is_good = false;
-                    break;
-                }
-            }
-            if is_good {
-                result_list.push(current_closest.idx);
-            }
-        }
+/// Links of a previously built graph, converted into point offsets of another segment.
+/// Points of the reused graph are not inserted again, when the new graph is built.
+pub struct ReusedGraph {
+    pub(super) m: usize,
+    pub(super) m0: usize,
+    pub(super) points: Vec<(PointOffsetType, LayersContainer)>,
+}
+
+impl ReusedGraph {
+    pub fn num_points(&self) -> usize {
+        self.points.len()
+    }
+}
 
-        result_list
+/// Content of the graph file.
+///
+/// Graph files of the legacy format store all links in `links_layers`.
+/// Current format keeps `links_layers` empty and stores links in a separate compact file.
+#[derive(Deserialize, Serialize, Debug)]
+pub(super) struct GraphLayersData {
+    pub(super) max_level: usize,
+    pub(super) m: usize,
+    pub(super) m0: usize,
+    pub(super) ef_construct: usize,
+    pub(super) level_factor: f64,
+    pub(super) use_heuristic: bool,
+    pub(super) links_layers: Vec<LayersContainer>,
+    pub(super) entry_points: EntryPoints,
+}
+
+/// Object contains links between nodes for HNSW search
+///
+/// Assume all scores are similarities. Larger score = closer points
+pub struct GraphLayers {
+    pub(super) max_level: usize,
+    pub(super) m: usize,
+    pub(super) m0: usize,
+    pub(super) ef_construct: usize,
+    // Factor of level probability
+    pub(super) level_factor: f64,
+    // Exclude points according to "not closer than base" heuristic?
+    pub(super) use_heuristic: bool,
+    pub(super) links: GraphLinks,
+    pub(super) entry_points: EntryPoints,
+
+    pub(super) visited_pool: VisitedPool,
+}
+
+impl GraphLayersBase for GraphLayers {
+    fn get_visited_list_from_pool(&self) -> VisitedList {
+        self.visited_pool.get(self.num_points())
     }
 
-    /// <https://github.com/nmslib/hnswlib/issues/99>
-    fn select_candidates_with_heuristic<F>(
-        candidates: FixedLengthPriorityQueue<ScoredPointOffset>,
-        m: usize,
-        score_internal: F,
-    ) -> Vec<PointOffsetType>
+    fn return_visited_list_to_pool(&self, visited_list: VisitedList) {
+        self.visited_pool.return_back(visited_list);
+    }
+
+    fn links_map<F>(&self, point_id: PointOffsetType, level: usize, mut f: F)
     where
-        F: FnMut(PointOffsetType, PointOffsetType) -> ScoreType,
+        F: FnMut(PointOffsetType),
     {
-        let closest_iter = candidates.into_iter();
-        Self::select_candidate_with_heuristic_from_sorted(closest_iter, m, score_internal)
+        for link in self.links(point_id, level) {
+            f(*link);
+        }
     }
 
-    pub fn link_new_point(
-        &mut self,
-        point_id: PointOffsetType,
-        level: usize,
-        mut points_scorer: FilteredScorer,
-    ) {
-        // Check if there is an suitable entry point
-        //   - entry point level if higher or equal
-        //   - it satisfies filters
-
-        self.set_levels(point_id, level);
-
-        let entry_point_opt = self.entry_points.new_point(point_id, level, |point_id| {
-            points_scorer.check_point(point_id)
-        });
-        match entry_point_opt {
-            // New point is a new empty entry (for this filter, at least)
-            // We can't do much here, so just quit
-            None => {}
-
-            // Entry point found.
-            Some(entry_point) => {
-                let mut level_entry = if entry_point.level > level {
-                    // The entry point is higher than a new point
-                    // Let's find closest one on same level
-
-                    // greedy search for a single closest point
-                    self.search_entry(
-                        entry_point.point_id,
-                        entry_point.level,
-                        level,
-                        &mut points_scorer,
-                    )
-                } else {
-                    ScoredPointOffset {
-                        idx: entry_point.point_id,
-                        score: points_scorer.score_internal(point_id, entry_point.point_id),
-                    }
-                };
-                // minimal common level for entry points
-                let linking_level = min(level, entry_point.level);
-
-                for curr_level in (0..=linking_level).rev() {
-                    let level_m = self.get_m(curr_level);
-                    let existing_links = &self.links_layers[point_id as usize][curr_level];
-
-                    let nearest_points = self.search_on_level(
-                        level_entry,
-                        curr_level,
-                        self.ef_construct,
-                        &mut points_scorer,
-                        existing_links,
-                    );
-
-                    let scorer = |a, b| points_scorer.score_internal(a, b);
-
-                    if self.use_heuristic {
-                        let selected_nearest =
-                            Self::select_candidates_with_heuristic(nearest_points, level_m, scorer);
-                        self.links_layers[point_id as usize][curr_level]
-                            .clone_from(&selected_nearest);
-
-                        for &other_point in &selected_nearest {
-                            let other_point_links =
-                                &mut self.links_layers[other_point as usize][curr_level];
-                            if other_point_links.len() < level_m {
-                                // If linked point is lack of neighbours
-                                other_point_links.push(point_id);
-                            } else {
-                                let mut candidates = BinaryHeap::with_capacity(level_m + 1);
-                                candidates.push(ScoredPointOffset {
-                                    idx: point_id,
-                                    score: scorer(point_id, other_point),
-                                });
-                                for other_point_link in
-                                    other_point_links.iter().take(level_m).copied()
-                                {
-                                    candidates.push(ScoredPointOffset {
-                                        idx: other_point_link,
-                                        score: scorer(other_point_link, other_point),
-                                    });
-                                }
-                                let selected_candidates =
-                                    Self::select_candidate_with_heuristic_from_sorted(
-                                        candidates.into_sorted_vec().into_iter().rev(),
-                                        level_m,
-                                        scorer,
-                                    );
-                                other_point_links.clear(); // this do not free memory, which is good
-                                for selected in selected_candidates.iter().copied() {
-                                    other_point_links.push(selected);
-                                }
-                            }
-                        }
-                    } else {
-                        for nearest_point in &nearest_points {
-                            Self::connect_new_point(
-                                &mut self.links_layers[point_id as usize][curr_level],
-                                nearest_point.idx,
-                                point_id,
-                                level_m,
-                                scorer,
-                            );
-
-                            Self::connect_new_point(
-                                &mut self.links_layers[nearest_point.idx as usize][curr_level],
-                                point_id,
-                                nearest_point.idx,
-                                level_m,
-                                scorer,
-                            );
-                            if nearest_point.score > level_entry.score {
-                                level_entry = *nearest_point;
-                            }
-                        }
-                    }
-                }
-            }
+    fn get_m(&self, level: usize) -> usize {
+        if level == 0 {
+            self.m0
+        } else {
+            self.m
         }
     }
+}
 
-    pub fn merge_from_other(&mut self, other: GraphLayers) {
-        let mut visited_list = self.visited_pool.get(self.num_points());
-        if other.links_layers.len() > self.links_layers.len() {
-            self.links_layers.resize(other.links_layers.len(), vec![]);
-        }
-        for (point_id, layers) in other.links_layers.into_iter().enumerate() {
-            let current_layers = &mut self.links_layers[point_id];
-            for (level, other_links) in layers.into_iter().enumerate() {
-                if current_layers.len() <= level {
-                    current_layers.push(other_links);
-                } else {
-                    visited_list.next_iteration();
-                    let current_links = &mut current_layers[level];
-                    current_links.iter().copied().for_each(|x| {
-                        visited_list.check_and_update_visited(x);
-                    });
-                    for other_link in other_links
-                        .into_iter()
-                        .filter(|x| !visited_list.check_and_update_visited(*x))
-                    {
-                        current_links.push(other_link);
-                    }
-                }
-            }
+impl GraphLayers {
+    /// Creates an empty graph. Use `GraphLayersBuilder` to construct a graph with links.
+    pub fn new(
+        num_vectors: usize, // Initial number of points in index
+        m: usize,           // Expected M for non-first layer
+        m0: usize,          // Expected M for first layer
+        ef_construct: usize,
+        entry_points_num: usize, // Depends on number of points
+        use_heuristic: bool,
+    ) -> Self {
+        GraphLayers {
+            max_level: 0,
+            m,
+            m0,
+            ef_construct,
+            level_factor: 1.0 / (m as f64).ln(),
+            use_heuristic,
+            links: GraphLinks::from_layers(&vec![vec![vec![]]; num_vectors]),
+            entry_points: EntryPoints::new(entry_points_num),
+            visited_pool: VisitedPool::new(),
         }
-        self.entry_points.merge_from_other(other.entry_points);
+    }
 
-        self.visited_pool.return_back(visited_list);
+    fn num_points(&self) -> usize {
+        self.links.num_points()
     }
 
+    pub fn point_level(&self, point_id: PointOffsetType) -> usize {
+        self.links.point_level(point_id)
+    }
+
+    /// Get links of current point
+    pub fn links(&self, point_id: PointOffsetType, level: usize) -> LinkContainerRef {
+        self.links.links(point_id, level)
+    }
+
+    /// Export links of the graph for reuse in another segment.
+    ///
+    /// `mapping` converts point offsets of this graph into offsets of the new segment.
+    /// Only mapped points are exported, links to not mapped points are dropped.
+    pub fn reuse_links(&self, mapping: &HashMap<PointOffsetType, PointOffsetType>) -> ReusedGraph {
+        let points = mapping
+            .iter()
+            .filter(|(&old_id, _)| (old_id as usize) < self.num_points())
+            .map(|(&old_id, &new_id)| {
+                let layers = (0..=self.point_level(old_id))
+                    .map(|level| {
+                        self.links(old_id, level)
+                            .iter()
+                            .filter_map(|link| mapping.get(link).copied())
+                            .collect()
+                    })
+                    .collect();
+                (new_id, layers)
+            })
+            .collect();
+
+        ReusedGraph {
+            m: self.m,
+            m0: self.m0,
+            points,
+        }
+    }
+
+    /// Search for `top` closest points.
+    /// If `is_stopped` flag is raised during the search, the best points found so far are returned.
     pub fn search(
         &self,
         top: usize,
         ef: usize,
-        mut points_scorer: FilteredScorer,
+        points_scorer: &mut FilteredScorer,
+        is_stopped: &AtomicBool,
     ) -> Vec<ScoredPointOffset> {
         let entry_point = match self
             .entry_points
@@ -475,28 +325,82 @@ impl GraphLayers {
             Some(ep) => ep,
         };
 
-        let zero_level_entry = self.search_entry(
-            entry_point.point_id,
-            entry_point.level,
-            0,
-            &mut points_scorer,
-        );
-
-        let nearest =
-            self.search_on_level(zero_level_entry, 0, max(top, ef), &mut points_scorer, &[]);
-        nearest.into_iter().take(top).collect_vec()
+        self.search_from_entry(entry_point, top, ef, points_scorer, is_stopped)
     }
 
     pub fn get_path(path: &Path) -> PathBuf {
         path.join(HNSW_GRAPH_FILE)
     }
 
-    pub fn load(path: &Path) -> OperationResult<Self> {
-        Ok(read_bin(path)?)
+    /// Load graph from the directory.
+    /// If `on_disk` is set, links are memory-mapped instead of being read into RAM.
+    /// Graph of the legacy format is converted into the compact one first.
+    pub fn load(path: &Path, on_disk: bool) -> OperationResult<Self> {
+        let links_path = GraphLinks::get_path(path);
+        if !links_path.exists() {
+            Self::convert_legacy_graph(path)?;
+        }
+
+        let data: GraphLayersData = read_bin(&Self::get_path(path))?;
+        let links = if on_disk {
+            GraphLinks::open_mmap(&links_path)?
+        } else {
+            GraphLinks::load(&links_path)?
+        };
+
+        // Graph file is written before the links file, so the links might be outdated after a crash
+        if data
+            .entry_points
+            .iter()
+            .any(|entry| entry.point_id as usize >= links.num_points())
+        {
+            return Err(OperationError::service_error(
+                "HNSW graph does not match its links file",
+            ));
+        }
+
+        Ok(GraphLayers {
+            max_level: data.max_level,
+            m: data.m,
+            m0: data.m0,
+            ef_construct: data.ef_construct,
+            level_factor: data.level_factor,
+            use_heuristic: data.use_heuristic,
+            links,
+            entry_points: data.entry_points,
+            visited_pool: VisitedPool::new(),
+        })
+    }
+
+    /// Convert graph file with nested links into the compact format
+    pub fn convert_legacy_graph(path: &Path) -> OperationResult<()> {
+        let graph_path = Self::get_path(path);
+        debug!("converting HNSW graph {:?} into compact format", graph_path);
+
+        let mut data: GraphLayersData = read_bin(&graph_path)?;
+        // Links are saved first: until the graph file is overwritten, it still contains all links
+        GraphLinks::from_layers(&data.links_layers).save(&GraphLinks::get_path(path))?;
+
+        data.links_layers = vec![];
+        Ok(atomic_save_bin(&graph_path, &data)?)
     }
 
+    /// Save the graph file first and the links file after it.
+    /// Both files are replaced atomically, `load` rejects links which do not cover the graph.
     pub fn save(&self, path: &Path) -> OperationResult<()> {
-        Ok(atomic_save_bin(path, self)?)
+        let data = GraphLayersData {
+            max_level: self.max_level,
+            m: self.m,
+            m0: self.m0,
+            ef_construct: self.ef_construct,
+            level_factor: self.level_factor,
+            use_heuristic: self.use_heuristic,
+            links_layers: vec![],
+            entry_points: self.entry_points.clone(),
+        };
+        atomic_save_bin(&Self::get_path(path), &data)?;
+
+        self.links.save(&GraphLinks::get_path(path))
     }
 }
 
@@ -506,76 +410,17 @@ mod tests {
     use crate::fixtures::index_fixtures::{
         random_vector, FakeFilterContext, TestRawScorerProducer,
     };
+    use crate::index::hnsw_index::graph_layers_builder::GraphLayersBuilder;
     use crate::spaces::metric::Metric;
-    use crate::spaces::simple::{CosineMetric, DotProductMetric, EuclidMetric};
+    use crate::spaces::simple::{CosineMetric, DotProductMetric};
     use crate::types::VectorElementType;
-    use crate::vector_storage::RawScorer;
     use itertools::Itertools;
     use rand::rngs::StdRng;
-    use rand::seq::SliceRandom;
-    use rand::SeedableRng;
+    use rand::{Rng, SeedableRng};
     use std::fs::File;
     use std::io::Write;
     use tempdir::TempDir;
 
-    #[test]
-    fn test_connect_new_point() {
-        let num_points = 10;
-        let m = 6;
-        let ef_construct = 32;
-
-        // See illustration in docs
-        let points: Vec<Vec<VectorElementType>> = vec![
-            vec![21.79, 7.18],  // Target
-            vec![20.58, 5.46],  // 1  B - yes
-            vec![21.19, 4.51],  // 2  C
-            vec![24.73, 8.24],  // 3  D - yes
-            vec![24.55, 9.98],  // 4  E
-            vec![26.11, 6.85],  // 5  F
-            vec![17.64, 11.14], // 6  G - yes
-            vec![14.97, 11.52], // 7  I
-            vec![14.97, 9.60],  // 8  J
-            vec![16.23, 14.32], // 9  H
-            vec![12.69, 19.13], // 10 K
-        ];
-
-        let scorer = |a: PointOffsetType, b: PointOffsetType| {
-            -((points[a as usize][0] - points[b as usize][0]).powi(2)
-                + (points[a as usize][1] - points[b as usize][1]).powi(2))
-            .sqrt()
-        };
-
-        let mut insert_ids = (1..points.len() as PointOffsetType).collect_vec();
-
-        let mut candidates = FixedLengthPriorityQueue::new(insert_ids.len());
-        for &id in &insert_ids {
-            candidates.push(ScoredPointOffset {
-                idx: id,
-                score: scorer(0, id),
-            });
-        }
-
-        let res = GraphLayers::select_candidates_with_heuristic(candidates, m, scorer);
-
-        assert_eq!(&res, &vec![1, 3, 6]);
-
-        let mut rng = StdRng::seed_from_u64(42);
-
-        let mut graph_layers = GraphLayers::new(num_points, m, m, ef_construct, 1, true);
-        insert_ids.shuffle(&mut rng);
-        for &id in &insert_ids {
-            let level_m = graph_layers.get_m(0);
-            GraphLayers::connect_new_point(
-                &mut graph_layers.links_layers[0][0],
-                id,
-                0,
-                level_m,
-                scorer,
-            )
-        }
-        assert_eq!(graph_layers.links(0, 0), &vec![1, 2, 3, 4, 5, 6]);
-    }
-
     fn search_in_graph(
         query: &[VectorElementType],
         top: usize,
@@ -584,13 +429,23 @@ mod tests {
     ) -> Vec<ScoredPointOffset> {
         let fake_filter_context = FakeFilterContext {};
         let raw_scorer = vector_storage.get_raw_scorer(query.to_owned());
-        let scorer = FilteredScorer::new(&raw_scorer, Some(&fake_filter_context));
+        let mut scorer = FilteredScorer::new(&raw_scorer, Some(&fake_filter_context));
         let ef = 16;
-        graph.search(top, ef, scorer)
+        graph.search(top, ef, &mut scorer, &AtomicBool::new(false))
     }
 
     const M: usize = 8;
 
+    fn links_layers(graph: &GraphLayers) -> Vec<LayersContainer> {
+        (0..graph.num_points() as PointOffsetType)
+            .map(|point_id| {
+                (0..=graph.point_level(point_id))
+                    .map(|level| graph.links(point_id, level).to_vec())
+                    .collect()
+            })
+            .collect()
+    }
+
     fn create_graph_layer<TMetric: Metric, R>(
         num_vectors: usize,
         dim: usize,
@@ -606,7 +461,7 @@ mod tests {
 
         let vector_holder = TestRawScorerProducer::<TMetric>::new(dim, num_vectors, rng);
 
-        let mut graph_layers = GraphLayers::new(
+        let mut graph_layers_builder = GraphLayersBuilder::new(
             num_vectors,
             m,
             m * 2,
@@ -615,16 +470,20 @@ mod tests {
             use_heuristic,
         );
 
+        for idx in 0..(num_vectors as PointOffsetType) {
+            let level = graph_layers_builder.get_random_layer(rng);
+            graph_layers_builder.set_levels(idx, level);
+        }
+
         for idx in 0..(num_vectors as PointOffsetType) {
             let fake_filter_context = FakeFilterContext {};
             let added_vector = vector_holder.vectors.get(idx).to_vec();
             let raw_scorer = vector_holder.get_raw_scorer(added_vector.clone());
             let scorer = FilteredScorer::new(&raw_scorer, Some(&fake_filter_context));
-            let level = graph_layers.get_random_layer(rng);
-            graph_layers.link_new_point(idx, level, scorer);
+            graph_layers_builder.link_new_point(idx, scorer);
         }
 
-        (vector_holder, graph_layers)
+        (vector_holder, graph_layers_builder.into_graph_layers())
     }
 
     #[test]
@@ -640,10 +499,12 @@ mod tests {
         let vector_holder =
             TestRawScorerProducer::<DotProductMetric>::new(dim, num_vectors, &mut rng);
 
-        let mut graph_layers =
-            GraphLayers::new(num_vectors, m, m * 2, ef_construct, entry_points_num, false);
+        let graph_layers_builder =
+            GraphLayersBuilder::new(num_vectors, m, m * 2, ef_construct, entry_points_num, false);
+
+        *graph_layers_builder.links_layers[0][0].write() = vec![1, 2, 3, 4, 5, 6];
 
-        graph_layers.links_layers[0][0] = vec![1, 2, 3, 4, 5, 6];
+        let graph_layers = graph_layers_builder.into_graph_layers();
 
         let linking_idx: PointOffsetType = 7;
 
@@ -661,12 +522,10 @@ mod tests {
             32,
             &mut scorer,
             &[],
+            &AtomicBool::new(false),
         );
 
-        assert_eq!(
-            nearest_on_level.len(),
-            graph_layers.links_layers[0][0].len() + 1
-        );
+        assert_eq!(nearest_on_level.len(), graph_layers.links(0, 0).len() + 1);
 
         for nearest in &nearest_on_level {
             // eprintln!("nearest = {:#?}", nearest);
@@ -694,14 +553,78 @@ mod tests {
 
         let dir = TempDir::new("graph_dir").unwrap();
 
-        let path = GraphLayers::get_path(dir.path());
-        graph_layers.save(&path).unwrap();
+        graph_layers.save(dir.path()).unwrap();
 
-        let graph2 = GraphLayers::load(&path).unwrap();
+        let graph2 = GraphLayers::load(dir.path(), false).unwrap();
 
         let res2 = search_in_graph(&query, top, &vector_holder, &graph2);
 
-        assert_eq!(res1, res2)
+        assert_eq!(res1, res2);
+
+        let graph3 = GraphLayers::load(dir.path(), true).unwrap();
+        assert!(graph3.links.is_on_disk());
+
+        let res3 = search_in_graph(&query, top, &vector_holder, &graph3);
+
+        assert_eq!(res1, res3)
+    }
+
+    #[test]
+    fn test_convert_legacy_graph() {
+        let num_vectors = 100;
+        let dim = 8;
+        let top = 5;
+
+        let mut rng = StdRng::seed_from_u64(42);
+
+        let (vector_holder, graph_layers) =
+            create_graph_layer::<CosineMetric, _>(num_vectors, dim, false, &mut rng);
+
+        let dir = TempDir::new("graph_dir").unwrap();
+
+        let legacy_data = GraphLayersData {
+            max_level: graph_layers.max_level,
+            m: graph_layers.m,
+            m0: graph_layers.m0,
+            ef_construct: graph_layers.ef_construct,
+            level_factor: graph_layers.level_factor,
+            use_heuristic: graph_layers.use_heuristic,
+            links_layers: links_layers(&graph_layers),
+            entry_points: graph_layers.entry_points.clone(),
+        };
+        atomic_save_bin(&GraphLayers::get_path(dir.path()), &legacy_data).unwrap();
+
+        let converted = GraphLayers::load(dir.path(), true).unwrap();
+        assert!(GraphLinks::get_path(dir.path()).exists());
+        assert_eq!(links_layers(&converted), legacy_data.links_layers);
+
+        let query = random_vector(&mut rng, dim);
+        assert_eq!(
+            search_in_graph(&query, top, &vector_holder, &graph_layers),
+            search_in_graph(&query, top, &vector_holder, &converted),
+        );
+    }
+
+    #[test]
+    fn test_stopped_search() {
+        let num_vectors = 100;
+        let dim = 8;
+        let top = 5;
+
+        let mut rng = StdRng::seed_from_u64(42);
+
+        let (vector_holder, graph_layers) =
+            create_graph_layer::<CosineMetric, _>(num_vectors, dim, false, &mut rng);
+
+        let query = random_vector(&mut rng, dim);
+
+        let fake_filter_context = FakeFilterContext {};
+        let raw_scorer = vector_holder.get_raw_scorer(query);
+        let mut scorer = FilteredScorer::new(&raw_scorer, Some(&fake_filter_context));
+
+        // Only the entry point of the zero level is scored before the search notices the flag
+        let res = graph_layers.search(top, 16, &mut scorer, &AtomicBool::new(true));
+        assert_eq!(res.len(), 1);
     }
 
     #[test]
@@ -723,15 +646,12 @@ mod tests {
 
         assert!(main_entry.level > 0);
 
-        let num_levels = graph_layers
-            .links_layers
-            .iter()
-            .map(|x| x.len())
-            .max()
-            .unwrap();
+        let links_layers = links_layers(&graph_layers);
+
+        let num_levels = links_layers.iter().map(|x| x.len()).max().unwrap();
         assert_eq!(main_entry.level + 1, num_levels);
 
-        let total_links_0: usize = graph_layers.links_layers.iter().map(|x| x[0].len()).sum();
+        let total_links_0: usize = links_layers.iter().map(|x| x[0].len()).sum();
 
         assert!(total_links_0 > 0);
 
@@ -766,75 +686,34 @@ mod tests {
         let mut rng = StdRng::seed_from_u64(42);
 
         let vector_holder = TestRawScorerProducer::<CosineMetric>::new(DIM, NUM_VECTORS, &mut rng);
-        let mut graph_layers =
-            GraphLayers::new(NUM_VECTORS, M, M * 2, EF_CONSTRUCT, 10, USE_HEURISTIC);
+        let mut graph_layers_builder =
+            GraphLayersBuilder::new(NUM_VECTORS, M, M * 2, EF_CONSTRUCT, 10, USE_HEURISTIC);
+        for idx in 0..(NUM_VECTORS as PointOffsetType) {
+            let level = graph_layers_builder.get_random_layer(&mut rng);
+            graph_layers_builder.set_levels(idx, level);
+        }
         let fake_filter_context = FakeFilterContext {};
         for idx in 0..(NUM_VECTORS as PointOffsetType) {
             let added_vector = vector_holder.vectors.get(idx).to_vec();
             let raw_scorer = vector_holder.get_raw_scorer(added_vector);
             let scorer = FilteredScorer::new(&raw_scorer, Some(&fake_filter_context));
-            let level = graph_layers.get_random_layer(&mut rng);
-            graph_layers.link_new_point(idx, level, scorer);
+            graph_layers_builder.link_new_point(idx, scorer);
         }
+        let links_layers = links_layers(&graph_layers_builder.into_graph_layers());
 
-        let number_layers = graph_layers.links_layers.len();
+        let number_layers = links_layers.len();
         eprintln!("number_layers = {:#?}", number_layers);
 
-        let max_layers = graph_layers.links_layers.iter().map(|x| x.len()).max();
+        let max_layers = links_layers.iter().map(|x| x.len()).max();
         eprintln!("max_layers = {:#?}", max_layers);
 
-        eprintln!(
-            "graph_layers.links_layers[910] = {:#?}",
-            graph_layers.links_layers[910]
-        );
+        eprintln!("links_layers[910] = {:#?}", links_layers[910]);
 
-        let total_edges: usize = graph_layers.links_layers.iter().map(|x| x[0].len()).sum();
+        let total_edges: usize = links_layers.iter().map(|x| x[0].len()).sum();
         let avg_connectivity = total_edges as f64 / NUM_VECTORS as f64;
         eprintln!("avg_connectivity = {:#?}", avg_connectivity);
     }
 
-    #[test]
-    #[ignore]
-    fn test_candidate_selection_heuristics() {
-        const NUM_VECTORS: usize = 100;
-        const DIM: usize = 16;
-        const M: usize = 16;
-
-        let mut rng = StdRng::seed_from_u64(42);
-
-        let vector_holder = TestRawScorerProducer::<EuclidMetric>::new(DIM, NUM_VECTORS, &mut rng);
-
-        let mut candidates: FixedLengthPriorityQueue<ScoredPointOffset> =
-            FixedLengthPriorityQueue::new(NUM_VECTORS);
-
-        let new_vector_to_insert = random_vector(&mut rng, DIM);
-
-        let scorer = vector_holder.get_raw_scorer(new_vector_to_insert);
-
-        for i in 0..NUM_VECTORS {
-            candidates.push(ScoredPointOffset {
-                idx: i as PointOffsetType,
-                score: scorer.score_point(i as PointOffsetType),
-            });
-        }
-
-        let sorted_candidates = candidates.into_vec();
-
-        for x in sorted_candidates.iter().take(M) {
-            eprintln!("sorted_candidates = ({}, {})", x.idx, x.score);
-        }
-
-        let selected_candidates = GraphLayers::select_candidate_with_heuristic_from_sorted(
-            sorted_candidates.into_iter(),
-            M,
-            |a, b| scorer.score_internal(a, b),
-        );
-
-        for x in selected_candidates.iter() {
-            eprintln!("selected_candidates = {}", x);
-        }
-    }
-
     #[test]
     #[ignore]
     fn test_draw_hnsw_graph() {
@@ -846,7 +725,10 @@ mod tests {
         let (vector_holder, graph_layers) =
             create_graph_layer::<CosineMetric, _>(num_vectors, dim, true, &mut rng);
 
-        let graph_json = serde_json::to_string_pretty(&graph_layers).unwrap();
+        let graph_json = serde_json::to_string_pretty(&serde_json::json!({
+            "links_layers": links_layers(&graph_layers),
+        }))
+        .unwrap();
 
         let vectors_json = serde_json::to_string_pretty(
             &(0..vector_holder.vectors.len() as PointOffsetType)
@@ -0,0 +1,723 @@
+use crate::common::file_operations::atomic_save_bin;
+use crate::entry::entry_point::OperationResult;
+use crate::index::hnsw_index::entry_points::EntryPoints;
+use crate::index::hnsw_index::graph_layers::{
+    GraphLayers, GraphLayersBase, GraphLayersData, LayersContainer, LinkContainer,
+};
+use crate::index::hnsw_index::graph_links::GraphLinks;
+use crate::index::hnsw_index::point_scorer::FilteredScorer;
+use crate::index::visited_pool::{VisitedList, VisitedPool};
+use crate::types::{PointOffsetType, ScoreType};
+use crate::vector_storage::ScoredPointOffset;
+use parking_lot::{Mutex, RwLock};
+use rand::distributions::Uniform;
+use rand::Rng;
+use std::cmp::{max, min};
+use std::collections::BinaryHeap;
+use std::path::Path;
+use std::sync::atomic::AtomicBool;
+
+/// Graph under construction.
+///
+/// Each link list is protected by its own lock, so points could be linked concurrently
+/// from multiple threads. Levels of all points should be set with `set_levels` before linking.
+pub struct GraphLayersBuilder {
+    max_level: usize,
+    m: usize,
+    m0: usize,
+    ef_construct: usize,
+    // Factor of level probability
+    level_factor: f64,
+    // Exclude points according to "not closer than base" heuristic?
+    use_heuristic: bool,
+    pub(super) links_layers: Vec<Vec<RwLock<LinkContainer>>>,
+    entry_points: Mutex<EntryPoints>,
+
+    visited_pool: VisitedPool,
+}
+
+impl GraphLayersBase for GraphLayersBuilder {
+    fn get_visited_list_from_pool(&self) -> VisitedList {
+        self.visited_pool.get(self.num_points())
+    }
+
+    fn return_visited_list_to_pool(&self, visited_list: VisitedList) {
+        self.visited_pool.return_back(visited_list);
+    }
+
+    fn links_map<F>(&self, point_id: PointOffsetType, level: usize, mut f: F)
+    where
+        F: FnMut(PointOffsetType),
+    {
+        let links = self.links_layers[point_id as usize][level].read();
+        for link in links.iter() {
+            f(*link);
+        }
+    }
+
+    fn get_m(&self, level: usize) -> usize {
+        if level == 0 {
+            self.m0
+        } else {
+            self.m
+        }
+    }
+}
+
+impl GraphLayersBuilder {
+    pub fn new_with_params(
+        num_vectors: usize, // Initial number of points in index
+        m: usize,           // Expected M for non-first layer
+        m0: usize,          // Expected M for first layer
+        ef_construct: usize,
+        entry_points_num: usize, // Depends on number of points
+        use_heuristic: bool,
+        reserve: bool,
+    ) -> Self {
+        let links_layers = (0..num_vectors)
+            .map(|_| {
+                let mut links: LinkContainer = Vec::new();
+                if reserve {
+                    links.reserve(m0);
+                }
+                vec![RwLock::new(links)]
+            })
+            .collect();
+
+        GraphLayersBuilder {
+            max_level: 0,
+            m,
+            m0,
+            ef_construct,
+            level_factor: 1.0 / (m as f64).ln(),
+            use_heuristic,
+            links_layers,
+            entry_points: Mutex::new(EntryPoints::new(entry_points_num)),
+            visited_pool: VisitedPool::new(),
+        }
+    }
+
+    pub fn new(
+        num_vectors: usize, // Initial number of points in index
+        m: usize,           // Expected M for non-first layer
+        m0: usize,          // Expected M for first layer
+        ef_construct: usize,
+        entry_points_num: usize, // Depends on number of points
+        use_heuristic: bool,
+    ) -> Self {
+        Self::new_with_params(
+            num_vectors,
+            m,
+            m0,
+            ef_construct,
+            entry_points_num,
+            use_heuristic,
+            true,
+        )
+    }
+
+    /// Create a builder with links of the ready graph, so more points could be linked into it
+    pub fn from_graph_layers(graph: &GraphLayers) -> Self {
+        let links_layers = (0..graph.links.num_points() as PointOffsetType)
+            .map(|point_id| {
+                (0..=graph.point_level(point_id))
+                    .map(|level| RwLock::new(graph.links(point_id, level).to_vec()))
+                    .collect()
+            })
+            .collect();
+
+        GraphLayersBuilder {
+            max_level: graph.max_level,
+            m: graph.m,
+            m0: graph.m0,
+            ef_construct: graph.ef_construct,
+            level_factor: graph.level_factor,
+            use_heuristic: graph.use_heuristic,
+            links_layers,
+            entry_points: Mutex::new(graph.entry_points.clone()),
+            visited_pool: VisitedPool::new(),
+        }
+    }
+
+    pub fn num_points(&self) -> usize {
+        self.links_layers.len()
+    }
+
+    pub fn get_point_level(&self, point_id: PointOffsetType) -> usize {
+        self.links_layers[point_id as usize].len() - 1
+    }
+
+    /// Generate random level for a new point, according to geometric distribution
+    pub fn get_random_layer<R>(&self, rng: &mut R) -> usize
+    where
+        R: Rng + ?Sized,
+    {
+        let distribution = Uniform::new(0.0, 1.0);
+        let sample: f64 = rng.sample(distribution);
+        let picked_level = -sample.ln() * self.level_factor;
+        picked_level.round() as usize
+    }
+
+    /// Allocate link lists of the point for all levels up to `level`.
+    /// Should be called for all points before any of them is linked.
+    pub fn set_levels(&mut self, point_id: PointOffsetType, level: usize) {
+        if self.links_layers.len() <= point_id as usize {
+            self.links_layers
+                .resize_with(point_id as usize + 1, || vec![RwLock::new(vec![])]);
+        }
+        let m = self.m;
+        let point_layers = &mut self.links_layers[point_id as usize];
+        while point_layers.len() <= level {
+            point_layers.push(RwLock::new(Vec::with_capacity(m)));
+        }
+        self.max_level = max(level, self.max_level);
+    }
+
+    /// Add a point with links, taken from a previously built graph.
+    /// Should be called before any new point is linked.
+    pub fn add_reused_point(&mut self, point_id: PointOffsetType, layers: LayersContainer) {
+        let level = layers.len().saturating_sub(1);
+        self.set_levels(point_id, level);
+        for (links, reused_links) in self.links_layers[point_id as usize].iter_mut().zip(layers) {
+            *links.get_mut() = reused_links;
+        }
+        self.entry_points
+            .get_mut()
+            .new_point(point_id, level, |_| true);
+    }
+
+    /// Add links of the other graph, built for the same levels of points
+    pub fn merge_from_other(&mut self, other: GraphLayersBuilder) {
+        let mut visited_list = self.visited_pool.get(self.num_points());
+        if other.links_layers.len() > self.links_layers.len() {
+            self.links_layers
+                .resize_with(other.links_layers.len(), Vec::new);
+        }
+        for (point_id, layers) in other.links_layers.into_iter().enumerate() {
+            let current_layers = &mut self.links_layers[point_id];
+            for (level, other_links) in layers.into_iter().enumerate() {
+                let other_links = other_links.into_inner();
+                if current_layers.len() <= level {
+                    current_layers.push(RwLock::new(other_links));
+                } else {
+                    visited_list.next_iteration();
+                    let current_links = current_layers[level].get_mut();
+                    current_links.iter().copied().for_each(|x| {
+                        visited_list.check_and_update_visited(x);
+                    });
+                    for other_link in other_links
+                        .into_iter()
+                        .filter(|x| !visited_list.check_and_update_visited(*x))
+                    {
+                        current_links.push(other_link);
+                    }
+                }
+            }
+        }
+        self.entry_points
+            .get_mut()
+            .merge_from_other(other.entry_points.into_inner());
+
+        self.visited_pool.return_back(visited_list);
+    }
+
+    /// Convert the constructed graph into the compact read-only representation used for search
+    pub fn into_graph_layers(self) -> GraphLayers {
+        let links_layers: Vec<LayersContainer> = self
+            .links_layers
+            .into_iter()
+            .map(|point_layers| {
+                point_layers
+                    .into_iter()
+                    .map(|links| links.into_inner())
+                    .collect()
+            })
+            .collect();
+
+        GraphLayers {
+            max_level: self.max_level,
+            m: self.m,
+            m0: self.m0,
+            ef_construct: self.ef_construct,
+            level_factor: self.level_factor,
+            use_heuristic: self.use_heuristic,
+            links: GraphLinks::from_layers(&links_layers),
+            entry_points: self.entry_points.into_inner(),
+            visited_pool: self.visited_pool,
+        }
+    }
+
+    /// Save current state of the graph in the format of `GraphLayers`.
+    /// Links are written directly from the link lists, without copying the whole graph.
+    /// Graph should not be modified while saving.
+    pub fn save(&self, path: &Path) -> OperationResult<()> {
+        let data = GraphLayersData {
+            max_level: self.max_level,
+            m: self.m,
+            m0: self.m0,
+            ef_construct: self.ef_construct,
+            level_factor: self.level_factor,
+            use_heuristic: self.use_heuristic,
+            links_layers: vec![],
+            entry_points: self.entry_points.lock().clone(),
+        };
+        atomic_save_bin(&GraphLayers::get_path(path), &data)?;
+
+        GraphLinks::save_layers(&GraphLinks::get_path(path), &self.links_layers)
+    }
+
+    /// Search for `top` closest points in the graph under construction
+    pub fn search(
+        &self,
+        top: usize,
+        ef: usize,
+        points_scorer: &mut FilteredScorer,
+        is_stopped: &AtomicBool,
+    ) -> Vec<ScoredPointOffset> {
+        let entry_point = match self
+            .entry_points
+            .lock()
+            .get_entry_point(|point_id| points_scorer.check_point(point_id))
+        {
+            None => return vec![],
+            Some(ep) => ep,
+        };
+
+        self.search_from_entry(entry_point, top, ef, points_scorer, is_stopped)
+    }
+
+    /// Connect new point to links, so that links contains only closest points
+    fn connect_new_point<F>(
+        links: &mut LinkContainer,
+        new_point_id: PointOffsetType,
+        target_point_id: PointOffsetType,
+        level_m: usize,
+        mut score_internal: F,
+    ) where
+        F: FnMut(PointOffsetType, PointOffsetType) -> ScoreType,
+    {
+        // ToDo: binary search here ? (most likely does not worth it)
+        let new_to_target = score_internal(target_point_id, new_point_id);
+
+        let mut id_to_insert = links.len();
+        for (i, &item) in links.iter().enumerate() {
+            let target_to_link = score_internal(target_point_id, item);
+            if target_to_link < new_to_target {
+                id_to_insert = i;
+                break;
+            }
+        }
+
+        if links.len() < level_m {
+            links.insert(id_to_insert, new_point_id);
+        } else if id_to_insert != links.len() {
+            links.pop();
+            links.insert(id_to_insert, new_point_id);
+        }
+    }
+
+    /// <https://github.com/nmslib/hnswlib/issues/99>
+    fn select_candidate_with_heuristic_from_sorted<F>(
+        candidates: impl Iterator<Item = ScoredPointOffset>,
+        m: usize,
+        mut score_internal: F,
+    ) -> Vec<PointOffsetType>
+    where
+        F: FnMut(PointOffsetType, PointOffsetType) -> ScoreType,
+    {
+        let mut result_list = vec![];
+        result_list.reserve(m);
+        for current_closest in candidates {
+            if result_list.len() >= m {
+                break;
+            }
+            let mut is_good = true;
+            for &selected_point in &result_list {
+                let dist_to_already_selected = score_internal(current_closest.idx, selected_point);
+                if dist_to_already_selected > current_closest.score {
+                    is_good = false;
+                    break;
+                }
+            }
+            if is_good {
+                result_list.push(current_closest.idx);
+            }
+        }
+
+        result_list
+    }
+
+    /// Link the point into the graph on all levels, allocated for it with `set_levels`.
+    /// Only one link list is locked at a time, so other points could be linked concurrently.
+    ///
+    /// Point, which is already an entry point, is relinked without registering it again.
+    pub fn link_new_point(&self, point_id: PointOffsetType, mut points_scorer: FilteredScorer) {
+        // Check if there is an suitable entry point
+        //   - entry point level if higher or equal
+        //   - it satisfies filters
+
+        let level = self.get_point_level(point_id);
+
+        let entry_point_opt = {
+            let mut entry_points = self.entry_points.lock();
+            if entry_points.iter().any(|entry| entry.point_id == point_id) {
+                entry_points.get_entry_point(|other_id| {
+                    other_id != point_id && points_scorer.check_point(other_id)
+                })
+            } else {
+                entry_points.new_point(point_id, level, |point_id| {
+                    points_scorer.check_point(point_id)
+                })
+            }
+        };
+        match entry_point_opt {
+            // New point is a new empty entry (for this filter, at least)
+            // We can't do much here, so just quit
+            None => {}
+
+            // Entry point found.
+            Some(entry_point) => {
+                let mut level_entry = if entry_point.level > level {
+                    // The entry point is higher than a new point
+                    // Let's find closest one on same level
+
+                    // greedy search for a single closest point
+                    self.search_entry(
+                        entry_point.point_id,
+                        entry_point.level,
+                        level,
+                        &mut points_scorer,
+                    )
+                } else {
+                    ScoredPointOffset {
+                        idx: entry_point.point_id,
+                        score: points_scorer.score_internal(point_id, entry_point.point_id),
+                    }
+                };
+                // minimal common level for entry points
+                let linking_level = min(level, entry_point.level);
+
+                for curr_level in (0..=linking_level).rev() {
+                    let level_m = self.get_m(curr_level);
+                    let existing_links = self.links_layers[point_id as usize][curr_level]
+                        .read()
+                        .clone();
+
+                    let nearest_points = self.search_on_level(
+                        level_entry,
+                        curr_level,
+                        self.ef_construct,
+                        &mut points_scorer,
+                        &existing_links,
+                        &AtomicBool::new(false),
+                    );
+
+                    // The point might be already reachable through the links,
+                    // created concurrently by other points
+                    let nearest_points = nearest_points
+                        .into_iter()
+                        .filter(|nearest_point| nearest_point.idx != point_id);
+
+                    let scorer = |a, b| points_scorer.score_internal(a, b);
+
+                    if self.use_heuristic {
+                        let selected_nearest = Self::select_candidate_with_heuristic_from_sorted(
+                            nearest_points,
+                            level_m,
+                            scorer,
+                        );
+                        self.links_layers[point_id as usize][curr_level]
+                            .write()
+                            .clone_from(&selected_nearest);
+
+                        for &other_point in &selected_nearest {
+                            let mut other_point_links =
+                                self.links_layers[other_point as usize][curr_level].write();
+                            if other_point_links.len() < level_m {
+                                // If linked point is lack of neighbours
+                                other_point_links.push(point_id);
+                            } else {
+                                let mut candidates = BinaryHeap::with_capacity(level_m + 1);
+                                candidates.push(ScoredPointOffset {
+                                    idx: point_id,
+                                    score: scorer(point_id, other_point),
+                                });
+                                for other_point_link in
+                                    other_point_links.iter().take(level_m).copied()
+                                {
+                                    candidates.push(ScoredPointOffset {
+                                        idx: other_point_link,
+                                        score: scorer(other_point_link, other_point),
+                                    });
+                                }
+                                let selected_candidates =
+                                    Self::select_candidate_with_heuristic_from_sorted(
+                                        candidates.into_sorted_vec().into_iter().rev(),
+                                        level_m,
+                                        scorer,
+                                    );
+                                other_point_links.clear(); // this do not free memory, which is good
+                                for selected in selected_candidates.iter().copied() {
+                                    other_point_links.push(selected);
+                                }
+                            }
+                        }
+                    } else {
+                        for nearest_point in nearest_points {
+                            Self::connect_new_point(
+                                &mut self.links_layers[point_id as usize][curr_level].write(),
+                                nearest_point.idx,
+                                point_id,
+                                level_m,
+                                scorer,
+                            );
+
+                            Self::connect_new_point(
+                                &mut self.links_layers[nearest_point.idx as usize][curr_level]
+                                    .write(),
+                                point_id,
+                                nearest_point.idx,
+                                level_m,
+                                scorer,
+                            );
+                            if nearest_point.score > level_entry.score {
+                                level_entry = nearest_point;
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::index_fixtures::{
+        random_vector, FakeFilterContext, TestRawScorerProducer,
+    };
+    use crate::spaces::metric::Metric;
+    use crate::spaces::simple::{CosineMetric, EuclidMetric};
+    use crate::spaces::tools::FixedLengthPriorityQueue;
+    use crate::types::VectorElementType;
+    use crate::vector_storage::RawScorer;
+    use itertools::Itertools;
+    use rand::rngs::StdRng;
+    use rand::seq::SliceRandom;
+    use rand::SeedableRng;
+    use rayon::prelude::*;
+
+    #[test]
+    fn test_connect_new_point() {
+        let num_points = 10;
+        let m = 6;
+        let ef_construct = 32;
+
+        // See illustration in docs
+        let points: Vec<Vec<VectorElementType>> = vec![
+            vec![21.79, 7.18],  // Target
+            vec![20.58, 5.46],  // 1  B - yes
+            vec![21.19, 4.51],  // 2  C
+            vec![24.73, 8.24],  // 3  D - yes
+            vec![24.55, 9.98],  // 4  E
+            vec![26.11, 6.85],  // 5  F
+            vec![17.64, 11.14], // 6  G - yes
+            vec![14.97, 11.52], // 7  I
+            vec![14.97, 9.60],  // 8  J
+            vec![16.23, 14.32], // 9  H
+            vec![12.69, 19.13], // 10 K
+        ];
+
+        let scorer = |a: PointOffsetType, b: PointOffsetType| {
+            -((points[a as usize][0] - points[b as usize][0]).powi(2)
+                + (points[a as usize][1] - points[b as usize][1]).powi(2))
+            .sqrt()
+        };
+
+        let mut insert_ids = (1..points.len() as PointOffsetType).collect_vec();
+
+        let mut candidates = FixedLengthPriorityQueue::new(insert_ids.len());
+        for &id in &insert_ids {
+            candidates.push(ScoredPointOffset {
+                idx: id,
+                score: scorer(0, id),
+            });
+        }
+
+        let res = GraphLayersBuilder::select_candidate_with_heuristic_from_sorted(
+            candidates.into_iter(),
+            m,
+            scorer,
+        );
+
+        assert_eq!(&res, &vec![1, 3, 6]);
+
+        let mut rng = StdRng::seed_from_u64(42);
+
+        let graph_layers_builder = GraphLayersBuilder::new(num_points, m, m, ef_construct, 1, true);
+        insert_ids.shuffle(&mut rng);
+        for &id in &insert_ids {
+            let level_m = graph_layers_builder.get_m(0);
+            GraphLayersBuilder::connect_new_point(
+                &mut graph_layers_builder.links_layers[0][0].write(),
+                id,
+                0,
+                level_m,
+                scorer,
+            )
+        }
+        assert_eq!(
+            *graph_layers_builder.links_layers[0][0].read(),
+            vec![1, 2, 3, 4, 5, 6]
+        );
+    }
+
+    #[test]
+    fn test_relink_does_not_duplicate_entry_points() {
+        let num_vectors = 100;
+        let dim = 8;
+        let m = 8;
+
+        let mut rng = StdRng::seed_from_u64(42);
+        let vector_holder = TestRawScorerProducer::<CosineMetric>::new(dim, num_vectors, &mut rng);
+        let mut graph_layers_builder = GraphLayersBuilder::new(num_vectors, m, m * 2, 16, 10, true);
+
+        let link_all = |graph_layers_builder: &GraphLayersBuilder| {
+            for idx in 0..(num_vectors as PointOffsetType) {
+                let added_vector = vector_holder.vectors.get(idx).to_vec();
+                let raw_scorer = vector_holder.get_raw_scorer(added_vector);
+                graph_layers_builder.link_new_point(idx, FilteredScorer::new(&raw_scorer, None));
+            }
+        };
+        for idx in 0..(num_vectors as PointOffsetType) {
+            let level = graph_layers_builder.get_random_layer(&mut rng);
+            graph_layers_builder.set_levels(idx, level);
+        }
+        link_all(&graph_layers_builder);
+        // Relink all points, as updated vectors do
+        link_all(&graph_layers_builder);
+
+        let entry_points = graph_layers_builder.entry_points.lock();
+        let entry_ids = entry_points
+            .iter()
+            .map(|entry| entry.point_id)
+            .collect_vec();
+        assert!(!entry_ids.is_empty());
+        assert_eq!(entry_ids.iter().unique().count(), entry_ids.len());
+    }
+
+    #[test]
+    fn test_parallel_graph_build() {
+        let num_vectors = 1000;
+        let dim = 8;
+        let m = 8;
+        let ef_construct = 16;
+        let top = 5;
+
+        type M = CosineMetric;
+
+        let mut rng = StdRng::seed_from_u64(42);
+
+        let vector_holder = TestRawScorerProducer::<M>::new(dim, num_vectors, &mut rng);
+
+        let mut graph_layers_builder =
+            GraphLayersBuilder::new(num_vectors, m, m * 2, ef_construct, 10, true);
+
+        for idx in 0..(num_vectors as PointOffsetType) {
+            let level = graph_layers_builder.get_random_layer(&mut rng);
+            graph_layers_builder.set_levels(idx, level);
+        }
+
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(4)
+            .build()
+            .unwrap();
+
+        pool.install(|| {
+            (0..(num_vectors as PointOffsetType))
+                .into_par_iter()
+                .for_each(|idx| {
+                    let fake_filter_context = FakeFilterContext {};
+                    let added_vector = vector_holder.vectors.get(idx).to_vec();
+                    let raw_scorer = vector_holder.get_raw_scorer(added_vector);
+                    let scorer = FilteredScorer::new(&raw_scorer, Some(&fake_filter_context));
+                    graph_layers_builder.link_new_point(idx, scorer);
+                })
+        });
+
+        let graph_layers = graph_layers_builder.into_graph_layers();
+
+        // Point should never be linked to itself
+        for point_id in 0..(num_vectors as PointOffsetType) {
+            for level in 0..=graph_layers.point_level(point_id) {
+                assert!(!graph_layers.links(point_id, level).contains(&point_id));
+            }
+        }
+
+        let total_links_0: usize = (0..(num_vectors as PointOffsetType))
+            .map(|point_id| graph_layers.links(point_id, 0).len())
+            .sum();
+        assert!(total_links_0 as f64 / num_vectors as f64 > m as f64);
+
+        let query = random_vector(&mut rng, dim);
+        let processed_query = M::preprocess(&query).unwrap_or_else(|| query.clone());
+        let mut reference_top = FixedLengthPriorityQueue::new(top);
+        for idx in 0..vector_holder.vectors.len() as PointOffsetType {
+            let vec = &vector_holder.vectors.get(idx);
+            reference_top.push(ScoredPointOffset {
+                idx,
+                score: M::similarity(vec, &processed_query),
+            });
+        }
+
+        let fake_filter_context = FakeFilterContext {};
+        let raw_scorer = vector_holder.get_raw_scorer(query);
+        let mut scorer = FilteredScorer::new(&raw_scorer, Some(&fake_filter_context));
+        let graph_search = graph_layers.search(top, 64, &mut scorer, &AtomicBool::new(false));
+
+        assert_eq!(reference_top.into_vec(), graph_search);
+    }
+
+    #[test]
+    #[ignore]
+    fn test_candidate_selection_heuristics() {
+        const NUM_VECTORS: usize = 100;
+        const DIM: usize = 16;
+        const M: usize = 16;
+
+        let mut rng = StdRng::seed_from_u64(42);
+
+        let vector_holder = TestRawScorerProducer::<EuclidMetric>::new(DIM, NUM_VECTORS, &mut rng);
+
+        let mut candidates: FixedLengthPriorityQueue<ScoredPointOffset> =
+            FixedLengthPriorityQueue::new(NUM_VECTORS);
+
+        let new_vector_to_insert = random_vector(&mut rng, DIM);
+
+        let scorer = vector_holder.get_raw_scorer(new_vector_to_insert);
+
+        for i in 0..NUM_VECTORS {
+            candidates.push(ScoredPointOffset {
+                idx: i as PointOffsetType,
+                score: scorer.score_point(i as PointOffsetType),
+            });
+        }
+
+        let sorted_candidates = candidates.into_vec();
+
+        for x in sorted_candidates.iter().take(M) {
+            eprintln!("sorted_candidates = ({}, {})", x.idx, x.score);
+        }
+
+        let selected_candidates = GraphLayersBuilder::select_candidate_with_heuristic_from_sorted(
+            sorted_candidates.into_iter(),
+            M,
+            |a, b| scorer.score_internal(a, b),
+        );
+
+        for x in selected_candidates.iter() {
+            eprintln!("selected_candidates = {}", x);
+        }
+    }
+}
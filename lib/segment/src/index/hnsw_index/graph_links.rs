@@ -0,0 +1,463 @@
+//! Compact, offset-indexed representation of HNSW graph links.
+//!
+//! Links are stored in a single flat file, which could be either read into RAM or memory-mapped.
+//! File layout, all numbers are stored in little-endian byte order:
+//!
+//! * header: number of points, total number of point levels and total number of links (`u64` each)
+//! * point offsets: `points + 1` values of `u64` - index of the first level of each point
+//! * level offsets: `levels + 1` values of `u64` - index of the first link of each level
+//! * links: `links` values of `u32`
+
+use crate::common::error_logging::LogError;
+use crate::common::file_operations::atomic_save_raw;
+use crate::entry::entry_point::{OperationError, OperationResult};
+use crate::index::hnsw_index::graph_layers::{LayersContainer, LinkContainer, LinkContainerRef};
+use crate::types::PointOffsetType;
+use atomicwrites::{AllowOverwrite, AtomicFile};
+use memmap::{Mmap, MmapOptions};
+use parking_lot::RwLock;
+use std::fs::{File, OpenOptions};
+use std::io::{BufWriter, Read, Write};
+use std::mem::size_of;
+use std::path::{Path, PathBuf};
+
+pub const HNSW_LINKS_FILE: &str = "links.bin";
+
+const HEADER_SIZE: usize = 3 * size_of::<u64>();
+
+fn data_size(num_points: usize, num_levels: usize, num_links: usize) -> usize {
+    HEADER_SIZE
+        + (num_points + 1) * size_of::<u64>()
+        + (num_levels + 1) * size_of::<u64>()
+        + num_links * size_of::<PointOffsetType>()
+}
+
+/// Same as `data_size`, but returns `None` on overflow. Used for sizes read from the file
+fn checked_data_size(num_points: usize, num_levels: usize, num_links: usize) -> Option<usize> {
+    let offsets = num_points
+        .checked_add(1)?
+        .checked_add(num_levels.checked_add(1)?)?
+        .checked_mul(size_of::<u64>())?;
+    let links = num_links.checked_mul(size_of::<PointOffsetType>())?;
+    HEADER_SIZE.checked_add(offsets)?.checked_add(links)
+}
+
+fn u64_to_u8(v: &[u64]) -> &[u8] {
+    unsafe { std::slice::from_raw_parts(v.as_ptr() as *const u8, v.len() * size_of::<u64>()) }
+}
+
+fn u64_to_u8_mut(v: &mut [u64]) -> &mut [u8] {
+    unsafe { std::slice::from_raw_parts_mut(v.as_mut_ptr() as *mut u8, v.len() * size_of::<u64>()) }
+}
+
+/// Caller should guarantee, that the slice is aligned to `T`
+fn u8_to_slice<T>(v: &[u8]) -> &[T] {
+    unsafe { std::slice::from_raw_parts(v.as_ptr() as *const T, v.len() / size_of::<T>()) }
+}
+
+/// Convert content of the links file between little-endian and native byte order.
+/// Conversion is symmetric, so it is used both for reading and writing. No-op on little-endian hosts.
+fn convert_byte_order(data: &mut [u64], num_points: usize, num_levels: usize, num_links: usize) {
+    if cfg!(target_endian = "little") {
+        return;
+    }
+    let links_start = data_size(num_points, num_levels, 0) / size_of::<u64>();
+    let (offsets, links) = data.split_at_mut(links_start);
+    for value in offsets {
+        *value = value.swap_bytes();
+    }
+    let links: &mut [PointOffsetType] = unsafe {
+        std::slice::from_raw_parts_mut(links.as_mut_ptr() as *mut PointOffsetType, num_links)
+    };
+    for link in links {
+        *link = link.swap_bytes();
+    }
+}
+
+enum GraphLinksStorage {
+    /// Content of the links file. Stored as `u64` to keep all sections aligned
+    Ram(Vec<u64>),
+    Mmap(Mmap),
+}
+
+/// Read-only links of all points on all levels of the HNSW graph
+pub struct GraphLinks {
+    storage: GraphLinksStorage,
+    num_points: usize,
+    num_levels: usize,
+    num_links: usize,
+}
+
+impl GraphLinks {
+    pub fn get_path(path: &Path) -> PathBuf {
+        path.join(HNSW_LINKS_FILE)
+    }
+
+    /// Convert nested links into the compact representation, stored in RAM
+    pub fn from_layers(links_layers: &[LayersContainer]) -> Self {
+        let num_points = links_layers.len();
+        // Points without layers get an empty level 0, so that each point has at least one level
+        let point_levels = |layers: &LayersContainer| layers.len().max(1);
+        let num_levels: usize = links_layers.iter().map(point_levels).sum();
+        let num_links: usize = links_layers.iter().flatten().map(|links| links.len()).sum();
+
+        let size = data_size(num_points, num_levels, num_links);
+        let words = (size + size_of::<u64>() - 1) / size_of::<u64>();
+        let mut data: Vec<u64> = Vec::with_capacity(words);
+
+        data.extend([num_points as u64, num_levels as u64, num_links as u64]);
+
+        let mut level_offset = 0;
+        data.push(level_offset);
+        for layers in links_layers {
+            level_offset += point_levels(layers) as u64;
+            data.push(level_offset);
+        }
+
+        let mut link_offset = 0;
+        data.push(link_offset);
+        for layers in links_layers {
+            if layers.is_empty() {
+                data.push(link_offset);
+            }
+            for links in layers {
+                link_offset += links.len() as u64;
+                data.push(link_offset);
+            }
+        }
+
+        let links_start = data.len();
+        data.resize(words, 0);
+
+        let links_data: &mut [PointOffsetType] = unsafe {
+            std::slice::from_raw_parts_mut(
+                data[links_start..].as_mut_ptr() as *mut PointOffsetType,
+                num_links,
+            )
+        };
+        for (dst, src) in links_data
+            .iter_mut()
+            .zip(links_layers.iter().flatten().flatten())
+        {
+            *dst = *src;
+        }
+
+        GraphLinks {
+            storage: GraphLinksStorage::Ram(data),
+            num_points,
+            num_levels,
+            num_links,
+        }
+    }
+
+    /// Write links of the graph under construction directly into the links file.
+    /// Each link list is read under its own lock, so the graph is never copied as a whole.
+    /// Caller should guarantee, that links are not modified while saving.
+    pub fn save_layers(
+        path: &Path,
+        links_layers: &[Vec<RwLock<LinkContainer>>],
+    ) -> OperationResult<()> {
+        let num_points = links_layers.len();
+        let point_levels = |layers: &Vec<RwLock<LinkContainer>>| layers.len().max(1);
+        let num_levels: usize = links_layers.iter().map(point_levels).sum();
+
+        let mut level_offsets: Vec<u64> = Vec::with_capacity(num_levels + 1);
+        let mut link_offset = 0;
+        level_offsets.push(link_offset);
+        for layers in links_layers {
+            if layers.is_empty() {
+                level_offsets.push(link_offset);
+            }
+            for links in layers {
+                link_offset += links.read().len() as u64;
+                level_offsets.push(link_offset);
+            }
+        }
+        let num_links = link_offset as usize;
+
+        let af = AtomicFile::new(path, AllowOverwrite);
+        af.write(|f| {
+            let mut writer = BufWriter::new(f);
+            for value in [num_points as u64, num_levels as u64, num_links as u64] {
+                writer.write_all(&value.to_le_bytes())?;
+            }
+
+            let mut level_offset = 0u64;
+            writer.write_all(&level_offset.to_le_bytes())?;
+            for layers in links_layers {
+                level_offset += point_levels(layers) as u64;
+                writer.write_all(&level_offset.to_le_bytes())?;
+            }
+
+            for offset in &level_offsets {
+                writer.write_all(&offset.to_le_bytes())?;
+            }
+
+            for links in links_layers.iter().flatten() {
+                for link in links.read().iter() {
+                    writer.write_all(&link.to_le_bytes())?;
+                }
+            }
+            writer.flush()
+        })?;
+        Ok(())
+    }
+
+    /// Read the whole links file into RAM
+    pub fn load(path: &Path) -> OperationResult<Self> {
+        let mut file = File::open(path)?;
+        let file_size = file.metadata()?.len() as usize;
+        let mut data = vec![0u64; (file_size + size_of::<u64>() - 1) / size_of::<u64>()];
+        file.read_exact(&mut u64_to_u8_mut(&mut data)[..file_size])?;
+        Self::from_storage(GraphLinksStorage::Ram(data), file_size)
+    }
+
+    /// Memory-map the links file. Links are read from disk on demand
+    ///
+    /// Links could only be mapped as-is on little-endian hosts, other hosts read them into RAM.
+    pub fn open_mmap(path: &Path) -> OperationResult<Self> {
+        if cfg!(target_endian = "big") {
+            return Self::load(path);
+        }
+        let file = OpenOptions::new().read(true).write(false).open(path)?;
+        let mmap = unsafe { MmapOptions::new().map(&file) }.describe("Open graph links mmap")?;
+        let file_size = mmap.len();
+        Self::from_storage(GraphLinksStorage::Mmap(mmap), file_size)
+    }
+
+    fn from_storage(mut storage: GraphLinksStorage, file_size: usize) -> OperationResult<Self> {
+        let bytes = match &storage {
+            GraphLinksStorage::Ram(data) => u64_to_u8(data),
+            GraphLinksStorage::Mmap(mmap) => &mmap[..],
+        };
+
+        if file_size < HEADER_SIZE {
+            return Err(OperationError::service_error(
+                "Graph links file is too small",
+            ));
+        }
+
+        let header: &[u64] = u8_to_slice(&bytes[..HEADER_SIZE]);
+        let (num_points, num_levels, num_links) = (
+            u64::from_le(header[0]) as usize,
+            u64::from_le(header[1]) as usize,
+            u64::from_le(header[2]) as usize,
+        );
+
+        let corrupted = || OperationError::service_error("Graph links file is corrupted");
+
+        match checked_data_size(num_points, num_levels, num_links) {
+            Some(size) if size <= file_size => {}
+            _ => return Err(corrupted()),
+        }
+
+        if let GraphLinksStorage::Ram(data) = &mut storage {
+            convert_byte_order(data, num_points, num_levels, num_links);
+        }
+
+        let links = GraphLinks {
+            storage,
+            num_points,
+            num_levels,
+            num_links,
+        };
+        if !links.is_consistent() {
+            return Err(corrupted());
+        }
+        Ok(links)
+    }
+
+    /// Check, that offsets are monotonic and all links point to existing points,
+    /// so that reading the links never goes out of bounds
+    fn is_consistent(&self) -> bool {
+        let point_offsets = self.point_offsets();
+        let level_offsets = self.level_offsets();
+        // Each point has at least one level
+        point_offsets[0] == 0
+            && point_offsets.windows(2).all(|pair| pair[0] < pair[1])
+            && point_offsets[self.num_points] == self.num_levels as u64
+            && level_offsets[0] == 0
+            && level_offsets.windows(2).all(|pair| pair[0] <= pair[1])
+            && level_offsets[self.num_levels] == self.num_links as u64
+            && self
+                .all_links()
+                .iter()
+                .all(|&link| (link as usize) < self.num_points)
+    }
+
+    pub fn save(&self, path: &Path) -> OperationResult<()> {
+        if cfg!(target_endian = "little") {
+            return Ok(atomic_save_raw(path, self.bytes())?);
+        }
+        let bytes = self.bytes();
+        let mut data = vec![0u64; (bytes.len() + size_of::<u64>() - 1) / size_of::<u64>()];
+        u64_to_u8_mut(&mut data)[..bytes.len()].copy_from_slice(bytes);
+        convert_byte_order(&mut data, self.num_points, self.num_levels, self.num_links);
+        Ok(atomic_save_raw(path, &u64_to_u8(&data)[..bytes.len()])?)
+    }
+
+    fn bytes(&self) -> &[u8] {
+        let size = data_size(self.num_points, self.num_levels, self.num_links);
+        match &self.storage {
+            GraphLinksStorage::Ram(data) => &u64_to_u8(data)[..size],
+            GraphLinksStorage::Mmap(mmap) => &mmap[..size],
+        }
+    }
+
+    fn point_offsets(&self) -> &[u64] {
+        let start = HEADER_SIZE;
+        let end = start + (self.num_points + 1) * size_of::<u64>();
+        u8_to_slice(&self.bytes()[start..end])
+    }
+
+    fn level_offsets(&self) -> &[u64] {
+        let start = HEADER_SIZE + (self.num_points + 1) * size_of::<u64>();
+        let end = start + (self.num_levels + 1) * size_of::<u64>();
+        u8_to_slice(&self.bytes()[start..end])
+    }
+
+    fn all_links(&self) -> &[PointOffsetType] {
+        let start = HEADER_SIZE
+            + (self.num_points + 1) * size_of::<u64>()
+            + (self.num_levels + 1) * size_of::<u64>();
+        u8_to_slice(&self.bytes()[start..])
+    }
+
+    pub fn num_points(&self) -> usize {
+        self.num_points
+    }
+
+    pub fn point_level(&self, point_id: PointOffsetType) -> usize {
+        let point_offsets = self.point_offsets();
+        let idx = point_id as usize;
+        // Each point has at least one level, unless the file is corrupted
+        (point_offsets[idx + 1].saturating_sub(point_offsets[idx]) as usize).saturating_sub(1)
+    }
+
+    pub fn links(&self, point_id: PointOffsetType, level: usize) -> LinkContainerRef {
+        let level_idx = self.point_offsets()[point_id as usize] as usize + level;
+        let level_offsets = self.level_offsets();
+        let start = level_offsets[level_idx] as usize;
+        let end = level_offsets[level_idx + 1] as usize;
+        &self.all_links()[start..end]
+    }
+
+    pub fn is_on_disk(&self) -> bool {
+        matches!(self.storage, GraphLinksStorage::Mmap(_))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempdir::TempDir;
+
+    fn to_layers(links: &GraphLinks) -> Vec<LayersContainer> {
+        (0..links.num_points() as PointOffsetType)
+            .map(|point_id| {
+                (0..=links.point_level(point_id))
+                    .map(|level| links.links(point_id, level).to_vec())
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_graph_links_construction() {
+        let links_layers: Vec<LayersContainer> = vec![
+            vec![vec![1, 2, 3], vec![2]],
+            vec![vec![0, 2]],
+            vec![vec![0, 1, 3], vec![0], vec![]],
+            vec![vec![]],
+            vec![vec![2, 0]],
+        ];
+
+        let links = GraphLinks::from_layers(&links_layers);
+        assert!(!links.is_on_disk());
+        assert_eq!(links.num_points(), 5);
+        assert_eq!(links.point_level(0), 1);
+        assert_eq!(links.point_level(2), 2);
+        assert_eq!(links.links(2, 1), &[0]);
+        assert_eq!(to_layers(&links), links_layers);
+
+        let dir = TempDir::new("graph_links").unwrap();
+        let path = GraphLinks::get_path(dir.path());
+        links.save(&path).unwrap();
+
+        let file_content = std::fs::read(&path).unwrap();
+        assert_eq!(file_content[..size_of::<u64>()], 5u64.to_le_bytes());
+
+        let loaded = GraphLinks::load(&path).unwrap();
+        assert_eq!(to_layers(&loaded), links_layers);
+
+        let mmap = GraphLinks::open_mmap(&path).unwrap();
+        assert!(mmap.is_on_disk());
+        assert_eq!(to_layers(&mmap), links_layers);
+    }
+
+    #[test]
+    fn test_graph_links_point_without_layers() {
+        let links = GraphLinks::from_layers(&[vec![vec![1]], vec![], vec![vec![0], vec![]]]);
+        assert_eq!(links.point_level(1), 0);
+        assert!(links.links(1, 0).is_empty());
+        assert_eq!(links.links(2, 0), &[0]);
+    }
+
+    #[test]
+    fn test_graph_links_save_layers() {
+        let links_layers: Vec<LayersContainer> = vec![
+            vec![vec![1, 2], vec![2]],
+            vec![],
+            vec![vec![0, 1], vec![0], vec![]],
+        ];
+        let locked_layers: Vec<Vec<RwLock<LinkContainer>>> = links_layers
+            .iter()
+            .map(|layers| layers.iter().cloned().map(RwLock::new).collect())
+            .collect();
+
+        let dir = TempDir::new("graph_links").unwrap();
+        let path = GraphLinks::get_path(dir.path());
+        GraphLinks::save_layers(&path, &locked_layers).unwrap();
+        let streamed = std::fs::read(&path).unwrap();
+
+        GraphLinks::from_layers(&links_layers).save(&path).unwrap();
+        assert_eq!(streamed, std::fs::read(&path).unwrap());
+    }
+
+    #[test]
+    fn test_graph_links_corrupted_file() {
+        let links_layers: Vec<LayersContainer> =
+            vec![vec![vec![1, 2], vec![2]], vec![vec![0]], vec![vec![0, 1]]];
+        let dir = TempDir::new("graph_links").unwrap();
+        let path = GraphLinks::get_path(dir.path());
+        GraphLinks::from_layers(&links_layers).save(&path).unwrap();
+        let content = std::fs::read(&path).unwrap();
+
+        let assert_corrupted = |data: &[u8]| {
+            std::fs::write(&path, data).unwrap();
+            assert!(GraphLinks::load(&path).is_err());
+            assert!(GraphLinks::open_mmap(&path).is_err());
+        };
+
+        // Truncated file
+        assert_corrupted(&content[..content.len() - 1]);
+
+        // Number of points, which overflows the file size
+        let mut data = content.clone();
+        data[..size_of::<u64>()].copy_from_slice(&u64::MAX.to_le_bytes());
+        assert_corrupted(&data);
+
+        // Decreasing point offsets
+        let mut data = content.clone();
+        let second_point_offset = HEADER_SIZE + size_of::<u64>();
+        data[second_point_offset..second_point_offset + size_of::<u64>()]
+            .copy_from_slice(&10u64.to_le_bytes());
+        assert_corrupted(&data);
+
+        // Link to a non-existing point
+        let mut data = content;
+        let last_link = data.len() - size_of::<PointOffsetType>();
+        data[last_link..].copy_from_slice(&100u32.to_le_bytes());
+        assert_corrupted(&data);
+    }
+}
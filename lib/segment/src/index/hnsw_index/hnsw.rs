@@ -1,18 +1,26 @@
 use crate::entry::entry_point::{OperationError, OperationResult};
 use crate::index::hnsw_index::build_condition_checker::BuildConditionChecker;
 use crate::index::hnsw_index::config::HnswGraphConfig;
-use crate::index::hnsw_index::graph_layers::GraphLayers;
+use crate::index::hnsw_index::graph_layers::{GraphLayers, ReusedGraph};
+use crate::index::hnsw_index::graph_layers_builder::GraphLayersBuilder;
 use crate::index::hnsw_index::point_scorer::FilteredScorer;
 use crate::index::sample_estimation::sample_check_cardinality;
+use crate::index::visited_pool::VisitedList;
 use crate::index::{PayloadIndexSS, VectorIndex};
 use crate::types::Condition::Field;
-use crate::types::{FieldCondition, Filter, HnswConfig, SearchParams, VectorElementType};
+use crate::types::{
+    CardinalityExplain, DenseQuery, FieldCondition, Filter, HnswConfig, PointOffsetType,
+    SearchParams, SearchStrategy, SegmentSearchExplain,
+};
 use crate::vector_storage::{ScoredPointOffset, VectorStorageSS};
 use atomic_refcell::AtomicRefCell;
+use bit_vec::BitVec;
+use itertools::Itertools;
 use log::debug;
 
 use rand::thread_rng;
-use std::cmp::max;
+use rayon::prelude::*;
+use std::cmp::{max, min};
 use std::fs::create_dir_all;
 use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicBool, Ordering};
@@ -20,12 +28,54 @@ use std::sync::Arc;
 
 const HNSW_USE_HEURISTIC: bool = true;
 
+/// Number of points, which are linked sequentially before the parallel linking starts.
+/// Parallel insertion into an almost empty graph produces poorly connected regions.
+const SINGLE_THREADED_HNSW_BUILD_THRESHOLD: usize = 32;
+
+/// Links given points with `link` function in the current rayon thread pool.
+/// Returns `Cancelled` error once the `stopped` flag is raised.
+fn link_points<F>(points: &[PointOffsetType], stopped: &AtomicBool, link: F) -> OperationResult<()>
+where
+    F: Fn(PointOffsetType) + Sync + Send,
+{
+    let check_stopped = || {
+        if stopped.load(Ordering::Relaxed) {
+            Err(OperationError::Cancelled {
+                description: "Cancelled by external thread".to_string(),
+            })
+        } else {
+            Ok(())
+        }
+    };
+
+    let (first_points, rest_points) =
+        points.split_at(min(SINGLE_THREADED_HNSW_BUILD_THRESHOLD, points.len()));
+
+    for &point_id in first_points {
+        check_stopped()?;
+        link(point_id);
+    }
+
+    rest_points.par_iter().try_for_each(|&point_id| {
+        check_stopped()?;
+        link(point_id);
+        Ok(())
+    })
+}
+
 pub struct HNSWIndex {
     vector_storage: Arc<AtomicRefCell<VectorStorageSS>>,
     payload_index: Arc<AtomicRefCell<PayloadIndexSS>>,
     config: HnswGraphConfig,
     path: PathBuf,
     graph: GraphLayers,
+    /// Keep graph links memory-mapped instead of loading them into RAM
+    on_disk: bool,
+    /// Graph of the appendable segment, which is updated with every inserted vector.
+    /// If set, it is used for search instead of `graph`.
+    incremental_graph: Option<GraphLayersBuilder>,
+    /// Incremental graph was changed since it had been saved last time
+    graph_changed: AtomicBool,
 }
 
 impl HNSWIndex {
@@ -48,9 +98,11 @@ impl HNSWIndex {
             )
         };
 
+        let on_disk = hnsw_config.on_disk.unwrap_or(false);
+
         let graph_path = GraphLayers::get_path(path);
         let graph = if graph_path.exists() {
-            GraphLayers::load(&graph_path)?
+            GraphLayers::load(path, on_disk)?
         } else {
             let total_points = vector_storage.borrow().total_vector_count();
             GraphLayers::new(
@@ -69,17 +121,74 @@ impl HNSWIndex {
             config,
             path: path.to_owned(),
             graph,
+            on_disk,
+            incremental_graph: None,
+            graph_changed: AtomicBool::new(false),
         })
     }
 
+    /// Open index of the appendable segment, which links new vectors into the graph on insertion.
+    /// Vectors, which were inserted after the graph had been saved, are linked on opening.
+    /// `updated_points` - points, which might have been changed after the graph had been saved.
+    /// They are re-linked as well.
+    pub fn open_incremental(
+        path: &Path,
+        vector_storage: Arc<AtomicRefCell<VectorStorageSS>>,
+        payload_index: Arc<AtomicRefCell<PayloadIndexSS>>,
+        hnsw_config: HnswConfig,
+        updated_points: &[PointOffsetType],
+    ) -> OperationResult<Self> {
+        let is_graph_saved = GraphLayers::get_path(path).exists();
+        let mut index = Self::open(path, vector_storage, payload_index, hnsw_config)?;
+
+        let graph = GraphLayersBuilder::from_graph_layers(&index.graph);
+        // Links are kept in the incremental graph only
+        index.graph = GraphLayers::new(
+            0,
+            index.config.m,
+            index.config.m0,
+            index.config.ef_construct,
+            1,
+            HNSW_USE_HEURISTIC,
+        );
+
+        let linked_points = if is_graph_saved {
+            graph.num_points()
+        } else {
+            0
+        };
+        index.incremental_graph = Some(graph);
+
+        let not_linked_points = index
+            .vector_storage
+            .borrow()
+            .iter_ids()
+            .filter(|&point_id| point_id as usize >= linked_points)
+            .chain(
+                updated_points
+                    .iter()
+                    .copied()
+                    .filter(|&point_id| (point_id as usize) < linked_points),
+            )
+            .collect_vec();
+        debug!("linking {} points on opening", not_linked_points.len());
+        for point_id in not_linked_points {
+            index.update_vector(point_id)?;
+        }
+
+        Ok(index)
+    }
+
     fn save_config(&self) -> OperationResult<()> {
         let config_path = HnswGraphConfig::get_config_path(&self.path);
         self.config.save(&config_path)
     }
 
     fn save_graph(&self) -> OperationResult<()> {
-        let graph_path = GraphLayers::get_path(&self.path);
-        self.graph.save(&graph_path)
+        match &self.incremental_graph {
+            Some(graph) => graph.save(&self.path),
+            None => self.graph.save(&self.path),
+        }
     }
 
     pub fn save(&self) -> OperationResult<()> {
@@ -88,44 +197,70 @@ impl HNSWIndex {
         Ok(())
     }
 
+    /// Build a graph for the points of a single payload block.
+    /// Levels of the points are taken from the main graph, so graphs could be merged afterwards.
     pub fn build_filtered_graph(
         &self,
-        graph: &mut GraphLayers,
+        main_graph: &GraphLayersBuilder,
         condition: FieldCondition,
-        block_condition_checker: &mut BuildConditionChecker,
-    ) {
-        block_condition_checker.filter_list.next_iteration();
+        block_filter_list: &mut VisitedList,
+        stopped: &AtomicBool,
+    ) -> OperationResult<GraphLayersBuilder> {
+        block_filter_list.next_iteration();
 
         let filter = Filter::new_must(Field(condition));
 
         let payload_index = self.payload_index.borrow();
         let vector_storage = self.vector_storage.borrow();
 
-        for block_point_id in payload_index.query_points(&filter) {
-            block_condition_checker
-                .filter_list
-                .check_and_update_visited(block_point_id);
+        let block_points = payload_index.query_points(&filter).collect_vec();
+
+        for &block_point_id in &block_points {
+            block_filter_list.check_and_update_visited(block_point_id);
         }
 
-        for block_point_id in payload_index.query_points(&filter) {
+        // ToDo: re-use graph layer for same payload
+        let mut graph_layers_builder = GraphLayersBuilder::new_with_params(
+            vector_storage.total_vector_count(),
+            self.config.m,
+            self.config.m0,
+            self.config.ef_construct,
+            1,
+            HNSW_USE_HEURISTIC,
+            false,
+        );
+
+        for &block_point_id in &block_points {
+            let level = main_graph.get_point_level(block_point_id);
+            graph_layers_builder.set_levels(block_point_id, level);
+        }
+
+        let block_filter_list: &VisitedList = block_filter_list;
+        link_points(&block_points, stopped, |block_point_id| {
             let vector = vector_storage.get_vector(block_point_id).unwrap();
             let raw_scorer = vector_storage.raw_scorer(vector);
-            block_condition_checker.current_point = block_point_id;
+            let block_condition_checker = BuildConditionChecker {
+                filter_list: block_filter_list,
+                current_point: block_point_id,
+            };
             let points_scorer =
-                FilteredScorer::new(raw_scorer.as_ref(), Some(block_condition_checker));
+                FilteredScorer::new(raw_scorer.as_ref(), Some(&block_condition_checker));
 
-            let level = self.graph.point_level(block_point_id);
-            graph.link_new_point(block_point_id, level, points_scorer);
-        }
+            graph_layers_builder.link_new_point(block_point_id, points_scorer);
+        })?;
+
+        Ok(graph_layers_builder)
     }
 
+    /// Search with the graph. Also returns the number of scored points.
     pub fn search_with_graph(
         &self,
-        vector: &[VectorElementType],
+        query: DenseQuery,
         filter: Option<&Filter>,
         top: usize,
         params: Option<&SearchParams>,
-    ) -> Vec<ScoredPointOffset> {
+        is_stopped: &AtomicBool,
+    ) -> (Vec<ScoredPointOffset>, usize) {
         let req_ef = params
             .and_then(|params| params.hnsw_ef)
             .unwrap_or(self.config.ef);
@@ -134,27 +269,201 @@ impl HNSWIndex {
         let ef = max(req_ef, top);
 
         let vector_storage = self.vector_storage.borrow();
-        let raw_scorer = vector_storage.raw_scorer(vector.to_owned());
+        let raw_scorer = vector_storage.query_scorer(query);
         let payload_index = self.payload_index.borrow();
 
         let filter_context = filter.map(|f| payload_index.filter_context(f));
 
-        let points_scorer = FilteredScorer::new(raw_scorer.as_ref(), filter_context.as_deref());
+        let mut points_scorer = FilteredScorer::new(raw_scorer.as_ref(), filter_context.as_deref());
 
-        self.graph.search(top, ef, points_scorer)
+        // All `ef` candidates are kept, so that storage could re-score them
+        let candidates = match &self.incremental_graph {
+            Some(graph) => graph.search(ef, ef, &mut points_scorer, is_stopped),
+            None => self.graph.search(ef, ef, &mut points_scorer, is_stopped),
+        };
+        let visited_points = points_scorer.scored_points();
+        (
+            vector_storage.rescore(query, candidates, top),
+            visited_points,
+        )
+    }
+
+    /// Score all points, which satisfy the filter, without using the graph.
+    /// Also returns the number of scored points.
+    fn search_plain(
+        &self,
+        query: DenseQuery,
+        filter: Option<&Filter>,
+        top: usize,
+        is_stopped: &AtomicBool,
+    ) -> (Vec<ScoredPointOffset>, usize) {
+        let vector_storage = self.vector_storage.borrow();
+        let mut visited_points = 0;
+        let result = match filter {
+            Some(filter) => {
+                let payload_index = self.payload_index.borrow();
+                let mut filtered_ids = payload_index
+                    .query_points(filter)
+                    .take_while(|_| !is_stopped.load(Ordering::Relaxed))
+                    .inspect(|_| visited_points += 1);
+                vector_storage.score_query(query, &mut filtered_ids, top)
+            }
+            None => {
+                let mut all_ids = vector_storage
+                    .iter_ids()
+                    .take_while(|_| !is_stopped.load(Ordering::Relaxed))
+                    .inspect(|_| visited_points += 1);
+                vector_storage.score_query(query, &mut all_ids, top)
+            }
+        };
+        (result, visited_points)
+    }
+
+    /// Build graph for all points. Points of `reused_graph` keep their links and are not inserted again.
+    fn build_graph(
+        &mut self,
+        reused_graph: Option<ReusedGraph>,
+        stopped: &AtomicBool,
+    ) -> OperationResult<()> {
+        // Build main index graph
+        let vector_storage = self.vector_storage.borrow();
+        let mut rng = thread_rng();
+
+        let total_points = vector_storage.total_vector_count();
+
+        debug!("building hnsw for {}", total_points);
+        let mut graph_layers_builder = GraphLayersBuilder::new(
+            total_points,
+            self.config.m,
+            self.config.m0,
+            self.config.ef_construct,
+            max(1, total_points / self.config.indexing_threshold * 10),
+            HNSW_USE_HEURISTIC,
+        );
+
+        let mut is_reused = BitVec::from_elem(total_points, false);
+        match reused_graph {
+            Some(reused_graph)
+                if reused_graph.m == self.config.m && reused_graph.m0 == self.config.m0 =>
+            {
+                debug!("reusing links of {} points", reused_graph.num_points());
+                for (point_id, mut layers) in reused_graph.points {
+                    if vector_storage.is_deleted(point_id) {
+                        continue;
+                    }
+                    for links in layers.iter_mut() {
+                        links.retain(|&link| !vector_storage.is_deleted(link));
+                    }
+                    graph_layers_builder.add_reused_point(point_id, layers);
+                    is_reused.set(point_id as usize, true);
+                }
+            }
+            Some(_) => debug!("graph parameters are changed, links can't be reused"),
+            None => {}
+        }
+
+        let point_ids = vector_storage
+            .iter_ids()
+            .filter(|&point_id| !is_reused[point_id as usize])
+            .collect_vec();
+
+        for &vector_id in &point_ids {
+            let level = graph_layers_builder.get_random_layer(&mut rng);
+            graph_layers_builder.set_levels(vector_id, level);
+        }
+
+        link_points(&point_ids, stopped, |vector_id| {
+            let vector = vector_storage.get_vector(vector_id).unwrap();
+            let raw_scorer = vector_storage.raw_scorer(vector);
+            let points_scorer = FilteredScorer::new(raw_scorer.as_ref(), None);
+
+            graph_layers_builder.link_new_point(vector_id, points_scorer);
+        })?;
+
+        debug!("finish main graph");
+
+        let total_vectors_count = vector_storage.total_vector_count();
+        let mut block_filter_list = VisitedList::new(total_vectors_count);
+
+        let payload_index = self.payload_index.borrow();
+
+        for (field, _) in payload_index.indexed_fields() {
+            debug!("building additional index for field {}", &field);
+
+            // ToDo: Think about using connectivity threshold (based on 1/m0) instead of `indexing_threshold`
+            for payload_block in
+                payload_index.payload_blocks(&field, self.config.indexing_threshold)
+            {
+                let additional_graph = self.build_filtered_graph(
+                    &graph_layers_builder,
+                    payload_block.condition,
+                    &mut block_filter_list,
+                    stopped,
+                )?;
+                graph_layers_builder.merge_from_other(additional_graph);
+            }
+        }
+        debug!("finish additional payload field indexing");
+
+        if self.incremental_graph.is_some() {
+            // Keep the graph mutable, so the following insertions could be linked into it
+            self.incremental_graph = Some(graph_layers_builder);
+            return self.save();
+        }
+
+        self.graph = graph_layers_builder.into_graph_layers();
+        self.save()?;
+
+        if self.on_disk {
+            // Replace links, built in RAM, with the memory-mapped ones
+            self.graph = GraphLayers::load(&self.path, true)?;
+        }
+        Ok(())
     }
 }
 
 impl VectorIndex for HNSWIndex {
-    fn search(
+    fn search_with_explain(
         &self,
-        vector: &[VectorElementType],
+        query: DenseQuery,
         filter: Option<&Filter>,
         top: usize,
         params: Option<&SearchParams>,
-    ) -> Vec<ScoredPointOffset> {
+        is_stopped: &AtomicBool,
+    ) -> (Vec<ScoredPointOffset>, SegmentSearchExplain) {
+        let mut explain = SegmentSearchExplain {
+            strategy: SearchStrategy::Plain,
+            cardinality: None,
+            sampled_above_threshold: None,
+            visited_points: 0,
+        };
+        let search_plain = |explain: SegmentSearchExplain| {
+            let (result, visited_points) = self.search_plain(query, filter, top, is_stopped);
+            let explain = SegmentSearchExplain {
+                strategy: SearchStrategy::Plain,
+                visited_points,
+                ..explain
+            };
+            (result, explain)
+        };
+        let search_with_graph = |explain: SegmentSearchExplain| {
+            let (result, visited_points) =
+                self.search_with_graph(query, filter, top, params, is_stopped);
+            let explain = SegmentSearchExplain {
+                strategy: SearchStrategy::Graph,
+                visited_points,
+                ..explain
+            };
+            (result, explain)
+        };
+
+        let exact = params.map(|params| params.exact).unwrap_or(false);
+        if exact {
+            return search_plain(explain);
+        }
+
         match filter {
-            None => self.search_with_graph(vector, None, top, params),
+            None => search_with_graph(explain),
             Some(query_filter) => {
                 // depending on the amount of filtered-out points the optimal strategy could be
                 // - to retrieve possible points and score them after
@@ -164,110 +473,99 @@ impl VectorIndex for HNSWIndex {
                 let query_cardinality = payload_index.estimate_cardinality(query_filter);
 
                 // debug!("query_cardinality: {:#?}", query_cardinality);
+                explain.cardinality = Some(CardinalityExplain {
+                    min: query_cardinality.min,
+                    exp: query_cardinality.exp,
+                    max: query_cardinality.max,
+                    indexing_threshold: self.config.indexing_threshold,
+                });
 
                 let vector_storage = self.vector_storage.borrow();
 
                 if query_cardinality.max < self.config.indexing_threshold {
                     // if cardinality is small - use plain index
-                    let mut filtered_ids = payload_index.query_points(query_filter);
-                    return vector_storage.score_points(vector, &mut filtered_ids, top);
+                    return search_plain(explain);
                 }
 
                 if query_cardinality.min > self.config.indexing_threshold {
                     // if cardinality is high enough - use HNSW index
-                    return self.search_with_graph(vector, filter, top, params);
+                    return search_with_graph(explain);
                 }
 
                 let filter_context = payload_index.filter_context(query_filter);
 
                 // Fast cardinality estimation is not enough, do sample estimation of cardinality
 
-                return if sample_check_cardinality(
+                let above_threshold = sample_check_cardinality(
                     vector_storage.sample_ids(),
                     |idx| filter_context.check(idx),
                     self.config.indexing_threshold,
                     vector_storage.vector_count(),
-                ) {
+                );
+                explain.sampled_above_threshold = Some(above_threshold);
+                return if above_threshold {
                     // if cardinality is high enough - use HNSW index
-                    self.search_with_graph(vector, filter, top, params)
+                    search_with_graph(explain)
                 } else {
                     // if cardinality is small - use plain index
-                    let mut filtered_ids = payload_index.query_points(query_filter);
-                    vector_storage.score_points(vector, &mut filtered_ids, top)
+                    search_plain(explain)
                 };
             }
         }
     }
 
     fn build_index(&mut self, stopped: &AtomicBool) -> OperationResult<()> {
-        // Build main index graph
-        let vector_storage = self.vector_storage.borrow();
-        let mut rng = thread_rng();
-
-        let total_points = vector_storage.total_vector_count();
-
-        debug!("building hnsw for {}", total_points);
-        self.graph = GraphLayers::new(
-            total_points,
-            self.config.m,
-            self.config.m0,
-            self.config.ef_construct,
-            max(1, total_points / self.config.indexing_threshold * 10),
-            HNSW_USE_HEURISTIC,
-        );
+        self.build_graph(None, stopped)
+    }
 
-        for vector_id in vector_storage.iter_ids() {
-            if stopped.load(Ordering::Relaxed) {
-                return Err(OperationError::Cancelled {
-                    description: "Cancelled by external thread".to_string(),
-                });
-            }
-            let vector = vector_storage.get_vector(vector_id).unwrap();
-            let raw_scorer = vector_storage.raw_scorer(vector);
-            let points_scorer = FilteredScorer::new(raw_scorer.as_ref(), None);
+    fn build_index_with_graph(
+        &mut self,
+        reused_graph: ReusedGraph,
+        stopped: &AtomicBool,
+    ) -> OperationResult<()> {
+        self.build_graph(Some(reused_graph), stopped)
+    }
 
-            let level = self.graph.get_random_layer(&mut rng);
-            self.graph.link_new_point(vector_id, level, points_scorer);
+    fn graph(&self) -> Option<&GraphLayers> {
+        match self.incremental_graph {
+            Some(_) => None,
+            None => Some(&self.graph),
         }
+    }
 
-        debug!("finish main graph");
+    fn update_vector(&mut self, point_id: PointOffsetType) -> OperationResult<()> {
+        let graph = match &mut self.incremental_graph {
+            Some(graph) => graph,
+            None => return Ok(()),
+        };
 
-        let total_vectors_count = vector_storage.total_vector_count();
-        let mut block_condition_checker = BuildConditionChecker::new(total_vectors_count);
+        let vector_storage = self.vector_storage.borrow();
+        let vector = match vector_storage.get_vector(point_id) {
+            Some(vector) => vector,
+            None => return Ok(()),
+        };
 
-        let payload_index = self.payload_index.borrow();
+        // Updated point keeps its level, existing links are used as a starting point for re-linking
+        if point_id as usize >= graph.num_points() {
+            let level = graph.get_random_layer(&mut thread_rng());
+            graph.set_levels(point_id, level);
+        }
 
-        for (field, _) in payload_index.indexed_fields() {
-            debug!("building additional index for field {}", &field);
+        let raw_scorer = vector_storage.raw_scorer(vector);
+        let points_scorer = FilteredScorer::new(raw_scorer.as_ref(), None);
+        graph.link_new_point(point_id, points_scorer);
+        *self.graph_changed.get_mut() = true;
+        Ok(())
+    }
 
-            // ToDo: Think about using connectivity threshold (based on 1/m0) instead of `indexing_threshold`
-            for payload_block in
-                payload_index.payload_blocks(&field, self.config.indexing_threshold)
-            {
-                if stopped.load(Ordering::Relaxed) {
-                    return Err(OperationError::Cancelled {
-                        description: "Cancelled by external thread".to_string(),
-                    });
-                }
-                // ToDo: re-use graph layer for same payload
-                let mut additional_graph = GraphLayers::new_with_params(
-                    self.vector_storage.borrow().total_vector_count(),
-                    self.config.m,
-                    self.config.m0,
-                    self.config.ef_construct,
-                    1,
-                    HNSW_USE_HEURISTIC,
-                    false,
-                );
-                self.build_filtered_graph(
-                    &mut additional_graph,
-                    payload_block.condition,
-                    &mut block_condition_checker,
-                );
-                self.graph.merge_from_other(additional_graph);
-            }
+    /// Save the incremental graph, if it was changed since the last flush
+    fn flush(&self) -> OperationResult<()> {
+        if self.incremental_graph.is_none() || !self.graph_changed.swap(false, Ordering::Relaxed) {
+            return Ok(());
         }
-        debug!("finish additional payload field indexing");
-        self.save()
+        self.save().map_err(|err| {
+            self.graph_changed.store(true, Ordering::Relaxed);
+            err
+        })
     }
 }
@@ -3,6 +3,8 @@ pub mod build_condition_checker;
 mod config;
 mod entry_points;
 pub mod graph_layers;
+pub mod graph_layers_builder;
+pub mod graph_links;
 pub mod hnsw;
 pub mod point_scorer;
 mod search_context;
@@ -1,3 +1,5 @@
+use std::cell::Cell;
+
 use crate::payload_storage::FilterContext;
 use crate::types::{PointOffsetType, ScoreType};
 use crate::vector_storage::{RawScorer, ScoredPointOffset};
@@ -6,6 +8,8 @@ pub struct FilteredScorer<'a> {
     pub raw_scorer: &'a dyn RawScorer,
     pub filter_context: Option<&'a dyn FilterContext>,
     points_buffer: Vec<ScoredPointOffset>,
+    /// Number of points, scored so far
+    scored_points: Cell<usize>,
 }
 
 impl<'a> FilteredScorer<'a> {
@@ -17,9 +21,15 @@ impl<'a> FilteredScorer<'a> {
             raw_scorer,
             filter_context,
             points_buffer: Vec::new(),
+            scored_points: Cell::new(0),
         }
     }
 
+    /// Number of points, scored by this scorer so far
+    pub fn scored_points(&self) -> usize {
+        self.scored_points.get()
+    }
+
     pub fn check_point(&self, point_id: PointOffsetType) -> bool {
         match self.filter_context {
             None => self.raw_scorer.check_point(point_id),
@@ -59,6 +69,8 @@ impl<'a> FilteredScorer<'a> {
             }
         };
 
+        self.scored_points
+            .set(self.scored_points.get() + filtered_point_ids.len());
         self.points_buffer
             .resize(limit, ScoredPointOffset::default());
         let count = self
@@ -68,6 +80,7 @@ impl<'a> FilteredScorer<'a> {
     }
 
     pub fn score_point(&self, point_id: PointOffsetType) -> ScoreType {
+        self.scored_points.set(self.scored_points.get() + 1);
         self.raw_scorer.score_point(point_id)
     }
 
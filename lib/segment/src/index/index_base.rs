@@ -1,9 +1,10 @@
 use crate::entry::entry_point::OperationResult;
 use crate::index::field_index::{CardinalityEstimation, PayloadBlockCondition};
+use crate::index::hnsw_index::graph_layers::{GraphLayers, ReusedGraph};
 use crate::payload_storage::FilterContext;
 use crate::types::{
-    Filter, PayloadKeyType, PayloadKeyTypeRef, PayloadSchemaType, PointOffsetType, SearchParams,
-    VectorElementType,
+    DenseQuery, FacetValue, Filter, PayloadKeyType, PayloadKeyTypeRef, PayloadSchemaType,
+    PointOffsetType, SearchParams, SegmentSearchExplain,
 };
 use crate::vector_storage::ScoredPointOffset;
 use std::collections::HashMap;
@@ -12,16 +13,56 @@ use std::sync::atomic::AtomicBool;
 /// Trait for vector searching
 pub trait VectorIndex {
     /// Return list of Ids with fitting
+    /// Search is interrupted as soon as `is_stopped` flag is raised, partial result is returned in this case.
     fn search(
         &self,
-        vector: &[VectorElementType],
+        query: DenseQuery,
         filter: Option<&Filter>,
         top: usize,
         params: Option<&SearchParams>,
-    ) -> Vec<ScoredPointOffset>;
+        is_stopped: &AtomicBool,
+    ) -> Vec<ScoredPointOffset> {
+        self.search_with_explain(query, filter, top, params, is_stopped)
+            .0
+    }
+
+    /// Same as `search`, but also returns the decisions of the search planner
+    fn search_with_explain(
+        &self,
+        query: DenseQuery,
+        filter: Option<&Filter>,
+        top: usize,
+        params: Option<&SearchParams>,
+        is_stopped: &AtomicBool,
+    ) -> (Vec<ScoredPointOffset>, SegmentSearchExplain);
 
     /// Force internal index rebuild.
     fn build_index(&mut self, stopped: &AtomicBool) -> OperationResult<()>;
+
+    /// Force internal index rebuild, reusing links of the previously built graph if possible.
+    fn build_index_with_graph(
+        &mut self,
+        _reused_graph: ReusedGraph,
+        stopped: &AtomicBool,
+    ) -> OperationResult<()> {
+        self.build_index(stopped)
+    }
+
+    /// Graph of the index, if the index is graph-based
+    fn graph(&self) -> Option<&GraphLayers> {
+        None
+    }
+
+    /// Update index with the vector, inserted or replaced in the vector storage.
+    /// Only indexes of appendable segments, which support incremental updates, need to implement it.
+    fn update_vector(&mut self, _point_id: PointOffsetType) -> OperationResult<()> {
+        Ok(())
+    }
+
+    /// Persist changes, made by `update_vector`
+    fn flush(&self) -> OperationResult<()> {
+        Ok(())
+    }
 }
 
 pub trait PayloadIndex {
@@ -56,6 +97,16 @@ pub trait PayloadIndex {
         field: PayloadKeyTypeRef,
         threshold: usize,
     ) -> Box<dyn Iterator<Item = PayloadBlockCondition> + '_>;
+
+    /// Count given points with each value of the keyword or integer field with the map index.
+    /// Returns `None` if there is no map index for the field.
+    fn count_map_values(
+        &self,
+        _field: PayloadKeyTypeRef,
+        _points: &mut dyn Iterator<Item = PointOffsetType>,
+    ) -> Option<HashMap<FacetValue, usize>> {
+        None
+    }
 }
 
 pub type VectorIndexSS = dyn VectorIndex + Sync + Send;
@@ -1,8 +1,8 @@
 use crate::index::{PayloadIndex, PayloadIndexSS, VectorIndex};
 use crate::payload_storage::{ConditionCheckerSS, FilterContext};
 use crate::types::{
-    Filter, PayloadKeyType, PayloadKeyTypeRef, PayloadSchemaType, PointOffsetType, SearchParams,
-    VectorElementType,
+    DenseQuery, Filter, PayloadKeyType, PayloadKeyTypeRef, PayloadSchemaType, PointOffsetType,
+    SearchParams, SearchStrategy, SegmentSearchExplain,
 };
 use crate::vector_storage::{ScoredPointOffset, VectorStorageSS};
 use std::collections::HashMap;
@@ -15,7 +15,7 @@ use crate::index::payload_config::PayloadConfig;
 use atomic_refcell::AtomicRefCell;
 use std::fs::create_dir_all;
 use std::path::{Path, PathBuf};
-use std::sync::atomic::AtomicBool;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 
 /// Implementation of `PayloadIndex` which does not really indexes anything.
@@ -154,23 +154,42 @@ impl PlainIndex {
 }
 
 impl VectorIndex for PlainIndex {
-    fn search(
+    fn search_with_explain(
         &self,
-        vector: &[VectorElementType],
+        query: DenseQuery,
         filter: Option<&Filter>,
         top: usize,
         _params: Option<&SearchParams>,
-    ) -> Vec<ScoredPointOffset> {
-        match filter {
+        is_stopped: &AtomicBool,
+    ) -> (Vec<ScoredPointOffset>, SegmentSearchExplain) {
+        let mut visited_points = 0;
+        let result = match filter {
             Some(filter) => {
                 let borrowed_payload_index = self.payload_index.borrow();
-                let mut filtered_ids = borrowed_payload_index.query_points(filter);
+                let mut filtered_ids = borrowed_payload_index
+                    .query_points(filter)
+                    .take_while(|_| !is_stopped.load(Ordering::Relaxed))
+                    .inspect(|_| visited_points += 1);
                 self.vector_storage
                     .borrow()
-                    .score_points(vector, &mut filtered_ids, top)
+                    .score_query(query, &mut filtered_ids, top)
             }
-            None => self.vector_storage.borrow().score_all(vector, top),
-        }
+            None => {
+                let vector_storage = self.vector_storage.borrow();
+                let mut all_ids = vector_storage
+                    .iter_ids()
+                    .take_while(|_| !is_stopped.load(Ordering::Relaxed))
+                    .inspect(|_| visited_points += 1);
+                vector_storage.score_query(query, &mut all_ids, top)
+            }
+        };
+        let explain = SegmentSearchExplain {
+            strategy: SearchStrategy::Plain,
+            cardinality: None,
+            sampled_above_threshold: None,
+            visited_points,
+        };
+        (result, explain)
     }
 
     fn build_index(&mut self, _stopped: &AtomicBool) -> OperationResult<()> {
@@ -0,0 +1,221 @@
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use rocksdb::{IteratorMode, DB};
+
+use crate::common::rocksdb_operations::{db_write_options, open_db_with_cf};
+use crate::entry::entry_point::{OperationError, OperationResult};
+use crate::spaces::tools::peek_top_largest_scores_iterable;
+use crate::types::{PointOffsetType, ScoreType, SparseVector};
+use crate::vector_storage::ScoredPointOffset;
+
+const DB_NAME: &str = "sparse_vectors";
+
+/// Checks that sparse vector is well-formed: each index has exactly one value
+pub fn check_sparse_vector(vector: &SparseVector) -> OperationResult<()> {
+    if vector.indices.len() != vector.values.len() {
+        return Err(OperationError::WrongSparseVector {
+            description: format!(
+                "number of indices ({}) does not match number of values ({})",
+                vector.indices.len(),
+                vector.values.len()
+            ),
+        });
+    }
+    let mut unique_indices = HashSet::with_capacity(vector.indices.len());
+    if let Some(index) = vector
+        .indices
+        .iter()
+        .find(|&&index| !unique_indices.insert(index))
+    {
+        return Err(OperationError::WrongSparseVector {
+            description: format!("index {} is used more than once", index),
+        });
+    }
+    Ok(())
+}
+
+/// Inverted index of sparse vectors.
+///
+/// For each dimension keeps a posting list of points with non-zero value in it, sorted by point,
+/// so the dot product with a query is only computed for points, sharing at least one dimension with it.
+/// Persists vectors in `store`, the index itself is rebuilt on load.
+pub struct SparseVectorIndex {
+    vectors: HashMap<PointOffsetType, SparseVector>,
+    postings: HashMap<u32, Vec<(PointOffsetType, ScoreType)>>,
+    store: DB,
+}
+
+impl SparseVectorIndex {
+    pub fn open(path: &Path) -> OperationResult<Self> {
+        let store = open_db_with_cf(path, &[DB_NAME])?;
+        let mut index = SparseVectorIndex {
+            vectors: Default::default(),
+            postings: Default::default(),
+            store,
+        };
+
+        let cf_handle = index.store.cf_handle(DB_NAME).unwrap();
+        let stored: Vec<(PointOffsetType, SparseVector)> = index
+            .store
+            .iterator_cf(cf_handle, IteratorMode::Start)
+            .map(|(key, val)| {
+                (
+                    serde_cbor::from_slice(&key).unwrap(),
+                    serde_cbor::from_slice(&val).unwrap(),
+                )
+            })
+            .collect();
+        for (point_id, vector) in stored {
+            index.add_postings(point_id, &vector);
+            index.vectors.insert(point_id, vector);
+        }
+
+        Ok(index)
+    }
+
+    fn add_postings(&mut self, point_id: PointOffsetType, vector: &SparseVector) {
+        for (&dim, &value) in vector.indices.iter().zip(vector.values.iter()) {
+            let posting = self.postings.entry(dim).or_default();
+            match posting.binary_search_by_key(&point_id, |&(posting_point, _)| posting_point) {
+                Ok(position) => posting[position].1 = value,
+                Err(position) => posting.insert(position, (point_id, value)),
+            }
+        }
+    }
+
+    fn remove_postings(&mut self, point_id: PointOffsetType, vector: &SparseVector) {
+        for dim in &vector.indices {
+            if let Some(posting) = self.postings.get_mut(dim) {
+                if let Ok(position) =
+                    posting.binary_search_by_key(&point_id, |&(posting_point, _)| posting_point)
+                {
+                    posting.remove(position);
+                }
+                if posting.is_empty() {
+                    self.postings.remove(dim);
+                }
+            }
+        }
+    }
+
+    /// Set sparse vector of the point, replacing the previous one
+    pub fn upsert(
+        &mut self,
+        point_id: PointOffsetType,
+        vector: SparseVector,
+    ) -> OperationResult<()> {
+        check_sparse_vector(&vector)?;
+        if let Some(old_vector) = self.vectors.remove(&point_id) {
+            self.remove_postings(point_id, &old_vector);
+        }
+
+        let cf_handle = self.store.cf_handle(DB_NAME).unwrap();
+        self.store.put_cf_opt(
+            cf_handle,
+            serde_cbor::to_vec(&point_id).unwrap(),
+            serde_cbor::to_vec(&vector).unwrap(),
+            &db_write_options(),
+        )?;
+
+        self.add_postings(point_id, &vector);
+        self.vectors.insert(point_id, vector);
+        Ok(())
+    }
+
+    /// Remove sparse vector of the point, returns removed vector if any
+    pub fn remove(&mut self, point_id: PointOffsetType) -> OperationResult<Option<SparseVector>> {
+        let old_vector = match self.vectors.remove(&point_id) {
+            None => return Ok(None),
+            Some(vector) => vector,
+        };
+        self.remove_postings(point_id, &old_vector);
+
+        let cf_handle = self.store.cf_handle(DB_NAME).unwrap();
+        self.store
+            .delete_cf(cf_handle, serde_cbor::to_vec(&point_id).unwrap())?;
+        Ok(Some(old_vector))
+    }
+
+    pub fn get(&self, point_id: PointOffsetType) -> Option<&SparseVector> {
+        self.vectors.get(&point_id)
+    }
+
+    /// Find `top` points with the largest dot product with `query` among points, accepted by `check_point`
+    pub fn search(
+        &self,
+        query: &SparseVector,
+        check_point: impl Fn(PointOffsetType) -> bool,
+        top: usize,
+        is_stopped: &AtomicBool,
+    ) -> Vec<ScoredPointOffset> {
+        let mut scores: HashMap<PointOffsetType, ScoreType> = HashMap::new();
+        for (dim, &query_value) in query.indices.iter().zip(query.values.iter()) {
+            if is_stopped.load(Ordering::Relaxed) {
+                break;
+            }
+            if let Some(posting) = self.postings.get(dim) {
+                for &(point_id, value) in posting {
+                    *scores.entry(point_id).or_insert(0.0) += query_value * value;
+                }
+            }
+        }
+
+        let candidates = scores
+            .into_iter()
+            .take_while(|_| !is_stopped.load(Ordering::Relaxed))
+            .filter(|&(point_id, _)| check_point(point_id))
+            .map(|(idx, score)| ScoredPointOffset { idx, score });
+        peek_top_largest_scores_iterable(candidates, top)
+    }
+
+    pub fn flush(&self) -> OperationResult<()> {
+        let cf_handle = self.store.cf_handle(DB_NAME).unwrap();
+        Ok(self.store.flush_cf(cf_handle)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempdir::TempDir;
+
+    fn sparse(indices: Vec<u32>, values: Vec<ScoreType>) -> SparseVector {
+        SparseVector { indices, values }
+    }
+
+    #[test]
+    fn test_sparse_vector_index() {
+        let dir = TempDir::new("sparse_index").unwrap();
+        {
+            let mut index = SparseVectorIndex::open(dir.path()).unwrap();
+            index.upsert(0, sparse(vec![1, 5], vec![1.0, 2.0])).unwrap();
+            index.upsert(1, sparse(vec![5, 7], vec![1.0, 3.0])).unwrap();
+            index.upsert(2, sparse(vec![2], vec![10.0])).unwrap();
+            index.upsert(3, sparse(vec![7], vec![1.0])).unwrap();
+
+            assert!(index.upsert(4, sparse(vec![1, 1], vec![1.0, 2.0])).is_err());
+            assert!(index.upsert(4, sparse(vec![1, 2], vec![1.0])).is_err());
+
+            index.upsert(3, sparse(vec![1], vec![0.5])).unwrap();
+            assert!(index.remove(2).unwrap().is_some());
+            index.flush().unwrap();
+        }
+
+        let index = SparseVectorIndex::open(dir.path()).unwrap();
+        assert!(index.get(2).is_none());
+
+        let query = sparse(vec![1, 5, 7], vec![1.0, 1.0, 1.0]);
+        let stopped = AtomicBool::new(false);
+        let result = index.search(&query, |_| true, 10, &stopped);
+        let ids: Vec<_> = result.iter().map(|scored| scored.idx).collect();
+        assert_eq!(ids, vec![1, 0, 3]);
+        assert_eq!(result[0].score, 4.0);
+        assert_eq!(result[1].score, 3.0);
+
+        let result = index.search(&query, |point_id| point_id != 1, 1, &stopped);
+        let ids: Vec<_> = result.iter().map(|scored| scored.idx).collect();
+        assert_eq!(ids, vec![0]);
+    }
+}
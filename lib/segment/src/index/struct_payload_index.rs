@@ -26,8 +26,8 @@ use crate::index::PayloadIndex;
 use crate::payload_storage::payload_storage_enum::PayloadStorageEnum;
 use crate::payload_storage::{FilterContext, PayloadStorage};
 use crate::types::{
-    Condition, FieldCondition, Filter, IsEmptyCondition, PayloadKeyType, PayloadKeyTypeRef,
-    PayloadSchemaType, PointOffsetType,
+    Condition, FacetValue, FieldCondition, Filter, IsEmptyCondition, PayloadKeyType,
+    PayloadKeyTypeRef, PayloadSchemaType, PointOffsetType,
 };
 
 pub const PAYLOAD_FIELD_INDEX_PATH: &str = "fields";
@@ -414,4 +414,35 @@ impl PayloadIndex for StructPayloadIndex {
             }
         }
     }
+
+    fn count_map_values(
+        &self,
+        field: PayloadKeyTypeRef,
+        points: &mut dyn Iterator<Item = PointOffsetType>,
+    ) -> Option<HashMap<FacetValue, usize>> {
+        for field_index in self.field_indexes.get(field)? {
+            match field_index {
+                FieldIndex::KeywordIndex(index) => {
+                    let counts = index.count_values(points);
+                    return Some(
+                        counts
+                            .into_iter()
+                            .map(|(value, count)| (FacetValue::Keyword(value.clone()), count))
+                            .collect(),
+                    );
+                }
+                FieldIndex::IntMapIndex(index) => {
+                    let counts = index.count_values(points);
+                    return Some(
+                        counts
+                            .into_iter()
+                            .map(|(value, count)| (FacetValue::Integer(*value), count))
+                            .collect(),
+                    );
+                }
+                _ => {}
+            }
+        }
+        None
+    }
 }
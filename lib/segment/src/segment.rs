@@ -3,22 +3,31 @@ use crate::entry::entry_point::{
     get_service_error, OperationError, OperationResult, SegmentEntry, SegmentFailedState,
 };
 use crate::id_tracker::IdTrackerSS;
+use crate::index::sparse_vector_index::{check_sparse_vector, SparseVectorIndex};
 use crate::index::{PayloadIndexSS, VectorIndexSS};
 use crate::payload_storage::payload_storage_enum::PayloadStorageEnum;
 use crate::payload_storage::{ConditionCheckerSS, PayloadStorage};
+use crate::spaces::tools::peek_top_largest_scores_iterable;
 use crate::types::{
-    infer_value_type, Filter, Payload, PayloadIndexInfo, PayloadKeyType, PayloadKeyTypeRef,
-    PayloadSchemaType, PointIdType, PointOffsetType, ScoredPoint, SearchParams, SegmentConfig,
-    SegmentInfo, SegmentState, SegmentType, SeqNumberType, VectorElementType, WithPayload,
+    infer_value_type, Condition, DenseQuery, FacetValue, Filter, FilterExplain, Indexes, Payload,
+    PayloadIndexInfo, PayloadKeyType, PayloadKeyTypeRef, PayloadSchemaType, PointExplain,
+    PointIdType, PointOffsetType, QueryVector, RecallEvaluation, ScoredPoint, SearchParams,
+    SearchStrategy, SegmentConfig, SegmentExplain, SegmentInfo, SegmentSearchExplain, SegmentState,
+    SegmentType, SeqNumberType, SparseVector, VectorElementType, WithPayload,
 };
-use crate::vector_storage::VectorStorageSS;
+use crate::vector_storage::{ScoredPointOffset, VectorStorageSS};
 use atomic_refcell::AtomicRefCell;
 use atomicwrites::{AllowOverwrite, AtomicFile};
-use std::collections::HashMap;
+use itertools::Itertools;
+use std::cell::Cell;
+use std::cmp::{max, min};
+use std::collections::{HashMap, HashSet};
 use std::fs::{remove_dir_all, rename};
 use std::io::Write;
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
 pub const SEGMENT_STATE_FILE: &str = "segment.json";
 
@@ -42,6 +51,8 @@ pub struct Segment {
     pub payload_index: Arc<AtomicRefCell<PayloadIndexSS>>,
     pub condition_checker: Arc<ConditionCheckerSS>,
     pub vector_index: Arc<AtomicRefCell<VectorIndexSS>>,
+    /// Optional sparse vectors of the points, indexed for dot product search
+    pub sparse_index: Arc<AtomicRefCell<SparseVectorIndex>>,
     /// Shows if it is possible to insert more points into this segment
     pub appendable_flag: bool,
     /// Shows what kind of indexes and storages are used in this segment
@@ -69,6 +80,10 @@ impl Segment {
             if let Some(payload) = payload {
                 payload_storage.assign(new_internal_index, &payload)?;
             }
+            let mut sparse_index = self.sparse_index.borrow_mut();
+            if let Some(sparse_vector) = sparse_index.remove(old_internal_id)? {
+                sparse_index.upsert(new_internal_index, sparse_vector)?;
+            }
         }
 
         Ok(new_internal_index)
@@ -209,6 +224,62 @@ impl Segment {
             .unwrap())
     }
 
+    /// Search among sparse vectors of the points, which satisfy the filter
+    fn search_sparse(
+        &self,
+        query: &SparseVector,
+        filter: Option<&Filter>,
+        top: usize,
+        is_stopped: &AtomicBool,
+    ) -> (Vec<ScoredPointOffset>, SegmentSearchExplain) {
+        let vector_storage = self.vector_storage.borrow();
+        let payload_index = self.payload_index.borrow();
+        let filter_context = filter.map(|filter| payload_index.filter_context(filter));
+        let visited_points = Cell::new(0);
+        let result = self.sparse_index.borrow().search(
+            query,
+            |point_id| {
+                visited_points.set(visited_points.get() + 1);
+                !vector_storage.is_deleted(point_id)
+                    && filter_context
+                        .as_ref()
+                        .map_or(true, |context| context.check(point_id))
+            },
+            top,
+            is_stopped,
+        );
+        let explain = SegmentSearchExplain {
+            strategy: SearchStrategy::Sparse,
+            cardinality: None,
+            sampled_above_threshold: None,
+            visited_points: visited_points.get(),
+        };
+        (result, explain)
+    }
+
+    /// Check each top-level condition of the filter separately for the point
+    fn explain_filter(&self, filter: &Filter, point_offset: PointOffsetType) -> FilterExplain {
+        let payload_index = self.payload_index.borrow();
+        let check_conditions = |conditions: &Option<Vec<Condition>>| {
+            conditions.as_ref().map(|conditions| {
+                conditions
+                    .iter()
+                    .map(|condition| {
+                        let condition_filter = Filter::new_must(condition.clone());
+                        payload_index
+                            .filter_context(&condition_filter)
+                            .check(point_offset)
+                    })
+                    .collect()
+            })
+        };
+        FilterExplain {
+            should: check_conditions(&filter.should),
+            must: check_conditions(&filter.must),
+            must_not: check_conditions(&filter.must_not),
+        }
+    }
+
     /// Retrieve payload by internal ID
     #[inline]
     fn payload_by_offset(&self, point_offset: PointOffsetType) -> OperationResult<Payload> {
@@ -219,6 +290,97 @@ impl Segment {
         self.save_state(&self.get_state())
     }
 
+    /// Measure quality of the vector index.
+    ///
+    /// Randomly sampled stored vectors are used as queries. For each query, `top` results of the
+    /// index search are compared with the results of the exact search.
+    ///
+    /// # Arguments
+    ///
+    /// * `sample` - max number of points to use as queries
+    /// * `top` - number of nearest neighbours to compare
+    /// * `hnsw_ef` - size of the beam of the index search. Index default is used if not specified
+    ///
+    pub fn evaluate_recall(
+        &self,
+        sample: usize,
+        top: usize,
+        hnsw_ef: Option<usize>,
+        is_stopped: &AtomicBool,
+    ) -> OperationResult<RecallEvaluation> {
+        let ef = match self.segment_config.index {
+            Indexes::Plain { .. } => 0,
+            Indexes::Hnsw(hnsw_config) => max(hnsw_ef.unwrap_or(hnsw_config.ef_construct), top),
+        };
+        let index_params = SearchParams {
+            hnsw_ef,
+            exact: false,
+        };
+        let exact_params = SearchParams {
+            hnsw_ef: None,
+            exact: true,
+        };
+
+        let vector_storage = self.vector_storage.borrow();
+        let vector_index = self.vector_index.borrow();
+
+        // Sampling can't produce more unique ids than there are stored vectors
+        let sample = min(sample, vector_storage.vector_count());
+        let query_ids = vector_storage
+            .sample_ids()
+            .unique()
+            .take(sample)
+            .collect_vec();
+
+        let mut queries = 0;
+        let mut recall_sum = 0.0;
+        let mut search_time = Duration::default();
+        for query_id in query_ids {
+            if is_stopped.load(Ordering::Relaxed) {
+                return Err(OperationError::Cancelled {
+                    description: "Recall evaluation is cancelled".to_string(),
+                });
+            }
+            // Stored vectors are already preprocessed
+            let vector = match vector_storage.get_vector(query_id) {
+                Some(vector) => vector,
+                None => continue,
+            };
+            let query = DenseQuery::Nearest(&vector);
+
+            let start = Instant::now();
+            let index_result =
+                vector_index.search(query, None, top, Some(&index_params), is_stopped);
+            search_time += start.elapsed();
+
+            let exact_result =
+                vector_index.search(query, None, top, Some(&exact_params), is_stopped);
+            if exact_result.is_empty() {
+                continue;
+            }
+
+            let exact_ids: HashSet<_> = exact_result.iter().map(|point| point.idx).collect();
+            let found = index_result
+                .iter()
+                .filter(|point| exact_ids.contains(&point.idx))
+                .count();
+
+            queries += 1;
+            recall_sum += found as f64 / exact_ids.len() as f64;
+        }
+
+        Ok(RecallEvaluation {
+            queries,
+            recall: if queries > 0 {
+                recall_sum / queries as f64
+            } else {
+                0.0
+            },
+            search_time,
+            ef,
+        })
+    }
+
     fn infer_from_payload_data(&self, key: PayloadKeyTypeRef) -> Option<PayloadSchemaType> {
         let payload_store = self.payload_storage.borrow();
         let id = payload_store.iter_ids().next();
@@ -244,27 +406,48 @@ impl SegmentEntry for Segment {
         self.id_tracker.borrow().version(point_id)
     }
 
-    fn search(
+    fn search_query(
         &self,
-        vector: &[VectorElementType],
+        query: &QueryVector,
         with_payload: &WithPayload,
         with_vector: bool,
+        with_explain: bool,
         filter: Option<&Filter>,
         top: usize,
         params: Option<&SearchParams>,
-    ) -> OperationResult<Vec<ScoredPoint>> {
+        is_stopped: &AtomicBool,
+    ) -> OperationResult<(Vec<ScoredPoint>, Vec<SegmentExplain>)> {
         let expected_vector_dim = self.vector_storage.borrow().vector_dim();
-        if expected_vector_dim != vector.len() {
-            return Err(OperationError::WrongVector {
-                expected_dim: expected_vector_dim,
-                received_dim: vector.len(),
-            });
+        for vector in query.vectors() {
+            if expected_vector_dim != vector.len() {
+                return Err(OperationError::WrongVector {
+                    expected_dim: expected_vector_dim,
+                    received_dim: vector.len(),
+                });
+            }
         }
 
-        let internal_result = self
-            .vector_index
-            .borrow()
-            .search(vector, filter, top, params);
+        let search_dense = |query: DenseQuery| {
+            self.vector_index
+                .borrow()
+                .search_with_explain(query, filter, top, params, is_stopped)
+        };
+        let (internal_result, search_explain) = match query {
+            QueryVector::Nearest(vector) => search_dense(DenseQuery::Nearest(vector)),
+            QueryVector::Recommend(recommend_query) => {
+                search_dense(DenseQuery::Recommend(recommend_query))
+            }
+            QueryVector::Sparse(sparse_query) => {
+                check_sparse_vector(sparse_query)?;
+                self.search_sparse(sparse_query, filter, top, is_stopped)
+            }
+        };
+
+        if is_stopped.load(Ordering::Relaxed) {
+            return Err(OperationError::Cancelled {
+                description: "Search was interrupted".to_string(),
+            });
+        }
 
         let id_tracker = self.id_tracker.borrow();
 
@@ -311,7 +494,29 @@ impl SegmentEntry for Segment {
                 })
             })
             .collect();
-        res
+        let res = res?;
+
+        let explain = if with_explain {
+            let points = match filter {
+                Some(filter) => res
+                    .iter()
+                    .zip(&internal_result)
+                    .map(|(point, scored_point_offset)| PointExplain {
+                        id: point.id,
+                        filter: self.explain_filter(filter, scored_point_offset.idx),
+                    })
+                    .collect(),
+                None => vec![],
+            };
+            vec![SegmentExplain {
+                search: search_explain,
+                points,
+            }]
+        } else {
+            vec![]
+        };
+
+        Ok((res, explain))
     }
 
     fn upsert_point(
@@ -337,14 +542,16 @@ impl SegmentEntry for Segment {
 
             let stored_internal_point = segment.id_tracker.borrow().internal_id(point_id);
 
-            let was_replaced = if let Some(existing_internal_id) = stored_internal_point {
+            let (was_replaced, new_index) = if let Some(existing_internal_id) =
+                stored_internal_point
+            {
                 let new_index = segment.update_vector(existing_internal_id, processed_vector)?;
                 if new_index != existing_internal_id {
                     let mut id_tracker = segment.id_tracker.borrow_mut();
                     id_tracker.drop(point_id)?;
                     id_tracker.set_link(point_id, new_index)?;
                 }
-                true
+                (true, new_index)
             } else {
                 let new_index = segment
                     .vector_storage
@@ -354,9 +561,11 @@ impl SegmentEntry for Segment {
                     .id_tracker
                     .borrow_mut()
                     .set_link(point_id, new_index)?;
-                false
+                (false, new_index)
             };
 
+            segment.vector_index.borrow_mut().update_vector(new_index)?;
+
             Ok(was_replaced)
         })
     }
@@ -372,6 +581,7 @@ impl SegmentEntry for Segment {
             match internal_id {
                 Some(internal_id) => {
                     segment.vector_storage.borrow_mut().delete(internal_id)?;
+                    segment.sparse_index.borrow_mut().remove(internal_id)?;
                     id_tracker.drop(point_id)?;
                     Ok(true)
                 }
@@ -440,6 +650,22 @@ impl SegmentEntry for Segment {
         })
     }
 
+    fn set_sparse_vector(
+        &mut self,
+        op_num: SeqNumberType,
+        point_id: PointIdType,
+        sparse_vector: &SparseVector,
+    ) -> OperationResult<bool> {
+        self.handle_version_and_failure(op_num, Some(point_id), |segment| {
+            let internal_id = segment.lookup_internal_id(point_id)?;
+            segment
+                .sparse_index
+                .borrow_mut()
+                .upsert(internal_id, sparse_vector.clone())?;
+            Ok(true)
+        })
+    }
+
     fn vector(&self, point_id: PointIdType) -> OperationResult<Vec<VectorElementType>> {
         let internal_id = self.lookup_internal_id(point_id)?;
         self.vector_by_offset(internal_id)
@@ -450,6 +676,11 @@ impl SegmentEntry for Segment {
         self.payload_by_offset(internal_id)
     }
 
+    fn sparse_vector(&self, point_id: PointIdType) -> OperationResult<Option<SparseVector>> {
+        let internal_id = self.lookup_internal_id(point_id)?;
+        Ok(self.sparse_index.borrow().get(internal_id).cloned())
+    }
+
     fn iter_points(&self) -> Box<dyn Iterator<Item = PointIdType> + '_> {
         // Sorry for that, but I didn't find any way easier.
         // If you try simply return iterator - it won't work because AtomicRef should exist
@@ -463,12 +694,14 @@ impl SegmentEntry for Segment {
         offset: Option<PointIdType>,
         limit: usize,
         filter: Option<&'a Filter>,
+        is_stopped: &AtomicBool,
     ) -> Vec<PointIdType> {
         match filter {
             None => self
                 .id_tracker
                 .borrow()
                 .iter_from(offset)
+                .take_while(|_| !is_stopped.load(Ordering::Relaxed))
                 .map(|x| x.0)
                 .take(limit)
                 .collect(),
@@ -478,6 +711,7 @@ impl SegmentEntry for Segment {
                 self.id_tracker
                     .borrow()
                     .iter_from(offset)
+                    .take_while(|_| !is_stopped.load(Ordering::Relaxed))
                     .filter(move |(_, internal_id)| filter_context.check(*internal_id))
                     .map(|x| x.0)
                     .take(limit)
@@ -486,6 +720,123 @@ impl SegmentEntry for Segment {
         }
     }
 
+    fn facet(
+        &self,
+        key: PayloadKeyTypeRef,
+        filter: Option<&Filter>,
+    ) -> OperationResult<HashMap<FacetValue, usize>> {
+        let payload_index = self.payload_index.borrow();
+        let field_type = match payload_index.indexed_fields().get(key) {
+            None => return Ok(HashMap::new()),
+            Some(&field_type) => field_type,
+        };
+
+        let vector_storage = self.vector_storage.borrow();
+        let mut points: Box<dyn Iterator<Item = PointOffsetType>> = match filter {
+            None => vector_storage.iter_ids(),
+            // Points, selected with the field index, might be already deleted
+            Some(filter) => Box::new(
+                payload_index
+                    .query_points(filter)
+                    .filter(|&point| !vector_storage.is_deleted(point)),
+            ),
+        };
+        if let Some(counts) = payload_index.count_map_values(key, &mut points) {
+            return Ok(counts);
+        }
+
+        // Field index is not built in plain segments, values are read from the payload
+        let mut counts = HashMap::new();
+        for point in points {
+            let payload = self.payload_by_offset(point)?;
+            if let Some(value) = payload.get_value(key) {
+                for facet_value in FacetValue::from_value(value, field_type) {
+                    *counts.entry(facet_value).or_insert(0) += 1;
+                }
+            }
+        }
+        Ok(counts)
+    }
+
+    fn sample_neighbours(
+        &self,
+        queries: &[(PointIdType, Vec<VectorElementType>)],
+        points: &[PointIdType],
+        top: usize,
+    ) -> OperationResult<Vec<Vec<ScoredPoint>>> {
+        let vector_storage = self.vector_storage.borrow();
+        let id_tracker = self.id_tracker.borrow();
+        let expected_vector_dim = vector_storage.vector_dim();
+        for (_, vector) in queries {
+            if expected_vector_dim != vector.len() {
+                return Err(OperationError::WrongVector {
+                    expected_dim: expected_vector_dim,
+                    received_dim: vector.len(),
+                });
+            }
+        }
+
+        let stored_offset = |point_id: PointIdType| {
+            id_tracker
+                .internal_id(point_id)
+                .filter(|&offset| !vector_storage.is_deleted(offset))
+        };
+        let offsets: Vec<(PointIdType, PointOffsetType)> = points
+            .iter()
+            .filter_map(|&point_id| stored_offset(point_id).map(|offset| (point_id, offset)))
+            .collect();
+        let first_offset = match offsets.first() {
+            None => return Ok(vec![vec![]; queries.len()]),
+            Some(&(_, offset)) => offset,
+        };
+        // Internal scores do not depend on the query of the scorer
+        let internal_scorer = vector_storage.raw_scorer_internal(first_offset);
+
+        queries
+            .iter()
+            .map(|(query_id, vector)| {
+                let candidates = offsets
+                    .iter()
+                    .filter(|&&(point_id, _)| point_id != *query_id);
+                let scores: Vec<_> = match stored_offset(*query_id) {
+                    Some(query_offset) => candidates
+                        .map(|&(_, offset)| ScoredPointOffset {
+                            idx: offset,
+                            score: internal_scorer.score_internal(query_offset, offset),
+                        })
+                        .collect(),
+                    None => {
+                        let raw_scorer = vector_storage.raw_scorer(vector.clone());
+                        candidates
+                            .map(|&(_, offset)| ScoredPointOffset {
+                                idx: offset,
+                                score: raw_scorer.score_point(offset),
+                            })
+                            .collect()
+                    }
+                };
+                peek_top_largest_scores_iterable(scores, top)
+                    .into_iter()
+                    .map(|scored| {
+                        let point_id = id_tracker.external_id(scored.idx).ok_or_else(|| {
+                            OperationError::service_error(&format!(
+                                "Corrupter id_tracker, no external value for {}",
+                                scored.idx
+                            ))
+                        })?;
+                        Ok(ScoredPoint {
+                            id: point_id,
+                            version: id_tracker.version(point_id).unwrap_or(0),
+                            score: scored.score,
+                            payload: None,
+                            vector: None,
+                        })
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
     fn has_point(&self, point_id: PointIdType) -> bool {
         self.id_tracker.borrow().internal_id(point_id).is_some()
     }
@@ -548,6 +899,8 @@ impl SegmentEntry for Segment {
         self.id_tracker.borrow().flush()?;
         self.payload_storage.borrow().flush()?;
         self.vector_storage.borrow().flush()?;
+        self.vector_index.borrow().flush()?;
+        self.sparse_index.borrow().flush()?;
         self.save_state(&state)?;
 
         *persisted_version = state.version;
@@ -618,7 +971,8 @@ impl SegmentEntry for Segment {
         filter: &'a Filter,
     ) -> OperationResult<usize> {
         let mut deleted_points = 0;
-        for point_id in self.read_filtered(None, usize::MAX, Some(filter)) {
+        let not_stopped = AtomicBool::new(false);
+        for point_id in self.read_filtered(None, usize::MAX, Some(filter), &not_stopped) {
             deleted_points += self.delete_point(op_num, point_id)? as usize;
         }
 
@@ -688,6 +1042,7 @@ mod tests {
             payload_index: Some(PayloadIndexType::Plain),
             storage_type: StorageType::InMemory,
             distance: Distance::Dot,
+            vector_datatype: Default::default(),
         };
 
         let mut segment = build_segment(dir.path(), &config).unwrap();
@@ -731,6 +1086,7 @@ mod tests {
                 Some(&filter_valid),
                 1,
                 None,
+                &AtomicBool::new(false),
             )
             .unwrap();
         assert_eq!(results_with_valid_filter.len(), 1);
@@ -743,6 +1099,7 @@ mod tests {
                 Some(&filter_invalid),
                 1,
                 None,
+                &AtomicBool::new(false),
             )
             .unwrap();
         assert!(results_with_invalid_filter.is_empty());
@@ -1,9 +1,10 @@
 use crate::common::error_logging::LogError;
 use crate::entry::entry_point::{OperationError, OperationResult, SegmentEntry};
+use crate::index::hnsw_index::graph_layers::ReusedGraph;
 use crate::payload_storage::PayloadStorage;
 use crate::segment::Segment;
 use crate::segment_constructor::{build_segment, load_segment};
-use crate::types::{PayloadKeyType, PayloadSchemaType, SegmentConfig};
+use crate::types::{PayloadKeyType, PayloadSchemaType, PointOffsetType, SegmentConfig};
 use core::cmp;
 use std::collections::HashMap;
 use std::fs;
@@ -16,6 +17,8 @@ pub struct SegmentBuilder {
     pub destination_path: PathBuf,
     pub temp_path: PathBuf,
     pub indexed_fields: HashMap<PayloadKeyType, PayloadSchemaType>,
+    /// Links of the largest source segment graph, which are carried over into the new index
+    reused_graph: Option<ReusedGraph>,
 }
 
 impl SegmentBuilder {
@@ -34,6 +37,7 @@ impl SegmentBuilder {
             destination_path,
             temp_path,
             indexed_fields: Default::default(),
+            reused_graph: None,
         })
     }
 
@@ -59,13 +63,18 @@ impl SegmentBuilder {
                 let other_id_tracker = other.id_tracker.borrow();
                 let other_vector_storage = other.vector_storage.borrow();
                 let other_payload_storage = other.payload_storage.borrow();
+                let other_sparse_index = other.sparse_index.borrow();
 
                 let mut id_tracker = self_segment.id_tracker.borrow_mut();
                 let mut vector_storage = self_segment.vector_storage.borrow_mut();
                 let mut payload_storage = self_segment.payload_storage.borrow_mut();
+                let mut sparse_index = self_segment.sparse_index.borrow_mut();
 
                 let new_internal_range = vector_storage.update_from(&*other_vector_storage)?;
 
+                // Internal ids of the points, which are carried over from `other` segment
+                let mut old_to_new: HashMap<PointOffsetType, PointOffsetType> = HashMap::new();
+
                 for (new_internal_id, old_internal_id) in
                     new_internal_range.zip(other_vector_storage.iter_ids())
                 {
@@ -86,6 +95,7 @@ impl SegmentBuilder {
                                 new_internal_id,
                                 &other_payload_storage.payload(old_internal_id),
                             )?;
+                            old_to_new.insert(old_internal_id, new_internal_id);
                         }
                         Some(existing_version) => {
                             if existing_version < other_version {
@@ -93,6 +103,7 @@ impl SegmentBuilder {
                                 let existing_internal_id =
                                     id_tracker.internal_id(external_id).unwrap();
                                 vector_storage.delete(existing_internal_id)?;
+                                sparse_index.remove(existing_internal_id)?;
                                 id_tracker.drop(external_id)?;
                                 id_tracker.set_link(external_id, new_internal_id)?;
                                 id_tracker.set_version(external_id, other_version)?;
@@ -100,6 +111,7 @@ impl SegmentBuilder {
                                     new_internal_id,
                                     &other_payload_storage.payload(old_internal_id),
                                 )?;
+                                old_to_new.insert(old_internal_id, new_internal_id);
                             } else {
                                 // Old version is still good, do not move anything else
                                 // Mark newly added vector as removed
@@ -109,10 +121,28 @@ impl SegmentBuilder {
                     }
                 }
 
+                for (&old_internal_id, &new_internal_id) in &old_to_new {
+                    if let Some(sparse_vector) = other_sparse_index.get(old_internal_id) {
+                        sparse_index.upsert(new_internal_id, sparse_vector.clone())?;
+                    }
+                }
+
                 for (field, payload_schema) in other.payload_index.borrow().indexed_fields() {
                     self.indexed_fields.insert(field, payload_schema);
                 }
 
+                // Keep the graph, which saves the most of the insertions
+                let reused_points = self
+                    .reused_graph
+                    .as_ref()
+                    .map(|graph| graph.num_points())
+                    .unwrap_or(0);
+                if old_to_new.len() > reused_points {
+                    if let Some(graph) = other.vector_index.borrow().graph() {
+                        self.reused_graph = Some(graph.reuse_links(&old_to_new));
+                    }
+                }
+
                 Ok(true)
             }
         }
@@ -125,6 +155,8 @@ impl SegmentBuilder {
             })?;
             self.segment = None;
 
+            segment.vector_storage.borrow_mut().finalize()?;
+
             for (field, payload_schema) in &self.indexed_fields {
                 segment.create_field_index(segment.version(), field, &Some(*payload_schema))?;
                 if stopped.load(Ordering::Relaxed) {
@@ -134,7 +166,13 @@ impl SegmentBuilder {
                 }
             }
 
-            segment.vector_index.borrow_mut().build_index(stopped)?;
+            match self.reused_graph.take() {
+                Some(reused_graph) => segment
+                    .vector_index
+                    .borrow_mut()
+                    .build_index_with_graph(reused_graph, stopped)?,
+                None => segment.vector_index.borrow_mut().build_index(stopped)?,
+            }
 
             segment.flush()?;
             // Now segment is going to be evicted from RAM
@@ -1,19 +1,25 @@
 use crate::entry::entry_point::{OperationError, OperationResult};
 use crate::id_tracker::simple_id_tracker::SimpleIdTracker;
+use crate::id_tracker::IdTracker;
 use crate::index::hnsw_index::hnsw::HNSWIndex;
 use crate::index::plain_payload_index::{PlainIndex, PlainPayloadIndex};
+use crate::index::sparse_vector_index::SparseVectorIndex;
 use crate::index::struct_payload_index::StructPayloadIndex;
 use crate::index::{PayloadIndexSS, VectorIndexSS};
 use crate::payload_storage::query_checker::SimpleConditionChecker;
 use crate::payload_storage::simple_payload_storage::SimplePayloadStorage;
 use crate::segment::{Segment, SEGMENT_STATE_FILE};
 use crate::types::{
-    Indexes, PayloadIndexType, SegmentConfig, SegmentState, SegmentType, SeqNumberType, StorageType,
+    Distance, Indexes, PayloadIndexType, SegmentConfig, SegmentState, SegmentType, SeqNumberType,
+    StorageType, VectorStorageDatatype,
 };
-use crate::vector_storage::memmap_vector_storage::open_memmap_vector_storage;
-use crate::vector_storage::simple_vector_storage::open_simple_vector_storage;
+use crate::vector_storage::binary_vector_storage::open_binary_vector_storage;
+use crate::vector_storage::memmap_vector_storage::open_memmap_vector_storage_with_datatype;
+use crate::vector_storage::quantized_vector_storage::open_quantized_vector_storage;
+use crate::vector_storage::simple_vector_storage::open_simple_vector_storage_with_datatype;
 use crate::vector_storage::VectorStorageSS;
 use atomic_refcell::AtomicRefCell;
+use itertools::Itertools;
 use std::fs::{create_dir_all, File};
 use std::io::Read;
 use std::path::Path;
@@ -34,16 +40,45 @@ fn create_segment(
     let payload_index_path = segment_path.join("payload_index");
     let vector_storage_path = segment_path.join("vector_storage");
     let vector_index_path = segment_path.join("vector_index");
+    let sparse_index_path = segment_path.join("sparse_index");
 
     let id_tracker = sp(SimpleIdTracker::open(&tracker_path)?);
 
+    // Bit-packed binary vectors are always kept in RAM
+    if config.distance == Distance::Hamming
+        && (config.storage_type != StorageType::InMemory
+            || config.vector_datatype != VectorStorageDatatype::Float32)
+    {
+        return Err(OperationError::WrongConfig {
+            description: format!(
+                "Hamming distance requires in-memory storage of {:?} vectors",
+                VectorStorageDatatype::Float32
+            ),
+        });
+    }
+
     let vector_storage: Arc<AtomicRefCell<VectorStorageSS>> = match config.storage_type {
-        StorageType::InMemory => {
-            open_simple_vector_storage(&vector_storage_path, config.vector_size, config.distance)?
-        }
-        StorageType::Mmap => {
-            open_memmap_vector_storage(&vector_storage_path, config.vector_size, config.distance)?
+        StorageType::InMemory if config.distance == Distance::Hamming => {
+            open_binary_vector_storage(&vector_storage_path, config.vector_size)?
         }
+        StorageType::InMemory => open_simple_vector_storage_with_datatype(
+            &vector_storage_path,
+            config.vector_size,
+            config.distance,
+            config.vector_datatype,
+        )?,
+        StorageType::Mmap => open_memmap_vector_storage_with_datatype(
+            &vector_storage_path,
+            config.vector_size,
+            config.distance,
+            config.vector_datatype,
+        )?,
+        StorageType::ProductQuantized(quantization_config) => open_quantized_vector_storage(
+            &vector_storage_path,
+            config.vector_size,
+            config.distance,
+            quantization_config,
+        )?,
     };
 
     let payload_storage = sp(SimplePayloadStorage::open(&payload_storage_path)?.into());
@@ -68,11 +103,49 @@ fn create_segment(
             )?),
         };
 
+    let segment_type = match config.index {
+        Indexes::Plain { .. } => match config.payload_index.unwrap_or_default() {
+            PayloadIndexType::Plain => SegmentType::Plain,
+            PayloadIndexType::Struct => SegmentType::Indexed,
+        },
+        Indexes::Hnsw { .. } => SegmentType::Indexed,
+    };
+
+    // Segments with incremental HNSW index accept new points, same as plain ones
+    let is_incremental_index = matches!(
+        config.index,
+        Indexes::Hnsw(hnsw_config) if hnsw_config.incremental.unwrap_or(false)
+    );
+
+    let appendable_flag = (segment_type == SegmentType::Plain {} || is_incremental_index)
+        && config.storage_type == StorageType::InMemory;
+
     let vector_index: Arc<AtomicRefCell<VectorIndexSS>> = match config.index {
         Indexes::Plain { .. } => sp(PlainIndex::new(
             vector_storage.clone(),
             payload_index.clone(),
         )),
+        Indexes::Hnsw(hnsw_config) if appendable_flag => {
+            // Graph is saved along with the segment state.
+            // Points, updated after that, might still be linked by their previous vectors.
+            let id_tracker = id_tracker.borrow();
+            let updated_points = id_tracker
+                .iter_internal()
+                .filter(|&internal_id| {
+                    id_tracker
+                        .external_id(internal_id)
+                        .and_then(|external_id| id_tracker.version(external_id))
+                        .map_or(false, |point_version| point_version > version)
+                })
+                .collect_vec();
+            sp(HNSWIndex::open_incremental(
+                &vector_index_path,
+                vector_storage.clone(),
+                payload_index.clone(),
+                hnsw_config,
+                &updated_points,
+            )?)
+        }
         Indexes::Hnsw(hnsw_config) => sp(HNSWIndex::open(
             &vector_index_path,
             vector_storage.clone(),
@@ -81,16 +154,7 @@ fn create_segment(
         )?),
     };
 
-    let segment_type = match config.index {
-        Indexes::Plain { .. } => match config.payload_index.unwrap_or_default() {
-            PayloadIndexType::Plain => SegmentType::Plain,
-            PayloadIndexType::Struct => SegmentType::Indexed,
-        },
-        Indexes::Hnsw { .. } => SegmentType::Indexed,
-    };
-
-    let appendable_flag =
-        segment_type == SegmentType::Plain {} && config.storage_type == StorageType::InMemory;
+    let sparse_index = sp(SparseVectorIndex::open(&sparse_index_path)?);
 
     Ok(Segment {
         version,
@@ -102,6 +166,7 @@ fn create_segment(
         payload_index,
         condition_checker,
         vector_index,
+        sparse_index,
         appendable_flag,
         segment_type,
         segment_config: config.clone(),
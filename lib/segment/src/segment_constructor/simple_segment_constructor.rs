@@ -1,6 +1,6 @@
 use crate::segment::Segment;
 
-use crate::types::{Distance, Indexes, SegmentConfig};
+use crate::types::{Distance, HnswConfig, Indexes, SegmentConfig, VectorStorageDatatype};
 
 use crate::entry::entry_point::OperationResult;
 use crate::segment_constructor::build_segment;
@@ -25,6 +25,41 @@ pub fn build_simple_segment(
             payload_index: None,
             distance,
             storage_type: Default::default(),
+            vector_datatype: Default::default(),
+        },
+    )
+}
+
+/// Build new appendable segment in given directory.
+/// Segment is indexed with HNSW on each insertion, if it is enabled by `incremental` flag of `hnsw_config`.
+/// Otherwise, plain index is used.
+///
+/// # Arguments
+///
+/// * `path` - path to collection\`s segment directory
+/// * `vector_datatype` - type of stored vector elements
+///
+pub fn build_appendable_segment(
+    path: &Path,
+    dim: usize,
+    distance: Distance,
+    hnsw_config: &HnswConfig,
+    vector_datatype: VectorStorageDatatype,
+) -> OperationResult<Segment> {
+    let index = if hnsw_config.incremental.unwrap_or(false) {
+        Indexes::Hnsw(*hnsw_config)
+    } else {
+        Indexes::Plain {}
+    };
+    build_segment(
+        path,
+        &SegmentConfig {
+            vector_size: dim,
+            index,
+            payload_index: None,
+            distance,
+            storage_type: Default::default(),
+            vector_datatype,
         },
     )
 }
@@ -0,0 +1,169 @@
+use crate::types::{Distance, ScoreType, VectorElementType};
+
+use super::metric::Metric;
+
+/// Word of bit-packed binary vector
+pub type PackedWord = u64;
+
+const BITS_PER_WORD: usize = PackedWord::BITS as usize;
+
+/// Metric for binary vectors. Score is a negative number of differing bits.
+///
+/// All positive elements are considered as set bits, others - as unset.
+#[derive(Clone)]
+pub struct HammingMetric {}
+
+impl Metric for HammingMetric {
+    fn distance() -> Distance {
+        Distance::Hamming
+    }
+
+    fn similarity(v1: &[VectorElementType], v2: &[VectorElementType]) -> ScoreType {
+        let distance = v1
+            .iter()
+            .zip(v2)
+            .filter(|(a, b)| (**a > 0.0) != (**b > 0.0))
+            .count();
+        -(distance as ScoreType)
+    }
+
+    fn preprocess(vector: &[VectorElementType]) -> Option<Vec<VectorElementType>> {
+        if vector.iter().all(|x| *x == 0.0 || *x == 1.0) {
+            return None;
+        }
+        Some(
+            vector
+                .iter()
+                .map(|x| if *x > 0.0 { 1.0 } else { 0.0 })
+                .collect(),
+        )
+    }
+
+    fn postprocess(score: ScoreType) -> ScoreType {
+        score.abs()
+    }
+}
+
+/// Number of words, required to store vector of `dim` bits
+pub fn packed_len(dim: usize) -> usize {
+    (dim + BITS_PER_WORD - 1) / BITS_PER_WORD
+}
+
+/// Pack vector into bits, all positive elements are considered as set bits
+pub fn pack_bits(vector: &[VectorElementType]) -> Vec<PackedWord> {
+    let mut packed = vec![0; packed_len(vector.len())];
+    for (idx, value) in vector.iter().enumerate() {
+        if *value > 0.0 {
+            packed[idx / BITS_PER_WORD] |= 1 << (idx % BITS_PER_WORD);
+        }
+    }
+    packed
+}
+
+/// Expand first `dim` packed bits into a vector of zeros and ones
+pub fn unpack_bits(packed: &[PackedWord], dim: usize) -> Vec<VectorElementType> {
+    (0..dim)
+        .map(|idx| {
+            if (packed[idx / BITS_PER_WORD] >> (idx % BITS_PER_WORD)) & 1 == 1 {
+                1.0
+            } else {
+                0.0
+            }
+        })
+        .collect()
+}
+
+/// Expand bytes into a vector of zeros and ones, most significant bit of each byte goes first
+pub fn unpack_bytes(bytes: &[u8]) -> Vec<VectorElementType> {
+    bytes
+        .iter()
+        .flat_map(|byte| {
+            (0..8)
+                .rev()
+                .map(move |bit| ((byte >> bit) & 1) as VectorElementType)
+        })
+        .collect()
+}
+
+/// Number of differing bits of packed vectors
+pub fn hamming_distance(v1: &[PackedWord], v2: &[PackedWord]) -> u32 {
+    #[cfg(target_arch = "x86_64")]
+    {
+        if is_x86_feature_detected!("popcnt") {
+            return unsafe { hamming_distance_popcnt(v1, v2) };
+        }
+    }
+
+    hamming_distance_scalar(v1, v2)
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "popcnt")]
+unsafe fn hamming_distance_popcnt(v1: &[PackedWord], v2: &[PackedWord]) -> u32 {
+    let mut distance = 0;
+    for (a, b) in v1.iter().zip(v2) {
+        distance += (a ^ b).count_ones();
+    }
+    distance
+}
+
+fn hamming_distance_scalar(v1: &[PackedWord], v2: &[PackedWord]) -> u32 {
+    v1.iter().zip(v2).map(|(a, b)| (a ^ b).count_ones()).sum()
+}
+
+/// Same score as `HammingMetric::similarity`, but for packed vectors
+pub fn hamming_similarity_packed(v1: &[PackedWord], v2: &[PackedWord]) -> ScoreType {
+    -(hamming_distance(v1, v2) as ScoreType)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::Rng;
+
+    fn random_binary_vector(dim: usize) -> Vec<VectorElementType> {
+        let mut rng = rand::thread_rng();
+        (0..dim)
+            .map(|_| if rng.gen_bool(0.5) { 1.0 } else { 0.0 })
+            .collect()
+    }
+
+    #[test]
+    fn test_pack_bits() {
+        let vector = random_binary_vector(130);
+        let packed = pack_bits(&vector);
+        assert_eq!(packed.len(), 3);
+        assert_eq!(unpack_bits(&packed, vector.len()), vector);
+
+        assert_eq!(pack_bits(&[0.3, -1.0, 0.0, 2.0]), vec![0b1001]);
+        assert_eq!(
+            unpack_bytes(&[0b1000_0001, 0b0100_0000]),
+            vec![1.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0]
+        );
+    }
+
+    #[test]
+    fn test_packed_similarity() {
+        for _ in 0..10 {
+            let v1 = random_binary_vector(256);
+            let v2 = random_binary_vector(256);
+            assert_eq!(
+                hamming_similarity_packed(&pack_bits(&v1), &pack_bits(&v2)),
+                HammingMetric::similarity(&v1, &v2)
+            );
+            assert_eq!(
+                hamming_distance_scalar(&pack_bits(&v1), &pack_bits(&v2)),
+                hamming_distance(&pack_bits(&v1), &pack_bits(&v2))
+            );
+        }
+
+        let v1 = vec![1.0, 0.0, 1.0, 1.0];
+        let v2 = vec![0.0, 0.0, 1.0, 0.0];
+        assert_eq!(HammingMetric::similarity(&v1, &v2), -2.0);
+        assert_eq!(HammingMetric::postprocess(-2.0), 2.0);
+        assert_eq!(
+            HammingMetric::preprocess(&[0.5, -1.0, 1.0]),
+            Some(vec![1.0, 0.0, 1.0])
+        );
+    }
+}
@@ -0,0 +1,230 @@
+use crate::spaces::metric::Metric;
+use crate::types::{ScoreType, VectorElementType};
+use serde::{Deserialize, Serialize};
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+use super::half_sse::*;
+
+#[cfg(target_arch = "x86_64")]
+use super::half_avx::*;
+
+#[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
+use super::half_neon::*;
+
+/// Number of elements, converted to full precision at once during scoring
+const CONVERSION_CHUNK: usize = 64;
+
+/// Vector element of half precision, stored as bits of IEEE 754 binary16
+#[derive(Debug, Deserialize, Serialize, Copy, Clone, Default, PartialEq, Eq)]
+#[repr(transparent)]
+pub struct Float16(u16);
+
+impl Float16 {
+    pub fn from_bits(bits: u16) -> Self {
+        Float16(bits)
+    }
+
+    pub fn to_bits(self) -> u16 {
+        self.0
+    }
+
+    /// Round to the nearest representable value, ties to even
+    pub fn from_f32(value: f32) -> Self {
+        const F32_INFINITY: u32 = 255 << 23;
+        // Smallest value, which overflows half precision
+        const F16_OVERFLOW: u32 = (127 + 16) << 23;
+        // Smallest value, which is normal in half precision
+        const F16_MIN_NORMAL: u32 = 113 << 23;
+        const DENORMAL_MAGIC: u32 = ((127 - 15) + (23 - 10) + 1) << 23;
+        // Re-bias exponent from 127 to 15 and add half of the dropped mantissa bits minus one,
+        // ties are rounded to even by adding the lowest bit of the kept mantissa afterwards
+        const REBIAS: u32 = ((15 - 127) as u32).wrapping_shl(23).wrapping_add(0xfff);
+
+        let bits = value.to_bits();
+        let sign = bits & 0x8000_0000;
+        let abs = bits ^ sign;
+
+        let half = if abs >= F16_OVERFLOW {
+            // Infinity or NaN, NaN stays quiet
+            if abs > F32_INFINITY {
+                0x7e00
+            } else {
+                0x7c00
+            }
+        } else if abs < F16_MIN_NORMAL {
+            // Denormal or zero, let FPU do the rounding by aligning mantissa with addition
+            let aligned = f32::from_bits(abs) + f32::from_bits(DENORMAL_MAGIC);
+            aligned.to_bits().wrapping_sub(DENORMAL_MAGIC) as u16
+        } else {
+            let mantissa_odd = (abs >> 13) & 1;
+            (abs.wrapping_add(REBIAS).wrapping_add(mantissa_odd) >> 13) as u16
+        };
+
+        Float16(half | (sign >> 16) as u16)
+    }
+
+    pub fn to_f32(self) -> f32 {
+        f32::from_bits(f16_bits_to_f32_bits(self.0 as u32))
+    }
+}
+
+/// Magic multiplier, which re-biases exponent of half precision value, shifted into f32 position
+pub(crate) const REBIAS_MULTIPLIER: f32 = 5.192_296_9e33; // 2^112
+/// Any shifted and re-biased half precision value, which is not less, was infinity or NaN
+pub(crate) const INFINITY_THRESHOLD: f32 = 65536.0; // 2^16
+
+/// Converts bits of half precision value into bits of f32.
+/// Vectorized implementations follow the same steps.
+fn f16_bits_to_f32_bits(half: u32) -> u32 {
+    let shifted = (half & 0x7fff) << 13;
+    // Multiplication handles denormal values as well
+    let rebiased = f32::from_bits(shifted) * REBIAS_MULTIPLIER;
+    let infinity = if rebiased >= INFINITY_THRESHOLD {
+        255 << 23
+    } else {
+        0
+    };
+    rebiased.to_bits() | infinity | ((half & 0x8000) << 16)
+}
+
+pub fn f16_to_f32_scalar(src: &[Float16], dst: &mut [VectorElementType]) {
+    for (half, value) in src.iter().zip(dst.iter_mut()) {
+        *value = half.to_f32();
+    }
+}
+
+/// Convert vector of half precision into full precision `dst` of the same length
+pub fn f16_to_f32(src: &[Float16], dst: &mut [VectorElementType]) {
+    assert_eq!(src.len(), dst.len());
+
+    #[cfg(target_arch = "x86_64")]
+    {
+        if is_x86_feature_detected!("avx2") {
+            return unsafe { f16_to_f32_avx(src, dst) };
+        }
+    }
+
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    {
+        if is_x86_feature_detected!("sse2") {
+            return unsafe { f16_to_f32_sse(src, dst) };
+        }
+    }
+
+    #[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
+    {
+        if std::arch::is_aarch64_feature_detected!("neon") {
+            return unsafe { f16_to_f32_neon(src, dst) };
+        }
+    }
+
+    f16_to_f32_scalar(src, dst)
+}
+
+pub fn f32_to_f16(vector: &[VectorElementType]) -> Vec<Float16> {
+    vector.iter().copied().map(Float16::from_f32).collect()
+}
+
+/// Similarity between full precision query and vector of half precision.
+///
+/// Stored vector is converted by chunks into a buffer on stack, which is possible as similarity
+/// of all supported metrics is a sum over dimensions.
+pub fn half_similarity<TMetric: Metric>(
+    query: &[VectorElementType],
+    stored: &[Float16],
+) -> ScoreType {
+    let mut buffer = [0.0; CONVERSION_CHUNK];
+    query
+        .chunks(CONVERSION_CHUNK)
+        .zip(stored.chunks(CONVERSION_CHUNK))
+        .map(|(query_chunk, stored_chunk)| {
+            let converted = &mut buffer[..stored_chunk.len()];
+            f16_to_f32(stored_chunk, converted);
+            TMetric::similarity(query_chunk, converted)
+        })
+        .sum()
+}
+
+/// Similarity between two vectors of half precision
+pub fn half_similarity_stored<TMetric: Metric>(v1: &[Float16], v2: &[Float16]) -> ScoreType {
+    let mut buffer1 = [0.0; CONVERSION_CHUNK];
+    let mut buffer2 = [0.0; CONVERSION_CHUNK];
+    v1.chunks(CONVERSION_CHUNK)
+        .zip(v2.chunks(CONVERSION_CHUNK))
+        .map(|(chunk1, chunk2)| {
+            let converted1 = &mut buffer1[..chunk1.len()];
+            let converted2 = &mut buffer2[..chunk2.len()];
+            f16_to_f32(chunk1, converted1);
+            f16_to_f32(chunk2, converted2);
+            TMetric::similarity(converted1, converted2)
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::spaces::simple::{DotProductMetric, EuclidMetric};
+
+    #[test]
+    fn test_round_trip() {
+        for value in [0.0, -0.0, 1.0, -2.5, 0.1, 65504.0, 6.1e-5, 1e-7, -3.0e-6] {
+            let half = Float16::from_f32(value);
+            let restored = half.to_f32();
+            assert!(
+                (restored - value).abs() <= value.abs() / 1024.0 + 6e-8,
+                "{} is restored as {}",
+                value,
+                restored
+            );
+        }
+
+        assert_eq!(Float16::from_f32(1.0).to_bits(), 0x3c00);
+        assert_eq!(Float16::from_f32(-2.0).to_bits(), 0xc000);
+        assert_eq!(Float16::from_f32(65504.0).to_bits(), 0x7bff);
+        // Smallest denormal value
+        assert_eq!(Float16::from_f32(5.960_464_5e-8).to_bits(), 0x0001);
+        // Ties are rounded to even
+        assert_eq!(Float16::from_f32(1.0 + 1.0 / 2048.0).to_bits(), 0x3c00);
+        assert_eq!(Float16::from_f32(1.0 + 3.0 / 2048.0).to_bits(), 0x3c02);
+
+        assert_eq!(Float16::from_f32(1e6).to_f32(), f32::INFINITY);
+        assert_eq!(
+            Float16::from_f32(f32::NEG_INFINITY).to_f32(),
+            f32::NEG_INFINITY
+        );
+        assert!(Float16::from_f32(f32::NAN).to_f32().is_nan());
+    }
+
+    #[test]
+    fn test_vectorized_conversion() {
+        let halves = (0..=u16::MAX).map(Float16::from_bits).collect::<Vec<_>>();
+
+        let mut expected = vec![0.0; halves.len()];
+        f16_to_f32_scalar(&halves, &mut expected);
+
+        // Odd length checks the processing of the remainder
+        let mut converted = vec![0.0; halves.len() - 1];
+        f16_to_f32(&halves[1..], &mut converted);
+
+        for (expected, converted) in expected[1..].iter().zip(&converted) {
+            assert_eq!(expected.to_bits(), converted.to_bits());
+        }
+    }
+
+    #[test]
+    fn test_half_similarity() {
+        let query: Vec<f32> = (0..100).map(|x| x as f32 / 10.0).collect();
+        let vector: Vec<f32> = (0..100).map(|x| (100 - x) as f32 / 8.0).collect();
+        let stored = f32_to_f16(&vector);
+
+        // All stored values are exactly representable in half precision
+        let score = half_similarity::<DotProductMetric>(&query, &stored);
+        let expected = DotProductMetric::similarity(&query, &vector);
+        assert!((score - expected).abs() < 1e-2);
+        assert_eq!(
+            half_similarity_stored::<EuclidMetric>(&stored, &stored),
+            0.0
+        );
+    }
+}
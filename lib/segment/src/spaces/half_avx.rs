@@ -0,0 +1,39 @@
+use crate::spaces::half::{f16_to_f32_scalar, Float16, INFINITY_THRESHOLD, REBIAS_MULTIPLIER};
+use crate::types::VectorElementType;
+
+use std::arch::x86_64::*;
+
+/// Converts 8 half precision values, widened to 32 bits, following `f16_bits_to_f32_bits`
+#[target_feature(enable = "avx2")]
+unsafe fn f16x8_to_f32x8_avx(half: __m256i) -> __m256 {
+    let shifted = _mm256_slli_epi32(_mm256_and_si256(half, _mm256_set1_epi32(0x7fff)), 13);
+    let rebiased = _mm256_mul_ps(
+        _mm256_castsi256_ps(shifted),
+        _mm256_set1_ps(REBIAS_MULTIPLIER),
+    );
+    let infinity = _mm256_and_ps(
+        _mm256_cmp_ps(rebiased, _mm256_set1_ps(INFINITY_THRESHOLD), _CMP_GE_OQ),
+        _mm256_castsi256_ps(_mm256_set1_epi32(255 << 23)),
+    );
+    let sign = _mm256_slli_epi32(_mm256_and_si256(half, _mm256_set1_epi32(0x8000)), 16);
+    _mm256_or_ps(_mm256_or_ps(rebiased, infinity), _mm256_castsi256_ps(sign))
+}
+
+#[target_feature(enable = "avx2")]
+pub(crate) unsafe fn f16_to_f32_avx(src: &[Float16], dst: &mut [VectorElementType]) {
+    let n = src.len();
+    let m = n - (n % 16);
+    let mut src_ptr = src.as_ptr() as *const __m128i;
+    let mut dst_ptr: *mut f32 = dst.as_mut_ptr();
+    let mut i: usize = 0;
+    while i < m {
+        let half8_1 = _mm256_cvtepu16_epi32(_mm_loadu_si128(src_ptr));
+        let half8_2 = _mm256_cvtepu16_epi32(_mm_loadu_si128(src_ptr.add(1)));
+        _mm256_storeu_ps(dst_ptr, f16x8_to_f32x8_avx(half8_1));
+        _mm256_storeu_ps(dst_ptr.add(8), f16x8_to_f32x8_avx(half8_2));
+        src_ptr = src_ptr.add(2);
+        dst_ptr = dst_ptr.add(16);
+        i += 16;
+    }
+    f16_to_f32_scalar(&src[m..], &mut dst[m..]);
+}
@@ -0,0 +1,44 @@
+#[cfg(target_feature = "neon")]
+use crate::spaces::half::{f16_to_f32_scalar, Float16, INFINITY_THRESHOLD, REBIAS_MULTIPLIER};
+#[cfg(target_feature = "neon")]
+use crate::types::VectorElementType;
+
+#[cfg(target_feature = "neon")]
+use std::arch::aarch64::*;
+
+/// Converts 4 half precision values, widened to 32 bits, following `f16_bits_to_f32_bits`
+#[cfg(target_feature = "neon")]
+unsafe fn f16x4_to_f32x4_neon(half: uint32x4_t) -> float32x4_t {
+    let shifted = vshlq_n_u32(vandq_u32(half, vdupq_n_u32(0x7fff)), 13);
+    let rebiased = vmulq_f32(
+        vreinterpretq_f32_u32(shifted),
+        vdupq_n_f32(REBIAS_MULTIPLIER),
+    );
+    let infinity = vandq_u32(
+        vcgeq_f32(rebiased, vdupq_n_f32(INFINITY_THRESHOLD)),
+        vdupq_n_u32(255 << 23),
+    );
+    let sign = vshlq_n_u32(vandq_u32(half, vdupq_n_u32(0x8000)), 16);
+    vreinterpretq_f32_u32(vorrq_u32(
+        vorrq_u32(vreinterpretq_u32_f32(rebiased), infinity),
+        sign,
+    ))
+}
+
+#[cfg(target_feature = "neon")]
+pub(crate) unsafe fn f16_to_f32_neon(src: &[Float16], dst: &mut [VectorElementType]) {
+    let n = src.len();
+    let m = n - (n % 8);
+    let mut src_ptr = src.as_ptr() as *const u16;
+    let mut dst_ptr: *mut f32 = dst.as_mut_ptr();
+    let mut i: usize = 0;
+    while i < m {
+        let half8 = vld1q_u16(src_ptr);
+        vst1q_f32(dst_ptr, f16x4_to_f32x4_neon(vmovl_u16(vget_low_u16(half8))));
+        vst1q_f32(dst_ptr.add(4), f16x4_to_f32x4_neon(vmovl_high_u16(half8)));
+        src_ptr = src_ptr.add(8);
+        dst_ptr = dst_ptr.add(8);
+        i += 8;
+    }
+    f16_to_f32_scalar(&src[m..], &mut dst[m..]);
+}
@@ -0,0 +1,43 @@
+use crate::spaces::half::{f16_to_f32_scalar, Float16, INFINITY_THRESHOLD, REBIAS_MULTIPLIER};
+use crate::types::VectorElementType;
+
+#[cfg(target_arch = "x86")]
+use std::arch::x86::*;
+
+#[cfg(target_arch = "x86_64")]
+use std::arch::x86_64::*;
+
+/// Converts 4 half precision values, widened to 32 bits, following `f16_bits_to_f32_bits`
+#[target_feature(enable = "sse2")]
+unsafe fn f16x4_to_f32x4_sse(half: __m128i) -> __m128 {
+    let shifted = _mm_slli_epi32(_mm_and_si128(half, _mm_set1_epi32(0x7fff)), 13);
+    let rebiased = _mm_mul_ps(_mm_castsi128_ps(shifted), _mm_set1_ps(REBIAS_MULTIPLIER));
+    let infinity = _mm_and_ps(
+        _mm_cmpge_ps(rebiased, _mm_set1_ps(INFINITY_THRESHOLD)),
+        _mm_castsi128_ps(_mm_set1_epi32(255 << 23)),
+    );
+    let sign = _mm_slli_epi32(_mm_and_si128(half, _mm_set1_epi32(0x8000)), 16);
+    _mm_or_ps(_mm_or_ps(rebiased, infinity), _mm_castsi128_ps(sign))
+}
+
+#[target_feature(enable = "sse2")]
+pub(crate) unsafe fn f16_to_f32_sse(src: &[Float16], dst: &mut [VectorElementType]) {
+    let n = src.len();
+    let m = n - (n % 8);
+    let mut src_ptr = src.as_ptr() as *const __m128i;
+    let mut dst_ptr: *mut f32 = dst.as_mut_ptr();
+    let zero = _mm_setzero_si128();
+    let mut i: usize = 0;
+    while i < m {
+        let half8 = _mm_loadu_si128(src_ptr);
+        _mm_storeu_ps(dst_ptr, f16x4_to_f32x4_sse(_mm_unpacklo_epi16(half8, zero)));
+        _mm_storeu_ps(
+            dst_ptr.add(4),
+            f16x4_to_f32x4_sse(_mm_unpackhi_epi16(half8, zero)),
+        );
+        src_ptr = src_ptr.add(1);
+        dst_ptr = dst_ptr.add(8);
+        i += 8;
+    }
+    f16_to_f32_scalar(&src[m..], &mut dst[m..]);
+}
@@ -1,3 +1,5 @@
+pub mod binary;
+pub mod half;
 pub mod metric;
 pub mod simple;
 pub mod tools;
@@ -10,3 +12,12 @@ pub mod simple_avx;
 
 #[cfg(target_arch = "aarch64")]
 pub mod simple_neon;
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+pub mod half_sse;
+
+#[cfg(target_arch = "x86_64")]
+pub mod half_avx;
+
+#[cfg(target_arch = "aarch64")]
+pub mod half_neon;
@@ -26,6 +26,9 @@ pub struct CosineMetric {}
 #[derive(Clone)]
 pub struct EuclidMetric {}
 
+#[derive(Clone)]
+pub struct ManhattanMetric {}
+
 impl Metric for EuclidMetric {
     fn distance() -> Distance {
         Distance::Euclid
@@ -68,6 +71,48 @@ impl Metric for EuclidMetric {
     }
 }
 
+impl Metric for ManhattanMetric {
+    fn distance() -> Distance {
+        Distance::Manhattan
+    }
+
+    fn similarity(v1: &[VectorElementType], v2: &[VectorElementType]) -> ScoreType {
+        #[cfg(target_arch = "x86_64")]
+        {
+            if is_x86_feature_detected!("avx")
+                && is_x86_feature_detected!("fma")
+                && v1.len() >= MIN_DIM_SIZE_AVX
+            {
+                return unsafe { manhattan_similarity_avx(v1, v2) };
+            }
+        }
+
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+        {
+            if is_x86_feature_detected!("sse") && v1.len() >= MIN_DIM_SIZE_SIMD {
+                return unsafe { manhattan_similarity_sse(v1, v2) };
+            }
+        }
+
+        #[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
+        {
+            if std::arch::is_aarch64_feature_detected!("neon") && v1.len() >= MIN_DIM_SIZE_SIMD {
+                return unsafe { manhattan_similarity_neon(v1, v2) };
+            }
+        }
+
+        manhattan_similarity(v1, v2)
+    }
+
+    fn preprocess(_vector: &[VectorElementType]) -> Option<Vec<VectorElementType>> {
+        None
+    }
+
+    fn postprocess(score: ScoreType) -> ScoreType {
+        score.abs()
+    }
+}
+
 impl Metric for DotProductMetric {
     fn distance() -> Distance {
         Distance::Dot
@@ -187,6 +232,16 @@ pub fn euclid_similarity(v1: &[VectorElementType], v2: &[VectorElementType]) ->
     -s
 }
 
+pub fn manhattan_similarity(v1: &[VectorElementType], v2: &[VectorElementType]) -> ScoreType {
+    let s: ScoreType = v1
+        .iter()
+        .copied()
+        .zip(v2.iter().copied())
+        .map(|(a, b)| (a - b).abs())
+        .sum();
+    -s
+}
+
 pub fn cosine_preprocess(vector: &[VectorElementType]) -> Vec<VectorElementType> {
     let mut length: f32 = vector.iter().map(|x| x * x).sum();
     length = length.sqrt();
@@ -206,4 +261,15 @@ mod tests {
         let res = CosineMetric::preprocess(&[0.0, 0.0, 0.0, 0.0]);
         eprintln!("res = {:#?}", res);
     }
+
+    #[test]
+    fn test_manhattan_similarity() {
+        let v1: Vec<f32> = (0..40).map(|x| x as f32).collect();
+        let v2: Vec<f32> = (0..40).map(|x| (x % 7) as f32 * 2.0).collect();
+
+        let expected: f32 = v1.iter().zip(&v2).map(|(a, b)| (a - b).abs()).sum();
+        assert_eq!(ManhattanMetric::similarity(&v1, &v2), -expected);
+        assert_eq!(ManhattanMetric::similarity(&v1[..3], &v2[..3]), -1.0);
+        assert_eq!(ManhattanMetric::postprocess(-3.5), 3.5);
+    }
 }
@@ -43,6 +43,47 @@ pub(crate) unsafe fn euclid_similarity_neon(
     -result
 }
 
+#[cfg(target_feature = "neon")]
+pub(crate) unsafe fn manhattan_similarity_neon(
+    v1: &[VectorElementType],
+    v2: &[VectorElementType],
+) -> ScoreType {
+    let n = v1.len();
+    let m = n - (n % 16);
+    let mut ptr1: *const f32 = v1.as_ptr();
+    let mut ptr2: *const f32 = v2.as_ptr();
+    let mut sum1 = vdupq_n_f32(0.);
+    let mut sum2 = vdupq_n_f32(0.);
+    let mut sum3 = vdupq_n_f32(0.);
+    let mut sum4 = vdupq_n_f32(0.);
+
+    let mut i: usize = 0;
+    while i < m {
+        sum1 = vaddq_f32(sum1, vabdq_f32(vld1q_f32(ptr1), vld1q_f32(ptr2)));
+        sum2 = vaddq_f32(
+            sum2,
+            vabdq_f32(vld1q_f32(ptr1.add(4)), vld1q_f32(ptr2.add(4))),
+        );
+        sum3 = vaddq_f32(
+            sum3,
+            vabdq_f32(vld1q_f32(ptr1.add(8)), vld1q_f32(ptr2.add(8))),
+        );
+        sum4 = vaddq_f32(
+            sum4,
+            vabdq_f32(vld1q_f32(ptr1.add(12)), vld1q_f32(ptr2.add(12))),
+        );
+
+        ptr1 = ptr1.add(16);
+        ptr2 = ptr2.add(16);
+        i += 16;
+    }
+    let mut result = vaddvq_f32(sum1) + vaddvq_f32(sum2) + vaddvq_f32(sum3) + vaddvq_f32(sum4);
+    for i in 0..n - m {
+        result += (*ptr1.add(i) - *ptr2.add(i)).abs();
+    }
+    -result
+}
+
 #[cfg(target_feature = "neon")]
 pub(crate) unsafe fn cosine_preprocess_neon(
     vector: &[VectorElementType],
@@ -133,6 +174,10 @@ mod tests {
             let euclid = euclid_similarity(&v1, &v2);
             assert_eq!(euclid_simd, euclid);
 
+            let manhattan_simd = unsafe { manhattan_similarity_neon(&v1, &v2) };
+            let manhattan = manhattan_similarity(&v1, &v2);
+            assert_eq!(manhattan_simd, manhattan);
+
             let dot_simd = unsafe { dot_similarity_neon(&v1, &v2) };
             let dot = dot_similarity(&v1, &v2);
             assert_eq!(dot_simd, dot);
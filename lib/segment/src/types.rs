@@ -1,5 +1,6 @@
+use crate::spaces::binary::HammingMetric;
 use crate::spaces::metric::Metric;
-use crate::spaces::simple::{CosineMetric, DotProductMetric, EuclidMetric};
+use crate::spaces::simple::{CosineMetric, DotProductMetric, EuclidMetric, ManhattanMetric};
 use geo::prelude::HaversineDistance;
 use geo::Point;
 use itertools::Itertools;
@@ -11,6 +12,7 @@ use std::cmp::Ordering;
 use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fmt::Formatter;
 use std::str::FromStr;
+use std::time::Duration;
 use uuid::Uuid;
 
 /// Type of point index inside a segment
@@ -85,6 +87,12 @@ pub enum Distance {
     Euclid,
     /// https://en.wikipedia.org/wiki/Dot_product
     Dot,
+    /// https://en.wikipedia.org/wiki/Hamming_distance
+    /// Vectors are binary, all positive elements are considered as set bits.
+    /// Vectors are kept bit-packed in RAM regardless of the storage type.
+    Hamming,
+    /// https://en.wikipedia.org/wiki/Taxicab_geometry
+    Manhattan,
 }
 
 impl Distance {
@@ -93,6 +101,8 @@ impl Distance {
             1 => Some(Distance::Cosine),
             2 => Some(Distance::Euclid),
             3 => Some(Distance::Dot),
+            4 => Some(Distance::Hamming),
+            5 => Some(Distance::Manhattan),
             _ => None,
         }
     }
@@ -102,6 +112,8 @@ impl Distance {
             Distance::Cosine => 1,
             Distance::Euclid => 2,
             Distance::Dot => 3,
+            Distance::Hamming => 4,
+            Distance::Manhattan => 5,
         }
     }
 
@@ -113,6 +125,20 @@ impl Distance {
             Distance::Cosine => CosineMetric::preprocess(vector),
             Distance::Euclid => EuclidMetric::preprocess(vector),
             Distance::Dot => DotProductMetric::preprocess(vector),
+            Distance::Hamming => HammingMetric::preprocess(vector),
+            Distance::Manhattan => ManhattanMetric::preprocess(vector),
+        }
+    }
+
+    /// Similarity of preprocessed vectors, same as the internal score of the vector storage.
+    /// Greater the value - closer the vectors
+    pub fn similarity(&self, v1: &[VectorElementType], v2: &[VectorElementType]) -> ScoreType {
+        match self {
+            Distance::Cosine => CosineMetric::similarity(v1, v2),
+            Distance::Euclid => EuclidMetric::similarity(v1, v2),
+            Distance::Dot => DotProductMetric::similarity(v1, v2),
+            Distance::Hamming => HammingMetric::similarity(v1, v2),
+            Distance::Manhattan => ManhattanMetric::similarity(v1, v2),
         }
     }
 
@@ -121,13 +147,25 @@ impl Distance {
             Distance::Cosine => CosineMetric::postprocess(score),
             Distance::Euclid => EuclidMetric::postprocess(score),
             Distance::Dot => DotProductMetric::postprocess(score),
+            Distance::Hamming => HammingMetric::postprocess(score),
+            Distance::Manhattan => ManhattanMetric::postprocess(score),
+        }
+    }
+
+    /// Inverse of `postprocess_score`: internal score of the vector storage, which corresponds to
+    /// the score of the search result
+    pub fn internal_score(&self, score: ScoreType) -> ScoreType {
+        match self {
+            Distance::Cosine | Distance::Dot => score,
+            Distance::Euclid => -(score * score),
+            Distance::Hamming | Distance::Manhattan => -score.abs(),
         }
     }
 
     pub fn distance_order(&self) -> Order {
         match self {
             Distance::Cosine | Distance::Dot => Order::LargeBetter,
-            Distance::Euclid => Order::SmallBetter,
+            Distance::Euclid | Distance::Hamming | Distance::Manhattan => Order::SmallBetter,
         }
     }
 
@@ -180,6 +218,75 @@ impl PartialEq for ScoredPoint {
     }
 }
 
+/// How the segment has looked for the points of the search result
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SearchStrategy {
+    /// Every point, which satisfies the filter, is scored
+    Plain,
+    /// Points are found with the HNSW graph
+    Graph,
+    /// Points are found with the index of sparse vectors
+    Sparse,
+}
+
+/// Cardinality estimation of the filter, used to choose the search strategy
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub struct CardinalityExplain {
+    /// Minimal possible number of points, which satisfy the filter
+    pub min: usize,
+    /// Expected number of points, which satisfy the filter
+    pub exp: usize,
+    /// Maximal possible number of points, which satisfy the filter
+    pub max: usize,
+    /// Filters with fewer points are searched with the plain strategy
+    pub indexing_threshold: usize,
+}
+
+/// Decisions of the search planner of a single segment
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub struct SegmentSearchExplain {
+    pub strategy: SearchStrategy,
+    /// Estimated number of points, which satisfy the filter.
+    /// Only present if the strategy was chosen by the filter.
+    pub cardinality: Option<CardinalityExplain>,
+    /// Whether the sampling found more points, which satisfy the filter, than the indexing threshold.
+    /// Only present if the cardinality estimation was not enough to choose the strategy.
+    pub sampled_above_threshold: Option<bool>,
+    /// Number of points, scored during the search
+    pub visited_points: usize,
+}
+
+/// Results of the top-level filter conditions for the point, in the order of the conditions
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub struct FilterExplain {
+    pub should: Option<Vec<bool>>,
+    pub must: Option<Vec<bool>>,
+    pub must_not: Option<Vec<bool>>,
+}
+
+/// Which conditions of the filter the found point satisfies
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub struct PointExplain {
+    pub id: PointIdType,
+    pub filter: FilterExplain,
+}
+
+/// Explanation of how a single segment was searched
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub struct SegmentExplain {
+    /// Decisions of the search planner of the segment
+    pub search: SegmentSearchExplain,
+    /// Results of the filter conditions for each point, found in the segment, in the order of the scores.
+    /// Empty if the search is not filtered.
+    pub points: Vec<PointExplain>,
+}
+
 /// Type of segment
 #[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, Copy, PartialEq)]
 #[serde(rename_all = "snake_case")]
@@ -212,6 +319,19 @@ pub struct SegmentInfo {
     pub index_schema: HashMap<PayloadKeyType, PayloadIndexInfo>,
 }
 
+/// Quality of the vector index of the segment, measured against the exact search
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecallEvaluation {
+    /// Number of sampled points, used as queries
+    pub queries: usize,
+    /// Mean share of the exact nearest neighbours, found by the index search
+    pub recall: f64,
+    /// Total time spent on the index search
+    pub search_time: Duration,
+    /// Size of the beam, used by the index search
+    pub ef: usize,
+}
+
 /// Additional parameters of the search
 #[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, Copy, PartialEq)]
 #[serde(rename_all = "snake_case")]
@@ -219,6 +339,67 @@ pub struct SearchParams {
     /// Params relevant to HNSW index
     /// /// Size of the beam in a beam-search. Larger the value - more accurate the result, more time required for search.
     pub hnsw_ef: Option<usize>,
+    /// Search without approximation. If set to true, search may run long but with exact results.
+    #[serde(default)]
+    pub exact: bool,
+}
+
+/// Positive and negative examples of the recommendation query.
+/// Each point is scored against every example separately, see `RecommendRawScorer`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecommendQuery {
+    pub positive: Vec<Vec<VectorElementType>>,
+    pub negative: Vec<Vec<VectorElementType>>,
+}
+
+/// Query of the vector search, defines how stored vectors are scored
+#[derive(Debug, Clone, PartialEq)]
+pub enum QueryVector {
+    /// Look for the vectors closest to the given one
+    Nearest(Vec<VectorElementType>),
+    /// Look for the vectors close to any of positive examples and far from all negative ones.
+    /// Scores are always "larger is better", regardless of the distance function.
+    Recommend(RecommendQuery),
+    /// Look for the points with the largest dot product of their sparse vectors with the given one.
+    /// Served by the sparse vector index instead of the dense vectors.
+    Sparse(SparseVector),
+}
+
+/// Dense part of `QueryVector`, served by vector storages and vector indexes
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DenseQuery<'a> {
+    Nearest(&'a [VectorElementType]),
+    Recommend(&'a RecommendQuery),
+}
+
+impl QueryVector {
+    /// All dense vectors, used by the query
+    pub fn vectors(&self) -> Box<dyn Iterator<Item = &Vec<VectorElementType>> + '_> {
+        match self {
+            QueryVector::Nearest(vector) => Box::new(std::iter::once(vector)),
+            QueryVector::Recommend(query) => {
+                Box::new(query.positive.iter().chain(query.negative.iter()))
+            }
+            QueryVector::Sparse(_) => Box::new(std::iter::empty()),
+        }
+    }
+}
+
+/// Sparse vector, defined by the indices and the values of its non-zero elements.
+/// Could be used along with the dense vector of the point, e.g. for keyword-based relevance.
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub struct SparseVector {
+    /// Indices of the non-zero elements, must be unique
+    pub indices: Vec<u32>,
+    /// Values of the non-zero elements, in the same order as indices
+    pub values: Vec<VectorElementType>,
+}
+
+impl From<Vec<VectorElementType>> for QueryVector {
+    fn from(vector: Vec<VectorElementType>) -> Self {
+        QueryVector::Nearest(vector)
+    }
 }
 
 /// Vector index configuration of the segment
@@ -246,6 +427,12 @@ pub struct HnswConfig {
     /// If payload chunk is smaller than `full_scan_threshold` additional indexing won't be used -
     /// in this case full-scan search should be preferred by query planner and additional indexing is not required.
     pub full_scan_threshold: usize,
+    /// Store HNSW index on disk. If set to false, index will be stored in RAM. Default: false
+    #[serde(default)]
+    pub on_disk: Option<bool>,
+    /// Build HNSW index of appendable segments incrementally, on each update. If set to false, appendable segments are not indexed until optimization. Default: false
+    #[serde(default)]
+    pub incremental: Option<bool>,
 }
 
 impl Default for HnswConfig {
@@ -254,10 +441,24 @@ impl Default for HnswConfig {
             m: 16,
             ef_construct: 100,
             full_scan_threshold: DEFAULT_FULL_SCAN_THRESHOLD,
+            on_disk: None,
+            incremental: None,
         }
     }
 }
 
+impl HnswConfig {
+    /// Whether segments, indexed with the other config, should be re-indexed to apply this one.
+    /// All params are persisted in the index of the segment, except `incremental`,
+    /// which only applies to appendable segments.
+    pub fn mismatch_requires_rebuild(&self, other: &HnswConfig) -> bool {
+        self.m != other.m
+            || self.ef_construct != other.ef_construct
+            || self.full_scan_threshold != other.full_scan_threshold
+            || self.on_disk.unwrap_or(false) != other.on_disk.unwrap_or(false)
+    }
+}
+
 impl Indexes {
     pub fn default_hnsw() -> Self {
         Indexes::Hnsw(Default::default())
@@ -296,6 +497,21 @@ pub enum StorageType {
     InMemory,
     /// Use memmap to store vectors, a little slower than `InMemory`, but requires little RAM
     Mmap,
+    /// Keep only compact product quantization codes of vectors in RAM and score them approximately.
+    /// Original vectors are stored in memmap file
+    ProductQuantized(ProductQuantizationConfig),
+}
+
+/// Config of product quantization of stored vectors
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Copy, Clone, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub struct ProductQuantizationConfig {
+    /// Number of vector dimensions, encoded by a single byte.
+    /// Larger the value - less RAM is required, less accurate the scores are.
+    pub bucket_size: usize,
+    /// Re-score top candidates of the index search with original vectors, stored on disk. Default: true
+    #[serde(default)]
+    pub rescore: Option<bool>,
 }
 
 impl Default for StorageType {
@@ -304,6 +520,25 @@ impl Default for StorageType {
     }
 }
 
+/// Type of stored vector elements.
+/// Queries and returned vectors are always of full precision.
+/// Product quantized storage keeps original vectors in full precision regardless of this type
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Copy, Clone, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum VectorStorageDatatype {
+    /// 4 bytes per element
+    Float32,
+    /// Half precision, 2 bytes per element. Requires twice less RAM and disk space,
+    /// but scores are less accurate
+    Float16,
+}
+
+impl Default for VectorStorageDatatype {
+    fn default() -> Self {
+        VectorStorageDatatype::Float32
+    }
+}
+
 #[derive(Debug, Deserialize, Serialize, JsonSchema, Clone)]
 #[serde(rename_all = "snake_case")]
 pub struct SegmentConfig {
@@ -317,6 +552,9 @@ pub struct SegmentConfig {
     pub payload_index: Option<PayloadIndexType>,
     /// Type of vector storage
     pub storage_type: StorageType,
+    /// Type of stored vector elements
+    #[serde(default)]
+    pub vector_datatype: VectorStorageDatatype,
 }
 
 /// Default value based on <https://github.com/google-research/google-research/blob/master/scann/docs/algorithms.md>
@@ -565,6 +803,34 @@ pub fn infer_value_type(value: &Value) -> Option<PayloadSchemaType> {
     }
 }
 
+/// Value of the keyword or integer payload field, counted by the facet
+#[derive(
+    Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq, Eq, Hash, PartialOrd, Ord,
+)]
+#[serde(untagged)]
+pub enum FacetValue {
+    Keyword(String),
+    Integer(IntPayloadType),
+}
+
+impl FacetValue {
+    /// Values of the payload field, which are indexed by the map index of the given type.
+    /// Repeated values are returned once.
+    pub fn from_value(value: &Value, field_type: PayloadSchemaType) -> Vec<FacetValue> {
+        let single_value = |value: &Value| match field_type {
+            PayloadSchemaType::Keyword => value
+                .as_str()
+                .map(|keyword| FacetValue::Keyword(keyword.to_owned())),
+            PayloadSchemaType::Integer => value.as_i64().map(FacetValue::Integer),
+            PayloadSchemaType::Float | PayloadSchemaType::Geo => None,
+        };
+        match value {
+            Value::Array(values) => values.iter().filter_map(single_value).unique().collect(),
+            _ => single_value(value).into_iter().collect(),
+        }
+    }
+}
+
 /// Match by keyword (deprecated)
 #[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq)]
 #[serde(rename_all = "snake_case")]
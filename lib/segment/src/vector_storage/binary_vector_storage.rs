@@ -0,0 +1,311 @@
+use std::mem::size_of;
+use std::ops::Range;
+use std::path::Path;
+use std::sync::Arc;
+
+use atomic_refcell::AtomicRefCell;
+use bit_vec::BitVec;
+use log::debug;
+use rocksdb::{IteratorMode, DB};
+use serde::{Deserialize, Serialize};
+
+use crate::common::rocksdb_operations::{db_write_options, open_db};
+use crate::entry::entry_point::OperationResult;
+use crate::spaces::binary::{
+    hamming_similarity_packed, pack_bits, packed_len, unpack_bits, PackedWord,
+};
+use crate::spaces::tools::peek_top_largest_scores_iterable;
+use crate::types::{PointOffsetType, ScoreType, VectorElementType};
+use crate::vector_storage::chunked_vectors::ChunkedVectors;
+use crate::vector_storage::{RawScorer, ScoredPointOffset, VectorStorage, VectorStorageSS};
+
+/// In-memory storage of bit-packed binary vectors, compared with Hamming distance.
+/// Persisted on update using `store`, same as `SimpleVectorStorage`
+pub struct BinaryVectorStorage {
+    dim: usize,
+    vectors: ChunkedVectors<PackedWord>,
+    deleted: BitVec,
+    deleted_count: usize,
+    store: DB,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+struct StoredRecord {
+    pub deleted: bool,
+    pub vector: Vec<PackedWord>,
+}
+
+pub struct BinaryRawScorer<'a> {
+    pub query: Vec<PackedWord>,
+    pub vectors: &'a ChunkedVectors<PackedWord>,
+    pub deleted: &'a BitVec,
+}
+
+impl RawScorer for BinaryRawScorer<'_> {
+    fn score_points(&self, points: &[PointOffsetType], scores: &mut [ScoredPointOffset]) -> usize {
+        let mut size: usize = 0;
+        for point_id in points.iter().copied() {
+            if self.deleted[point_id as usize] {
+                continue;
+            }
+            scores[size] = ScoredPointOffset {
+                idx: point_id,
+                score: self.score_point(point_id),
+            };
+
+            size += 1;
+            if size == scores.len() {
+                return size;
+            }
+        }
+        size
+    }
+
+    fn check_point(&self, point: PointOffsetType) -> bool {
+        (point as usize) < self.vectors.len() && !self.deleted[point as usize]
+    }
+
+    fn score_point(&self, point: PointOffsetType) -> ScoreType {
+        hamming_similarity_packed(&self.query, self.vectors.get(point))
+    }
+
+    fn score_internal(&self, point_a: PointOffsetType, point_b: PointOffsetType) -> ScoreType {
+        hamming_similarity_packed(self.vectors.get(point_a), self.vectors.get(point_b))
+    }
+}
+
+pub fn open_binary_vector_storage(
+    path: &Path,
+    dim: usize,
+) -> OperationResult<Arc<AtomicRefCell<VectorStorageSS>>> {
+    let mut vectors = ChunkedVectors::new(packed_len(dim));
+    let mut deleted = BitVec::new();
+    let mut deleted_count = 0;
+
+    let store = open_db(path)?;
+
+    for (key, val) in store.iterator(IteratorMode::Start) {
+        let point_id: PointOffsetType = bincode::deserialize(&key).unwrap();
+        let stored_record: StoredRecord = bincode::deserialize(&val).unwrap();
+        if stored_record.deleted {
+            deleted_count += 1;
+        }
+
+        while deleted.len() <= (point_id as usize) {
+            deleted.push(false);
+        }
+
+        deleted.set(point_id as usize, stored_record.deleted);
+        vectors.insert(point_id, &stored_record.vector);
+    }
+
+    debug!("Segment vectors: {}", vectors.len());
+    debug!(
+        "Estimated segment size {} MB",
+        vectors.len() * packed_len(dim) * size_of::<PackedWord>() / 1024 / 1024
+    );
+
+    Ok(Arc::new(AtomicRefCell::new(BinaryVectorStorage {
+        dim,
+        vectors,
+        deleted,
+        deleted_count,
+        store,
+    })))
+}
+
+impl BinaryVectorStorage {
+    fn update_stored(&self, point_id: PointOffsetType) -> OperationResult<()> {
+        let record = StoredRecord {
+            deleted: self.deleted[point_id as usize],
+            vector: self.vectors.get(point_id).to_vec(),
+        };
+        self.store.put_opt(
+            bincode::serialize(&point_id).unwrap(),
+            bincode::serialize(&record).unwrap(),
+            &db_write_options(),
+        )?;
+
+        Ok(())
+    }
+
+    fn raw_scorer_packed(&self, query: Vec<PackedWord>) -> BinaryRawScorer {
+        BinaryRawScorer {
+            query,
+            vectors: &self.vectors,
+            deleted: &self.deleted,
+        }
+    }
+}
+
+impl VectorStorage for BinaryVectorStorage {
+    fn vector_dim(&self) -> usize {
+        self.dim
+    }
+
+    fn vector_count(&self) -> usize {
+        self.vectors.len() - self.deleted_count
+    }
+
+    fn deleted_count(&self) -> usize {
+        self.deleted_count
+    }
+
+    fn total_vector_count(&self) -> usize {
+        self.vectors.len()
+    }
+
+    fn get_vector(&self, key: PointOffsetType) -> Option<Vec<VectorElementType>> {
+        if self.deleted.get(key as usize).unwrap_or(true) {
+            return None;
+        }
+        Some(unpack_bits(self.vectors.get(key), self.dim))
+    }
+
+    fn put_vector(&mut self, vector: Vec<VectorElementType>) -> OperationResult<PointOffsetType> {
+        assert_eq!(self.dim, vector.len());
+        let new_id = self.vectors.push(&pack_bits(&vector));
+        self.deleted.push(false);
+        self.update_stored(new_id)?;
+        Ok(new_id)
+    }
+
+    fn update_vector(
+        &mut self,
+        key: PointOffsetType,
+        vector: Vec<VectorElementType>,
+    ) -> OperationResult<PointOffsetType> {
+        self.vectors.insert(key, &pack_bits(&vector));
+        self.update_stored(key)?;
+        Ok(key)
+    }
+
+    fn update_from(&mut self, other: &VectorStorageSS) -> OperationResult<Range<PointOffsetType>> {
+        let start_index = self.vectors.len() as PointOffsetType;
+        for point_id in other.iter_ids() {
+            let other_vector = other.get_vector(point_id).unwrap();
+            self.deleted.push(false);
+            let new_id = self.vectors.push(&pack_bits(&other_vector));
+            self.update_stored(new_id)?;
+        }
+        let end_index = self.vectors.len() as PointOffsetType;
+        Ok(start_index..end_index)
+    }
+
+    fn delete(&mut self, key: PointOffsetType) -> OperationResult<()> {
+        if (key as usize) >= self.deleted.len() {
+            return Ok(());
+        }
+        if !self.deleted[key as usize] {
+            self.deleted_count += 1;
+        }
+        self.deleted.set(key as usize, true);
+        self.update_stored(key)?;
+        Ok(())
+    }
+
+    fn is_deleted(&self, key: PointOffsetType) -> bool {
+        self.deleted[key as usize]
+    }
+
+    fn iter_ids(&self) -> Box<dyn Iterator<Item = PointOffsetType> + '_> {
+        let iter = (0..self.vectors.len() as PointOffsetType)
+            .filter(move |id| !self.deleted[*id as usize]);
+        Box::new(iter)
+    }
+
+    fn flush(&self) -> OperationResult<()> {
+        Ok(self.store.flush()?)
+    }
+
+    fn raw_scorer(&self, vector: Vec<VectorElementType>) -> Box<dyn RawScorer + '_> {
+        Box::new(self.raw_scorer_packed(pack_bits(&vector)))
+    }
+
+    fn raw_scorer_internal(&self, point_id: PointOffsetType) -> Box<dyn RawScorer + '_> {
+        Box::new(self.raw_scorer_packed(self.vectors.get(point_id).to_vec()))
+    }
+
+    fn score_points(
+        &self,
+        vector: &[VectorElementType],
+        points: &mut dyn Iterator<Item = PointOffsetType>,
+        top: usize,
+    ) -> Vec<ScoredPointOffset> {
+        let query = pack_bits(vector);
+        let scores = points
+            .filter(|point_id| !self.deleted[*point_id as usize])
+            .map(|point_id| ScoredPointOffset {
+                idx: point_id,
+                score: hamming_similarity_packed(&query, self.vectors.get(point_id)),
+            });
+        peek_top_largest_scores_iterable(scores, top)
+    }
+
+    fn score_all(&self, vector: &[VectorElementType], top: usize) -> Vec<ScoredPointOffset> {
+        self.score_points(vector, &mut self.iter_ids(), top)
+    }
+
+    fn score_internal(
+        &self,
+        point: PointOffsetType,
+        points: &mut dyn Iterator<Item = PointOffsetType>,
+        top: usize,
+    ) -> Vec<ScoredPointOffset> {
+        let vector = self.get_vector(point).unwrap();
+        self.score_points(&vector, points, top)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tempdir::TempDir;
+
+    use super::*;
+
+    #[test]
+    fn test_score_points() {
+        let dir = TempDir::new("storage_dir").unwrap();
+        let dim = 70;
+        let storage = open_binary_vector_storage(dir.path(), dim).unwrap();
+
+        let vec0: Vec<_> = (0..dim).map(|i| (i % 2) as VectorElementType).collect();
+        let vec1: Vec<_> = (0..dim).map(|i| (i % 3 == 0) as u8 as f32).collect();
+        let vec2 = vec![1.0; dim];
+        let mut vec3 = vec![1.0; dim];
+        vec3[69] = 0.0;
+
+        {
+            let mut borrowed_storage = storage.borrow_mut();
+            borrowed_storage.put_vector(vec0.clone()).unwrap();
+            borrowed_storage.put_vector(vec1).unwrap();
+            borrowed_storage.put_vector(vec2.clone()).unwrap();
+            borrowed_storage.put_vector(vec3).unwrap();
+
+            assert_eq!(borrowed_storage.get_vector(0).unwrap(), vec0);
+
+            let closest = borrowed_storage.score_all(&vec2, 2);
+            assert_eq!(closest[0].idx, 2);
+            assert_eq!(closest[0].score, 0.0);
+            assert_eq!(closest[1].idx, 3);
+            assert_eq!(closest[1].score, -1.0);
+
+            let raw_scorer = borrowed_storage.raw_scorer(vec2.clone());
+            assert_eq!(raw_scorer.score_point(0), -35.0);
+            assert_eq!(raw_scorer.score_internal(2, 3), -1.0);
+            drop(raw_scorer);
+
+            borrowed_storage.delete(2).unwrap();
+            borrowed_storage.flush().unwrap();
+        }
+        drop(storage);
+
+        // Vectors are restored from persisted storage
+        let storage = open_binary_vector_storage(dir.path(), dim).unwrap();
+        let borrowed_storage = storage.borrow();
+        assert_eq!(borrowed_storage.vector_count(), 3);
+        assert_eq!(borrowed_storage.get_vector(0).unwrap(), vec0);
+        let closest = borrowed_storage.score_all(&vec2, 1);
+        assert_eq!(closest[0].idx, 3);
+    }
+}
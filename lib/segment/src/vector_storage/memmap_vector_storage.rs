@@ -1,9 +1,13 @@
-use crate::entry::entry_point::OperationResult;
+use crate::entry::entry_point::{OperationError, OperationResult};
+use crate::spaces::half::Float16;
 use crate::spaces::metric::Metric;
-use crate::spaces::simple::{CosineMetric, DotProductMetric, EuclidMetric};
+use crate::spaces::simple::{CosineMetric, DotProductMetric, EuclidMetric, ManhattanMetric};
 use crate::spaces::tools::peek_top_largest_scores_iterable;
-use crate::types::{Distance, PointOffsetType, ScoreType, VectorElementType};
+use crate::types::{
+    Distance, PointOffsetType, ScoreType, VectorElementType, VectorStorageDatatype,
+};
 use crate::vector_storage::mmap_vectors::MmapVectors;
+use crate::vector_storage::stored_element::StoredElement;
 use crate::vector_storage::{RawScorer, ScoredPointOffset, VectorStorage, VectorStorageSS};
 use atomic_refcell::AtomicRefCell;
 use std::fs::{create_dir_all, OpenOptions};
@@ -20,15 +24,16 @@ fn vf_to_u8<T>(v: &[T]) -> &[u8] {
 
 /// An scored iterator over search result.
 /// Keeps iteration context, which allows to use this iterator in external functions safely
-pub struct MemmapRawScorer<'a, TMetric: Metric> {
+pub struct MemmapRawScorer<'a, TMetric: Metric, TElement: StoredElement = VectorElementType> {
     query: Vec<VectorElementType>,
     metric: std::marker::PhantomData<TMetric>,
-    mmap_store: &'a MmapVectors,
+    mmap_store: &'a MmapVectors<TElement>,
 }
 
-impl<TMetric> RawScorer for MemmapRawScorer<'_, TMetric>
+impl<TMetric, TElement> RawScorer for MemmapRawScorer<'_, TMetric, TElement>
 where
     TMetric: Metric,
+    TElement: StoredElement,
 {
     fn score_points(&self, points: &[PointOffsetType], scores: &mut [ScoredPointOffset]) -> usize {
         let mut size: usize = 0;
@@ -39,7 +44,7 @@ where
             let other_vector = self.mmap_store.raw_vector(*point).unwrap();
             scores[size] = ScoredPointOffset {
                 idx: *point,
-                score: TMetric::similarity(&self.query, other_vector),
+                score: TElement::similarity::<TMetric>(&self.query, other_vector),
             };
 
             size += 1;
@@ -57,13 +62,13 @@ where
 
     fn score_point(&self, point: PointOffsetType) -> ScoreType {
         let other_vector = self.mmap_store.raw_vector(point).unwrap();
-        TMetric::similarity(&self.query, other_vector)
+        TElement::similarity::<TMetric>(&self.query, other_vector)
     }
 
     fn score_internal(&self, point_a: PointOffsetType, point_b: PointOffsetType) -> ScoreType {
         let vector_a = self.mmap_store.raw_vector(point_a).unwrap();
         let vector_b = self.mmap_store.raw_vector(point_b).unwrap();
-        TMetric::similarity(vector_a, vector_b)
+        TElement::similarity_stored::<TMetric>(vector_a, vector_b)
     }
 }
 
@@ -73,10 +78,10 @@ where
 /// but possible to mark some vectors as removed
 ///
 /// Mem-mapped storage can only be constructed from another storage
-pub struct MemmapVectorStorage<TMetric: Metric> {
+pub struct MemmapVectorStorage<TMetric: Metric, TElement: StoredElement = VectorElementType> {
     vectors_path: PathBuf,
     deleted_path: PathBuf,
-    mmap_store: Option<MmapVectors>,
+    mmap_store: Option<MmapVectors<TElement>>,
     metric: PhantomData<TMetric>,
 }
 
@@ -85,44 +90,79 @@ pub fn open_memmap_vector_storage(
     dim: usize,
     distance: Distance,
 ) -> OperationResult<Arc<AtomicRefCell<VectorStorageSS>>> {
-    create_dir_all(path)?;
-
-    let vectors_path = path.join("matrix.dat");
-    let deleted_path = path.join("deleted.dat");
+    open_memmap_vector_storage_with_datatype(path, dim, distance, VectorStorageDatatype::Float32)
+}
 
-    let mmap_store = MmapVectors::open(&vectors_path, &deleted_path, dim)?;
+pub fn open_memmap_vector_storage_with_datatype(
+    path: &Path,
+    dim: usize,
+    distance: Distance,
+    datatype: VectorStorageDatatype,
+) -> OperationResult<Arc<AtomicRefCell<VectorStorageSS>>> {
+    match datatype {
+        VectorStorageDatatype::Float32 => {
+            open_typed_memmap_vector_storage::<VectorElementType>(path, dim, distance)
+        }
+        VectorStorageDatatype::Float16 => {
+            open_typed_memmap_vector_storage::<Float16>(path, dim, distance)
+        }
+    }
+}
 
+fn open_typed_memmap_vector_storage<TElement: StoredElement>(
+    path: &Path,
+    dim: usize,
+    distance: Distance,
+) -> OperationResult<Arc<AtomicRefCell<VectorStorageSS>>> {
     match distance {
         Distance::Cosine => Ok(Arc::new(AtomicRefCell::new(MemmapVectorStorage::<
             CosineMetric,
-        > {
-            vectors_path,
-            deleted_path,
-            mmap_store: Some(mmap_store),
-            metric: PhantomData,
-        }))),
+            TElement,
+        >::open(path, dim)?))),
         Distance::Euclid => Ok(Arc::new(AtomicRefCell::new(MemmapVectorStorage::<
             EuclidMetric,
-        > {
-            vectors_path,
-            deleted_path,
-            mmap_store: Some(mmap_store),
-            metric: PhantomData,
-        }))),
+            TElement,
+        >::open(path, dim)?))),
         Distance::Dot => Ok(Arc::new(AtomicRefCell::new(MemmapVectorStorage::<
             DotProductMetric,
-        > {
+            TElement,
+        >::open(path, dim)?))),
+        Distance::Hamming => Err(OperationError::WrongConfig {
+            description: "Hamming vectors are only kept in binary vector storage".to_string(),
+        }),
+        Distance::Manhattan => Ok(Arc::new(AtomicRefCell::new(MemmapVectorStorage::<
+            ManhattanMetric,
+            TElement,
+        >::open(path, dim)?))),
+    }
+}
+
+impl<TMetric, TElement> MemmapVectorStorage<TMetric, TElement>
+where
+    TMetric: Metric,
+    TElement: StoredElement,
+{
+    pub fn open(path: &Path, dim: usize) -> OperationResult<Self> {
+        create_dir_all(path)?;
+
+        let vectors_path = path.join("matrix.dat");
+        let deleted_path = path.join("deleted.dat");
+
+        let mmap_store = MmapVectors::open(&vectors_path, &deleted_path, dim)?;
+
+        Ok(MemmapVectorStorage {
             vectors_path,
             deleted_path,
             mmap_store: Some(mmap_store),
             metric: PhantomData,
-        }))),
+        })
     }
 }
 
-impl<TMetric> VectorStorage for MemmapVectorStorage<TMetric>
+impl<TMetric, TElement> VectorStorage for MemmapVectorStorage<TMetric, TElement>
 where
     TMetric: Metric,
+    TElement: StoredElement,
 {
     fn vector_dim(&self) -> usize {
         self.mmap_store.as_ref().unwrap().dim
@@ -176,8 +216,9 @@ where
                 .open(&self.vectors_path)?;
 
             for id in other.iter_ids() {
-                let vector = &other.get_vector(id).unwrap();
-                let raw_bites = vf_to_u8(vector);
+                let vector = other.get_vector(id).unwrap();
+                let stored = TElement::from_vector(&vector);
+                let raw_bites = vf_to_u8(&stored[..]);
                 file.write_all(raw_bites)?;
                 end_index += 1;
             }
@@ -234,7 +275,7 @@ where
     }
 
     fn raw_scorer(&self, vector: Vec<VectorElementType>) -> Box<dyn RawScorer + '_> {
-        Box::new(MemmapRawScorer::<TMetric> {
+        Box::new(MemmapRawScorer::<TMetric, TElement> {
             query: TMetric::preprocess(&vector).unwrap_or(vector),
             metric: PhantomData,
             mmap_store: self.mmap_store.as_ref().unwrap(),
@@ -242,7 +283,7 @@ where
     }
 
     fn raw_scorer_internal(&self, point_id: PointOffsetType) -> Box<dyn RawScorer + '_> {
-        Box::new(MemmapRawScorer::<TMetric> {
+        Box::new(MemmapRawScorer::<TMetric, TElement> {
             query: self.get_vector(point_id).unwrap(),
             metric: PhantomData,
             mmap_store: self.mmap_store.as_ref().unwrap(),
@@ -272,7 +313,7 @@ where
                 let other_vector = self.mmap_store.as_ref().unwrap().raw_vector(point).unwrap();
                 ScoredPointOffset {
                     idx: point,
-                    score: TMetric::similarity(preprocessed_vector, other_vector),
+                    score: TElement::similarity::<TMetric>(preprocessed_vector, other_vector),
                 }
             });
         peek_top_largest_scores_iterable(scores, top)
@@ -287,7 +328,7 @@ where
             let other_vector = self.mmap_store.as_ref().unwrap().raw_vector(point).unwrap();
             ScoredPointOffset {
                 idx: point,
-                score: TMetric::similarity(preprocessed_vector, other_vector),
+                score: TElement::similarity::<TMetric>(preprocessed_vector, other_vector),
             }
         });
 
@@ -308,7 +349,9 @@ where
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::vector_storage::simple_vector_storage::open_simple_vector_storage;
+    use crate::vector_storage::simple_vector_storage::{
+        open_simple_vector_storage, open_simple_vector_storage_with_datatype,
+    };
     use std::mem::transmute;
     use tempdir::TempDir;
 
@@ -420,6 +463,60 @@ mod tests {
         assert_eq!(res[2].score, -1.0);
     }
 
+    #[test]
+    fn test_half_precision_storage() {
+        let dist = Distance::Dot;
+        let dir = TempDir::new("storage_dir").unwrap();
+        let storage = open_memmap_vector_storage_with_datatype(
+            dir.path(),
+            4,
+            dist,
+            VectorStorageDatatype::Float16,
+        )
+        .unwrap();
+        let mut borrowed_storage = storage.borrow_mut();
+
+        let vec1 = vec![1.0, 0.0, 1.0, 1.0];
+        let vec2 = vec![0.5, 0.25, -1.0, 0.0];
+        let vec3 = vec![1.0, 1.0, 1.0, 1.0];
+
+        {
+            let dir2 = TempDir::new("storage_dir2").unwrap();
+            let storage2 = open_simple_vector_storage_with_datatype(
+                dir2.path(),
+                4,
+                dist,
+                VectorStorageDatatype::Float16,
+            )
+            .unwrap();
+            {
+                let mut borrowed_storage2 = storage2.borrow_mut();
+                borrowed_storage2.put_vector(vec1).unwrap();
+                borrowed_storage2.put_vector(vec2.clone()).unwrap();
+                borrowed_storage2.put_vector(vec3.clone()).unwrap();
+            }
+            borrowed_storage.update_from(&*storage2.borrow()).unwrap();
+        }
+
+        // 4 bytes of header and 2 bytes per element
+        let file_size = std::fs::metadata(dir.path().join("matrix.dat"))
+            .unwrap()
+            .len();
+        assert_eq!(file_size, 4 + 3 * 4 * 2);
+
+        // Values are exactly representable in half precision
+        assert_eq!(borrowed_storage.get_vector(1).unwrap(), vec2);
+
+        let res = borrowed_storage.score_all(&vec3, 3);
+        assert_eq!(res[0].idx, 2);
+        assert_eq!(res[0].score, 4.0);
+        assert_eq!(res[2].idx, 1);
+        assert_eq!(res[2].score, -0.25);
+
+        let scorer = borrowed_storage.raw_scorer_internal(0);
+        assert_eq!(scorer.score_internal(0, 2), 3.0);
+    }
+
     #[test]
     fn test_casts() {
         let data: Vec<VectorElementType> = vec![0.42, 0.069, 333.1, 100500.];
@@ -1,23 +1,26 @@
 use crate::common::error_logging::LogError;
 use crate::entry::entry_point::OperationResult;
 use crate::types::{PointOffsetType, VectorElementType};
+use crate::vector_storage::stored_element::StoredElement;
 use memmap::{Mmap, MmapMut, MmapOptions};
 use std::fs::{File, OpenOptions};
 use std::io::Write;
-use std::mem::{size_of, transmute};
+use std::marker::PhantomData;
+use std::mem::size_of;
 use std::path::Path;
 
 const HEADER_SIZE: usize = 4;
 const DELETED_HEADER: &[u8; 4] = b"drop";
 const VECTORS_HEADER: &[u8; 4] = b"data";
 
-/// Mem-mapped file with vectors and soft-delete flags
-pub struct MmapVectors {
+/// Mem-mapped file with vectors of `T` elements and soft-delete flags
+pub struct MmapVectors<T = VectorElementType> {
     pub dim: usize,
     pub num_vectors: usize,
     mmap: Mmap,
     deleted_mmap: MmapMut,
     pub deleted_count: usize,
+    element: PhantomData<T>,
 }
 
 fn open_read(path: &Path) -> OperationResult<Mmap> {
@@ -50,14 +53,14 @@ fn ensure_mmap_file_exists(path: &Path, header: &[u8]) -> OperationResult<()> {
     Ok(())
 }
 
-impl MmapVectors {
+impl<T: Copy> MmapVectors<T> {
     pub fn open(vectors_path: &Path, deleted_path: &Path, dim: usize) -> OperationResult<Self> {
         ensure_mmap_file_exists(vectors_path, VECTORS_HEADER).describe("Create mmap data file")?;
         ensure_mmap_file_exists(deleted_path, DELETED_HEADER)
             .describe("Create mmap deleted flags file")?;
 
         let mmap = open_read(vectors_path).describe("Open mmap for reading")?;
-        let num_vectors = (mmap.len() - HEADER_SIZE) / dim / size_of::<VectorElementType>();
+        let num_vectors = (mmap.len() - HEADER_SIZE) / dim / size_of::<T>();
 
         let deleted_mmap = open_write(deleted_path).describe("Open mmap for writing")?;
 
@@ -71,11 +74,12 @@ impl MmapVectors {
             mmap,
             deleted_mmap,
             deleted_count,
+            element: PhantomData,
         })
     }
 
     pub fn data_offset(&self, key: PointOffsetType) -> Option<usize> {
-        let vector_data_length = self.dim * size_of::<VectorElementType>();
+        let vector_data_length = self.dim * size_of::<T>();
         let offset = (key as usize) * vector_data_length + HEADER_SIZE;
         if key >= (self.num_vectors as PointOffsetType) {
             return None;
@@ -84,16 +88,15 @@ impl MmapVectors {
     }
 
     pub fn raw_size(&self) -> usize {
-        self.dim * size_of::<VectorElementType>()
+        self.dim * size_of::<T>()
     }
 
-    pub fn raw_vector_offset(&self, offset: usize) -> &[VectorElementType] {
+    pub fn raw_vector_offset(&self, offset: usize) -> &[T] {
         let byte_slice = &self.mmap[offset..(offset + self.raw_size())];
-        let arr: &[VectorElementType] = unsafe { transmute(byte_slice) };
-        &arr[0..self.dim]
+        unsafe { std::slice::from_raw_parts(byte_slice.as_ptr() as *const T, self.dim) }
     }
 
-    pub fn raw_vector(&self, key: PointOffsetType) -> Option<&[VectorElementType]> {
+    pub fn raw_vector(&self, key: PointOffsetType) -> Option<&[T]> {
         self.data_offset(key)
             .map(|offset| self.raw_vector_offset(offset))
     }
@@ -104,13 +107,16 @@ impl MmapVectors {
             .map(|x| *x > 0)
     }
 
-    /// Creates returns owned vector (copy of internal vector)
-    pub fn get_vector(&self, key: PointOffsetType) -> Option<Vec<VectorElementType>> {
+    /// Creates returns owned vector (copy of internal vector), converted to full precision
+    pub fn get_vector(&self, key: PointOffsetType) -> Option<Vec<VectorElementType>>
+    where
+        T: StoredElement,
+    {
         match self.deleted(key) {
             None | Some(true) => None,
             Some(false) => self
                 .data_offset(key)
-                .map(|offset| self.raw_vector_offset(offset).to_vec()),
+                .map(|offset| T::to_vector(self.raw_vector_offset(offset)).into_owned()),
         }
     }
 
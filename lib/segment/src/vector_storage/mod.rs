@@ -1,8 +1,13 @@
+pub mod binary_vector_storage;
 pub mod chunked_vectors;
 pub mod memmap_vector_storage;
 mod mmap_vectors;
+mod product_quantization;
+pub mod quantized_vector_storage;
+pub mod recommend_scorer;
 pub mod simple_vector_storage;
 pub mod storage_points_iterator;
+pub mod stored_element;
 mod vector_storage_base;
 
 pub use vector_storage_base::*;
@@ -0,0 +1,235 @@
+use crate::spaces::metric::Metric;
+use crate::spaces::simple::euclid_similarity;
+use crate::types::{ScoreType, VectorElementType};
+use rand::seq::index::sample;
+use rand::Rng;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::ops::Range;
+
+/// Max number of centroids in a codebook, so that each code fits into a single byte
+pub const CODEBOOK_SIZE: usize = 256;
+/// Number of k-means iterations, performed to train a codebook
+const TRAINING_ITERATIONS: usize = 16;
+
+/// Splits vectors into buckets of consecutive dimensions and encodes each bucket
+/// with the id of the closest centroid of the bucket codebook.
+///
+/// Scores between a query and encoded vectors are computed asymmetrically:
+/// query is not encoded, instead scores between query buckets and all centroids are
+/// pre-computed into a lookup table, so scoring an encoded vector only requires summation.
+/// It works for Cosine, Dot, Euclid and Manhattan metrics, as their similarity is a sum over dimensions.
+/// Hamming vectors are stored bit-packed and are never quantized.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct ProductQuantizer {
+    dim: usize,
+    bucket_size: usize,
+    /// Flattened centroids of each bucket
+    codebooks: Vec<Vec<VectorElementType>>,
+}
+
+impl ProductQuantizer {
+    /// Train codebooks with k-means over the given vectors
+    pub fn train(dim: usize, bucket_size: usize, vectors: &[Vec<VectorElementType>]) -> Self {
+        let bucket_size = bucket_size.clamp(1, dim.max(1));
+        let num_buckets = (dim + bucket_size - 1) / bucket_size;
+
+        let codebooks = (0..num_buckets)
+            .into_par_iter()
+            .map(|bucket| {
+                let range = Self::bucket_range(dim, bucket_size, bucket);
+                let sub_vectors: Vec<&[VectorElementType]> = vectors
+                    .iter()
+                    .map(|vector| &vector[range.clone()])
+                    .collect();
+                Self::train_codebook(&sub_vectors, range.len())
+            })
+            .collect();
+
+        ProductQuantizer {
+            dim,
+            bucket_size,
+            codebooks,
+        }
+    }
+
+    fn bucket_range(dim: usize, bucket_size: usize, bucket: usize) -> Range<usize> {
+        let start = bucket * bucket_size;
+        start..(start + bucket_size).min(dim)
+    }
+
+    fn train_codebook(vectors: &[&[VectorElementType]], len: usize) -> Vec<VectorElementType> {
+        let num_centroids = vectors.len().min(CODEBOOK_SIZE);
+        let mut rng = rand::thread_rng();
+
+        let mut centroids: Vec<VectorElementType> = sample(&mut rng, vectors.len(), num_centroids)
+            .into_iter()
+            .flat_map(|idx| vectors[idx].iter().copied())
+            .collect();
+
+        for _ in 0..TRAINING_ITERATIONS {
+            let mut sums = vec![0.0; centroids.len()];
+            let mut counts = vec![0usize; num_centroids];
+            for vector in vectors {
+                let code = Self::closest_centroid(&centroids, len, vector);
+                counts[code] += 1;
+                sums[code * len..(code + 1) * len]
+                    .iter_mut()
+                    .zip(vector.iter())
+                    .for_each(|(sum, value)| *sum += value);
+            }
+            for (code, count) in counts.into_iter().enumerate() {
+                let centroid = &mut centroids[code * len..(code + 1) * len];
+                if count == 0 {
+                    // Re-seed empty cluster with a random vector
+                    let vector = vectors[rng.gen_range(0..vectors.len())];
+                    centroid.copy_from_slice(vector);
+                    continue;
+                }
+                centroid
+                    .iter_mut()
+                    .zip(&sums[code * len..(code + 1) * len])
+                    .for_each(|(value, sum)| *value = sum / count as VectorElementType);
+            }
+        }
+        centroids
+    }
+
+    fn closest_centroid(
+        centroids: &[VectorElementType],
+        len: usize,
+        vector: &[VectorElementType],
+    ) -> usize {
+        centroids
+            .chunks_exact(len)
+            .map(|centroid| euclid_similarity(centroid, vector))
+            .enumerate()
+            .fold((0, ScoreType::MIN), |best, (code, score)| {
+                if score > best.1 {
+                    (code, score)
+                } else {
+                    best
+                }
+            })
+            .0
+    }
+
+    pub fn num_buckets(&self) -> usize {
+        self.codebooks.len()
+    }
+
+    fn bucket_len(&self, bucket: usize) -> usize {
+        Self::bucket_range(self.dim, self.bucket_size, bucket).len()
+    }
+
+    fn centroid(&self, bucket: usize, code: u8) -> &[VectorElementType] {
+        let len = self.bucket_len(bucket);
+        let code = code as usize;
+        &self.codebooks[bucket][code * len..(code + 1) * len]
+    }
+
+    /// Encode vector with one byte per bucket
+    pub fn encode(&self, vector: &[VectorElementType]) -> Vec<u8> {
+        (0..self.num_buckets())
+            .map(|bucket| {
+                let range = Self::bucket_range(self.dim, self.bucket_size, bucket);
+                Self::closest_centroid(&self.codebooks[bucket], range.len(), &vector[range]) as u8
+            })
+            .collect()
+    }
+
+    /// Pre-compute scores between buckets of the query and all centroids
+    pub fn lookup_table<TMetric: Metric>(&self, query: &[VectorElementType]) -> Vec<ScoreType> {
+        let mut table = vec![0.0; self.num_buckets() * CODEBOOK_SIZE];
+        for (bucket, codebook) in self.codebooks.iter().enumerate() {
+            let range = Self::bucket_range(self.dim, self.bucket_size, bucket);
+            let len = range.len();
+            let query_bucket = &query[range];
+            for (code, centroid) in codebook.chunks_exact(len).enumerate() {
+                table[bucket * CODEBOOK_SIZE + code] = TMetric::similarity(query_bucket, centroid);
+            }
+        }
+        table
+    }
+
+    /// Approximate score of encoded vector, using lookup table of the query
+    pub fn score_codes(lookup_table: &[ScoreType], codes: &[u8]) -> ScoreType {
+        codes
+            .iter()
+            .enumerate()
+            .map(|(bucket, code)| lookup_table[bucket * CODEBOOK_SIZE + *code as usize])
+            .sum()
+    }
+
+    /// Approximate score between two encoded vectors
+    pub fn score_between_codes<TMetric: Metric>(
+        &self,
+        codes_a: &[u8],
+        codes_b: &[u8],
+    ) -> ScoreType {
+        codes_a
+            .iter()
+            .zip(codes_b)
+            .enumerate()
+            .map(|(bucket, (code_a, code_b))| {
+                TMetric::similarity(
+                    self.centroid(bucket, *code_a),
+                    self.centroid(bucket, *code_b),
+                )
+            })
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::index_fixtures::random_vector;
+    use crate::spaces::simple::DotProductMetric;
+    use rand::thread_rng;
+
+    #[test]
+    fn test_encoded_scores_are_close() {
+        let mut rnd = thread_rng();
+        let dim = 16;
+        let vectors: Vec<_> = (0..2000).map(|_| random_vector(&mut rnd, dim)).collect();
+
+        let quantizer = ProductQuantizer::train(dim, 2, &vectors);
+        assert_eq!(quantizer.num_buckets(), 8);
+
+        let query = random_vector(&mut rnd, dim);
+        let lookup_table = quantizer.lookup_table::<DotProductMetric>(&query);
+
+        for vector in vectors.iter().take(100) {
+            let codes = quantizer.encode(vector);
+            assert_eq!(codes.len(), 8);
+
+            let exact = DotProductMetric::similarity(&query, vector);
+            let approximate = ProductQuantizer::score_codes(&lookup_table, &codes);
+            assert!(
+                (exact - approximate).abs() < 0.5,
+                "exact score {} is too far from {}",
+                exact,
+                approximate
+            );
+        }
+    }
+
+    #[test]
+    fn test_incomplete_last_bucket() {
+        let mut rnd = thread_rng();
+        let dim = 7;
+        let vectors: Vec<_> = (0..100).map(|_| random_vector(&mut rnd, dim)).collect();
+
+        // Number of centroids is limited by the number of training vectors
+        let quantizer = ProductQuantizer::train(dim, 3, &vectors);
+        assert_eq!(quantizer.num_buckets(), 3);
+
+        // Vectors of the training set are encoded exactly, as each of them is a centroid
+        let codes = quantizer.encode(&vectors[0]);
+        let lookup_table = quantizer.lookup_table::<DotProductMetric>(&vectors[1]);
+        let score = ProductQuantizer::score_codes(&lookup_table, &codes);
+        let exact = DotProductMetric::similarity(&vectors[0], &vectors[1]);
+        assert!((exact - score).abs() < 1e-4);
+    }
+}
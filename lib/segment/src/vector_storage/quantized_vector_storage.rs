@@ -0,0 +1,454 @@
+use crate::common::file_operations::{atomic_save_bin, read_bin};
+use crate::entry::entry_point::{OperationError, OperationResult};
+use crate::spaces::metric::Metric;
+use crate::spaces::simple::{CosineMetric, DotProductMetric, EuclidMetric, ManhattanMetric};
+use crate::types::{
+    DenseQuery, Distance, PointOffsetType, ProductQuantizationConfig, ScoreType, VectorElementType,
+};
+use crate::vector_storage::memmap_vector_storage::MemmapVectorStorage;
+use crate::vector_storage::product_quantization::ProductQuantizer;
+use crate::vector_storage::{RawScorer, ScoredPointOffset, VectorStorage, VectorStorageSS};
+use atomic_refcell::AtomicRefCell;
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+const QUANTIZATION_FILE: &str = "quantization.bin";
+/// Max number of vectors, used to train codebooks
+const TRAINING_SAMPLE_SIZE: usize = 50_000;
+
+/// Persisted state of the quantization: codebooks and codes of all stored vectors
+#[derive(Debug, Deserialize, Serialize, Clone)]
+struct QuantizedVectors {
+    quantizer: ProductQuantizer,
+    codes: Vec<u8>,
+}
+
+impl QuantizedVectors {
+    /// Codes of the point, `None` if the point was added after quantization
+    fn codes(&self, point: PointOffsetType) -> Option<&[u8]> {
+        let num_buckets = self.quantizer.num_buckets();
+        let offset = point as usize * num_buckets;
+        self.codes.get(offset..offset + num_buckets)
+    }
+}
+
+/// Scores encoded vectors with pre-computed lookup table of the query.
+/// Points without codes are scored with the original vectors.
+pub struct QuantizedRawScorer<'a, TMetric: Metric> {
+    query: Vec<VectorElementType>,
+    lookup_table: Vec<ScoreType>,
+    quantized: &'a QuantizedVectors,
+    originals: &'a MemmapVectorStorage<TMetric>,
+}
+
+impl<TMetric> QuantizedRawScorer<'_, TMetric>
+where
+    TMetric: Metric,
+{
+    fn score_original(&self, query: &[VectorElementType], point: PointOffsetType) -> ScoreType {
+        self.originals
+            .get_vector(point)
+            .map_or(ScoreType::MIN, |vector| TMetric::similarity(query, &vector))
+    }
+}
+
+impl<TMetric> RawScorer for QuantizedRawScorer<'_, TMetric>
+where
+    TMetric: Metric,
+{
+    fn score_points(&self, points: &[PointOffsetType], scores: &mut [ScoredPointOffset]) -> usize {
+        let mut size: usize = 0;
+        for point in points.iter().copied() {
+            if !self.check_point(point) {
+                continue;
+            }
+            scores[size] = ScoredPointOffset {
+                idx: point,
+                score: self.score_point(point),
+            };
+
+            size += 1;
+            if size == scores.len() {
+                return size;
+            }
+        }
+        size
+    }
+
+    fn check_point(&self, point: PointOffsetType) -> bool {
+        (point as usize) < self.originals.total_vector_count() && !self.originals.is_deleted(point)
+    }
+
+    fn score_point(&self, point: PointOffsetType) -> ScoreType {
+        match self.quantized.codes(point) {
+            Some(codes) => ProductQuantizer::score_codes(&self.lookup_table, codes),
+            None => self.score_original(&self.query, point),
+        }
+    }
+
+    fn score_internal(&self, point_a: PointOffsetType, point_b: PointOffsetType) -> ScoreType {
+        match (self.quantized.codes(point_a), self.quantized.codes(point_b)) {
+            (Some(codes_a), Some(codes_b)) => self
+                .quantized
+                .quantizer
+                .score_between_codes::<TMetric>(codes_a, codes_b),
+            _ => match self.originals.get_vector(point_a) {
+                Some(vector_a) => self.score_original(&vector_a, point_b),
+                None => ScoreType::MIN,
+            },
+        }
+    }
+}
+
+/// Keeps product quantization codes of vectors in RAM, while original vectors are mem-mapped.
+///
+/// Index search is performed over the codes, top candidates are optionally re-scored with
+/// the original vectors. Exact search always uses the original vectors.
+///
+/// Same as mem-mapped storage, it can only be constructed from another storage.
+/// Codebooks are trained once, when all vectors are added and the storage is finalized.
+pub struct QuantizedVectorStorage<TMetric: Metric> {
+    quantization_path: PathBuf,
+    config: ProductQuantizationConfig,
+    originals: MemmapVectorStorage<TMetric>,
+    /// Not set until the storage is finalized. Original vectors are used for scoring until then
+    quantized: Option<QuantizedVectors>,
+}
+
+pub fn open_quantized_vector_storage(
+    path: &Path,
+    dim: usize,
+    distance: Distance,
+    config: ProductQuantizationConfig,
+) -> OperationResult<Arc<AtomicRefCell<VectorStorageSS>>> {
+    match distance {
+        Distance::Cosine => Ok(Arc::new(AtomicRefCell::new(QuantizedVectorStorage::<
+            CosineMetric,
+        >::open(
+            path, dim, config
+        )?))),
+        Distance::Euclid => Ok(Arc::new(AtomicRefCell::new(QuantizedVectorStorage::<
+            EuclidMetric,
+        >::open(
+            path, dim, config
+        )?))),
+        Distance::Dot => Ok(Arc::new(AtomicRefCell::new(QuantizedVectorStorage::<
+            DotProductMetric,
+        >::open(
+            path, dim, config
+        )?))),
+        Distance::Hamming => Err(OperationError::WrongConfig {
+            description: "Hamming vectors are only kept in binary vector storage".to_string(),
+        }),
+        Distance::Manhattan => Ok(Arc::new(AtomicRefCell::new(QuantizedVectorStorage::<
+            ManhattanMetric,
+        >::open(
+            path, dim, config
+        )?))),
+    }
+}
+
+impl<TMetric> QuantizedVectorStorage<TMetric>
+where
+    TMetric: Metric,
+{
+    pub fn open(
+        path: &Path,
+        dim: usize,
+        config: ProductQuantizationConfig,
+    ) -> OperationResult<Self> {
+        let originals = MemmapVectorStorage::open(path, dim)?;
+
+        let quantization_path = path.join(QUANTIZATION_FILE);
+        let quantized = if quantization_path.exists() {
+            Some(read_bin(&quantization_path)?)
+        } else {
+            None
+        };
+
+        Ok(QuantizedVectorStorage {
+            quantization_path,
+            config,
+            originals,
+            quantized,
+        })
+    }
+
+    /// Train codebooks on a sample of stored vectors and encode all of them.
+    /// Storage without vectors is not quantized.
+    fn quantize(&mut self) -> OperationResult<()> {
+        if self.originals.vector_count() == 0 {
+            return Ok(());
+        }
+
+        let total_vectors = self.originals.total_vector_count();
+        let sample_size = self.originals.vector_count().min(TRAINING_SAMPLE_SIZE);
+        let training_vectors = self
+            .originals
+            .sample_ids()
+            .unique()
+            .take(sample_size)
+            .filter_map(|id| self.originals.get_vector(id))
+            .collect_vec();
+
+        let quantizer = ProductQuantizer::train(
+            self.originals.vector_dim(),
+            self.config.bucket_size,
+            &training_vectors,
+        );
+
+        // Deleted vectors are encoded as well, so codes could be addressed by point offset
+        let mut codes = Vec::with_capacity(total_vectors * quantizer.num_buckets());
+        for id in 0..total_vectors as PointOffsetType {
+            match self.originals.get_vector(id) {
+                Some(vector) => codes.extend(quantizer.encode(&vector)),
+                None => codes.extend(std::iter::repeat(0).take(quantizer.num_buckets())),
+            }
+        }
+
+        let quantized = QuantizedVectors { quantizer, codes };
+        atomic_save_bin(&self.quantization_path, &quantized)?;
+        self.quantized = Some(quantized);
+        Ok(())
+    }
+
+    fn raw_scorer_for<'a>(
+        &'a self,
+        quantized: &'a QuantizedVectors,
+        query: &[VectorElementType],
+    ) -> Box<dyn RawScorer + 'a> {
+        Box::new(QuantizedRawScorer::<TMetric> {
+            query: query.to_vec(),
+            lookup_table: quantized.quantizer.lookup_table::<TMetric>(query),
+            quantized,
+            originals: &self.originals,
+        })
+    }
+}
+
+impl<TMetric> VectorStorage for QuantizedVectorStorage<TMetric>
+where
+    TMetric: Metric,
+{
+    fn vector_dim(&self) -> usize {
+        self.originals.vector_dim()
+    }
+
+    fn vector_count(&self) -> usize {
+        self.originals.vector_count()
+    }
+
+    fn deleted_count(&self) -> usize {
+        self.originals.deleted_count()
+    }
+
+    fn total_vector_count(&self) -> usize {
+        self.originals.total_vector_count()
+    }
+
+    fn get_vector(&self, key: PointOffsetType) -> Option<Vec<VectorElementType>> {
+        self.originals.get_vector(key)
+    }
+
+    fn put_vector(&mut self, _vector: Vec<VectorElementType>) -> OperationResult<PointOffsetType> {
+        Err(OperationError::service_error(
+            "Can't put vector in quantized storage",
+        ))
+    }
+
+    fn update_vector(
+        &mut self,
+        _key: PointOffsetType,
+        _vector: Vec<VectorElementType>,
+    ) -> OperationResult<PointOffsetType> {
+        Err(OperationError::service_error(
+            "Can't directly update vector in quantized storage",
+        ))
+    }
+
+    fn update_from(&mut self, other: &VectorStorageSS) -> OperationResult<Range<PointOffsetType>> {
+        self.originals.update_from(other)
+    }
+
+    fn finalize(&mut self) -> OperationResult<()> {
+        self.quantize()
+    }
+
+    fn delete(&mut self, key: PointOffsetType) -> OperationResult<()> {
+        self.originals.delete(key)
+    }
+
+    fn is_deleted(&self, key: PointOffsetType) -> bool {
+        self.originals.is_deleted(key)
+    }
+
+    fn iter_ids(&self) -> Box<dyn Iterator<Item = PointOffsetType> + '_> {
+        self.originals.iter_ids()
+    }
+
+    fn flush(&self) -> OperationResult<()> {
+        self.originals.flush()
+    }
+
+    fn raw_scorer(&self, vector: Vec<VectorElementType>) -> Box<dyn RawScorer + '_> {
+        match &self.quantized {
+            Some(quantized) => {
+                let query = TMetric::preprocess(&vector).unwrap_or(vector);
+                self.raw_scorer_for(quantized, &query)
+            }
+            None => self.originals.raw_scorer(vector),
+        }
+    }
+
+    fn raw_scorer_internal(&self, point_id: PointOffsetType) -> Box<dyn RawScorer + '_> {
+        match (&self.quantized, self.originals.get_vector(point_id)) {
+            (Some(quantized), Some(query)) => self.raw_scorer_for(quantized, &query),
+            _ => self.originals.raw_scorer_internal(point_id),
+        }
+    }
+
+    fn score_points(
+        &self,
+        vector: &[VectorElementType],
+        points: &mut dyn Iterator<Item = PointOffsetType>,
+        top: usize,
+    ) -> Vec<ScoredPointOffset> {
+        self.originals.score_points(vector, points, top)
+    }
+
+    fn score_all(&self, vector: &[VectorElementType], top: usize) -> Vec<ScoredPointOffset> {
+        self.originals.score_all(vector, top)
+    }
+
+    fn score_internal(
+        &self,
+        point: PointOffsetType,
+        points: &mut dyn Iterator<Item = PointOffsetType>,
+        top: usize,
+    ) -> Vec<ScoredPointOffset> {
+        self.originals.score_internal(point, points, top)
+    }
+
+    fn rescore(
+        &self,
+        query: DenseQuery,
+        candidates: Vec<ScoredPointOffset>,
+        top: usize,
+    ) -> Vec<ScoredPointOffset> {
+        // Candidates of not quantized storage are scored with original vectors already
+        if self.quantized.is_none() || !self.config.rescore.unwrap_or(true) {
+            return candidates.into_iter().take(top).collect();
+        }
+        self.originals
+            .score_query(query, &mut candidates.iter().map(|x| x.idx), top)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::index_fixtures::random_vector;
+    use crate::vector_storage::simple_vector_storage::open_simple_vector_storage;
+    use rand::thread_rng;
+    use tempdir::TempDir;
+
+    #[test]
+    fn test_quantized_storage() {
+        let dim = 8;
+        let dist = Distance::Dot;
+        let config = ProductQuantizationConfig {
+            bucket_size: 2,
+            rescore: None,
+        };
+        let mut rnd = thread_rng();
+        let vectors = (0..500).map(|_| random_vector(&mut rnd, dim)).collect_vec();
+
+        let dir = TempDir::new("storage_dir").unwrap();
+        {
+            let storage = open_quantized_vector_storage(dir.path(), dim, dist, config).unwrap();
+            let mut borrowed_storage = storage.borrow_mut();
+
+            let dir2 = TempDir::new("storage_dir2").unwrap();
+            let storage2 = open_simple_vector_storage(dir2.path(), dim, dist).unwrap();
+            {
+                let mut borrowed_storage2 = storage2.borrow_mut();
+                for vector in &vectors {
+                    borrowed_storage2.put_vector(vector.clone()).unwrap();
+                }
+            }
+            borrowed_storage.update_from(&*storage2.borrow()).unwrap();
+            borrowed_storage.delete(3).unwrap();
+            assert!(borrowed_storage.put_vector(vectors[0].clone()).is_err());
+            borrowed_storage.finalize().unwrap();
+            borrowed_storage.flush().unwrap();
+        }
+
+        // Codes are persisted together with original vectors
+        let storage = open_quantized_vector_storage(dir.path(), dim, dist, config).unwrap();
+        let borrowed_storage = storage.borrow();
+
+        assert_eq!(borrowed_storage.vector_count(), 499);
+        assert_eq!(borrowed_storage.get_vector(7).unwrap(), vectors[7]);
+
+        let query = random_vector(&mut rnd, dim);
+        let scorer = borrowed_storage.raw_scorer(query.clone());
+        assert!(!scorer.check_point(3));
+        assert!(!scorer.check_point(500));
+
+        let points = (0..20).collect_vec();
+        let mut scores = vec![ScoredPointOffset::default(); points.len()];
+        let scored = scorer.score_points(&points, &mut scores);
+        assert_eq!(scored, 19);
+        for scored_point in &scores[..scored] {
+            let exact = DotProductMetric::similarity(&query, &vectors[scored_point.idx as usize]);
+            assert!((exact - scored_point.score).abs() < 0.5);
+        }
+
+        // Rescoring uses original vectors
+        let rescored =
+            borrowed_storage.rescore(DenseQuery::Nearest(&query), scores[..scored].to_vec(), 5);
+        assert_eq!(rescored.len(), 5);
+        let exact = borrowed_storage.score_points(&query, &mut points.iter().copied(), 5);
+        assert_eq!(rescored, exact);
+    }
+
+    #[test]
+    fn test_points_added_after_quantization() {
+        let dim = 8;
+        let dist = Distance::Dot;
+        let config = ProductQuantizationConfig {
+            bucket_size: 2,
+            rescore: None,
+        };
+        let mut rnd = thread_rng();
+        let vectors = (0..20).map(|_| random_vector(&mut rnd, dim)).collect_vec();
+
+        let dir = TempDir::new("storage_dir").unwrap();
+        let mut storage =
+            QuantizedVectorStorage::<DotProductMetric>::open(dir.path(), dim, config).unwrap();
+
+        for chunk in vectors.chunks(10) {
+            let dir2 = TempDir::new("storage_dir2").unwrap();
+            let storage2 = open_simple_vector_storage(dir2.path(), dim, dist).unwrap();
+            for vector in chunk {
+                storage2.borrow_mut().put_vector(vector.clone()).unwrap();
+            }
+            storage.update_from(&*storage2.borrow()).unwrap();
+            if storage.quantized.is_none() {
+                storage.finalize().unwrap();
+            }
+        }
+
+        // Last 10 points have no codes and are scored with the original vectors
+        let scorer = storage.raw_scorer_internal(15);
+        assert!(scorer.check_point(5));
+        assert!(scorer.check_point(15));
+        let exact = DotProductMetric::similarity(&vectors[15], &vectors[12]);
+        assert!((scorer.score_point(12) - exact).abs() < 1e-5);
+        assert!((scorer.score_internal(12, 5) - scorer.score_internal(5, 12)).abs() < 1e-5);
+        assert!(scorer.score_internal(3, 5).is_finite());
+    }
+}
@@ -0,0 +1,113 @@
+use crate::types::{PointOffsetType, ScoreType};
+use crate::vector_storage::{RawScorer, ScoredPointOffset};
+
+/// Maps similarity into (-1, 1) range, preserving the order
+fn fast_sigmoid(x: ScoreType) -> ScoreType {
+    x / (1.0 + x.abs())
+}
+
+/// Maps similarity into (0, 1) range, preserving the order
+fn scaled_fast_sigmoid(x: ScoreType) -> ScoreType {
+    0.5 * (fast_sigmoid(x) + 1.0)
+}
+
+/// Best score among positive and negative examples.
+///
+/// If the point is closer to some positive example than to any negative one,
+/// the score is a positive value, growing with the similarity to the best positive example.
+/// Otherwise the score is negative, and the closer the point is to the negative example - the lower it is.
+pub fn best_score(
+    positive: impl Iterator<Item = ScoreType>,
+    negative: impl Iterator<Item = ScoreType>,
+) -> ScoreType {
+    let max_positive = positive.fold(ScoreType::NEG_INFINITY, ScoreType::max);
+    let max_negative = negative.fold(ScoreType::NEG_INFINITY, ScoreType::max);
+
+    if max_positive > max_negative {
+        scaled_fast_sigmoid(max_positive)
+    } else {
+        -scaled_fast_sigmoid(max_negative)
+    }
+}
+
+/// Scores points against each of the recommendation examples separately.
+/// Holds a raw scorer for each example, so any vector storage could be used.
+pub struct RecommendRawScorer<'a> {
+    positive: Vec<Box<dyn RawScorer + 'a>>,
+    negative: Vec<Box<dyn RawScorer + 'a>>,
+}
+
+impl<'a> RecommendRawScorer<'a> {
+    /// At least one positive scorer is required
+    pub fn new(
+        positive: Vec<Box<dyn RawScorer + 'a>>,
+        negative: Vec<Box<dyn RawScorer + 'a>>,
+    ) -> Self {
+        debug_assert!(!positive.is_empty());
+        RecommendRawScorer { positive, negative }
+    }
+}
+
+impl<'a> RawScorer for RecommendRawScorer<'a> {
+    fn score_points(&self, points: &[PointOffsetType], scores: &mut [ScoredPointOffset]) -> usize {
+        let mut size: usize = 0;
+        for point_id in points.iter().copied() {
+            if !self.check_point(point_id) {
+                continue;
+            }
+            scores[size] = ScoredPointOffset {
+                idx: point_id,
+                score: self.score_point(point_id),
+            };
+
+            size += 1;
+            if size == scores.len() {
+                return size;
+            }
+        }
+        size
+    }
+
+    fn check_point(&self, point: PointOffsetType) -> bool {
+        self.positive[0].check_point(point)
+    }
+
+    fn score_point(&self, point: PointOffsetType) -> ScoreType {
+        best_score(
+            self.positive.iter().map(|scorer| scorer.score_point(point)),
+            self.negative.iter().map(|scorer| scorer.score_point(point)),
+        )
+    }
+
+    fn score_internal(&self, point_a: PointOffsetType, point_b: PointOffsetType) -> ScoreType {
+        // Similarity of stored points does not depend on the query
+        self.positive[0].score_internal(point_a, point_b)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_best_score() {
+        // Closer to positive example
+        let score = best_score([0.9, 0.1].into_iter(), [0.5].into_iter());
+        assert!(score > 0.0);
+        // Closer to negative example
+        let score = best_score([0.2, 0.1].into_iter(), [0.5].into_iter());
+        assert!(score < 0.0);
+        // No negative examples
+        let score = best_score([-3.0].into_iter(), [].into_iter());
+        assert!(score > 0.0);
+
+        // Order of similarities is preserved
+        let near = best_score([0.9].into_iter(), [0.1].into_iter());
+        let far = best_score([0.3].into_iter(), [0.1].into_iter());
+        assert!(near > far);
+        let near_negative = best_score([0.1].into_iter(), [0.9].into_iter());
+        let far_negative = best_score([0.1].into_iter(), [0.3].into_iter());
+        assert!(near_negative < far_negative);
+        assert!(far_negative < far);
+    }
+}
@@ -7,46 +7,51 @@ use rocksdb::{IteratorMode, DB};
 use serde::{Deserialize, Serialize};
 
 use crate::common::rocksdb_operations::{db_write_options, open_db};
-use crate::entry::entry_point::OperationResult;
+use crate::entry::entry_point::{OperationError, OperationResult};
 use crate::spaces::tools::peek_top_largest_scores_iterable;
-use crate::types::{Distance, PointOffsetType, ScoreType, VectorElementType};
+use crate::types::{
+    Distance, PointOffsetType, ScoreType, VectorElementType, VectorStorageDatatype,
+};
 use crate::vector_storage::{RawScorer, ScoredPointOffset, VectorStorageSS};
 
 use super::chunked_vectors::ChunkedVectors;
+use super::stored_element::StoredElement;
 use super::vector_storage_base::VectorStorage;
+use crate::spaces::half::Float16;
 use crate::spaces::metric::Metric;
-use crate::spaces::simple::{CosineMetric, DotProductMetric, EuclidMetric};
+use crate::spaces::simple::{CosineMetric, DotProductMetric, EuclidMetric, ManhattanMetric};
 use atomic_refcell::AtomicRefCell;
 use bit_vec::BitVec;
 use std::mem::size_of;
 use std::sync::Arc;
 
 /// In-memory vector storage with on-update persistence using `store`
-pub struct SimpleVectorStorage<TMetric: Metric> {
+pub struct SimpleVectorStorage<TMetric: Metric, TElement: StoredElement = VectorElementType> {
     dim: usize,
     metric: PhantomData<TMetric>,
-    vectors: ChunkedVectors,
+    vectors: ChunkedVectors<TElement>,
     deleted: BitVec,
     deleted_count: usize,
     store: DB,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
-struct StoredRecord {
+struct StoredRecord<TElement> {
     pub deleted: bool,
-    pub vector: Vec<VectorElementType>,
+    pub vector: Vec<TElement>,
 }
 
-pub struct SimpleRawScorer<'a, TMetric: Metric> {
+pub struct SimpleRawScorer<'a, TMetric: Metric, TElement: StoredElement = VectorElementType> {
     pub query: Vec<VectorElementType>,
-    pub vectors: &'a ChunkedVectors,
+    pub vectors: &'a ChunkedVectors<TElement>,
     pub deleted: &'a BitVec,
     pub metric: PhantomData<TMetric>,
 }
 
-impl<TMetric> RawScorer for SimpleRawScorer<'_, TMetric>
+impl<TMetric, TElement> RawScorer for SimpleRawScorer<'_, TMetric, TElement>
 where
     TMetric: Metric,
+    TElement: StoredElement,
 {
     fn score_points(&self, points: &[PointOffsetType], scores: &mut [ScoredPointOffset]) -> usize {
         let mut size: usize = 0;
@@ -57,7 +62,7 @@ where
             let other_vector = self.vectors.get(point_id);
             scores[size] = ScoredPointOffset {
                 idx: point_id,
-                score: TMetric::similarity(&self.query, other_vector),
+                score: TElement::similarity::<TMetric>(&self.query, other_vector),
             };
 
             size += 1;
@@ -74,13 +79,13 @@ where
 
     fn score_point(&self, point: PointOffsetType) -> ScoreType {
         let other_vector = self.vectors.get(point);
-        TMetric::similarity(&self.query, other_vector)
+        TElement::similarity::<TMetric>(&self.query, other_vector)
     }
 
     fn score_internal(&self, point_a: PointOffsetType, point_b: PointOffsetType) -> ScoreType {
         let vector_a = self.vectors.get(point_a);
         let vector_b = self.vectors.get(point_b);
-        TMetric::similarity(vector_a, vector_b)
+        TElement::similarity_stored::<TMetric>(vector_a, vector_b)
     }
 }
 
@@ -89,7 +94,31 @@ pub fn open_simple_vector_storage(
     dim: usize,
     distance: Distance,
 ) -> OperationResult<Arc<AtomicRefCell<VectorStorageSS>>> {
-    let mut vectors = ChunkedVectors::new(dim);
+    open_simple_vector_storage_with_datatype(path, dim, distance, VectorStorageDatatype::Float32)
+}
+
+pub fn open_simple_vector_storage_with_datatype(
+    path: &Path,
+    dim: usize,
+    distance: Distance,
+    datatype: VectorStorageDatatype,
+) -> OperationResult<Arc<AtomicRefCell<VectorStorageSS>>> {
+    match datatype {
+        VectorStorageDatatype::Float32 => {
+            open_typed_simple_vector_storage::<VectorElementType>(path, dim, distance)
+        }
+        VectorStorageDatatype::Float16 => {
+            open_typed_simple_vector_storage::<Float16>(path, dim, distance)
+        }
+    }
+}
+
+fn open_typed_simple_vector_storage<TElement: StoredElement>(
+    path: &Path,
+    dim: usize,
+    distance: Distance,
+) -> OperationResult<Arc<AtomicRefCell<VectorStorageSS>>> {
+    let mut vectors = ChunkedVectors::<TElement>::new(dim);
     let mut deleted = BitVec::new();
     let mut deleted_count = 0;
 
@@ -97,7 +126,7 @@ pub fn open_simple_vector_storage(
 
     for (key, val) in store.iterator(IteratorMode::Start) {
         let point_id: PointOffsetType = bincode::deserialize(&key).unwrap();
-        let stored_record: StoredRecord = bincode::deserialize(&val).unwrap();
+        let stored_record: StoredRecord<TElement> = bincode::deserialize(&val).unwrap();
         if stored_record.deleted {
             deleted_count += 1;
         }
@@ -113,12 +142,13 @@ pub fn open_simple_vector_storage(
     debug!("Segment vectors: {}", vectors.len());
     debug!(
         "Estimated segment size {} MB",
-        vectors.len() * dim * size_of::<VectorElementType>() / 1024 / 1024
+        vectors.len() * dim * size_of::<TElement>() / 1024 / 1024
     );
 
     match distance {
         Distance::Cosine => Ok(Arc::new(AtomicRefCell::new(SimpleVectorStorage::<
             CosineMetric,
+            TElement,
         > {
             dim,
             metric: PhantomData,
@@ -129,6 +159,7 @@ pub fn open_simple_vector_storage(
         }))),
         Distance::Euclid => Ok(Arc::new(AtomicRefCell::new(SimpleVectorStorage::<
             EuclidMetric,
+            TElement,
         > {
             dim,
             metric: PhantomData,
@@ -139,6 +170,21 @@ pub fn open_simple_vector_storage(
         }))),
         Distance::Dot => Ok(Arc::new(AtomicRefCell::new(SimpleVectorStorage::<
             DotProductMetric,
+            TElement,
+        > {
+            dim,
+            metric: PhantomData,
+            vectors,
+            deleted,
+            deleted_count,
+            store,
+        }))),
+        Distance::Hamming => Err(OperationError::WrongConfig {
+            description: "Hamming vectors are only kept in binary vector storage".to_string(),
+        }),
+        Distance::Manhattan => Ok(Arc::new(AtomicRefCell::new(SimpleVectorStorage::<
+            ManhattanMetric,
+            TElement,
         > {
             dim,
             metric: PhantomData,
@@ -150,9 +196,10 @@ pub fn open_simple_vector_storage(
     }
 }
 
-impl<TMetric> SimpleVectorStorage<TMetric>
+impl<TMetric, TElement> SimpleVectorStorage<TMetric, TElement>
 where
     TMetric: Metric,
+    TElement: StoredElement,
 {
     fn update_stored(&self, point_id: PointOffsetType) -> OperationResult<()> {
         let v = self.vectors.get(point_id);
@@ -171,9 +218,10 @@ where
     }
 }
 
-impl<TMetric> VectorStorage for SimpleVectorStorage<TMetric>
+impl<TMetric, TElement> VectorStorage for SimpleVectorStorage<TMetric, TElement>
 where
     TMetric: Metric,
+    TElement: StoredElement,
 {
     fn vector_dim(&self) -> usize {
         self.dim
@@ -195,12 +243,12 @@ where
         if self.deleted.get(key as usize).unwrap_or(true) {
             return None;
         }
-        Some(self.vectors.get(key).to_vec())
+        Some(TElement::to_vector(self.vectors.get(key)).into_owned())
     }
 
     fn put_vector(&mut self, vector: Vec<VectorElementType>) -> OperationResult<PointOffsetType> {
         assert_eq!(self.dim, vector.len());
-        let new_id = self.vectors.push(&vector);
+        let new_id = self.vectors.push(&TElement::from_vector(&vector));
         self.deleted.push(false);
         self.update_stored(new_id)?;
         Ok(new_id)
@@ -211,7 +259,7 @@ where
         key: PointOffsetType,
         vector: Vec<VectorElementType>,
     ) -> OperationResult<PointOffsetType> {
-        self.vectors.insert(key, &vector);
+        self.vectors.insert(key, &TElement::from_vector(&vector));
         self.update_stored(key)?;
         Ok(key)
     }
@@ -222,7 +270,7 @@ where
             let other_vector = other.get_vector(point_id).unwrap();
             // Do not perform preprocessing - vectors should be already processed
             self.deleted.push(false);
-            let new_id = self.vectors.push(&other_vector);
+            let new_id = self.vectors.push(&TElement::from_vector(&other_vector));
             self.update_stored(new_id)?;
         }
         let end_index = self.vectors.len() as PointOffsetType;
@@ -256,7 +304,7 @@ where
     }
 
     fn raw_scorer(&self, vector: Vec<VectorElementType>) -> Box<dyn RawScorer + '_> {
-        Box::new(SimpleRawScorer::<TMetric> {
+        Box::new(SimpleRawScorer::<TMetric, TElement> {
             query: TMetric::preprocess(&vector).unwrap_or(vector),
             vectors: &self.vectors,
             deleted: &self.deleted,
@@ -265,8 +313,8 @@ where
     }
 
     fn raw_scorer_internal(&self, point_id: PointOffsetType) -> Box<dyn RawScorer + '_> {
-        Box::new(SimpleRawScorer::<TMetric> {
-            query: self.vectors.get(point_id).to_vec(),
+        Box::new(SimpleRawScorer::<TMetric, TElement> {
+            query: TElement::to_vector(self.vectors.get(point_id)).into_owned(),
             vectors: &self.vectors,
             deleted: &self.deleted,
             metric: PhantomData,
@@ -286,7 +334,7 @@ where
                 let other_vector = self.vectors.get(point_id);
                 ScoredPointOffset {
                     idx: point_id,
-                    score: TMetric::similarity(&preprocessed_vector, other_vector),
+                    score: TElement::similarity::<TMetric>(&preprocessed_vector, other_vector),
                 }
             });
         peek_top_largest_scores_iterable(scores, top)
@@ -302,7 +350,7 @@ where
                 let other_vector = &self.vectors.get(point_id);
                 ScoredPointOffset {
                     idx: point_id,
-                    score: TMetric::similarity(&preprocessed_vector, other_vector),
+                    score: TElement::similarity::<TMetric>(&preprocessed_vector, other_vector),
                 }
             });
         peek_top_largest_scores_iterable(scores, top)
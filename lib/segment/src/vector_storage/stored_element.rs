@@ -0,0 +1,63 @@
+use crate::spaces::half::{
+    f16_to_f32, f32_to_f16, half_similarity, half_similarity_stored, Float16,
+};
+use crate::spaces::metric::Metric;
+use crate::types::{ScoreType, VectorElementType};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::borrow::Cow;
+
+/// Type of vector elements, as they are kept in vector storage.
+///
+/// Queries are always of full precision, stored elements are converted on the fly if needed.
+pub trait StoredElement:
+    Copy + Default + Serialize + DeserializeOwned + Send + Sync + 'static
+{
+    fn from_vector(vector: &[VectorElementType]) -> Cow<[Self]>;
+
+    fn to_vector(stored: &[Self]) -> Cow<[VectorElementType]>;
+
+    /// Similarity between full precision query and stored vector
+    fn similarity<TMetric: Metric>(query: &[VectorElementType], stored: &[Self]) -> ScoreType;
+
+    /// Similarity between two stored vectors
+    fn similarity_stored<TMetric: Metric>(v1: &[Self], v2: &[Self]) -> ScoreType;
+}
+
+impl StoredElement for VectorElementType {
+    fn from_vector(vector: &[VectorElementType]) -> Cow<[Self]> {
+        Cow::Borrowed(vector)
+    }
+
+    fn to_vector(stored: &[Self]) -> Cow<[VectorElementType]> {
+        Cow::Borrowed(stored)
+    }
+
+    fn similarity<TMetric: Metric>(query: &[VectorElementType], stored: &[Self]) -> ScoreType {
+        TMetric::similarity(query, stored)
+    }
+
+    fn similarity_stored<TMetric: Metric>(v1: &[Self], v2: &[Self]) -> ScoreType {
+        TMetric::similarity(v1, v2)
+    }
+}
+
+impl StoredElement for Float16 {
+    fn from_vector(vector: &[VectorElementType]) -> Cow<[Self]> {
+        Cow::Owned(f32_to_f16(vector))
+    }
+
+    fn to_vector(stored: &[Self]) -> Cow<[VectorElementType]> {
+        let mut vector = vec![0.0; stored.len()];
+        f16_to_f32(stored, &mut vector);
+        Cow::Owned(vector)
+    }
+
+    fn similarity<TMetric: Metric>(query: &[VectorElementType], stored: &[Self]) -> ScoreType {
+        half_similarity::<TMetric>(query, stored)
+    }
+
+    fn similarity_stored<TMetric: Metric>(v1: &[Self], v2: &[Self]) -> ScoreType {
+        half_similarity_stored::<TMetric>(v1, v2)
+    }
+}
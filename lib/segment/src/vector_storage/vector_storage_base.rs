@@ -1,5 +1,7 @@
 use crate::entry::entry_point::OperationResult;
-use crate::types::{PointOffsetType, ScoreType, VectorElementType};
+use crate::spaces::tools::peek_top_largest_scores_iterable;
+use crate::types::{DenseQuery, PointOffsetType, ScoreType, VectorElementType};
+use crate::vector_storage::recommend_scorer::RecommendRawScorer;
 use ordered_float::OrderedFloat;
 use rand::Rng;
 use std::cmp::Ordering;
@@ -59,6 +61,10 @@ pub trait VectorStorage {
         vector: Vec<VectorElementType>,
     ) -> OperationResult<PointOffsetType>;
     fn update_from(&mut self, other: &VectorStorageSS) -> OperationResult<Range<PointOffsetType>>;
+    /// Prepare storage for search, once all vectors are added with `update_from`
+    fn finalize(&mut self) -> OperationResult<()> {
+        Ok(())
+    }
     fn delete(&mut self, key: PointOffsetType) -> OperationResult<()>;
     fn is_deleted(&self, key: PointOffsetType) -> bool;
     fn iter_ids(&self) -> Box<dyn Iterator<Item = PointOffsetType> + '_>;
@@ -84,6 +90,59 @@ pub trait VectorStorage {
         top: usize,
     ) -> Vec<ScoredPointOffset>;
 
+    /// Generate a `RawScorer` for any kind of query
+    fn query_scorer(&self, query: DenseQuery) -> Box<dyn RawScorer + '_> {
+        match query {
+            DenseQuery::Nearest(vector) => self.raw_scorer(vector.to_vec()),
+            DenseQuery::Recommend(query) => Box::new(RecommendRawScorer::new(
+                query
+                    .positive
+                    .iter()
+                    .map(|vector| self.raw_scorer(vector.clone()))
+                    .collect(),
+                query
+                    .negative
+                    .iter()
+                    .map(|vector| self.raw_scorer(vector.clone()))
+                    .collect(),
+            )),
+        }
+    }
+
+    /// Same as `score_points`, but for any kind of query
+    fn score_query(
+        &self,
+        query: DenseQuery,
+        points: &mut dyn Iterator<Item = PointOffsetType>,
+        top: usize,
+    ) -> Vec<ScoredPointOffset> {
+        match query {
+            DenseQuery::Nearest(vector) => self.score_points(vector, points, top),
+            DenseQuery::Recommend(_) => {
+                let scorer = self.query_scorer(query);
+                let scores =
+                    points
+                        .filter(|&point_id| scorer.check_point(point_id))
+                        .map(|point_id| ScoredPointOffset {
+                            idx: point_id,
+                            score: scorer.score_point(point_id),
+                        });
+                peek_top_largest_scores_iterable(scores, top)
+            }
+        }
+    }
+
+    /// Select `top` of the candidates, found with scores of `query_scorer`.
+    /// Storages, which score approximately, may re-score candidates with precise vectors
+    fn rescore(
+        &self,
+        _query: DenseQuery,
+        candidates: Vec<ScoredPointOffset>,
+        top: usize,
+    ) -> Vec<ScoredPointOffset> {
+        candidates.into_iter().take(top).collect()
+    }
+
     /// Iterator over `n` random ids which are not deleted
     fn sample_ids(&self) -> Box<dyn Iterator<Item = PointOffsetType> + '_> {
         let total = self.total_vector_count() as PointOffsetType;
@@ -10,9 +10,9 @@ mod tests {
     use segment::index::{PayloadIndex, VectorIndex};
     use segment::segment_constructor::build_segment;
     use segment::types::{
-        Condition, Distance, FieldCondition, Filter, HnswConfig, Indexes, Payload,
-        PayloadIndexType, PayloadSchemaType, Range, SearchParams, SegmentConfig, SeqNumberType,
-        StorageType,
+        Condition, DenseQuery, Distance, FieldCondition, Filter, HnswConfig, Indexes, Payload,
+        PayloadIndexType, PayloadSchemaType, Range, SearchParams, SearchStrategy, SegmentConfig,
+        SeqNumberType, StorageType,
     };
     use segment::vector_storage::storage_points_iterator::StoragePointsIterator;
     use serde_json::json;
@@ -45,6 +45,7 @@ mod tests {
             payload_index: Some(PayloadIndexType::Plain),
             storage_type: StorageType::InMemory,
             distance,
+            vector_datatype: Default::default(),
         };
 
         let int_key = "int";
@@ -82,6 +83,8 @@ mod tests {
             m,
             ef_construct,
             full_scan_threshold: indexing_threshold,
+            on_disk: None,
+            incremental: None,
         };
 
         let mut hnsw_index = HNSWIndex::open(
@@ -117,7 +120,8 @@ mod tests {
         let mut hits = 0;
         let attempts = 100;
         for _i in 0..attempts {
-            let query = random_vector(&mut rnd, dim);
+            let vector = random_vector(&mut rnd, dim);
+            let query = DenseQuery::Nearest(&vector);
 
             let range_size = 40;
             let left_range = rnd.gen_range(0..400);
@@ -136,18 +140,47 @@ mod tests {
             let filter_query = Some(&filter);
             // let filter_query = None;
 
-            let index_result = hnsw_index.search_with_graph(
-                &query,
+            let (index_result, _) = hnsw_index.search_with_graph(
+                query,
                 filter_query,
                 top,
-                Some(&SearchParams { hnsw_ef: Some(ef) }),
+                Some(&SearchParams {
+                    hnsw_ef: Some(ef),
+                    exact: false,
+                }),
+                &stopped,
             );
 
             let plain_result =
                 segment
                     .vector_index
                     .borrow()
-                    .search(&query, filter_query, top, None);
+                    .search(query, filter_query, top, None, &stopped);
+
+            // Exact search bypasses the graph and should match the plain search
+            let exact_result = hnsw_index.search(
+                query,
+                filter_query,
+                top,
+                Some(&SearchParams {
+                    hnsw_ef: None,
+                    exact: true,
+                }),
+                &stopped,
+            );
+            assert_eq!(exact_result, plain_result);
+
+            let (_, explain) =
+                hnsw_index.search_with_explain(query, filter_query, top, None, &stopped);
+            let cardinality = explain
+                .cardinality
+                .expect("Strategy of the filtered search is chosen by cardinality");
+            if cardinality.max < cardinality.indexing_threshold {
+                assert_eq!(explain.strategy, SearchStrategy::Plain);
+            }
+            if cardinality.min > cardinality.indexing_threshold {
+                assert_eq!(explain.strategy, SearchStrategy::Graph);
+            }
 
             if plain_result == index_result {
                 hits += 1;
@@ -15,6 +15,7 @@ mod tests {
         SegmentConfig, StorageType, WithPayload,
     };
     use std::path::Path;
+    use std::sync::atomic::AtomicBool;
     use tempdir::TempDir;
 
     fn build_test_segments(path_struct: &Path, path_plain: &Path) -> (Segment, Segment) {
@@ -27,6 +28,7 @@ mod tests {
             payload_index: Some(PayloadIndexType::Plain),
             storage_type: StorageType::InMemory,
             distance: Distance::Dot,
+            vector_datatype: Default::default(),
         };
 
         let mut plain_segment = build_segment(path_plain, &config).unwrap();
@@ -174,6 +176,7 @@ mod tests {
                     Some(&query_filter),
                     5,
                     None,
+                    &AtomicBool::new(false),
                 )
                 .unwrap();
             let struct_result = struct_segment
@@ -184,6 +187,7 @@ mod tests {
                     Some(&query_filter),
                     5,
                     None,
+                    &AtomicBool::new(false),
                 )
                 .unwrap();
 
@@ -211,6 +215,41 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_facet() {
+        // Compare counts, taken from the map index, with counts over the payload storage
+        let mut rnd = rand::thread_rng();
+
+        let dir1 = TempDir::new("segment1_dir").unwrap();
+        let dir2 = TempDir::new("segment2_dir").unwrap();
+
+        let (struct_segment, mut plain_segment) = build_test_segments(dir1.path(), dir2.path());
+        let opnum = struct_segment.version() + 1;
+        plain_segment
+            .create_field_index(opnum, STR_KEY, &Some(PayloadSchemaType::Keyword))
+            .unwrap();
+        plain_segment
+            .create_field_index(opnum, FLICKING_KEY, &Some(PayloadSchemaType::Integer))
+            .unwrap();
+
+        for key in [STR_KEY, FLICKING_KEY] {
+            let struct_counts = struct_segment.facet(key, None).unwrap();
+            let plain_counts = plain_segment.facet(key, None).unwrap();
+            assert!(!struct_counts.is_empty());
+            assert_eq!(struct_counts, plain_counts);
+
+            for _i in 0..10 {
+                let query_filter = random_filter(&mut rnd, 3);
+                let struct_counts = struct_segment.facet(key, Some(&query_filter)).unwrap();
+                let plain_counts = plain_segment.facet(key, Some(&query_filter)).unwrap();
+                assert_eq!(struct_counts, plain_counts);
+            }
+        }
+
+        // Field is not indexed
+        assert!(struct_segment.facet(GEO_KEY, None).unwrap().is_empty());
+    }
+
     #[test]
     fn test_struct_payload_geo_index() {
         // Compare search with plain and struct indexes
@@ -254,6 +293,7 @@ mod tests {
                     Some(&query_filter),
                     5,
                     None,
+                    &AtomicBool::new(false),
                 )
                 .unwrap();
 
@@ -278,6 +318,7 @@ mod tests {
                     Some(&query_filter),
                     5,
                     None,
+                    &AtomicBool::new(false),
                 )
                 .unwrap();
 
@@ -4,10 +4,16 @@ mod fixtures;
 mod tests {
     use crate::fixtures::segment::{build_segment_1, build_segment_2, empty_segment};
     use itertools::Itertools;
+    use rand::{thread_rng, Rng};
     use segment::entry::entry_point::{OperationError, SegmentEntry};
+    use segment::fixtures::payload_fixtures::random_vector;
     use segment::segment::Segment;
     use segment::segment_constructor::segment_builder::SegmentBuilder;
-    use segment::types::{Indexes, SegmentConfig};
+    use segment::segment_constructor::simple_segment_constructor::build_simple_segment;
+    use segment::types::{
+        Distance, Indexes, ProductQuantizationConfig, SegmentConfig, StorageType,
+        VectorStorageDatatype, WithPayload,
+    };
     use std::sync::atomic::{AtomicBool, Ordering};
     use std::sync::Arc;
     use std::time::{Duration, Instant};
@@ -68,6 +74,315 @@ mod tests {
         assert_eq!(merged_segment.point_version(3.into()), Some(100));
     }
 
+    #[test]
+    fn test_building_with_reused_graph() {
+        let dir = TempDir::new("segment_dir").unwrap();
+        let temp_dir = TempDir::new("segment_temp_dir").unwrap();
+        let stopped = AtomicBool::new(false);
+        let mut rnd = thread_rng();
+
+        let dim = 8;
+        let vectors = (0..1200)
+            .map(|_| random_vector(&mut rnd, dim))
+            .collect_vec();
+
+        let mut plain_segment = build_simple_segment(dir.path(), dim, Distance::Euclid).unwrap();
+        for idx in 0..1000 {
+            plain_segment
+                .upsert_point(1, (idx as u64).into(), &vectors[idx])
+                .unwrap();
+        }
+
+        let hnsw_config = SegmentConfig {
+            vector_size: dim,
+            distance: Distance::Euclid,
+            index: Indexes::Hnsw(Default::default()),
+            payload_index: None,
+            storage_type: Default::default(),
+            vector_datatype: Default::default(),
+        };
+
+        let mut builder = SegmentBuilder::new(dir.path(), temp_dir.path(), &hnsw_config).unwrap();
+        builder.update_from(&plain_segment, &stopped).unwrap();
+        let mut hnsw_segment = builder.build(&stopped).unwrap();
+
+        for idx in 0..100u64 {
+            hnsw_segment.delete_point(2, idx.into()).unwrap();
+        }
+
+        let mut new_segment = build_simple_segment(dir.path(), dim, Distance::Euclid).unwrap();
+        for idx in 1000..1200 {
+            new_segment
+                .upsert_point(3, (idx as u64).into(), &vectors[idx])
+                .unwrap();
+        }
+
+        // Links of the HNSW segment are carried over, only points of the new segment are inserted
+        let mut builder = SegmentBuilder::new(dir.path(), temp_dir.path(), &hnsw_config).unwrap();
+        builder.update_from(&hnsw_segment, &stopped).unwrap();
+        builder.update_from(&new_segment, &stopped).unwrap();
+        let merged_segment = builder.build(&stopped).unwrap();
+
+        assert_eq!(merged_segment.vectors_count(), 1100);
+
+        let mut hits = 0;
+        for idx in (100..200).chain(1000..1100) {
+            let res = merged_segment
+                .search(
+                    &vectors[idx],
+                    &WithPayload::default(),
+                    false,
+                    None,
+                    10,
+                    None,
+                    &stopped,
+                )
+                .unwrap();
+            assert!(res.iter().all(|point| point.id >= 100.into()));
+            if res[0].id == (idx as u64).into() {
+                hits += 1;
+            }
+        }
+        assert!(hits >= 190, "only {} of 200 points are found", hits);
+    }
+
+    #[test]
+    fn test_building_quantized_segment() {
+        let dir = TempDir::new("segment_dir").unwrap();
+        let temp_dir = TempDir::new("segment_temp_dir").unwrap();
+        let stopped = AtomicBool::new(false);
+        let mut rnd = thread_rng();
+
+        let dim = 16;
+        let vectors = (0..1000)
+            .map(|_| random_vector(&mut rnd, dim))
+            .collect_vec();
+
+        let mut plain_segment = build_simple_segment(dir.path(), dim, Distance::Euclid).unwrap();
+        for (idx, vector) in vectors.iter().enumerate() {
+            plain_segment
+                .upsert_point(1, (idx as u64).into(), vector)
+                .unwrap();
+        }
+
+        let quantized_config = SegmentConfig {
+            vector_size: dim,
+            distance: Distance::Euclid,
+            index: Indexes::Hnsw(Default::default()),
+            payload_index: None,
+            storage_type: StorageType::ProductQuantized(ProductQuantizationConfig {
+                bucket_size: 4,
+                rescore: None,
+            }),
+            vector_datatype: Default::default(),
+        };
+
+        let mut builder =
+            SegmentBuilder::new(dir.path(), temp_dir.path(), &quantized_config).unwrap();
+        builder.update_from(&plain_segment, &stopped).unwrap();
+        let mut quantized_segment = builder.build(&stopped).unwrap();
+
+        assert_eq!(quantized_segment.vectors_count(), 1000);
+        assert_eq!(quantized_segment.vector((7u64).into()).unwrap(), vectors[7]);
+
+        quantized_segment.delete_point(2, (0u64).into()).unwrap();
+
+        // Candidates are re-scored with original vectors, so the point itself is the closest one
+        let mut hits = 0;
+        for (idx, vector) in vectors.iter().enumerate().take(100) {
+            let res = quantized_segment
+                .search(
+                    vector,
+                    &WithPayload::default(),
+                    false,
+                    None,
+                    10,
+                    None,
+                    &stopped,
+                )
+                .unwrap();
+            assert!(res.iter().all(|point| point.id != (0u64).into()));
+            if res.first().map(|point| point.id) == Some((idx as u64).into()) {
+                hits += 1;
+            }
+        }
+        assert!(hits >= 95, "only {} of 99 points are found", hits);
+    }
+
+    #[test]
+    fn test_building_half_precision_segment() {
+        let dir = TempDir::new("segment_dir").unwrap();
+        let temp_dir = TempDir::new("segment_temp_dir").unwrap();
+        let stopped = AtomicBool::new(false);
+        let mut rnd = thread_rng();
+
+        let dim = 32;
+        let vectors = (0..500).map(|_| random_vector(&mut rnd, dim)).collect_vec();
+
+        let mut plain_segment = build_simple_segment(dir.path(), dim, Distance::Cosine).unwrap();
+        for (idx, vector) in vectors.iter().enumerate() {
+            plain_segment
+                .upsert_point(1, (idx as u64).into(), vector)
+                .unwrap();
+        }
+
+        let half_config = SegmentConfig {
+            vector_size: dim,
+            distance: Distance::Cosine,
+            index: Indexes::Hnsw(Default::default()),
+            payload_index: None,
+            storage_type: StorageType::Mmap,
+            vector_datatype: VectorStorageDatatype::Float16,
+        };
+
+        let mut builder = SegmentBuilder::new(dir.path(), temp_dir.path(), &half_config).unwrap();
+        builder.update_from(&plain_segment, &stopped).unwrap();
+        let half_segment = builder.build(&stopped).unwrap();
+
+        assert_eq!(half_segment.vectors_count(), 500);
+
+        // Stored vectors are normalized, so precision is lost only on rounding to half
+        let original = plain_segment.vector((7u64).into()).unwrap();
+        let restored = half_segment.vector((7u64).into()).unwrap();
+        assert!(original
+            .iter()
+            .zip(&restored)
+            .all(|(a, b)| (a - b).abs() < 1e-3));
+
+        for (idx, vector) in vectors.iter().enumerate().take(50) {
+            let res = half_segment
+                .search(
+                    vector,
+                    &WithPayload::default(),
+                    false,
+                    None,
+                    1,
+                    None,
+                    &stopped,
+                )
+                .unwrap();
+            assert_eq!(res[0].id, (idx as u64).into());
+            assert!((res[0].score - 1.0).abs() < 1e-2);
+        }
+    }
+
+    #[test]
+    fn test_building_binary_segment() {
+        let dir = TempDir::new("segment_dir").unwrap();
+        let temp_dir = TempDir::new("segment_temp_dir").unwrap();
+        let stopped = AtomicBool::new(false);
+        let mut rnd = thread_rng();
+
+        let dim = 256;
+        let vectors = (0..500)
+            .map(|_| {
+                (0..dim)
+                    .map(|_| if rnd.gen_bool(0.5) { 1.0 } else { 0.0 })
+                    .collect_vec()
+            })
+            .collect_vec();
+
+        let mut plain_segment = build_simple_segment(dir.path(), dim, Distance::Hamming).unwrap();
+        for (idx, vector) in vectors.iter().enumerate() {
+            plain_segment
+                .upsert_point(1, (idx as u64).into(), vector)
+                .unwrap();
+        }
+
+        let binary_config = SegmentConfig {
+            vector_size: dim,
+            distance: Distance::Hamming,
+            index: Indexes::Hnsw(Default::default()),
+            payload_index: None,
+            storage_type: StorageType::InMemory,
+            vector_datatype: Default::default(),
+        };
+
+        // Binary vectors are always kept in RAM
+        let mmap_config = SegmentConfig {
+            storage_type: StorageType::Mmap,
+            ..binary_config.clone()
+        };
+        assert!(SegmentBuilder::new(dir.path(), temp_dir.path(), &mmap_config).is_err());
+
+        let mut builder = SegmentBuilder::new(dir.path(), temp_dir.path(), &binary_config).unwrap();
+        builder.update_from(&plain_segment, &stopped).unwrap();
+        let binary_segment = builder.build(&stopped).unwrap();
+
+        assert_eq!(binary_segment.vectors_count(), 500);
+        assert_eq!(binary_segment.vector((7u64).into()).unwrap(), vectors[7]);
+
+        let mut query = vectors[42].clone();
+        query[0] = 1.0 - query[0];
+        query[100] = 1.0 - query[100];
+
+        for segment in [&plain_segment, &binary_segment] {
+            let res = segment
+                .search(
+                    &query,
+                    &WithPayload::default(),
+                    false,
+                    None,
+                    3,
+                    None,
+                    &stopped,
+                )
+                .unwrap();
+            assert_eq!(res[0].id, (42u64).into());
+            assert_eq!(res[0].score, -2.0);
+        }
+    }
+
+    #[test]
+    fn test_evaluate_recall() {
+        let dir = TempDir::new("segment_dir").unwrap();
+        let temp_dir = TempDir::new("segment_temp_dir").unwrap();
+        let stopped = AtomicBool::new(false);
+        let mut rnd = thread_rng();
+
+        let dim = 8;
+        let mut plain_segment = build_simple_segment(dir.path(), dim, Distance::Dot).unwrap();
+        for idx in 0..1000u64 {
+            plain_segment
+                .upsert_point(1, idx.into(), &random_vector(&mut rnd, dim))
+                .unwrap();
+        }
+
+        let hnsw_config = SegmentConfig {
+            vector_size: dim,
+            distance: Distance::Dot,
+            index: Indexes::Hnsw(Default::default()),
+            payload_index: None,
+            storage_type: Default::default(),
+            vector_datatype: Default::default(),
+        };
+
+        let mut builder = SegmentBuilder::new(dir.path(), temp_dir.path(), &hnsw_config).unwrap();
+        builder.update_from(&plain_segment, &stopped).unwrap();
+        let hnsw_segment = builder.build(&stopped).unwrap();
+
+        let evaluation = hnsw_segment
+            .evaluate_recall(50, 10, Some(16), &stopped)
+            .unwrap();
+
+        assert_eq!(evaluation.queries, 50);
+        assert_eq!(evaluation.ef, 16);
+        assert!(evaluation.recall > 0.9, "recall is {}", evaluation.recall);
+
+        // The exact search is used by the plain index, so the recall is always perfect
+        let evaluation = plain_segment
+            .evaluate_recall(50, 10, None, &stopped)
+            .unwrap();
+        assert_eq!(evaluation.queries, 50);
+        assert_eq!(evaluation.recall, 1.0);
+
+        // Sample is limited by the number of stored points
+        let evaluation = hnsw_segment
+            .evaluate_recall(5000, 10, None, &stopped)
+            .unwrap();
+        assert_eq!(evaluation.queries, 1000);
+    }
+
     fn estimate_build_time(segment: &Segment, stop_timeout_millis: u64) -> (u64, bool) {
         let stopped = Arc::new(AtomicBool::new(false));
 
@@ -80,6 +395,7 @@ mod tests {
             index: Indexes::Hnsw(Default::default()),
             payload_index: None,
             storage_type: Default::default(),
+            vector_datatype: Default::default(),
         };
 
         let mut builder =
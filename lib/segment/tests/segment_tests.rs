@@ -3,10 +3,22 @@ mod fixtures;
 #[cfg(test)]
 mod tests {
     use crate::fixtures::segment::build_segment_1;
+    use itertools::Itertools;
+    use rand::thread_rng;
     use segment::entry::entry_point::SegmentEntry;
-    use segment::types::{Condition, Filter, WithPayload};
+    use segment::fixtures::payload_fixtures::random_vector;
+    use segment::segment::Segment;
+    use segment::segment_constructor::load_segment;
+    use segment::segment_constructor::simple_segment_constructor::{
+        build_appendable_segment, build_simple_segment,
+    };
+    use segment::types::{
+        Condition, Distance, Filter, FilterExplain, HnswConfig, QueryVector, SearchStrategy,
+        VectorElementType, WithPayload,
+    };
     use std::collections::HashSet;
     use std::iter::FromIterator;
+    use std::sync::atomic::AtomicBool;
     use tempdir::TempDir;
 
     #[test]
@@ -20,7 +32,15 @@ mod tests {
         let query_vector = vec![1.0, 1.0, 1.0, 1.0];
 
         let res = segment
-            .search(&query_vector, &WithPayload::default(), false, None, 1, None)
+            .search(
+                &query_vector,
+                &WithPayload::default(),
+                false,
+                None,
+                1,
+                None,
+                &AtomicBool::new(false),
+            )
             .unwrap();
 
         let best_match = res.get(0).expect("Non-empty result");
@@ -42,6 +62,7 @@ mod tests {
                 Some(&frt),
                 1,
                 None,
+                &AtomicBool::new(false),
             )
             .unwrap();
 
@@ -56,4 +77,199 @@ mod tests {
 
         assert_eq!(&point_ids1, &point_ids2)
     }
+
+    #[test]
+    fn test_search_explain() {
+        let dir = TempDir::new("segment_dir").unwrap();
+
+        let segment = build_segment_1(dir.path());
+
+        let query = QueryVector::Nearest(vec![1.0, 1.0, 1.0, 1.0]);
+
+        let ids: HashSet<_> = HashSet::from_iter([3.into()]);
+        let frt = Filter {
+            should: None,
+            must: None,
+            must_not: Some(vec![Condition::HasId(ids.into())]),
+        };
+
+        let (res, explain) = segment
+            .search_query(
+                &query,
+                &WithPayload::default(),
+                false,
+                true,
+                Some(&frt),
+                2,
+                None,
+                &AtomicBool::new(false),
+            )
+            .unwrap();
+
+        assert_eq!(res.len(), 2);
+        assert_eq!(explain.len(), 1);
+        assert_eq!(explain[0].search.strategy, SearchStrategy::Plain);
+        assert_eq!(explain[0].search.visited_points, 4);
+        assert_eq!(
+            explain[0].points.iter().map(|point| point.id).collect_vec(),
+            res.iter().map(|point| point.id).collect_vec()
+        );
+        for point in &explain[0].points {
+            assert_eq!(
+                point.filter,
+                FilterExplain {
+                    should: None,
+                    must: None,
+                    must_not: Some(vec![false]),
+                }
+            );
+        }
+
+        // Segment is explained even if nothing is found in it
+        let ids: HashSet<_> = HashSet::from_iter([100.into()]);
+        let (res, explain) = segment
+            .search_query(
+                &query,
+                &WithPayload::default(),
+                false,
+                true,
+                Some(&Filter::new_must(Condition::HasId(ids.into()))),
+                2,
+                None,
+                &AtomicBool::new(false),
+            )
+            .unwrap();
+
+        assert!(res.is_empty());
+        assert_eq!(explain.len(), 1);
+        assert_eq!(explain[0].search.visited_points, 0);
+        assert!(explain[0].points.is_empty());
+
+        let (_, explain) = segment
+            .search_query(
+                &query,
+                &WithPayload::default(),
+                false,
+                false,
+                Some(&frt),
+                2,
+                None,
+                &AtomicBool::new(false),
+            )
+            .unwrap();
+
+        assert!(explain.is_empty());
+    }
+
+    fn count_self_hits(
+        segment: &Segment,
+        vectors: &[Vec<VectorElementType>],
+        ids: impl Iterator<Item = u64>,
+    ) -> usize {
+        let stopped = AtomicBool::new(false);
+        ids.filter(|&idx| {
+            let res = segment
+                .search(
+                    &vectors[idx as usize],
+                    &WithPayload::default(),
+                    false,
+                    None,
+                    1,
+                    None,
+                    &stopped,
+                )
+                .unwrap();
+            res.first().map(|point| point.id) == Some(idx.into())
+        })
+        .count()
+    }
+
+    #[test]
+    fn test_incremental_hnsw_segment() {
+        let dir = TempDir::new("segment_dir").unwrap();
+        let mut rnd = thread_rng();
+
+        let dim = 8;
+        let vectors = (0..600).map(|_| random_vector(&mut rnd, dim)).collect_vec();
+
+        let hnsw_config = HnswConfig {
+            incremental: Some(true),
+            ..Default::default()
+        };
+
+        let mut segment = build_appendable_segment(
+            dir.path(),
+            dim,
+            Distance::Euclid,
+            &hnsw_config,
+            Default::default(),
+        )
+        .unwrap();
+        assert!(segment.is_appendable());
+
+        for idx in 0..500u64 {
+            segment
+                .upsert_point(idx, idx.into(), &vectors[idx as usize])
+                .unwrap();
+        }
+        assert!(count_self_hits(&segment, &vectors, 0..500) >= 490);
+
+        // Deleted points are excluded from search results
+        for idx in 0..50u64 {
+            segment.delete_point(500 + idx, idx.into()).unwrap();
+        }
+        assert_eq!(count_self_hits(&segment, &vectors, 0..50), 0);
+
+        segment.flush().unwrap();
+        let segment_path = segment.current_path.clone();
+        drop(segment);
+
+        // Graph is restored and new points are linked into it after re-opening
+        let mut segment = load_segment(&segment_path).unwrap();
+        assert!(segment.is_appendable());
+        for idx in 500..600u64 {
+            segment
+                .upsert_point(1000 + idx, idx.into(), &vectors[idx as usize])
+                .unwrap();
+        }
+        assert!(count_self_hits(&segment, &vectors, 50..600) >= 540);
+    }
+
+    #[test]
+    fn test_manhattan_segment_search() {
+        let dir = TempDir::new("segment_dir").unwrap();
+
+        let mut segment = build_simple_segment(dir.path(), 4, Distance::Manhattan).unwrap();
+
+        segment
+            .upsert_point(1, 1.into(), &[1.0, 0.0, 1.0, 1.0])
+            .unwrap();
+        segment
+            .upsert_point(2, 2.into(), &[1.0, 0.0, 1.0, 0.0])
+            .unwrap();
+        segment
+            .upsert_point(3, 3.into(), &[-1.0, 2.0, 0.0, 1.0])
+            .unwrap();
+
+        let res = segment
+            .search(
+                &[1.0, 0.5, 1.0, 0.0],
+                &WithPayload::default(),
+                false,
+                None,
+                3,
+                None,
+                &AtomicBool::new(false),
+            )
+            .unwrap();
+
+        let ids = res.iter().map(|point| point.id).collect_vec();
+        assert_eq!(ids, vec![2.into(), 1.into(), 3.into()]);
+
+        let distances = res
+            .iter()
+            .map(|point| Distance::Manhattan.postprocess_score(point.score))
+            .collect_vec();
+        assert_eq!(distances, vec![0.5, 1.5, 5.5]);
+    }
 }
@@ -1,6 +1,6 @@
 use collection::operations::config_diff::{HnswConfigDiff, OptimizersConfigDiff, WalConfigDiff};
 use schemars::JsonSchema;
-use segment::types::Distance;
+use segment::types::{Distance, ProductQuantizationConfig, VectorStorageDatatype};
 use serde::{Deserialize, Serialize};
 
 // *Operation wrapper structure is only required for better OpenAPI generation
@@ -92,6 +92,12 @@ pub struct CreateCollection {
     pub wal_config: Option<WalConfigDiff>,
     /// Custom params for Optimizers.  If none - values from service configuration file are used.
     pub optimizers_config: Option<OptimizersConfigDiff>,
+    /// Store vectors of large segments as product quantization codes. If none - vectors are not quantized.
+    #[serde(default)]
+    pub product_quantization: Option<ProductQuantizationConfig>,
+    /// Type of stored vector elements. If none - `float32` is used.
+    #[serde(default)]
+    pub vector_datatype: Option<VectorStorageDatatype>,
 }
 
 pub const fn default_shard_number() -> u32 {
@@ -113,7 +119,13 @@ pub struct CreateCollectionOperation {
 pub struct UpdateCollection {
     /// Custom params for Optimizers.  If none - values from service configuration file are used.
     /// This operation is blocking, it will only proceed ones all current optimizations are complete
-    pub optimizers_config: Option<OptimizersConfigDiff>, // ToDo: Allow updates for other configuration params as well
+    pub optimizers_config: Option<OptimizersConfigDiff>,
+    /// Custom params for HNSW index. If none - current values are kept.
+    /// Segments indexed with different params are re-indexed by the optimizers in background.
+    pub hnsw_config: Option<HnswConfigDiff>,
+    /// Custom params for WAL. If none - current values are kept.
+    /// New values are saved, but take effect only after restart of the service.
+    pub wal_config: Option<WalConfigDiff>,
 }
 
 /// Operation for updating parameters of the existing collection
@@ -13,6 +13,8 @@ pub fn error_to_status(error: StorageError) -> tonic::Status {
         StorageError::NotFound { .. } => tonic::Code::NotFound,
         StorageError::ServiceError { .. } => tonic::Code::Internal,
         StorageError::BadRequest { .. } => tonic::Code::InvalidArgument,
+        StorageError::Timeout { .. } => tonic::Code::DeadlineExceeded,
+        StorageError::ResourceExhausted { .. } => tonic::Code::ResourceExhausted,
     };
     return tonic::Status::new(error_code, format!("{}", error));
 }
@@ -25,10 +27,24 @@ impl TryFrom<api::grpc::qdrant::CreateCollection> for CollectionMetaOperations {
             Some(api::grpc::qdrant::Distance::Cosine) => segment::types::Distance::Cosine,
             Some(api::grpc::qdrant::Distance::Euclid) => segment::types::Distance::Euclid,
             Some(api::grpc::qdrant::Distance::Dot) => segment::types::Distance::Dot,
+            Some(api::grpc::qdrant::Distance::Hamming) => segment::types::Distance::Hamming,
+            Some(api::grpc::qdrant::Distance::Manhattan) => segment::types::Distance::Manhattan,
             Some(_) => return Err(Status::failed_precondition("Unknown distance")),
             _ => return Err(Status::failed_precondition("Bad value of distance field!")),
         };
 
+        let vector_datatype = match value.vector_datatype {
+            None => None,
+            Some(datatype) => match api::grpc::qdrant::VectorStorageDatatype::from_i32(datatype) {
+                Some(datatype) => Some(datatype.into()),
+                None => {
+                    return Err(Status::failed_precondition(
+                        "Bad value of vector_datatype field!",
+                    ))
+                }
+            },
+        };
+
         Ok(Self::CreateCollection(CreateCollectionOperation {
             collection_name: value.collection_name,
             create_collection: CreateCollection {
@@ -38,6 +54,8 @@ impl TryFrom<api::grpc::qdrant::CreateCollection> for CollectionMetaOperations {
                 wal_config: value.wal_config.map(|v| v.into()),
                 optimizers_config: value.optimizers_config.map(|v| v.into()),
                 shard_number: value.shard_number.unwrap_or_else(default_shard_number),
+                product_quantization: value.product_quantization.map(|v| v.into()),
+                vector_datatype,
             },
         }))
     }
@@ -51,6 +69,8 @@ impl TryFrom<api::grpc::qdrant::UpdateCollection> for CollectionMetaOperations {
             collection_name: value.collection_name,
             update_collection: UpdateCollection {
                 optimizers_config: value.optimizers_config.map(|v| v.into()),
+                hnsw_config: value.hnsw_config.map(|v| v.into()),
+                wal_config: value.wal_config.map(|v| v.into()),
             },
         }))
     }
@@ -14,6 +14,10 @@ pub enum StorageError {
     ServiceError { description: String },
     #[error("Bad request: {description}")]
     BadRequest { description: String },
+    #[error("Timeout: {description}")]
+    Timeout { description: String },
+    #[error("Too many requests: {description}")]
+    ResourceExhausted { description: String },
 }
 
 impl StorageError {
@@ -38,6 +42,10 @@ impl From<CollectionError> for StorageError {
             CollectionError::Cancelled { description } => StorageError::ServiceError {
                 description: format!("Operation cancelled: {description}"),
             },
+            CollectionError::Timeout { description } => StorageError::Timeout { description },
+            CollectionError::ResourceExhausted { description } => {
+                StorageError::ResourceExhausted { description }
+            }
             err @ CollectionError::InconsistentFailure { .. } => StorageError::ServiceError {
                 description: format!("{err}"),
             },
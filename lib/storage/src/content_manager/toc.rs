@@ -7,17 +7,19 @@ use std::sync::Arc;
 use std::time::Duration;
 
 use tokio::runtime::Runtime;
-use tokio::sync::{RwLock, RwLockReadGuard};
+use tokio::sync::{RwLock, RwLockReadGuard, Semaphore, SemaphorePermit};
 
 use collection::config::{CollectionConfig, CollectionParams};
 use collection::operations::config_diff::DiffConfig;
 use collection::operations::types::{
-    PointRequest, RecommendRequest, Record, ScrollRequest, ScrollResult, SearchRequest,
-    UpdateResult,
+    CoreSearchRequest, DistanceMatrix, DistanceMatrixRequest, FacetRequest, FacetResult,
+    GroupsResult, HybridSearchRequest, PointRequest, PointsSample, RecallReport, RecallRequest,
+    RecommendRequest, Record, SampleNeighboursRequest, ScrollRequest, ScrollResult,
+    SearchExplainResult, SearchGroupsRequest, SearchRequest, UpdateResult,
 };
 use collection::operations::CollectionUpdateOperations;
 use collection::Collection;
-use segment::types::ScoredPoint;
+use segment::types::{Distance, Filter, ScoredPoint, VectorStorageDatatype};
 
 use crate::content_manager::{
     alias_mapping::AliasPersistence,
@@ -65,6 +67,8 @@ pub struct TableOfContent {
     collections: Arc<RwLock<Collections>>,
     storage_config: StorageConfig,
     search_runtime: Runtime,
+    /// Limits the number of concurrent search requests, if configured
+    search_permits: Option<Semaphore>,
     collection_management_runtime: Runtime,
     alias_persistence: RwLock<AliasPersistence>,
     segment_searcher: Box<dyn CollectionSearcher + Sync + Send>,
@@ -133,6 +137,11 @@ impl TableOfContent {
             collections: Arc::new(RwLock::new(collections)),
             storage_config: storage_config.clone(),
             search_runtime,
+            search_permits: storage_config
+                .performance
+                .max_concurrent_searches
+                .filter(|&limit| limit > 0)
+                .map(Semaphore::new),
             alias_persistence: RwLock::new(alias_persistence),
             segment_searcher: Box::new(SimpleCollectionSearcher::new()),
             collection_management_runtime,
@@ -202,8 +211,27 @@ impl TableOfContent {
             hnsw_config: hnsw_config_diff,
             wal_config: wal_config_diff,
             optimizers_config: optimizers_config_diff,
+            product_quantization,
+            vector_datatype,
         } = operation;
 
+        if let Some(quantization_config) = &product_quantization {
+            if quantization_config.bucket_size == 0 {
+                return Err(StorageError::BadInput {
+                    description: "`bucket_size` of product quantization cannot be 0".to_string(),
+                });
+            }
+        }
+
+        if distance == Distance::Hamming
+            && (product_quantization.is_some()
+                || vector_datatype.unwrap_or_default() != VectorStorageDatatype::Float32)
+        {
+            return Err(StorageError::BadInput {
+                description: "Hamming distance vectors are stored bit-packed, `product_quantization` and `vector_datatype` are not supported".to_string(),
+            });
+        }
+
         self.collections
             .read()
             .await
@@ -218,6 +246,8 @@ impl TableOfContent {
             shard_number: NonZeroU32::new(shard_number).ok_or(StorageError::BadInput {
                 description: "`shard_number` cannot be 0".to_string(),
             })?,
+            product_quantization,
+            vector_datatype: vector_datatype.unwrap_or_default(),
         };
         let wal_config = match wal_config_diff {
             None => self.storage_config.wal.clone(),
@@ -259,15 +289,18 @@ impl TableOfContent {
         collection_name: &str,
         operation: UpdateCollection,
     ) -> Result<bool, StorageError> {
-        match operation.optimizers_config {
-            None => {}
-            Some(new_optimizers_config) => {
-                let collection = self.get_collection(collection_name).await?;
-                collection
-                    .update_optimizer_params_from_diff(new_optimizers_config)
-                    .await?
-            }
+        let UpdateCollection {
+            optimizers_config,
+            hnsw_config,
+            wal_config,
+        } = operation;
+        if optimizers_config.is_none() && hnsw_config.is_none() && wal_config.is_none() {
+            return Ok(true);
         }
+        let collection = self.get_collection(collection_name).await?;
+        collection
+            .update_params_from_diff(optimizers_config, hnsw_config, wal_config)
+            .await?;
         Ok(true)
     }
 
@@ -419,6 +452,19 @@ impl TableOfContent {
         }))
     }
 
+    /// Reserves a slot for a search request, if the number of concurrent searches is limited.
+    /// Fails immediately if all slots are taken, so a request never waits for a free slot.
+    fn acquire_search_permit(&self) -> Result<Option<SemaphorePermit>, StorageError> {
+        match &self.search_permits {
+            None => Ok(None),
+            Some(search_permits) => search_permits.try_acquire().map(Some).map_err(|_| {
+                StorageError::ResourceExhausted {
+                    description: "Too many concurrent search requests".to_string(),
+                }
+            }),
+        }
+    }
+
     /// Recommend points using positive and negative example from the request
     ///
     /// # Arguments
@@ -435,6 +481,7 @@ impl TableOfContent {
         request: RecommendRequest,
         shard_selection: Option<ShardId>,
     ) -> Result<Vec<ScoredPoint>, StorageError> {
+        let _search_permit = self.acquire_search_permit()?;
         let collection = self.get_collection(collection_name).await?;
         collection
             .recommend_by(
@@ -457,16 +504,172 @@ impl TableOfContent {
     /// * `shard_selection` - which local shard to use
     /// # Result
     ///
-    /// Points with search score
+    /// Points with search score and, if requested, explanation of each searched segment
     pub async fn search(
         &self,
         collection_name: &str,
         request: SearchRequest,
         shard_selection: Option<ShardId>,
+    ) -> Result<SearchExplainResult, StorageError> {
+        let _search_permit = self.acquire_search_permit()?;
+        let collection = self.get_collection(collection_name).await?;
+        collection
+            .search_with_explain(
+                request,
+                self.segment_searcher.as_ref(),
+                self.search_runtime.handle(),
+                shard_selection,
+            )
+            .await
+            .map_err(|err| err.into())
+    }
+
+    /// Search for the closest points and group them by the value of the payload field
+    ///
+    /// # Arguments
+    ///
+    /// * `collection_name` - in what collection do we search
+    /// * `request` - [`SearchGroupsRequest`]
+    /// * `shard_selection` - which local shard to use
+    /// # Result
+    ///
+    /// Groups of points with search score
+    pub async fn search_groups(
+        &self,
+        collection_name: &str,
+        request: SearchGroupsRequest,
+        shard_selection: Option<ShardId>,
+    ) -> Result<GroupsResult, StorageError> {
+        let _search_permit = self.acquire_search_permit()?;
+        let collection = self.get_collection(collection_name).await?;
+        collection
+            .search_groups(
+                request,
+                self.segment_searcher.as_ref(),
+                self.search_runtime.handle(),
+                shard_selection,
+            )
+            .await
+            .map_err(|err| err.into())
+    }
+
+    /// Search with both dense and sparse vectors, fusing the results into a single list
+    ///
+    /// # Arguments
+    ///
+    /// * `collection_name` - in what collection do we search
+    /// * `request` - [`HybridSearchRequest`]
+    /// * `shard_selection` - which local shard to use
+    /// # Result
+    ///
+    /// Points with the score of the fusion
+    pub async fn hybrid_search(
+        &self,
+        collection_name: &str,
+        request: HybridSearchRequest,
+        shard_selection: Option<ShardId>,
+    ) -> Result<Vec<ScoredPoint>, StorageError> {
+        let _search_permit = self.acquire_search_permit()?;
+        let collection = self.get_collection(collection_name).await?;
+        collection
+            .hybrid_search(
+                request,
+                self.segment_searcher.as_ref(),
+                self.search_runtime.handle(),
+                shard_selection,
+            )
+            .await
+            .map_err(|err| err.into())
+    }
+
+    /// Count points with each value of the payload field
+    ///
+    /// # Arguments
+    ///
+    /// * `collection_name` - in what collection do we count
+    /// * `request` - [`FacetRequest`]
+    /// * `shard_selection` - which local shard to use
+    /// # Result
+    ///
+    /// Most frequent values of the field with the number of points
+    pub async fn facet(
+        &self,
+        collection_name: &str,
+        request: FacetRequest,
+        shard_selection: Option<ShardId>,
+    ) -> Result<FacetResult, StorageError> {
+        let _search_permit = self.acquire_search_permit()?;
+        let collection = self.get_collection(collection_name).await?;
+        collection
+            .facet(request, shard_selection)
+            .await
+            .map_err(|err| err.into())
+    }
+
+    /// Sample points and find the nearest neighbours of each of them among the sample
+    ///
+    /// # Arguments
+    ///
+    /// * `collection_name` - in what collection do we sample
+    /// * `request` - [`DistanceMatrixRequest`]
+    /// * `shard_selection` - which local shard to use
+    /// # Result
+    ///
+    /// Sparse matrix of scores between the sampled points
+    pub async fn distance_matrix(
+        &self,
+        collection_name: &str,
+        request: DistanceMatrixRequest,
+        shard_selection: Option<ShardId>,
+    ) -> Result<DistanceMatrix, StorageError> {
+        let _search_permit = self.acquire_search_permit()?;
+        let collection = self.get_collection(collection_name).await?;
+        collection
+            .distance_matrix(request, self.segment_searcher.as_ref(), shard_selection)
+            .await
+            .map_err(|err| err.into())
+    }
+
+    /// Random sample of points, which satisfy the filter. Used internally by the distance matrix.
+    pub async fn sample_points(
+        &self,
+        collection_name: &str,
+        filter: Option<&Filter>,
+        size: usize,
+        shard_selection: Option<ShardId>,
+    ) -> Result<PointsSample, StorageError> {
+        let collection = self.get_collection(collection_name).await?;
+        collection
+            .sample(filter, size, shard_selection)
+            .await
+            .map_err(|err| err.into())
+    }
+
+    /// Nearest neighbours of the sampled points among the sample. Used internally by the distance matrix.
+    pub async fn sample_neighbours(
+        &self,
+        collection_name: &str,
+        request: SampleNeighboursRequest,
+        shard_selection: Option<ShardId>,
+    ) -> Result<Vec<Vec<ScoredPoint>>, StorageError> {
+        let collection = self.get_collection(collection_name).await?;
+        collection
+            .sample_neighbours(request, shard_selection)
+            .await
+            .map_err(|err| err.into())
+    }
+
+    /// Same as `search`, but the query is not limited to a single vector
+    pub async fn core_search(
+        &self,
+        collection_name: &str,
+        request: CoreSearchRequest,
+        shard_selection: Option<ShardId>,
     ) -> Result<Vec<ScoredPoint>, StorageError> {
+        let _search_permit = self.acquire_search_permit()?;
         let collection = self.get_collection(collection_name).await?;
         collection
-            .search(
+            .core_search(
                 request,
                 self.segment_searcher.as_ref(),
                 self.search_runtime.handle(),
@@ -570,6 +773,72 @@ impl TableOfContent {
         result.map_err(|err| err.into())
     }
 
+    /// Cancel running optimizations of the collection and prevent new ones until resumed
+    ///
+    /// # Arguments
+    ///
+    /// * `collection_name` - which collection to pause optimizers for
+    ///
+    /// # Result
+    ///
+    /// `true` if pause signal was sent to all local shards
+    pub async fn pause_optimizers(&self, collection_name: &str) -> Result<bool, StorageError> {
+        let collection = self.get_collection(collection_name).await?;
+        collection.pause_optimizers()?;
+        Ok(true)
+    }
+
+    /// Resume previously paused optimizers of the collection
+    ///
+    /// # Arguments
+    ///
+    /// * `collection_name` - which collection to resume optimizers for
+    ///
+    /// # Result
+    ///
+    /// `true` if resume signal was sent to all local shards
+    pub async fn resume_optimizers(&self, collection_name: &str) -> Result<bool, StorageError> {
+        let collection = self.get_collection(collection_name).await?;
+        collection.resume_optimizers()?;
+        Ok(true)
+    }
+
+    /// Merge and index segments of the collection regardless of the optimizer thresholds
+    ///
+    /// # Arguments
+    ///
+    /// * `collection_name` - which collection to optimize
+    ///
+    /// # Result
+    ///
+    /// `true` if optimization was scheduled for all local shards.
+    /// Optimization itself is performed in background.
+    pub async fn force_optimize(&self, collection_name: &str) -> Result<bool, StorageError> {
+        let collection = self.get_collection(collection_name).await?;
+        collection.force_optimize()?;
+        Ok(true)
+    }
+
+    /// Measure quality of the vector index of the collection.
+    /// Sampled stored points are searched with both index and exact search, results are compared.
+    ///
+    /// # Arguments
+    ///
+    /// * `collection_name` - which collection to evaluate
+    /// * `request` - sample size, number of neighbours and search params
+    ///
+    /// # Result
+    ///
+    /// Recall and search time of each HNSW-indexed segment of the local shards and of the whole collection
+    pub async fn evaluate_recall(
+        &self,
+        collection_name: &str,
+        request: RecallRequest,
+    ) -> Result<RecallReport, StorageError> {
+        let collection = self.get_collection(collection_name).await?;
+        Ok(collection.evaluate_recall(&request).await?)
+    }
+
     pub fn collection_wal_entry(&self, id: u64) -> raft::Result<RaftEntry> {
         if id < 1 {
             return Err(raft::Error::Store(raft::StorageError::Unavailable));
@@ -12,6 +12,10 @@ pub type PeerAddressById = HashMap<u64, Uri>;
 #[derive(Debug, Deserialize, Serialize, JsonSchema, Clone)]
 pub struct PerformanceConfig {
     pub max_search_threads: usize,
+    /// Maximum number of search and recommend requests, processed at the same time.
+    /// Requests above the limit are rejected. If not set or 0 - the number is not limited.
+    #[serde(default)]
+    pub max_concurrent_searches: Option<usize>,
 }
 
 /// Global configuration of the storage, loaded on the service launch, default stored in ./config
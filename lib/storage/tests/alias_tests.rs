@@ -31,10 +31,12 @@ mod tests {
                 payload_indexing_threshold: 100,
                 flush_interval_sec: 2,
                 max_optimization_threads: 2,
+                max_queued_updates: None,
             },
             wal: Default::default(),
             performance: PerformanceConfig {
                 max_search_threads: 1,
+                max_concurrent_searches: None,
             },
             hnsw_index: Default::default(),
         };
@@ -55,6 +57,8 @@ mod tests {
                         wal_config: None,
                         optimizers_config: None,
                         shard_number: 1,
+                        product_quantization: None,
+                        vector_datatype: None,
                     },
                 }),
                 None,
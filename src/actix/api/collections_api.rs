@@ -2,6 +2,7 @@ use crate::actix::helpers::process_response;
 use crate::common::collections::*;
 use actix_web::rt::time::Instant;
 use actix_web::{delete, get, patch, post, put, web, Responder};
+use collection::operations::types::RecallRequest;
 use serde::Deserialize;
 use std::sync::Arc;
 use std::time::Duration;
@@ -126,6 +127,51 @@ async fn update_aliases(
     process_response(response, timing)
 }
 
+#[post("/collections/{name}/optimizers/pause")]
+async fn pause_optimizers(
+    toc: web::Data<Arc<TableOfContent>>,
+    path: web::Path<String>,
+) -> impl Responder {
+    let timing = Instant::now();
+    let name = path.into_inner();
+    let response = toc.pause_optimizers(&name).await;
+    process_response(response, timing)
+}
+
+#[post("/collections/{name}/optimizers/resume")]
+async fn resume_optimizers(
+    toc: web::Data<Arc<TableOfContent>>,
+    path: web::Path<String>,
+) -> impl Responder {
+    let timing = Instant::now();
+    let name = path.into_inner();
+    let response = toc.resume_optimizers(&name).await;
+    process_response(response, timing)
+}
+
+#[post("/collections/{name}/optimize")]
+async fn force_optimize(
+    toc: web::Data<Arc<TableOfContent>>,
+    path: web::Path<String>,
+) -> impl Responder {
+    let timing = Instant::now();
+    let name = path.into_inner();
+    let response = toc.force_optimize(&name).await;
+    process_response(response, timing)
+}
+
+#[post("/collections/{name}/recall")]
+async fn evaluate_recall(
+    toc: web::Data<Arc<TableOfContent>>,
+    path: web::Path<String>,
+    request: web::Json<RecallRequest>,
+) -> impl Responder {
+    let timing = Instant::now();
+    let name = path.into_inner();
+    let response = toc.evaluate_recall(&name, request.into_inner()).await;
+    process_response(response, timing)
+}
+
 // Configure services
 pub fn config_collections_api(cfg: &mut web::ServiceConfig) {
     cfg.service(get_collections)
@@ -134,7 +180,11 @@ pub fn config_collections_api(cfg: &mut web::ServiceConfig) {
         .service(create_collection)
         .service(update_collection)
         .service(delete_collection)
-        .service(update_aliases);
+        .service(update_aliases)
+        .service(pause_optimizers)
+        .service(resume_optimizers)
+        .service(force_optimize)
+        .service(evaluate_recall);
 }
 
 #[cfg(test)]
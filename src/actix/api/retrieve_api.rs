@@ -3,13 +3,15 @@ use std::sync::Arc;
 use actix_web::rt::time::Instant;
 use actix_web::{get, post, web, Responder};
 
-use collection::operations::types::{PointRequest, Record, ScrollRequest, ScrollResult};
+use collection::operations::types::{
+    FacetRequest, PointRequest, Record, ScrollRequest, ScrollResult,
+};
 use segment::types::{PointIdType, WithPayloadInterface};
 use storage::content_manager::errors::StorageError;
 use storage::content_manager::toc::TableOfContent;
 
 use crate::actix::helpers::process_response;
-use crate::common::points::do_get_points;
+use crate::common::points::{do_facet, do_get_points};
 
 async fn do_get_point(
     toc: &TableOfContent,
@@ -101,3 +103,22 @@ pub async fn scroll_points(
         scroll_get_points(&toc.into_inner(), &collection_name, request.into_inner()).await;
     process_response(response, timing)
 }
+
+#[post("/collections/{name}/points/facet")]
+pub async fn facet_points(
+    toc: web::Data<Arc<TableOfContent>>,
+    path: web::Path<String>,
+    request: web::Json<FacetRequest>,
+) -> impl Responder {
+    let collection_name = path.into_inner();
+    let timing = Instant::now();
+
+    let response = do_facet(
+        &toc.into_inner(),
+        &collection_name,
+        request.into_inner(),
+        None,
+    )
+    .await;
+    process_response(response, timing)
+}
@@ -3,11 +3,15 @@ use std::sync::Arc;
 use actix_web::rt::time::Instant;
 use actix_web::{post, web, Responder};
 
-use collection::operations::types::SearchRequest;
+use collection::operations::types::{
+    DistanceMatrixRequest, HybridSearchRequest, SearchGroupsRequest, SearchRequest,
+};
 use storage::content_manager::toc::TableOfContent;
 
 use crate::actix::helpers::process_response;
-use crate::common::points::do_search_points;
+use crate::common::points::{
+    do_distance_matrix, do_hybrid_search_points, do_search_point_groups, do_search_points,
+};
 
 #[post("/collections/{name}/points/search")]
 pub async fn search_points(
@@ -24,6 +28,84 @@ pub async fn search_points(
         request.into_inner(),
         None,
     )
+    .await
+    .map(|result| result.points);
+
+    process_response(response, timing)
+}
+
+#[post("/collections/{name}/points/search/explain")]
+pub async fn search_points_explain(
+    toc: web::Data<Arc<TableOfContent>>,
+    path: web::Path<String>,
+    request: web::Json<SearchRequest>,
+) -> impl Responder {
+    let collection_name = path.into_inner();
+    let timing = Instant::now();
+
+    let mut request = request.into_inner();
+    request.with_explain = true;
+
+    let response = do_search_points(&toc.into_inner(), &collection_name, request, None).await;
+
+    process_response(response, timing)
+}
+
+#[post("/collections/{name}/points/search/groups")]
+pub async fn search_point_groups(
+    toc: web::Data<Arc<TableOfContent>>,
+    path: web::Path<String>,
+    request: web::Json<SearchGroupsRequest>,
+) -> impl Responder {
+    let collection_name = path.into_inner();
+    let timing = Instant::now();
+
+    let response = do_search_point_groups(
+        &toc.into_inner(),
+        &collection_name,
+        request.into_inner(),
+        None,
+    )
+    .await;
+
+    process_response(response, timing)
+}
+
+#[post("/collections/{name}/points/search/hybrid")]
+pub async fn hybrid_search_points(
+    toc: web::Data<Arc<TableOfContent>>,
+    path: web::Path<String>,
+    request: web::Json<HybridSearchRequest>,
+) -> impl Responder {
+    let collection_name = path.into_inner();
+    let timing = Instant::now();
+
+    let response = do_hybrid_search_points(
+        &toc.into_inner(),
+        &collection_name,
+        request.into_inner(),
+        None,
+    )
+    .await;
+
+    process_response(response, timing)
+}
+
+#[post("/collections/{name}/points/search/matrix")]
+pub async fn distance_matrix_points(
+    toc: web::Data<Arc<TableOfContent>>,
+    path: web::Path<String>,
+    request: web::Json<DistanceMatrixRequest>,
+) -> impl Responder {
+    let collection_name = path.into_inner();
+    let timing = Instant::now();
+
+    let response = do_distance_matrix(
+        &toc.into_inner(),
+        &collection_name,
+        request.into_inner(),
+        None,
+    )
     .await;
 
     process_response(response, timing)
@@ -23,6 +23,8 @@ where
                 StorageError::NotFound { .. } => HttpResponse::NotFound(),
                 StorageError::ServiceError { .. } => HttpResponse::InternalServerError(),
                 StorageError::BadRequest { .. } => HttpResponse::BadRequest(),
+                StorageError::Timeout { .. } => HttpResponse::RequestTimeout(),
+                StorageError::ResourceExhausted { .. } => HttpResponse::TooManyRequests(),
             };
 
             resp.json(ApiResponse::<()> {
@@ -4,16 +4,22 @@ pub mod helpers;
 
 use crate::actix::api::collections_api::config_collections_api;
 use ::api::grpc::models::{ApiResponse, ApiStatus, VersionInfo};
+use actix_web::dev::Service;
 use actix_web::middleware::Logger;
 use actix_web::web::Data;
 use actix_web::{error, get, web, App, HttpRequest, HttpResponse, HttpServer, Responder};
+use futures::future::{ready, Either};
 use std::sync::Arc;
 use storage::content_manager::toc::TableOfContent;
 
 use crate::actix::api::recommend_api::recommend_points;
-use crate::actix::api::retrieve_api::{get_point, get_points, scroll_points};
-use crate::actix::api::search_api::search_points;
+use crate::actix::api::retrieve_api::{facet_points, get_point, get_points, scroll_points};
+use crate::actix::api::search_api::{
+    distance_matrix_points, hybrid_search_points, search_point_groups, search_points,
+    search_points_explain,
+};
 use crate::actix::api::update_api::config_update_api;
+use crate::common::rate_limiter::{RateLimiter, API_KEY_HEADER};
 use crate::settings::{max_web_workers, Settings};
 
 fn json_error_handler(err: error::JsonPayloadError, _req: &HttpRequest) -> error::Error {
@@ -35,17 +41,46 @@ fn json_error_handler(err: error::JsonPayloadError, _req: &HttpRequest) -> error
     error::InternalError::from_response(err, response).into()
 }
 
+fn too_many_requests_error() -> error::Error {
+    let detail = "Too many requests, rate limit exceeded";
+    let response = HttpResponse::TooManyRequests().json(ApiResponse::<()> {
+        result: None,
+        status: ApiStatus::Error(detail.to_string()),
+        time: 0.0,
+    });
+    error::InternalError::from_response(detail, response).into()
+}
+
 #[get("/")]
 pub async fn index() -> impl Responder {
     HttpResponse::Ok().json(VersionInfo::default())
 }
 
 #[allow(dead_code)]
-pub fn init(toc: Arc<TableOfContent>, settings: Settings) -> std::io::Result<()> {
+pub fn init(
+    toc: Arc<TableOfContent>,
+    rate_limiter: Option<Arc<RateLimiter>>,
+    settings: Settings,
+) -> std::io::Result<()> {
     actix_web::rt::System::new().block_on(async {
         let toc_data = web::Data::new(toc);
         HttpServer::new(move || {
+            let rate_limiter = rate_limiter.clone();
             App::new()
+                .wrap_fn(move |req, srv| {
+                    let is_allowed = rate_limiter.as_ref().map_or(true, |rate_limiter| {
+                        let api_key = req
+                            .headers()
+                            .get(API_KEY_HEADER)
+                            .and_then(|key| key.to_str().ok());
+                        rate_limiter.check(api_key)
+                    });
+                    if is_allowed {
+                        Either::Left(srv.call(req))
+                    } else {
+                        Either::Right(ready(Err(too_many_requests_error())))
+                    }
+                })
                 .wrap(Logger::default())
                 .app_data(toc_data.clone())
                 .app_data(Data::new(
@@ -59,7 +94,12 @@ pub fn init(toc: Arc<TableOfContent>, settings: Settings) -> std::io::Result<()>
                 .service(get_point)
                 .service(get_points)
                 .service(scroll_points)
+                .service(facet_points)
                 .service(search_points)
+                .service(search_points_explain)
+                .service(search_point_groups)
+                .service(hybrid_search_points)
+                .service(distance_matrix_points)
                 .service(recommend_points)
         })
         .workers(max_web_workers(&settings))
@@ -4,3 +4,5 @@ pub mod collections;
 pub mod helpers;
 #[allow(dead_code)] // May contain functions used in different binaries. Not actually dead
 pub mod points;
+#[allow(dead_code)] // May contain functions used in different binaries. Not actually dead
+pub mod rate_limiter;
@@ -1,7 +1,9 @@
 use collection::operations::payload_ops::{DeletePayload, PayloadOps, SetPayload};
 use collection::operations::point_ops::{PointInsertOperations, PointOperations, PointsSelector};
 use collection::operations::types::{
-    PointRequest, Record, ScrollRequest, ScrollResult, SearchRequest, UpdateResult,
+    CoreSearchRequest, DistanceMatrix, DistanceMatrixRequest, FacetRequest, FacetResult,
+    GroupsResult, HybridSearchRequest, PointRequest, Record, ScrollRequest, ScrollResult,
+    SearchExplainResult, SearchGroupsRequest, SearchRequest, UpdateResult,
 };
 use collection::operations::{CollectionUpdateOperations, CreateIndex, FieldIndexOperations};
 use collection::shard::ShardId;
@@ -145,10 +147,59 @@ pub async fn do_search_points(
     collection_name: &str,
     request: SearchRequest,
     shard_selection: Option<ShardId>,
-) -> Result<Vec<ScoredPoint>, StorageError> {
+) -> Result<SearchExplainResult, StorageError> {
     toc.search(collection_name, request, shard_selection).await
 }
 
+pub async fn do_search_point_groups(
+    toc: &TableOfContent,
+    collection_name: &str,
+    request: SearchGroupsRequest,
+    shard_selection: Option<ShardId>,
+) -> Result<GroupsResult, StorageError> {
+    toc.search_groups(collection_name, request, shard_selection)
+        .await
+}
+
+pub async fn do_hybrid_search_points(
+    toc: &TableOfContent,
+    collection_name: &str,
+    request: HybridSearchRequest,
+    shard_selection: Option<ShardId>,
+) -> Result<Vec<ScoredPoint>, StorageError> {
+    toc.hybrid_search(collection_name, request, shard_selection)
+        .await
+}
+
+pub async fn do_facet(
+    toc: &TableOfContent,
+    collection_name: &str,
+    request: FacetRequest,
+    shard_selection: Option<ShardId>,
+) -> Result<FacetResult, StorageError> {
+    toc.facet(collection_name, request, shard_selection).await
+}
+
+pub async fn do_distance_matrix(
+    toc: &TableOfContent,
+    collection_name: &str,
+    request: DistanceMatrixRequest,
+    shard_selection: Option<ShardId>,
+) -> Result<DistanceMatrix, StorageError> {
+    toc.distance_matrix(collection_name, request, shard_selection)
+        .await
+}
+
+pub async fn do_core_search_points(
+    toc: &TableOfContent,
+    collection_name: &str,
+    request: CoreSearchRequest,
+    shard_selection: Option<ShardId>,
+) -> Result<Vec<ScoredPoint>, StorageError> {
+    toc.core_search(collection_name, request, shard_selection)
+        .await
+}
+
 pub async fn do_get_points(
     toc: &TableOfContent,
     collection_name: &str,
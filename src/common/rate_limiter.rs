@@ -0,0 +1,149 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Name of the HTTP header and gRPC metadata key, which identifies the client for rate limiting
+pub const API_KEY_HEADER: &str = "api-key";
+
+/// Requests without API key are accounted together under this key
+const ANONYMOUS_KEY: &str = "";
+
+/// Max number of tracked clients. If all of them are tracked, requests of other clients are
+/// accounted together with anonymous ones
+const MAX_TRACKED_CLIENTS: usize = 10_000;
+
+/// How often clients with fully restored buckets are forgotten, if all clients are tracked
+const PURGE_INTERVAL: Duration = Duration::from_secs(1);
+
+struct TokenBucket {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    /// Adds tokens accumulated since the last refill, returns the current amount of tokens
+    fn refill(&mut self, now: Instant, rate: f64, capacity: f64) -> f64 {
+        let elapsed = now
+            .saturating_duration_since(self.last_refill)
+            .as_secs_f64();
+        self.tokens = (self.tokens + elapsed * rate).min(capacity);
+        self.last_refill = now;
+        self.tokens
+    }
+}
+
+/// Limits the rate of requests of each client, identified by API key, with a token bucket.
+/// Each client can make a burst of up to `requests_per_second` requests at once.
+pub struct RateLimiter {
+    requests_per_second: f64,
+    max_tracked_clients: usize,
+    buckets: Mutex<Buckets>,
+}
+
+#[derive(Default)]
+struct Buckets {
+    by_key: HashMap<String, TokenBucket>,
+    last_purge: Option<Instant>,
+}
+
+impl RateLimiter {
+    pub fn new(requests_per_second: u32) -> Self {
+        RateLimiter {
+            requests_per_second: requests_per_second as f64,
+            max_tracked_clients: MAX_TRACKED_CLIENTS,
+            buckets: Default::default(),
+        }
+    }
+
+    /// Consumes a token of the client, returns `false` if the request should be rejected
+    pub fn check(&self, api_key: Option<&str>) -> bool {
+        self.check_at(api_key, Instant::now())
+    }
+
+    fn check_at(&self, api_key: Option<&str>, now: Instant) -> bool {
+        let mut key = api_key.unwrap_or(ANONYMOUS_KEY);
+        let rate = self.requests_per_second;
+
+        let mut buckets = self.buckets.lock().unwrap();
+        let Buckets { by_key, last_purge } = &mut *buckets;
+
+        if by_key.len() >= self.max_tracked_clients && !by_key.contains_key(key) {
+            let purge_due = last_purge.map_or(true, |last_purge| {
+                now.saturating_duration_since(last_purge) >= PURGE_INTERVAL
+            });
+            if purge_due {
+                // Full bucket is not different from a new one, so there is no need to keep it
+                by_key.retain(|_, bucket| bucket.refill(now, rate, rate) < rate);
+                *last_purge = Some(now);
+            }
+            // Unknown clients can't get a new bucket with each new key
+            if by_key.len() >= self.max_tracked_clients {
+                key = ANONYMOUS_KEY;
+            }
+        }
+
+        let bucket = by_key.entry(key.to_string()).or_insert(TokenBucket {
+            tokens: rate,
+            last_refill: now,
+        });
+
+        if bucket.refill(now, rate, rate) >= 1.0 {
+            bucket.tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rate_limiter() {
+        let rate_limiter = RateLimiter::new(2);
+        let start = Instant::now();
+
+        assert!(rate_limiter.check_at(Some("key-1"), start));
+        assert!(rate_limiter.check_at(Some("key-1"), start));
+        assert!(!rate_limiter.check_at(Some("key-1"), start));
+
+        // Other clients are not affected
+        assert!(rate_limiter.check_at(Some("key-2"), start));
+        assert!(rate_limiter.check_at(None, start));
+
+        // Half a second restores one token
+        let later = start + Duration::from_millis(500);
+        assert!(rate_limiter.check_at(Some("key-1"), later));
+        assert!(!rate_limiter.check_at(Some("key-1"), later));
+    }
+
+    #[test]
+    fn test_rate_limiter_rotating_keys() {
+        let rate_limiter = RateLimiter {
+            max_tracked_clients: 3,
+            ..RateLimiter::new(2)
+        };
+        let start = Instant::now();
+
+        for key in ["key-1", "key-2", "key-3"] {
+            assert!(rate_limiter.check_at(Some(key), start));
+        }
+
+        // New keys share the bucket of anonymous requests, once all clients are tracked
+        assert!(rate_limiter.check_at(Some("key-4"), start));
+        assert!(rate_limiter.check_at(Some("key-5"), start));
+        assert!(!rate_limiter.check_at(Some("key-6"), start));
+        assert!(!rate_limiter.check_at(None, start));
+        assert!(rate_limiter.check_at(Some("key-1"), start));
+        assert_eq!(rate_limiter.buckets.lock().unwrap().by_key.len(), 4);
+
+        // Idle clients are forgotten, so new ones get their own buckets
+        let later = start + Duration::from_secs(2);
+        assert!(rate_limiter.check_at(Some("key-7"), later));
+        assert!(rate_limiter.check_at(Some("key-7"), later));
+        assert!(!rate_limiter.check_at(Some("key-7"), later));
+        assert!(rate_limiter.check_at(Some("key-8"), later));
+    }
+}
@@ -466,6 +466,8 @@ mod tests {
                         wal_config: None,
                         optimizers_config: None,
                         shard_number: 1,
+                        product_quantization: None,
+                        vector_datatype: None,
                     },
                 }),
                 None,
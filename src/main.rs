@@ -17,6 +17,7 @@ use clap::Parser;
 use storage::content_manager::toc::{ConsensusEnabled, TableOfContent};
 
 use crate::common::helpers::create_search_runtime;
+use crate::common::rate_limiter::RateLimiter;
 use crate::settings::Settings;
 
 /// Qdrant (read: quadrant ) is a vector similarity search engine.
@@ -71,6 +72,12 @@ fn main() -> std::io::Result<()> {
     });
 
     let toc_arc = Arc::new(toc);
+    // Shared between HTTP and gRPC, so that the rate is accounted across both APIs
+    let rate_limiter = settings
+        .service
+        .max_requests_per_second
+        .filter(|&requests_per_second| requests_per_second > 0)
+        .map(|requests_per_second| Arc::new(RateLimiter::new(requests_per_second)));
     let mut handles: Vec<JoinHandle<Result<(), Error>>> = vec![];
 
     if settings.cluster.enabled {
@@ -136,10 +143,11 @@ fn main() -> std::io::Result<()> {
     #[cfg(feature = "web")]
     {
         let toc_arc = toc_arc.clone();
+        let rate_limiter = rate_limiter.clone();
         let settings = settings.clone();
         let handle = thread::Builder::new()
             .name("web".to_string())
-            .spawn(move || actix::init(toc_arc, settings))
+            .spawn(move || actix::init(toc_arc, rate_limiter, settings))
             .unwrap();
         handles.push(handle);
     }
@@ -149,7 +157,7 @@ fn main() -> std::io::Result<()> {
         let settings = settings.clone();
         let handle = thread::Builder::new()
             .name("grpc".to_string())
-            .spawn(move || tonic::init(toc_arc, settings.service.host, grpc_port))
+            .spawn(move || tonic::init(toc_arc, rate_limiter, settings.service.host, grpc_port))
             .unwrap();
         handles.push(handle);
     } else {
@@ -3,8 +3,10 @@ use serde::{Deserialize, Serialize};
 
 use collection::operations::point_ops::{PointInsertOperations, PointsSelector};
 use collection::operations::types::{
-    CollectionInfo, PointRequest, RecommendRequest, Record, ScrollRequest, ScrollResult,
-    SearchRequest, UpdateResult,
+    CollectionInfo, DistanceMatrix, DistanceMatrixRequest, FacetRequest, FacetResult, GroupsResult,
+    HybridSearchRequest, PointRequest, RecallReport, RecallRequest, RecommendRequest, Record,
+    ScrollRequest, ScrollResult, SearchExplainResult, SearchGroupsRequest, SearchRequest,
+    UpdateResult,
 };
 use collection::operations::CollectionUpdateOperations;
 use segment::types::ScoredPoint;
@@ -39,6 +41,16 @@ struct AllDefinitions {
     ag: CreateFieldIndex,
     ah: PointsSelector,
     ai: PointInsertOperations,
+    aj: RecallRequest,
+    ak: RecallReport,
+    al: SearchGroupsRequest,
+    am: GroupsResult,
+    an: HybridSearchRequest,
+    ao: FacetRequest,
+    ap: FacetResult,
+    aq: DistanceMatrixRequest,
+    ar: DistanceMatrix,
+    at: SearchExplainResult,
 }
 
 fn save_schema<T: JsonSchema>() {
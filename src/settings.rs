@@ -10,6 +10,7 @@ pub struct ServiceConfig {
     pub grpc_port: Option<u16>, // None means that gRPC is disabled
     pub max_request_size_mb: usize,
     pub max_workers: Option<usize>,
+    pub max_requests_per_second: Option<u32>, // None or 0 means that rate is not limited
 }
 
 #[derive(Debug, Deserialize, Clone, Default)]
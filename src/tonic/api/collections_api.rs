@@ -3,10 +3,12 @@ use tonic::{Request, Response, Status};
 use crate::common::collections::*;
 use api::grpc::qdrant::collections_server::Collections;
 use api::grpc::qdrant::{
-    ChangeAliases, CollectionOperationResponse, CreateCollection, DeleteCollection,
-    GetCollectionInfoRequest, GetCollectionInfoResponse, ListCollectionsRequest,
-    ListCollectionsResponse, UpdateCollection,
+    ChangeAliases, CollectionOperationResponse, CreateCollection, DeleteCollection, EvaluateRecall,
+    EvaluateRecallResponse, ForceOptimize, GetCollectionInfoRequest, GetCollectionInfoResponse,
+    ListCollectionsRequest, ListCollectionsResponse, PauseOptimizers, ResumeOptimizers,
+    UpdateCollection,
 };
+use collection::operations::types::RecallRequest;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 
@@ -95,6 +97,83 @@ impl Collections for CollectionsService {
     ) -> Result<Response<CollectionOperationResponse>, Status> {
         self.perform_operation(request).await
     }
+
+    async fn pause_optimizers(
+        &self,
+        request: Request<PauseOptimizers>,
+    ) -> Result<Response<CollectionOperationResponse>, Status> {
+        let timing = Instant::now();
+        let PauseOptimizers { collection_name } = request.into_inner();
+        let result = self
+            .toc
+            .pause_optimizers(&collection_name)
+            .await
+            .map_err(error_to_status)?;
+        Ok(Response::new(CollectionOperationResponse::from((
+            timing, result,
+        ))))
+    }
+
+    async fn resume_optimizers(
+        &self,
+        request: Request<ResumeOptimizers>,
+    ) -> Result<Response<CollectionOperationResponse>, Status> {
+        let timing = Instant::now();
+        let ResumeOptimizers { collection_name } = request.into_inner();
+        let result = self
+            .toc
+            .resume_optimizers(&collection_name)
+            .await
+            .map_err(error_to_status)?;
+        Ok(Response::new(CollectionOperationResponse::from((
+            timing, result,
+        ))))
+    }
+
+    async fn force_optimize(
+        &self,
+        request: Request<ForceOptimize>,
+    ) -> Result<Response<CollectionOperationResponse>, Status> {
+        let timing = Instant::now();
+        let ForceOptimize { collection_name } = request.into_inner();
+        let result = self
+            .toc
+            .force_optimize(&collection_name)
+            .await
+            .map_err(error_to_status)?;
+        Ok(Response::new(CollectionOperationResponse::from((
+            timing, result,
+        ))))
+    }
+
+    async fn evaluate_recall(
+        &self,
+        request: Request<EvaluateRecall>,
+    ) -> Result<Response<EvaluateRecallResponse>, Status> {
+        let timing = Instant::now();
+        let EvaluateRecall {
+            collection_name,
+            sample,
+            top,
+            hnsw_ef,
+        } = request.into_inner();
+        let report = self
+            .toc
+            .evaluate_recall(
+                &collection_name,
+                RecallRequest {
+                    sample: sample as usize,
+                    top: top as usize,
+                    hnsw_ef: hnsw_ef.map(|ef| ef as usize),
+                },
+            )
+            .await
+            .map_err(error_to_status)?;
+        Ok(Response::new(EvaluateRecallResponse {
+            result: Some(report.into()),
+            time: timing.elapsed().as_secs_f64(),
+        }))
+    }
 }
 
 trait WithTimeout {
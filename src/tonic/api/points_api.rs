@@ -1,16 +1,19 @@
-use tonic::{Request, Response, Status};
+use tonic::{Request, Response, Status, Streaming};
 
 use api::grpc::qdrant::points_server::Points;
 
 use crate::tonic::api::points_common::{
-    clear_payload, create_field_index, delete, delete_field_index, delete_payload, get, recommend,
-    scroll, search, set_payload, upsert,
+    clear_payload, create_field_index, delete, delete_field_index, delete_payload, distance_matrix,
+    facet, get, recommend, scroll, search, search_groups, search_hybrid, set_payload, upsert,
+    upsert_stream,
 };
 use api::grpc::qdrant::{
     ClearPayloadPoints, CreateFieldIndexCollection, DeleteFieldIndexCollection,
-    DeletePayloadPoints, DeletePoints, GetPoints, GetResponse, PointsOperationResponse,
-    RecommendPoints, RecommendResponse, ScrollPoints, ScrollResponse, SearchPoints, SearchResponse,
-    SetPayloadPoints, UpsertPoints,
+    DeletePayloadPoints, DeletePoints, DistanceMatrixPoints, DistanceMatrixResponse, FacetPoints,
+    FacetResponse, GetPoints, GetResponse, PointsOperationResponse, RecommendPoints,
+    RecommendResponse, ScrollPoints, ScrollResponse, SearchGroupsResponse, SearchHybridPoints,
+    SearchPointGroups, SearchPoints, SearchResponse, SetPayloadPoints, UpsertPoints,
+    UpsertPointsChunk, UpsertStreamResponse,
 };
 use std::sync::Arc;
 
@@ -88,6 +91,34 @@ impl Points for PointsService {
         search(self.toc.as_ref(), request.into_inner(), None).await
     }
 
+    async fn search_groups(
+        &self,
+        request: Request<SearchPointGroups>,
+    ) -> Result<Response<SearchGroupsResponse>, Status> {
+        search_groups(self.toc.as_ref(), request.into_inner(), None).await
+    }
+
+    async fn search_hybrid(
+        &self,
+        request: Request<SearchHybridPoints>,
+    ) -> Result<Response<SearchResponse>, Status> {
+        search_hybrid(self.toc.as_ref(), request.into_inner(), None).await
+    }
+
+    async fn facet(
+        &self,
+        request: Request<FacetPoints>,
+    ) -> Result<Response<FacetResponse>, Status> {
+        facet(self.toc.as_ref(), request.into_inner(), None).await
+    }
+
+    async fn distance_matrix(
+        &self,
+        request: Request<DistanceMatrixPoints>,
+    ) -> Result<Response<DistanceMatrixResponse>, Status> {
+        distance_matrix(self.toc.as_ref(), request.into_inner(), None).await
+    }
+
     async fn scroll(
         &self,
         request: Request<ScrollPoints>,
@@ -101,6 +132,13 @@ impl Points for PointsService {
     ) -> Result<Response<RecommendResponse>, Status> {
         recommend(self.toc.as_ref(), request.into_inner(), None).await
     }
+
+    async fn upsert_stream(
+        &self,
+        request: Request<Streaming<UpsertPointsChunk>>,
+    ) -> Result<Response<UpsertStreamResponse>, Status> {
+        upsert_stream(self.toc.as_ref(), request.into_inner()).await
+    }
 }
 
 #[cfg(test)]
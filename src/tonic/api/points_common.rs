@@ -1,26 +1,41 @@
 use crate::common::points::{
-    do_clear_payload, do_create_index, do_delete_index, do_delete_payload, do_delete_points,
-    do_get_points, do_scroll_points, do_search_points, do_set_payload, do_update_points,
+    do_clear_payload, do_core_search_points, do_create_index, do_delete_index, do_delete_payload,
+    do_delete_points, do_distance_matrix, do_facet, do_get_points, do_hybrid_search_points,
+    do_scroll_points, do_search_point_groups, do_search_points, do_set_payload, do_update_points,
     CreateFieldIndex,
 };
 use api::grpc::conversions::proto_to_payloads;
 use api::grpc::qdrant::{
     ClearPayloadPoints, CreateFieldIndexCollection, DeleteFieldIndexCollection,
-    DeletePayloadPoints, DeletePoints, FieldType, GetPoints, GetResponse, PointsOperationResponse,
-    RecommendPoints, RecommendResponse, ScrollPoints, ScrollResponse, SearchPoints, SearchResponse,
-    SetPayloadPoints, UpsertPoints,
+    DeletePayloadPoints, DeletePoints, DistanceMatrixPoints, DistanceMatrixResponse, FacetPoints,
+    FacetResponse, FieldType, GetPoints, GetResponse, PointId, PointsOperationResponse,
+    RecommendPoints, RecommendResponse, SampleNeighbours, SampleNeighboursInternal,
+    SampleNeighboursResponse, SamplePointsInternal, SamplePointsResponse, ScrollPoints,
+    ScrollResponse, SearchGroupsResponse, SearchHybridPoints, SearchPointGroups, SearchPoints,
+    SearchResponse, SetPayloadPoints, UpsertPoints, UpsertPointsChunk, UpsertStreamResponse,
+    Vector,
 };
 use collection::operations::payload_ops::DeletePayload;
-use collection::operations::point_ops::{PointInsertOperations, PointOperations, PointsList};
-use collection::operations::types::{PointRequest, ScrollRequest, SearchRequest};
+use collection::operations::point_ops::{
+    PointInsertOperations, PointOperations, PointStruct, PointsList,
+};
+use collection::operations::types::{
+    CoreSearchRequest, DistanceMatrixRequest, FacetRequest, HybridSearchRequest, PointRequest,
+    RecommendExample, RecommendStrategy, SampleNeighboursRequest, ScrollRequest,
+    SearchGroupsRequest, SearchRequest,
+};
 use collection::operations::CollectionUpdateOperations;
 use collection::shard::ShardId;
-use segment::types::PayloadSchemaType;
+use futures::{Stream, TryStreamExt};
+use segment::types::{PayloadSchemaType, QueryVector};
 use std::time::Instant;
 use storage::content_manager::conversions::error_to_status;
 use storage::content_manager::toc::TableOfContent;
 use tonic::{Response, Status};
 
+/// Max number of points, written with a single update operation of the upsert stream
+const UPSERT_STREAM_BATCH_SIZE: usize = 1000;
+
 pub fn points_operation_response(
     timing: Instant,
     update_result: collection::operations::types::UpdateResult,
@@ -66,6 +81,97 @@ pub async fn upsert(
     Ok(Response::new(response))
 }
 
+async fn upsert_batch(
+    toc: &TableOfContent,
+    collection_name: &str,
+    points: Vec<PointStruct>,
+) -> Result<(), Status> {
+    let operation = CollectionUpdateOperations::PointOperation(PointOperations::UpsertPoints(
+        PointInsertOperations::PointsList(PointsList { points }),
+    ));
+
+    // Waiting for the batch to be applied prevents reading the stream faster,
+    // than the update worker is able to process it
+    do_update_points(toc, collection_name, operation, None, true)
+        .await
+        .map_err(error_to_status)?;
+
+    Ok(())
+}
+
+/// Reports, how many points of the stream were upserted before the failed batch
+fn upsert_stream_error(status: Status, points_count: usize) -> Status {
+    Status::new(
+        status.code(),
+        format!(
+            "{} points of the stream were upserted before the failure: {}",
+            points_count,
+            status.message()
+        ),
+    )
+}
+
+pub async fn upsert_stream(
+    toc: &TableOfContent,
+    mut chunks: impl Stream<Item = Result<UpsertPointsChunk, Status>> + Unpin,
+) -> Result<Response<UpsertStreamResponse>, Status> {
+    let timing = Instant::now();
+
+    let mut stream_collection_name: Option<String> = None;
+    let mut batch: Vec<PointStruct> = Vec::with_capacity(UPSERT_STREAM_BATCH_SIZE);
+    let mut points_count = 0;
+    let mut operations_count = 0;
+
+    while let Some(chunk) = chunks.try_next().await? {
+        let UpsertPointsChunk {
+            collection_name,
+            points,
+        } = chunk;
+
+        match &stream_collection_name {
+            Some(expected_name) if *expected_name != collection_name => {
+                return Err(Status::invalid_argument(format!(
+                    "All chunks of the stream must have the same collection name, expected: {}, got: {}",
+                    expected_name, collection_name
+                )));
+            }
+            Some(_) => {}
+            None => stream_collection_name = Some(collection_name),
+        }
+        let collection_name = stream_collection_name.as_deref().unwrap_or_default();
+
+        for point in points {
+            batch.push(point.try_into()?);
+            if batch.len() >= UPSERT_STREAM_BATCH_SIZE {
+                let batch_size = batch.len();
+                upsert_batch(toc, collection_name, std::mem::take(&mut batch))
+                    .await
+                    .map_err(|status| upsert_stream_error(status, points_count))?;
+                points_count += batch_size;
+                operations_count += 1;
+            }
+        }
+    }
+
+    if let Some(collection_name) = &stream_collection_name {
+        if !batch.is_empty() {
+            let batch_size = batch.len();
+            upsert_batch(toc, collection_name, batch)
+                .await
+                .map_err(|status| upsert_stream_error(status, points_count))?;
+            points_count += batch_size;
+            operations_count += 1;
+        }
+    }
+
+    let response = UpsertStreamResponse {
+        points_count: points_count as u64,
+        operations_count,
+        time: timing.elapsed().as_secs_f64(),
+    };
+    Ok(Response::new(response))
+}
+
 pub async fn delete(
     toc: &TableOfContent,
     delete_points: DeletePoints,
@@ -274,43 +380,255 @@ pub async fn search(
     search_points: SearchPoints,
     shard_selection: Option<ShardId>,
 ) -> Result<Response<SearchResponse>, Status> {
-    let SearchPoints {
+    let collection_name = search_points.collection_name.clone();
+    let search_request = SearchRequest::try_from(search_points)?;
+
+    let timing = Instant::now();
+    let search_result = do_search_points(toc, &collection_name, search_request, shard_selection)
+        .await
+        .map_err(error_to_status)?;
+
+    let response = SearchResponse {
+        result: search_result
+            .points
+            .into_iter()
+            .map(|point| point.into())
+            .collect(),
+        time: timing.elapsed().as_secs_f64(),
+        explain: search_result
+            .segments
+            .into_iter()
+            .map(|explain| explain.into())
+            .collect(),
+    };
+
+    Ok(Response::new(response))
+}
+
+pub async fn search_groups(
+    toc: &TableOfContent,
+    search_point_groups: SearchPointGroups,
+    shard_selection: Option<ShardId>,
+) -> Result<Response<SearchGroupsResponse>, Status> {
+    let collection_name = search_point_groups.collection_name.clone();
+    let search_groups_request = SearchGroupsRequest::try_from(search_point_groups)?;
+
+    let timing = Instant::now();
+    let groups_result = do_search_point_groups(
+        toc,
+        &collection_name,
+        search_groups_request,
+        shard_selection,
+    )
+    .await
+    .map_err(error_to_status)?;
+
+    let response = SearchGroupsResponse {
+        result: Some(groups_result.into()),
+        time: timing.elapsed().as_secs_f64(),
+    };
+
+    Ok(Response::new(response))
+}
+
+pub async fn search_hybrid(
+    toc: &TableOfContent,
+    search_hybrid_points: SearchHybridPoints,
+    shard_selection: Option<ShardId>,
+) -> Result<Response<SearchResponse>, Status> {
+    let collection_name = search_hybrid_points.collection_name.clone();
+    let hybrid_request = HybridSearchRequest::try_from(search_hybrid_points)?;
+
+    let timing = Instant::now();
+    let scored_points =
+        do_hybrid_search_points(toc, &collection_name, hybrid_request, shard_selection)
+            .await
+            .map_err(error_to_status)?;
+
+    let response = SearchResponse {
+        result: scored_points
+            .into_iter()
+            .map(|point| point.into())
+            .collect(),
+        time: timing.elapsed().as_secs_f64(),
+        explain: vec![],
+    };
+
+    Ok(Response::new(response))
+}
+
+pub async fn facet(
+    toc: &TableOfContent,
+    facet_points: FacetPoints,
+    shard_selection: Option<ShardId>,
+) -> Result<Response<FacetResponse>, Status> {
+    let collection_name = facet_points.collection_name.clone();
+    let facet_request = FacetRequest::try_from(facet_points)?;
+
+    let timing = Instant::now();
+    let facet_result = do_facet(toc, &collection_name, facet_request, shard_selection)
+        .await
+        .map_err(error_to_status)?;
+
+    let response = FacetResponse {
+        result: Some(facet_result.into()),
+        time: timing.elapsed().as_secs_f64(),
+    };
+
+    Ok(Response::new(response))
+}
+
+pub async fn distance_matrix(
+    toc: &TableOfContent,
+    distance_matrix_points: DistanceMatrixPoints,
+    shard_selection: Option<ShardId>,
+) -> Result<Response<DistanceMatrixResponse>, Status> {
+    let collection_name = distance_matrix_points.collection_name.clone();
+    let distance_matrix_request = DistanceMatrixRequest::try_from(distance_matrix_points)?;
+
+    let timing = Instant::now();
+    let matrix = do_distance_matrix(
+        toc,
+        &collection_name,
+        distance_matrix_request,
+        shard_selection,
+    )
+    .await
+    .map_err(error_to_status)?;
+
+    let response = DistanceMatrixResponse {
+        result: Some(matrix.into()),
+        time: timing.elapsed().as_secs_f64(),
+    };
+
+    Ok(Response::new(response))
+}
+
+/// Random sample of the points of the shard. Used to build the distance matrix on remote shards.
+pub async fn sample_points(
+    toc: &TableOfContent,
+    sample_points: SamplePointsInternal,
+) -> Result<Response<SamplePointsResponse>, Status> {
+    let SamplePointsInternal {
         collection_name,
-        vector,
         filter,
-        top,
-        with_vector,
-        with_payload,
-        params,
-        score_threshold,
-    } = search_points;
+        size,
+        shard_id,
+    } = sample_points;
+    let filter = filter.map(|f| f.try_into()).transpose()?;
 
-    let search_request = SearchRequest {
-        vector,
-        filter: filter.map(|f| f.try_into()).transpose()?,
-        params: params.map(|p| p.into()),
+    let timing = Instant::now();
+    let sample = toc
+        .sample_points(
+            &collection_name,
+            filter.as_ref(),
+            size as usize,
+            Some(shard_id),
+        )
+        .await
+        .map_err(error_to_status)?;
+
+    let response = SamplePointsResponse {
+        ids: sample.ids.into_iter().map(|id| id.into()).collect(),
+        total: sample.total as u64,
+        time: timing.elapsed().as_secs_f64(),
+    };
+
+    Ok(Response::new(response))
+}
+
+/// Neighbours of the sampled points among the points of the shard.
+/// Used to build the distance matrix on remote shards.
+pub async fn sample_neighbours(
+    toc: &TableOfContent,
+    sample_neighbours: SampleNeighboursInternal,
+) -> Result<Response<SampleNeighboursResponse>, Status> {
+    let SampleNeighboursInternal {
+        collection_name,
+        queries,
+        top,
+        shard_id,
+    } = sample_neighbours;
+    let queries = queries
+        .into_iter()
+        .map(|query| {
+            let id = query
+                .id
+                .ok_or_else(|| Status::invalid_argument("Sample query id is missing"))?
+                .try_into()?;
+            let vector = Some(query.vector).filter(|vector| !vector.is_empty());
+            Ok((id, vector))
+        })
+        .collect::<Result<_, Status>>()?;
+    let request = SampleNeighboursRequest {
+        queries,
         top: top as usize,
-        with_payload: with_payload.map(|wp| wp.try_into()).transpose()?,
-        with_vector: with_vector.unwrap_or(false),
-        score_threshold,
     };
 
     let timing = Instant::now();
-    let scored_points = do_search_points(toc, &collection_name, search_request, shard_selection)
+    let neighbours = toc
+        .sample_neighbours(&collection_name, request, Some(shard_id))
         .await
         .map_err(error_to_status)?;
 
+    let response = SampleNeighboursResponse {
+        result: neighbours
+            .into_iter()
+            .map(|neighbours| SampleNeighbours {
+                neighbours: neighbours.into_iter().map(|point| point.into()).collect(),
+            })
+            .collect(),
+        time: timing.elapsed().as_secs_f64(),
+    };
+
+    Ok(Response::new(response))
+}
+
+/// Search by the query, passed explicitly instead of the search vector.
+/// Used to forward recommendation and sparse queries to remote shards.
+pub async fn search_by_query(
+    toc: &TableOfContent,
+    search_points: SearchPoints,
+    query: QueryVector,
+    shard_selection: Option<ShardId>,
+) -> Result<Response<SearchResponse>, Status> {
+    let collection_name = search_points.collection_name.clone();
+    let mut search_request = CoreSearchRequest::from(SearchRequest::try_from(search_points)?);
+    search_request.query = query;
+
+    let timing = Instant::now();
+    let scored_points =
+        do_core_search_points(toc, &collection_name, search_request, shard_selection)
+            .await
+            .map_err(error_to_status)?;
+
     let response = SearchResponse {
         result: scored_points
             .into_iter()
             .map(|point| point.into())
             .collect(),
         time: timing.elapsed().as_secs_f64(),
+        explain: vec![],
     };
 
     Ok(Response::new(response))
 }
 
+/// Stored point ids go first, explicitly given vectors follow
+fn recommend_examples(
+    ids: Vec<PointId>,
+    vectors: Vec<Vector>,
+) -> Result<Vec<RecommendExample>, Status> {
+    ids.into_iter()
+        .map(|id| id.try_into().map(RecommendExample::PointId))
+        .chain(
+            vectors
+                .into_iter()
+                .map(|vector| Ok(RecommendExample::Vector(vector.data))),
+        )
+        .collect()
+}
+
 pub async fn recommend(
     toc: &TableOfContent,
     recommend_points: RecommendPoints,
@@ -326,23 +644,28 @@ pub async fn recommend(
         with_payload,
         params,
         score_threshold,
+        timeout,
+        strategy,
+        positive_vectors,
+        negative_vectors,
+        offset,
     } = recommend_points;
 
     let request = collection::operations::types::RecommendRequest {
-        positive: positive
-            .into_iter()
-            .map(|p| p.try_into())
-            .collect::<Result<_, _>>()?,
-        negative: negative
-            .into_iter()
-            .map(|p| p.try_into())
-            .collect::<Result<_, _>>()?,
+        positive: recommend_examples(positive, positive_vectors)?,
+        negative: recommend_examples(negative, negative_vectors)?,
+        strategy: strategy
+            .map(RecommendStrategy::try_from)
+            .transpose()?
+            .unwrap_or_default(),
         filter: filter.map(|f| f.try_into()).transpose()?,
         params: params.map(|p| p.into()),
         top: top as usize,
+        offset: offset.unwrap_or(0) as usize,
         with_payload: with_payload.map(|wp| wp.try_into()).transpose()?,
         with_vector: with_vector.unwrap_or(false),
         score_threshold,
+        timeout,
     };
 
     let timing = Instant::now();
@@ -374,6 +697,7 @@ pub async fn scroll(
         limit,
         with_vector,
         with_payload,
+        timeout,
     } = scroll_points;
 
     let scroll_request = ScrollRequest {
@@ -382,6 +706,7 @@ pub async fn scroll(
         filter: filter.map(|f| f.try_into()).transpose()?,
         with_payload: with_payload.map(|wp| wp.try_into()).transpose()?,
         with_vector: with_vector.unwrap_or(false),
+        timeout,
     };
 
     let timing = Instant::now();
@@ -436,3 +761,129 @@ pub async fn get(
 
     Ok(Response::new(response))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use api::grpc::qdrant::PointStruct as GrpcPointStruct;
+    use collection::optimizers_builder::OptimizersConfig;
+    use itertools::Itertools;
+    use segment::types::{Distance, ExtendedPointId};
+    use storage::content_manager::collection_meta_ops::{
+        CollectionMetaOperations, CreateCollection, CreateCollectionOperation,
+    };
+    use storage::types::{PerformanceConfig, StorageConfig};
+    use tempdir::TempDir;
+    use tokio::runtime::Runtime;
+
+    fn point(id: u64, vector: Vec<f32>) -> GrpcPointStruct {
+        GrpcPointStruct {
+            id: Some(ExtendedPointId::from(id).into()),
+            vector,
+            payload: Default::default(),
+            sparse_vector: None,
+            binary_vector: vec![],
+        }
+    }
+
+    fn chunk(points: Vec<GrpcPointStruct>) -> Result<UpsertPointsChunk, Status> {
+        Ok(UpsertPointsChunk {
+            collection_name: "test".to_string(),
+            points,
+        })
+    }
+
+    #[test]
+    fn test_upsert_stream() {
+        let storage_dir = TempDir::new("storage").unwrap();
+
+        let config = StorageConfig {
+            storage_path: storage_dir.path().to_str().unwrap().to_string(),
+            optimizers: OptimizersConfig {
+                deleted_threshold: 0.5,
+                vacuum_min_vector_number: 100,
+                default_segment_number: 2,
+                max_segment_size: 100_000,
+                memmap_threshold: 100_000,
+                indexing_threshold: 100_000,
+                payload_indexing_threshold: 100_000,
+                flush_interval_sec: 2,
+                max_optimization_threads: 1,
+                max_queued_updates: None,
+            },
+            wal: Default::default(),
+            performance: PerformanceConfig {
+                max_search_threads: 1,
+                max_concurrent_searches: None,
+            },
+            hnsw_index: Default::default(),
+        };
+
+        let runtime = Runtime::new().unwrap();
+        let handle = runtime.handle().clone();
+
+        let toc = TableOfContent::new(&config, runtime, None);
+
+        handle
+            .block_on(toc.submit_collection_operation(
+                CollectionMetaOperations::CreateCollection(CreateCollectionOperation {
+                    collection_name: "test".to_string(),
+                    create_collection: CreateCollection {
+                        vector_size: 4,
+                        distance: Distance::Dot,
+                        hnsw_config: None,
+                        wal_config: None,
+                        optimizers_config: None,
+                        shard_number: 1,
+                        product_quantization: None,
+                        vector_datatype: None,
+                    },
+                }),
+                None,
+            ))
+            .unwrap();
+
+        let points_count = |toc: &TableOfContent| {
+            handle.block_on(async {
+                let collection = toc.get_collection("test").await.unwrap();
+                collection.info(None).await.unwrap().vectors_count
+            })
+        };
+
+        // Chunks are split into batches of `UPSERT_STREAM_BATCH_SIZE` points
+        let valid_points = (0..UPSERT_STREAM_BATCH_SIZE as u64 + 10)
+            .map(|id| point(id, vec![1.0, 0.0, 1.0, 0.0]))
+            .collect_vec();
+        let (first_chunk, second_chunk) = valid_points.split_at(500);
+        let chunks = futures::stream::iter(vec![
+            chunk(first_chunk.to_vec()),
+            chunk(second_chunk.to_vec()),
+        ]);
+
+        let response = handle
+            .block_on(upsert_stream(&toc, chunks))
+            .unwrap()
+            .into_inner();
+        assert_eq!(response.points_count, UPSERT_STREAM_BATCH_SIZE as u64 + 10);
+        assert_eq!(response.operations_count, 2);
+        assert_eq!(points_count(&toc), UPSERT_STREAM_BATCH_SIZE + 10);
+
+        // The second batch contains a vector of wrong dimension
+        let first_batch = (10_000..10_000 + UPSERT_STREAM_BATCH_SIZE as u64)
+            .map(|id| point(id, vec![0.0, 1.0, 0.0, 1.0]))
+            .collect_vec();
+        let failing_batch = vec![point(20_000, vec![1.0, 1.0])];
+        let chunks = futures::stream::iter(vec![chunk(first_batch), chunk(failing_batch)]);
+
+        let status = handle.block_on(upsert_stream(&toc, chunks)).unwrap_err();
+        assert!(
+            status.message().starts_with(&format!(
+                "{} points of the stream were upserted before the failure",
+                UPSERT_STREAM_BATCH_SIZE
+            )),
+            "{}",
+            status.message()
+        );
+        assert_eq!(points_count(&toc), 2 * UPSERT_STREAM_BATCH_SIZE + 10);
+    }
+}
@@ -1,17 +1,20 @@
 use tonic::{Request, Response, Status};
 
 use crate::tonic::api::points_common::{
-    clear_payload, create_field_index, delete, delete_field_index, delete_payload, get, recommend,
-    scroll, search, set_payload, upsert,
+    clear_payload, create_field_index, delete, delete_field_index, delete_payload, facet, get,
+    recommend, sample_neighbours, sample_points, scroll, search, search_by_query, set_payload,
+    upsert,
 };
 use api::grpc::qdrant::points_internal_server::PointsInternal;
 use api::grpc::qdrant::{
     ClearPayloadPointsInternal, CreateFieldIndexCollectionInternal,
     DeleteFieldIndexCollectionInternal, DeletePayloadPointsInternal, DeletePointsInternal,
-    GetPointsInternal, GetResponse, PointsOperationResponse, RecommendPointsInternal,
-    RecommendResponse, ScrollPointsInternal, ScrollResponse, SearchPointsInternal, SearchResponse,
-    SetPayloadPointsInternal, UpsertPointsInternal,
+    FacetPointsInternal, FacetResponse, GetPointsInternal, GetResponse, PointsOperationResponse,
+    RecommendPointsInternal, RecommendResponse, SampleNeighboursInternal, SampleNeighboursResponse,
+    SamplePointsInternal, SamplePointsResponse, ScrollPointsInternal, ScrollResponse,
+    SearchPointsInternal, SearchResponse, SetPayloadPointsInternal, UpsertPointsInternal,
 };
+use segment::types::QueryVector;
 use std::sync::Arc;
 use storage::content_manager::toc::TableOfContent;
 
@@ -150,12 +153,19 @@ impl PointsInternal for PointsInternalService {
         let SearchPointsInternal {
             search_points,
             shard_id,
+            recommend_query,
+            sparse_query,
         } = request.into_inner();
 
         let search_points =
             search_points.ok_or_else(|| Status::invalid_argument("SearchPoints is missing"))?;
 
-        search(self.toc.as_ref(), search_points, Some(shard_id)).await
+        let query = match (recommend_query, sparse_query) {
+            (Some(recommend_query), _) => QueryVector::Recommend(recommend_query.into()),
+            (None, Some(sparse_query)) => QueryVector::Sparse(sparse_query.into()),
+            (None, None) => return search(self.toc.as_ref(), search_points, Some(shard_id)).await,
+        };
+        search_by_query(self.toc.as_ref(), search_points, query, Some(shard_id)).await
     }
 
     async fn recommend(
@@ -202,6 +212,35 @@ impl PointsInternal for PointsInternalService {
 
         get(self.toc.as_ref(), get_points, Some(shard_id)).await
     }
+
+    async fn facet(
+        &self,
+        request: Request<FacetPointsInternal>,
+    ) -> Result<Response<FacetResponse>, Status> {
+        let FacetPointsInternal {
+            facet_points,
+            shard_id,
+        } = request.into_inner();
+
+        let facet_points =
+            facet_points.ok_or_else(|| Status::invalid_argument("FacetPoints is missing"))?;
+
+        facet(self.toc.as_ref(), facet_points, Some(shard_id)).await
+    }
+
+    async fn sample(
+        &self,
+        request: Request<SamplePointsInternal>,
+    ) -> Result<Response<SamplePointsResponse>, Status> {
+        sample_points(self.toc.as_ref(), request.into_inner()).await
+    }
+
+    async fn sample_neighbours(
+        &self,
+        request: Request<SampleNeighboursInternal>,
+    ) -> Result<Response<SampleNeighboursResponse>, Status> {
+        sample_neighbours(self.toc.as_ref(), request.into_inner()).await
+    }
 }
 
 #[cfg(test)]
@@ -1,5 +1,6 @@
 mod api;
 
+use crate::common::rate_limiter::{RateLimiter, API_KEY_HEADER};
 use crate::tonic::api::collections_api::CollectionsService;
 use crate::tonic::api::collections_internal_api::CollectionsInternalService;
 use crate::tonic::api::points_api::PointsService;
@@ -31,7 +32,32 @@ impl Qdrant for QdrantService {
     }
 }
 
-pub fn init(toc: Arc<TableOfContent>, host: String, grpc_port: u16) -> std::io::Result<()> {
+/// Rejects requests of the clients, which exceeded the allowed rate
+fn rate_limit_interceptor(
+    rate_limiter: Option<Arc<RateLimiter>>,
+) -> impl FnMut(Request<()>) -> Result<Request<()>, Status> + Clone {
+    move |request: Request<()>| {
+        if let Some(rate_limiter) = &rate_limiter {
+            let api_key = request
+                .metadata()
+                .get(API_KEY_HEADER)
+                .and_then(|key| key.to_str().ok());
+            if !rate_limiter.check(api_key) {
+                return Err(Status::resource_exhausted(
+                    "Too many requests, rate limit exceeded",
+                ));
+            }
+        }
+        Ok(request)
+    }
+}
+
+pub fn init(
+    toc: Arc<TableOfContent>,
+    rate_limiter: Option<Arc<RateLimiter>>,
+    host: String,
+    grpc_port: u16,
+) -> std::io::Result<()> {
     let tonic_runtime = runtime::Builder::new_multi_thread()
         .enable_io()
         .enable_time()
@@ -48,13 +74,17 @@ pub fn init(toc: Arc<TableOfContent>, host: String, grpc_port: u16) -> std::io::
             let service = QdrantService::default();
             let collections_service = CollectionsService::new(toc.clone());
             let points_service = PointsService::new(toc.clone());
+            let interceptor = rate_limit_interceptor(rate_limiter);
 
             log::info!("Qdrant gRPC listening on {}", grpc_port);
 
             Server::builder()
                 .add_service(QdrantServer::new(service))
-                .add_service(CollectionsServer::new(collections_service))
-                .add_service(PointsServer::new(points_service))
+                .add_service(CollectionsServer::with_interceptor(
+                    collections_service,
+                    interceptor.clone(),
+                ))
+                .add_service(PointsServer::with_interceptor(points_service, interceptor))
                 .serve_with_shutdown(socket, async {
                     signal::ctrl_c().await.unwrap();
                     log::info!("Stopping gRPC");